@@ -0,0 +1,86 @@
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use mp4ameta::{Chapter, Img, ReadConfig, Tag, WriteChapters, WriteConfig};
+
+/// Size of the artwork of the fixture file.
+const ARTWORK_LEN: usize = 512 * 1024;
+/// Size of the padding that is appended to the large fixture file.
+const LARGE_PADDING_LEN: u32 = 64 * 1024 * 1024;
+
+/// Generates a file with a typical tag, including artwork and chapters.
+fn fixture() -> Vec<u8> {
+    let input = std::fs::read("../files/sample.m4a").unwrap();
+    let mut tag = Tag::read_from_slice(&input).unwrap();
+    tag.set_title("title");
+    tag.set_artist("artist");
+    tag.set_album("album");
+    tag.set_genre("genre");
+    tag.set_isrc("isrc");
+    tag.set_track(3, 12);
+    tag.set_artwork(Img::jpeg(vec![0xff; ARTWORK_LEN]));
+    *tag.chapter_list_mut() = chapters(20);
+    *tag.chapter_track_mut() = chapters(20);
+    tag.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap()
+}
+
+/// Generates a file with lots of trailing data, that has to be moved when the file grows.
+fn large_fixture() -> Vec<u8> {
+    let mut data = fixture();
+    data.extend_from_slice(&LARGE_PADDING_LEN.to_be_bytes());
+    data.extend_from_slice(b"free");
+    data.resize(data.len() + LARGE_PADDING_LEN as usize - 8, 0);
+    data
+}
+
+fn chapters(n: u64) -> Vec<Chapter> {
+    (0..n).map(|i| Chapter::new(Duration::from_millis(i * 20), format!("chapter {i}"))).collect()
+}
+
+fn read(c: &mut Criterion) {
+    let input = fixture();
+
+    c.bench_function("read full tag", |b| b.iter(|| Tag::read_from_slice(black_box(&input))));
+
+    let cfg = ReadConfig {
+        read_meta_items: true,
+        read_image_data: false,
+        ..ReadConfig::NONE
+    };
+    c.bench_function("read meta items only", |b| {
+        b.iter(|| Tag::read_with_slice(black_box(&input), &cfg))
+    });
+}
+
+fn write(c: &mut Criterion) {
+    let input = fixture();
+    let tag = Tag::read_from_slice(&input).unwrap();
+
+    c.bench_function("write same size", |b| {
+        b.iter(|| tag.write_to_vec(black_box(&input), &WriteConfig::DEFAULT))
+    });
+
+    let mut bigger = tag.clone();
+    bigger.add_artwork(Img::png(vec![0; ARTWORK_LEN]));
+    c.bench_function("write bigger", |b| {
+        b.iter(|| bigger.write_to_vec(black_box(&input), &WriteConfig::DEFAULT))
+    });
+
+    let input = large_fixture();
+    let mut chaptered = tag.clone();
+    *chaptered.chapter_track_mut() = chapters(500);
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::DEFAULT
+    };
+    let mut group = c.benchmark_group("large file");
+    group.sample_size(10);
+    group.bench_function("write chapter track", |b| {
+        b.iter(|| chaptered.write_to_vec(black_box(&input), &cfg))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, read, write);
+criterion_main!(benches);
@@ -0,0 +1,162 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use mp4ameta::{Chapter, FormatLayout, FormatStyle, Tag, Userdata};
+
+const USAGE: &str = "\
+Usage:
+    mp4ameta show <file> [--compact | --key-value] [--raw-idents]
+    mp4ameta set <file> [--<field> <value>]...
+    mp4ameta chapters import <file> <chapters.txt>
+    mp4ameta chapters export <file>
+
+Fields:
+    title, artist, album, album-artist, composer, genre, year, comment, track, total-tracks
+
+Chapter files contain one chapter per line, starting with a timestamp:
+    00:00:00.000 First chapter
+    01:23.456 Second chapter";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[&str]) -> Result<(), String> {
+    match args {
+        ["show", file, options @ ..] => show(file, options),
+        ["set", file, fields @ ..] => set(file, fields),
+        ["chapters", "import", file, chapters] => import_chapters(file, chapters),
+        ["chapters", "export", file] => export_chapters(file),
+        ["help" | "--help" | "-h"] => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!("invalid arguments\n\n{USAGE}")),
+    }
+}
+
+fn read(file: &str) -> Result<Tag, String> {
+    Tag::read_from_path(file).map_err(|e| format!("failed to read {file}: {e}"))
+}
+
+fn write(tag: &Tag, file: &str) -> Result<(), String> {
+    tag.write_to_path(file).map_err(|e| format!("failed to write {file}: {e}"))
+}
+
+fn show(file: &str, options: &[&str]) -> Result<(), String> {
+    let mut style = FormatStyle::VERBOSE;
+    for option in options {
+        match *option {
+            "--compact" => style.layout = FormatLayout::Compact,
+            "--key-value" => style.layout = FormatLayout::KeyValue,
+            "--raw-idents" => style.raw_idents = true,
+            _ => return Err(format!("unknown option {option}")),
+        }
+    }
+
+    let tag = read(file)?;
+    print!("{}", tag.format(style));
+    Ok(())
+}
+
+fn set(file: &str, fields: &[&str]) -> Result<(), String> {
+    if fields.is_empty() || !fields.len().is_multiple_of(2) {
+        return Err(format!("expected pairs of fields and values\n\n{USAGE}"));
+    }
+
+    let mut tag = read(file)?;
+    for pair in fields.chunks_exact(2) {
+        let field =
+            pair[0].strip_prefix("--").ok_or_else(|| format!("invalid field {}", pair[0]))?;
+        set_field(&mut tag, field, pair[1])?;
+    }
+    write(&tag, file)
+}
+
+fn set_field(userdata: &mut Userdata, field: &str, value: &str) -> Result<(), String> {
+    let number = || value.parse::<u16>().map_err(|_| format!("invalid number {value}"));
+    match field {
+        "title" => userdata.set_title(value),
+        "artist" => userdata.set_artist(value),
+        "album" => userdata.set_album(value),
+        "album-artist" => userdata.set_album_artist(value),
+        "composer" => userdata.set_composer(value),
+        "genre" => userdata.set_genre(value),
+        "year" => userdata.set_year(value),
+        "comment" => userdata.set_comment(value),
+        "track" => userdata.set_track_number(number()?),
+        "total-tracks" => userdata.set_total_tracks(number()?),
+        _ => return Err(format!("unknown field {field}")),
+    }
+    Ok(())
+}
+
+fn import_chapters(file: &str, chapters: &str) -> Result<(), String> {
+    let content =
+        std::fs::read_to_string(chapters).map_err(|e| format!("failed to read {chapters}: {e}"))?;
+    let chapters = parse_chapters(&content)?;
+
+    let mut tag = read(file)?;
+    *tag.chapter_list_mut() = chapters.clone();
+    *tag.chapter_track_mut() = chapters;
+    write(&tag, file)
+}
+
+fn export_chapters(file: &str) -> Result<(), String> {
+    let tag = read(file)?;
+    for c in tag.chapters() {
+        println!("{} {}", format_timestamp(c.start), c.title);
+    }
+    Ok(())
+}
+
+fn parse_chapters(content: &str) -> Result<Vec<Chapter>, String> {
+    let lines = content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    lines
+        .map(|(i, line)| {
+            let line = line.trim();
+            let (timestamp, title) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let start = parse_timestamp(timestamp)
+                .ok_or_else(|| format!("invalid timestamp on line {}: {timestamp}", i + 1))?;
+            Ok(Chapter::new(start, title.trim()))
+        })
+        .collect()
+}
+
+/// Parses a timestamp of the form `[[hh:]mm:]ss[.fff]`.
+fn parse_timestamp(s: &str) -> Option<Duration> {
+    let (rest, frac) = s.split_once('.').unwrap_or((s, ""));
+    let parts = rest.split(':').collect::<Vec<_>>();
+    if parts.len() > 3 {
+        return None;
+    }
+    let mut secs: u64 = 0;
+    for part in parts {
+        secs = secs.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+    }
+
+    let mut nanos = 0;
+    if !frac.is_empty() {
+        if frac.len() > 9 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        nanos = frac.parse::<u32>().ok()? * 10u32.pow(9 - frac.len() as u32);
+    }
+
+    Some(Duration::new(secs, nanos))
+}
+
+fn format_timestamp(d: Duration) -> String {
+    let secs = d.as_secs();
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    format!("{hours:02}:{mins:02}:{secs:02}.{:03}", d.subsec_millis())
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::process::{Command, Output};
+use std::time::Duration;
+
+use mp4ameta::{Chapter, FormatStyle, Tag};
+
+fn mp4ameta(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mp4ameta")).args(args).output().unwrap()
+}
+
+fn use_sample_file(target_file: &str) -> &str {
+    fs::create_dir_all("../target/cli").unwrap();
+    fs::copy("../files/sample.m4a", target_file).unwrap();
+    target_file
+}
+
+#[test]
+fn show() {
+    let output = mp4ameta(&["show", "../files/sample.m4a"]);
+    assert!(output.status.success());
+    let tag = Tag::read_from_path("../files/sample.m4a").unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), tag.to_string());
+
+    let output = mp4ameta(&["show", "../files/sample.m4a", "--key-value", "--raw-idents"]);
+    assert!(output.status.success());
+    let expected = tag.format(FormatStyle::KEY_VALUE.with_raw_idents()).to_string();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+
+    let output = mp4ameta(&["show", "../files/sample.m4a", "--unknown"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn set() {
+    let file = use_sample_file("../target/cli/set.m4a");
+    let output = mp4ameta(&["set", file, "--title", "new title", "--track", "3"]);
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(file).unwrap();
+    assert_eq!(tag.title(), Some("new title"));
+    assert_eq!(tag.track_number(), Some(3));
+
+    let output = mp4ameta(&["set", file, "--unknown", "value"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn chapters() {
+    let file = use_sample_file("../target/cli/chapters.m4a");
+    let chapters_file = "../target/cli/chapters.txt";
+    fs::write(chapters_file, "00:00:00.000 first\n\n00.25 second chapter\n").unwrap();
+
+    let output = mp4ameta(&["chapters", "import", file, chapters_file]);
+    assert!(output.status.success());
+
+    let expected = [
+        Chapter::new(Duration::ZERO, "first"),
+        Chapter::new(Duration::from_millis(250), "second chapter"),
+    ];
+    let tag = Tag::read_from_path(file).unwrap();
+    assert_eq!(tag.chapter_list(), expected);
+    assert_eq!(tag.chapter_track(), expected);
+
+    let output = mp4ameta(&["chapters", "export", file]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "00:00:00.000 first\n00:00:00.250 second chapter\n"
+    );
+
+    for invalid in ["00:00:00:00 too many components", "18446744073709551615:00 overflow"] {
+        fs::write(chapters_file, invalid).unwrap();
+        let output = mp4ameta(&["chapters", "import", file, chapters_file]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("invalid timestamp on line 1"), "{stderr}");
+    }
+    assert_eq!(Tag::read_from_path(file).unwrap().chapter_list(), expected);
+}
@@ -0,0 +1,22 @@
+use mp4ameta::{Tag, WriteConfig};
+
+/// Sets the title of a file that only exists in memory, without any filesystem access, like a
+/// browser based tag editor would. This example is also built for `wasm32-unknown-unknown` to
+/// ensure the crate stays usable there:
+///
+/// ```sh
+/// cargo build --example wasm --target wasm32-unknown-unknown
+/// ```
+pub fn set_title(input: &[u8], title: &str) -> mp4ameta::Result<Vec<u8>> {
+    let mut tag = Tag::read_from_slice(input)?;
+    tag.set_title(title);
+    tag.write_to_vec(input, &WriteConfig::DEFAULT)
+}
+
+fn main() {
+    let input = include_bytes!("../files/sample.m4a");
+    let output = set_title(input, "title").expect("error writing tag");
+
+    let tag = Tag::read_from_slice(&output).expect("error reading tag");
+    assert_eq!(tag.title(), Some("title"));
+}
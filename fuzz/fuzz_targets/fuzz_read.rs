@@ -1,8 +1,8 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
+use mp4ameta::ReadConfig;
 
 fuzz_target!(|data: &[u8]| {
-    let mut reader = std::io::Cursor::new(data);
-    _ = mp4ameta::Tag::read_from(&mut reader);
+    _ = mp4ameta::fuzz::read_tag(data, &ReadConfig::UNTRUSTED);
 });
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mp4ameta::ReadConfig;
+
+fuzz_target!(|data: &[u8]| {
+    _ = mp4ameta::fuzz::read_write_tag(data, &ReadConfig::UNTRUSTED);
+});
@@ -8,34 +8,78 @@ const HEADER: &str = "\
 // Edit the `generate.toml` file and run the code generation from the repository root
 // with the following command: `cargo run --manifest-path=gen/Cargo.toml`
 
-use crate::{ident, Data, Userdata};
+use std::borrow::Cow;
+
+use crate::ident::{IdentInfo, ValueKind};
+use crate::tag::TagFormatter;
+use crate::{ident, Data, DataIdent, Tag, Userdata};
 ";
 
 fn main() {
     let input: toml::Table = toml::from_str(INPUT).unwrap();
     let accessors = input["accessors"].as_table().unwrap();
 
+    let display_order = input["display"]["order"].as_array().unwrap();
+    let display_order = display_order.iter().map(|v| v.as_str().unwrap()).collect::<Vec<_>>();
+    let display_skip = input["display"]["skip"].as_array().unwrap();
+    let display_skip = display_skip.iter().map(|v| v.as_str().unwrap()).collect::<Vec<_>>();
+
     let mut output = String::from(HEADER);
+    let mut formatted = Vec::new();
+    let mut known_idents = Vec::new();
 
     for [value_ident, atom_ident] in str_table_iter(&accessors["single_strings"]) {
-        single_string_accessor(&mut output, value_ident, atom_ident);
+        formatted.push(single_string_accessor(&mut output, value_ident, atom_ident));
+        known_idents.push(KnownIdent::new(value_ident, atom_ident, "String", false));
     }
     for [value_ident, atom_ident] in str_table_iter(&accessors["multiple_strings"]) {
-        multiple_strings_accessor(&mut output, value_ident, atom_ident);
+        formatted.push(multiple_strings_accessor(&mut output, value_ident, atom_ident));
+        known_idents.push(KnownIdent::new(value_ident, atom_ident, "String", true));
     }
     for [value_ident, atom_ident] in str_table_iter(&accessors["bool_flags"]) {
-        bool_flag_accessor(&mut output, value_ident, atom_ident);
+        formatted.push(bool_flag_accessor(&mut output, value_ident, atom_ident));
+        known_idents.push(KnownIdent::new(value_ident, atom_ident, "Flag", false));
     }
     for [value_ident, atom_ident] in str_table_iter(&accessors["u16_ints"]) {
-        u16_int_accessor(&mut output, value_ident, atom_ident);
+        formatted.push(u16_int_accessor(&mut output, value_ident, atom_ident));
+        known_idents.push(KnownIdent::new(value_ident, atom_ident, "U16", false));
     }
     for [value_ident, atom_ident] in str_table_iter(&accessors["u32_ints"]) {
-        u32_int_accessor(&mut output, value_ident, atom_ident);
+        formatted.push(u32_int_accessor(&mut output, value_ident, atom_ident));
+        known_idents.push(KnownIdent::new(value_ident, atom_ident, "U32", false));
+    }
+    for (value_ident, info) in input["known_idents"].as_table().unwrap() {
+        let atom_ident = info["ident"].as_str().unwrap();
+        let kind = info["kind"].as_str().unwrap();
+        let multiple = info.get("multiple").and_then(|m| m.as_bool()).unwrap_or(false);
+        known_idents.push(KnownIdent::new(value_ident, atom_ident, kind, multiple));
     }
 
+    // every generated accessor has to be displayed
+    for name in formatted.iter() {
+        if !display_order.contains(&name.as_str()) && !display_skip.contains(&name.as_str()) {
+            panic!("`{name}` is missing from the display order");
+        }
+    }
+    display_order_formatter(&mut output, &display_order);
+    known_idents_table(&mut output, known_idents);
+
     std::fs::write(OUTPUT_PATH, &output).unwrap();
 }
 
+struct KnownIdent<'a> {
+    value_ident: &'a str,
+    atom_ident_string: &'a str,
+    kind: &'a str,
+    multiple: bool,
+}
+
+impl<'a> KnownIdent<'a> {
+    fn new(value_ident: &'a str, atom_ident_string: &'a str, kind: &'a str, multiple: bool) -> Self {
+        Self { value_ident, atom_ident_string, kind, multiple }
+    }
+}
+
 fn str_table_iter(value: &toml::Value) -> impl Iterator<Item = [&str; 2]> {
     let table = value.as_table().unwrap();
     table.iter().map(|(key, val)| {
@@ -55,7 +99,11 @@ fn base_values(value_ident: &str) -> (String, String, String) {
     (name, headline, atom_ident)
 }
 
-pub fn single_string_accessor(output: &mut String, value_ident: &str, atom_ident_string: &str) {
+pub fn single_string_accessor(
+    output: &mut String,
+    value_ident: &str,
+    atom_ident_string: &str,
+) -> String {
     let (name, headline, atom_ident) = base_values(value_ident);
 
     _ = write!(
@@ -85,11 +133,8 @@ impl Userdata {{
 
     /// Returns the {n} formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_{vi}(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
-        match self.{vi}() {{
-            Some(s) => writeln!(f, \"{n}: {{}}\", s),
-            None => Ok(()),
-        }}
+    pub(crate) fn format_{vi}(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {{
+        f.value(\"{n}\", Some(\"{ais}\"), self.{vi}())
     }}
 }}
 ",
@@ -99,13 +144,15 @@ impl Userdata {{
         vi = value_ident,
         ai = atom_ident,
     );
+
+    value_ident.to_owned()
 }
 
 pub fn multiple_strings_accessor(
     output: &mut String,
     value_ident: &str,
     atom_ident_string: &str,
-) {
+) -> String {
     let (name, headline, atom_ident) = base_values(value_ident);
 
     let mut value_ident_plural = value_ident.to_string();
@@ -144,8 +191,8 @@ impl Userdata {{
     }}
 
     /// Sets all {np} (`{ais}`). This will remove all other {np}.
-    pub fn set_{vip}(&mut self, {vip}: impl IntoIterator<Item = String>) {{
-        let data = {vip}.into_iter().map(Data::Utf8);
+    pub fn set_{vip}(&mut self, {vip}: impl IntoIterator<Item = impl Into<String>>) {{
+        let data = {vip}.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data({ai}, data);
     }}
 
@@ -155,8 +202,8 @@ impl Userdata {{
     }}
 
     /// Adds all {np} (`{ais}`).
-    pub fn add_{vip}(&mut self, {vip}: impl IntoIterator<Item = String>) {{
-        let data = {vip}.into_iter().map(Data::Utf8);
+    pub fn add_{vip}(&mut self, {vip}: impl IntoIterator<Item = impl Into<String>>) {{
+        let data = {vip}.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data({ai}, data);
     }}
 
@@ -172,16 +219,8 @@ impl Userdata {{
 
     /// Returns all {np} formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_{vip}(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
-        if self.{vip}().count() > 1 {{
-            writeln!(f, \"{np}:\")?;
-            for s in self.{vip}() {{
-                writeln!(f, \"    {{}}\", s)?;
-            }}
-        }} else if let Some(s) = self.{vi}() {{
-            writeln!(f, \"{n}: {{}}\", s)?;
-        }}
-        Ok(())
+    pub(crate) fn format_{vip}(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {{
+        f.values(\"{n}\", \"{np}\", Some(\"{ais}\"), self.{vip}())
     }}
 }}
 ",
@@ -193,9 +232,15 @@ impl Userdata {{
         vip = value_ident_plural,
         ai = atom_ident,
     );
+
+    value_ident_plural
 }
 
-pub fn bool_flag_accessor(output: &mut String, value_ident: &str, atom_ident_string: &str) {
+pub fn bool_flag_accessor(
+    output: &mut String,
+    value_ident: &str,
+    atom_ident_string: &str,
+) -> String {
     let (name, headline, atom_ident) = base_values(value_ident);
 
     _ = write!(
@@ -205,11 +250,7 @@ pub fn bool_flag_accessor(output: &mut String, value_ident: &str, atom_ident_str
 impl Userdata {{
     /// Returns the {n} flag (`{ais}`).
     pub fn {vi}(&self) -> bool {{
-        let vec = match self.bytes_of(&{ai}).next() {{
-            Some(v) => v,
-            None => return false,
-        }};
-        vec.first().map(|&v| v == 1).unwrap_or(false)
+        self.data_of(&{ai}).next().and_then(Data::flag).unwrap_or(false)
     }}
 
     /// Sets the {n} flag to true (`{ais}`).
@@ -224,11 +265,8 @@ impl Userdata {{
 
     /// Returns the {n} formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_{vi}(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
-        match self.{vi}() {{
-            true => writeln!(f, \"{n}\"),
-            false => Ok(()),
-        }}
+    pub(crate) fn format_{vi}(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {{
+        f.flag(\"{n}\", Some(\"{ais}\"), self.{vi}())
     }}
 }}
 ",
@@ -238,9 +276,15 @@ impl Userdata {{
         vi = value_ident,
         ai = atom_ident,
     );
+
+    value_ident.to_owned()
 }
 
-pub fn u16_int_accessor(output: &mut String, value_ident: &str, atom_ident_string: &str) {
+pub fn u16_int_accessor(
+    output: &mut String,
+    value_ident: &str,
+    atom_ident_string: &str,
+) -> String {
     let (name, headline, atom_ident) = base_values(value_ident);
 
     _ = write!(
@@ -267,11 +311,8 @@ impl Userdata {{
 
     /// Returns the {n} formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_{vi}(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
-        match self.{vi}() {{
-            Some(s) => writeln!(f, \"{n}: {{}}\", s),
-            None => Ok(()),
-        }}
+    pub(crate) fn format_{vi}(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {{
+        f.value(\"{n}\", Some(\"{ais}\"), self.{vi}())
     }}
 }}
 ",
@@ -281,9 +322,15 @@ impl Userdata {{
         vi = value_ident,
         ai = atom_ident,
     );
+
+    value_ident.to_owned()
 }
 
-pub fn u32_int_accessor(output: &mut String, value_ident: &str, atom_ident_string: &str) {
+pub fn u32_int_accessor(
+    output: &mut String,
+    value_ident: &str,
+    atom_ident_string: &str,
+) -> String {
     let (name, headline, atom_ident) = base_values(value_ident);
 
     _ = write!(
@@ -310,11 +357,8 @@ impl Userdata {{
 
     /// Returns the {n} formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_{vi}(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
-        match self.{vi}() {{
-            Some(s) => writeln!(f, \"{n}: {{}}\", s),
-            None => Ok(()),
-        }}
+    pub(crate) fn format_{vi}(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {{
+        f.value(\"{n}\", Some(\"{ais}\"), self.{vi}())
     }}
 }}
 ",
@@ -324,4 +368,57 @@ impl Userdata {{
         vi = value_ident,
         ai = atom_ident,
     );
+
+    value_ident.to_owned()
+}
+
+fn display_order_formatter(output: &mut String, display_order: &[&str]) {
+    _ = write!(
+        output,
+        "
+impl Tag {{
+    /// Formats all values in the display order of `generate.toml`.
+    pub(crate) fn format_all(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {{
+"
+    );
+    for name in display_order {
+        _ = writeln!(output, "        self.format_{name}(f)?;");
+    }
+    _ = write!(
+        output,
+        "        Ok(())
+    }}
+}}
+"
+    );
+}
+
+fn known_idents_table(output: &mut String, mut known_idents: Vec<KnownIdent<'_>>) {
+    known_idents.sort_by_key(|k| k.value_ident);
+
+    _ = write!(
+        output,
+        "
+/// All identifiers with dedicated accessors sorted by their name, for example to build field
+/// pickers and validators.
+pub const KNOWN_IDENTS: &[IdentInfo] = &[
+"
+    );
+    for k in known_idents {
+        let (_, _, atom_ident) = base_values(k.value_ident);
+        let ident = match k.atom_ident_string.starts_with("----:") {
+            true => format!(
+                "DataIdent::Freeform {{ mean: Cow::Borrowed({atom_ident}.mean), name: Cow::Borrowed({atom_ident}.name) }}"
+            ),
+            false => format!("DataIdent::Fourcc({atom_ident})"),
+        };
+        _ = writeln!(
+            output,
+            "    IdentInfo {{ ident: {ident}, name: \"{vi}\", kind: ValueKind::{k}, multiple: {m} }},",
+            vi = k.value_ident,
+            k = k.kind,
+            m = k.multiple,
+        );
+    }
+    _ = writeln!(output, "];");
 }
@@ -0,0 +1,49 @@
+/* C declarations of the mp4ameta C ABI, see `src/capi.rs` for documentation. */
+
+#ifndef MP4AMETA_H
+#define MP4AMETA_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MP4AMETA_CHAPTER_LIST 0
+#define MP4AMETA_CHAPTER_TRACK 1
+
+#define MP4AMETA_IMG_BMP 0
+#define MP4AMETA_IMG_JPEG 1
+#define MP4AMETA_IMG_PNG 2
+#define MP4AMETA_IMG_GIF 3
+#define MP4AMETA_IMG_HEIC 4
+#define MP4AMETA_IMG_WEBP 5
+
+typedef struct Mp4ametaTag Mp4ametaTag;
+
+const char *mp4ameta_last_error(void);
+
+Mp4ametaTag *mp4ameta_tag_read(const char *path);
+int mp4ameta_tag_write(const Mp4ametaTag *tag, const char *path);
+void mp4ameta_tag_free(Mp4ametaTag *tag);
+void mp4ameta_string_free(char *s);
+
+char *mp4ameta_tag_get_string(const Mp4ametaTag *tag, const uint8_t ident[4]);
+int mp4ameta_tag_set_string(Mp4ametaTag *tag, const uint8_t ident[4], const char *value);
+int mp4ameta_tag_remove(Mp4ametaTag *tag, const uint8_t ident[4]);
+
+const uint8_t *mp4ameta_tag_get_artwork(const Mp4ametaTag *tag, size_t *len, int *fmt);
+int mp4ameta_tag_set_artwork(Mp4ametaTag *tag, const uint8_t *data, size_t len, int fmt);
+int mp4ameta_tag_remove_artworks(Mp4ametaTag *tag);
+
+ptrdiff_t mp4ameta_tag_chapter_count(const Mp4ametaTag *tag, int kind);
+char *mp4ameta_tag_chapter_get(const Mp4ametaTag *tag, int kind, size_t index, uint64_t *start_ms);
+int mp4ameta_tag_chapter_add(Mp4ametaTag *tag, int kind, uint64_t start_ms, const char *title);
+int mp4ameta_tag_chapter_clear(Mp4ametaTag *tag, int kind);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,37 @@
+//! Utilities for replacing artwork without rewriting the file.
+//!
+//! Writing a tag rewrites the whole item list (`ilst`), and moves the media data (`mdat`) if the
+//! size of the movie atom (`moov`) changes. Artwork is usually the largest item, so swapping it can
+//! be expensive. These functions overwrite only the bytes of the existing artwork item instead.
+
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use crate::{Img, StorageFile, atom};
+
+/// Replaces the artwork (`covr`) of the file at the indicated path in place, see
+/// [`replace_artwork_in_place_in`].
+pub fn replace_artwork_in_place(
+    path: impl AsRef<Path>,
+    image: Img<impl Into<Vec<u8>>>,
+) -> crate::Result<bool> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    replace_artwork_in_place_in(&mut file, image)
+}
+
+/// Replaces the artwork (`covr`) of the file in place, reusing the space of the existing artwork.
+/// All existing artworks are replaced by the image.
+///
+/// This is only possible if the new artwork item is the same size as the existing one, or at
+/// least 8 bytes smaller, in which case the remaining space is filled with a free atom (`free`).
+/// Returns whether the artwork was replaced. If the file contains no artwork or the image doesn't
+/// fit, the file is left untouched and the tag has to be written instead, see
+/// [`Userdata::set_artwork`](crate::Userdata::set_artwork).
+///
+/// Like when writing a tag, classic QuickTime files without a filetype atom (`ftyp`) are accepted.
+pub fn replace_artwork_in_place_in(
+    file: &mut impl StorageFile,
+    image: Img<impl Into<Vec<u8>>>,
+) -> crate::Result<bool> {
+    atom::replace_artwork_in_place(file, image.into())
+}
@@ -111,7 +111,10 @@ pub enum Change<'a> {
     Replace(ReplaceAtom<'a>),
     Insert(InsertAtom<'a>),
     RemoveMdat(u64, u64),
+    #[cfg_attr(not(feature = "chapters"), allow(dead_code))]
     AppendMdat(u64, Vec<u8>),
+    /// Overwrites bytes in place, without changing the length.
+    Overwrite(u64, Vec<u8>),
 }
 
 impl std::fmt::Display for Change<'_> {
@@ -125,6 +128,7 @@ impl std::fmt::Display for Change<'_> {
             Change::Insert(i)                               => write!(f, "InsertAtom  {}  ", i.atom.fourcc()),
             Change::RemoveMdat(..)                          => write!(f, "RemoveMdat        "),
             Change::AppendMdat(..)                          => write!(f, "AppendMdat        "),
+            Change::Overwrite(..)                           => write!(f, "Overwrite         "),
         }?;
         write!(
             f,
@@ -147,6 +151,7 @@ impl Change<'_> {
             Self::Insert(c) => c.pos,
             Self::RemoveMdat(pos, _) => *pos,
             Self::AppendMdat(pos, _) => *pos,
+            Self::Overwrite(pos, _) => *pos,
         }
     }
 
@@ -159,6 +164,7 @@ impl Change<'_> {
             Self::Insert(c) => c.pos,
             Self::RemoveMdat(pos, len) => *pos + *len,
             Self::AppendMdat(pos, _) => *pos,
+            Self::Overwrite(pos, d) => *pos + d.len() as u64,
         }
     }
 
@@ -171,6 +177,7 @@ impl Change<'_> {
             Self::Insert(c) => c.atom.len() as i64,
             Self::RemoveMdat(_, len) => -(*len as i64),
             Self::AppendMdat(_, d) => d.len() as i64,
+            Self::Overwrite(_, _) => 0,
         }
     }
 
@@ -183,6 +190,7 @@ impl Change<'_> {
             Self::Insert(c) => c.level,
             Self::RemoveMdat(_, _) => u8::MAX,
             Self::AppendMdat(_, _) => u8::MAX,
+            Self::Overwrite(_, _) => u8::MAX,
         }
     }
 }
@@ -311,29 +319,29 @@ macro_rules! test_or_ignore {
 }
 
 macro_rules! atom_ref {
-    ($($name:ident $(<$lifetime:lifetime>)? $($nowrite:ident)? ,)+) => {
+    ($($(#[$attr:meta])* $name:ident $(<$lifetime:lifetime>)? $($nowrite:ident)? ,)+) => {
         #[derive(Debug)]
         pub enum AtomRef<'a> {
-            $($name(&'a $name $(<$lifetime>)?)),+
+            $($(#[$attr])* $name(&'a $name $(<$lifetime>)?)),+
         }
 
         impl AtomRef<'_> {
             pub fn write(&self, writer: &mut impl Write, changes: &[Change<'_>]) -> crate::Result<()> {
                 match self {
                     #[allow(unused)]
-                    $(Self::$name(a) => write_or_ignore!($($nowrite)?, {a.write(writer, changes)}),)+
+                    $($(#[$attr])* Self::$name(a) => write_or_ignore!($($nowrite)?, {a.write(writer, changes)}),)+
                 }
             }
 
             pub fn fourcc(&self) -> Fourcc {
                 match self {
-                    $(Self::$name(_) => $name::FOURCC,)+
+                    $($(#[$attr])* Self::$name(_) => $name::FOURCC,)+
                 }
             }
 
             fn len(&self) -> u64 {
                 match self {
-                    $(Self::$name(a) => a.len(),)+
+                    $($(#[$attr])* Self::$name(a) => a.len(),)+
                 }
             }
         }
@@ -344,6 +352,7 @@ macro_rules! atom_ref {
 
             $(
                 test_or_ignore! { $($nowrite)?,
+                    $(#[$attr])*
                     #[test]
                     #[allow(non_snake_case)]
                     fn $name() {
@@ -371,15 +380,23 @@ macro_rules! atom_ref {
 
 atom_ref!(
     Moov<'a> nowrite,
+    Ftyp,
     Udta<'a>,
+    #[cfg(feature = "chapters")]
     Chpl<'a>,
     Meta<'a>,
     Hdlr,
+    Keys<'a>,
     Ilst<'a>,
+    Free,
     Trak,
+    Tkhd,
+    Edts,
+    Elst,
     Tref,
     Chap,
     Mdia,
+    Mdhd,
     Minf,
     Dinf,
     Dref,
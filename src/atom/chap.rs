@@ -27,7 +27,7 @@ impl ParseAtom for Chap {
         }
 
         let num_entries = size.content_len() / ENTRY_SIZE;
-        let mut chapter_ids = Vec::with_capacity(num_entries as usize);
+        let mut chapter_ids = Vec::with_capacity(prealloc_capacity::<u32>(num_entries));
         for _ in 0..num_entries {
             chapter_ids.push(reader.read_be_u32()?);
         }
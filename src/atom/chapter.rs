@@ -0,0 +1,1045 @@
+//! Reading and writing of chapter lists (`chpl`) and chapter tracks.
+
+use super::*;
+
+impl ChplTimescale {
+    fn fixed_or_mvhd(self, mvhd_timescale: u32) -> u32 {
+        match self {
+            Self::Fixed(v) => v.get(),
+            Self::Mvhd => mvhd_timescale,
+            Self::Auto => DEFAULT_CHPL_TIMESCALE.get(),
+        }
+    }
+
+    /// Resolves the timescale of the chapter list, detecting it if it is [`ChplTimescale::Auto`].
+    fn resolve(self, mvhd: &Mvhd, chapters: &[chpl::ChplItem]) -> u32 {
+        if self != Self::Auto {
+            return self.fixed_or_mvhd(mvhd.timescale);
+        }
+
+        let Some(max_start) = chapters.iter().map(|c| c.start).max().filter(|s| *s > 0) else {
+            return DEFAULT_CHPL_TIMESCALE.get();
+        };
+        [DEFAULT_CHPL_TIMESCALE.get(), 1_000, mvhd.timescale]
+            .into_iter()
+            .filter(|t| *t > 0)
+            // max_start / t <= duration / mvhd.timescale
+            .filter(|t| {
+                max_start as u128 * mvhd.timescale as u128 <= mvhd.duration as u128 * *t as u128
+            })
+            .min()
+            .unwrap_or(DEFAULT_CHPL_TIMESCALE.get())
+    }
+}
+
+/// Reads the chapter list and all chapter tracks.
+pub(super) fn read_chapters(
+    reader: &mut (impl Read + Seek),
+    cfg: &ParseConfig<'_>,
+    moov: &mut Moov<'_>,
+    mvhd: &Mvhd,
+    duration: Duration,
+) -> crate::Result<ReadChapters> {
+    let chapter_trak = find_chapter_trak(&moov.trak).filter(|_| cfg.cfg.read_chapter_track);
+    let chapter_sources = ChapterSources {
+        has_chpl: moov.udta.as_ref().is_some_and(|udta| udta.chpl.is_some()),
+        has_track: chapter_trak.is_some(),
+        track_id: chapter_trak.map(|trak| trak.tkhd.id),
+    };
+
+    // chapter list atom
+    let mut chapter_list = Vec::new();
+    let mut chpl_timescale = None;
+    if cfg.cfg.read_chapter_list
+        && let Some(chpl) = moov.udta.as_mut().and_then(|udta| udta.chpl.take())
+        && let Some(mut chpl) = chpl.into_owned()
+    {
+        let timescale = cfg.cfg.chpl_timescale.resolve(mvhd, &chpl);
+        chpl_timescale = Some(timescale);
+
+        chpl.sort_by_key(|c| c.start);
+        chapter_list.extend(chpl.into_iter().map(|c| Chapter {
+            start: scale_duration(timescale, c.start),
+            title: c.title,
+        }));
+        warn_chapters_out_of_bounds(cfg, &chapter_list, duration);
+    }
+
+    // chapter tracks
+    let mut chapter_tracks = Vec::new();
+    if cfg.cfg.read_chapter_track {
+        for trak in chapter_traks(&moov.trak) {
+            warn_track_duration_mismatch(cfg, trak, mvhd);
+
+            let (timescale, samples) =
+                read_chapter_samples(reader, trak, cfg.cfg.max_chapter_count)?;
+            let titles = read_chapter_titles(reader, &samples).map_err(chapter_read_error)?;
+            let chapters = samples.iter().zip(titles).map(|(sample, title)| Chapter {
+                start: scale_duration(timescale, sample.time),
+                title,
+            });
+            let language = trak
+                .mdia
+                .as_ref()
+                .map_or(Language::UNDETERMINED, |m| mdhd::unpack_language(m.mdhd.language));
+            chapter_tracks.push(ChapterTrack {
+                track_id: trak.tkhd.id,
+                language,
+                timescale,
+                chapters: chapters.collect(),
+            });
+        }
+    }
+    let chapter_track = chapter_tracks.first().map(|t| t.chapters.clone()).unwrap_or_default();
+
+    warn_chapters_out_of_bounds(cfg, &chapter_track, duration);
+
+    Ok(ReadChapters {
+        sources: chapter_sources,
+        list: chapter_list,
+        chpl_timescale,
+        track: chapter_track,
+        tracks: chapter_tracks,
+    })
+}
+
+fn warn_chapters_out_of_bounds(cfg: &ParseConfig<'_>, chapters: &[Chapter], duration: Duration) {
+    for c in chapters.iter().filter(|c| c.start > duration) {
+        cfg.warn(ParseWarning::ChapterOutOfBounds { start: c.start, duration });
+    }
+}
+
+/// Reports a warning if the track header (`tkhd`) or media header (`mdhd`) duration of the track
+/// differs from the movie duration by more than one unit.
+fn warn_track_duration_mismatch(cfg: &ParseConfig<'_>, trak: &Trak, mvhd: &Mvhd) {
+    let mdhd = trak.mdia.as_ref().map(|m| &m.mdhd);
+    let (timescale, duration) = if !durations_match(mvhd.timescale, trak.tkhd.duration, mvhd) {
+        (mvhd.timescale, trak.tkhd.duration)
+    } else if let Some(mdhd) = mdhd.filter(|m| !durations_match(m.timescale, m.duration, mvhd)) {
+        (mdhd.timescale, mdhd.duration)
+    } else {
+        return;
+    };
+    cfg.warn(ParseWarning::TrackDurationMismatch {
+        track_id: trak.tkhd.id,
+        duration: scale_duration(timescale, duration),
+        movie_duration: scale_duration(mvhd.timescale, mvhd.duration),
+    });
+}
+
+/// Checks whether the duration matches the movie duration, allowing a difference of one unit of
+/// the coarser timescale.
+fn durations_match(timescale: u32, duration: u64, mvhd: &Mvhd) -> bool {
+    let a = duration as u128 * mvhd.timescale as u128;
+    let b = mvhd.duration as u128 * timescale as u128;
+    a.abs_diff(b) <= timescale.max(mvhd.timescale) as u128
+}
+
+/// Lazily reads the chapter track, see [`Tag::chapter_track_lazy`].
+pub(crate) fn read_chapter_track_lazy<R: Read + Seek>(
+    reader: &mut R,
+) -> crate::Result<ChapterTrackIter<'_, R>> {
+    let cfg = ReadConfig {
+        read_chapter_track: true,
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::NONE
+    };
+    let parse_cfg = ParseConfig::new(&cfg, false, None);
+
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let ftyp = parse_ftyp(reader, file_len, &cfg.brand_policy)?;
+    let moov = parse_moov(reader, &parse_cfg, file_len, ftyp.map_or(0, |f| f.len()))?;
+
+    let (timescale, samples) = match find_chapter_trak(&moov.trak) {
+        Some(trak) => read_chapter_samples(reader, trak, cfg.max_chapter_count)?,
+        None => (0, Vec::new()),
+    };
+    Ok(ChapterTrackIter { reader, timescale, samples: samples.into_iter() })
+}
+
+/// An iterator over the chapters of a chapter track, which reads the chapter titles on demand.
+///
+/// Returned by [`Tag::chapter_track_lazy`].
+#[derive(Debug)]
+pub struct ChapterTrackIter<'a, R> {
+    reader: &'a mut R,
+    timescale: u32,
+    samples: std::vec::IntoIter<ChapterSample>,
+}
+
+impl<R: Read + Seek> Iterator for ChapterTrackIter<'_, R> {
+    type Item = crate::Result<Chapter>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.samples.next()?;
+        let chapter = read_chapter_title(self.reader, sample.offset)
+            .map(|title| Chapter {
+                start: scale_duration(self.timescale, sample.time),
+                title,
+            })
+            .map_err(chapter_read_error);
+        Some(chapter)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.samples.size_hint()
+    }
+}
+
+impl<R: Read + Seek> ExactSizeIterator for ChapterTrackIter<'_, R> {}
+
+fn chapter_read_error(mut e: crate::Error) -> crate::Error {
+    let mut desc = e.description.into_owned();
+    desc.insert_str(0, "Error reading chapters: ");
+    e.description = desc.into();
+    e
+}
+
+/// Collects the offsets, sizes and start times of the samples of the chapter track.
+/// Returns the timescale of the chapter track alongside the samples.
+fn read_chapter_samples(
+    reader: &mut (impl Read + Seek),
+    trak: &Trak,
+    max_count: usize,
+) -> crate::Result<(u32, Vec<ChapterSample>)> {
+    let Some(mdia) = &trak.mdia else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(MEDIA),
+            "Media (mdia) atom of chapter track not found",
+        ));
+    };
+    let Some(stbl) = mdia.minf.as_ref().and_then(|a| a.stbl.as_ref()) else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(SAMPLE_TABLE),
+            "Sample table (stbl) of chapter track not found",
+        ));
+    };
+    let Some(stsc) = &stbl.stsc else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(SAMPLE_TABLE_SAMPLE_TO_CHUNK),
+            "Sample table sample to chunk (stsc) atom of chapter track not found",
+        ));
+    };
+    let Some(stsz) = &stbl.stsz else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(SAMPLE_TABLE_SAMPLE_SIZE),
+            "Sample table sample size (stsz) atom of chapter track not found",
+        ));
+    };
+    let Some(stts) = &stbl.stts else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(SAMPLE_TABLE_TIME_TO_SAMPLE),
+            "Sample table time to sample (stts) atom of chapter track not found",
+        ));
+    };
+    let timescale = mdia.mdhd.timescale;
+
+    let stsc_items = stsc.items.get_or_read(reader)?;
+    let stsz_sizes = stsz.sizes.get_or_read(reader)?;
+    let stts_items = stts.items.get_or_read(reader)?;
+    let num_samples = match stsz.uniform_sample_size {
+        0 => stsz_sizes.len(),
+        _ => stsz.uniform_sample_count as usize,
+    };
+
+    let samples = if let Some(co64) = &stbl.co64 {
+        let co64_offsets = co64.offsets.get_or_read(reader)?;
+        collect_chapter_samples(
+            &co64_offsets,
+            &stsc_items,
+            stsz.uniform_sample_size,
+            &stsz_sizes,
+            num_samples,
+            &stts_items,
+            max_count,
+        )
+    } else if let Some(stco) = &stbl.stco {
+        let stco_offsets = stco.offsets.get_or_read(reader)?;
+        collect_chapter_samples(
+            &stco_offsets,
+            &stsc_items,
+            stsz.uniform_sample_size,
+            &stsz_sizes,
+            num_samples,
+            &stts_items,
+            max_count,
+        )
+    } else {
+        Ok(Vec::new())
+    };
+
+    Ok((timescale, samples.map_err(chapter_read_error)?))
+}
+
+fn collect_chapter_samples<T: ChunkOffsetInt>(
+    offsets: &[T],
+    stsc: &[StscItem],
+    stsz_uniform_size: u32,
+    stsz_sizes: &[u32],
+    num_samples: usize,
+    stts: &[SttsItem],
+    max_count: usize,
+) -> crate::Result<Vec<ChapterSample>> {
+    let mut samples = Vec::with_capacity(prealloc_capacity::<ChapterSample>(num_samples as u64));
+    let mut time = 0;
+    let mut stco_idx = 0;
+    let mut stsz_iter = stsz_sizes.iter();
+    let mut stts_iter = stts.iter().flat_map(|stts_item| {
+        std::iter::repeat_n(stts_item.sample_duration, stts_item.sample_count as usize)
+    });
+
+    for (stsc_idx, stsc_item) in stsc.iter().enumerate() {
+        let stco_end_idx = match stsc.get(stsc_idx + 1) {
+            Some(next_stsc_item) => {
+                let end_idx = next_stsc_item.first_chunk as usize;
+                if end_idx > offsets.len() {
+                    return Err(crate::Error::new(
+                        ErrorKind::InvalidSampleTable,
+                        "Sample table sample to chunk (stsc) first chunk index is out of bounds",
+                    ));
+                }
+                if end_idx < stco_idx {
+                    return Err(crate::Error::new(
+                        ErrorKind::InvalidSampleTable,
+                        "Sample table sample to chunk (stsc) first chunk indices are decreasing",
+                    ));
+                }
+                end_idx
+            }
+            None => offsets.len(),
+        };
+
+        for o in offsets[stco_idx..stco_end_idx].iter().copied() {
+            let mut current_offset = o.into();
+
+            for _ in 0..stsc_item.samples_per_chunk {
+                if samples.len() >= max_count {
+                    return Err(crate::Error::new(
+                        ErrorKind::TooManyChapters,
+                        "Chapter track exceeds the maximum chapter count",
+                    ));
+                }
+                if samples.len() >= num_samples {
+                    return Err(crate::Error::new(
+                        ErrorKind::InvalidSampleTable,
+                        "Sample table sample to chunk (stsc) references more samples than the sample size (stsz) atom contains",
+                    ));
+                }
+
+                let size = if stsz_uniform_size != 0 {
+                    stsz_uniform_size
+                } else {
+                    let Some(size) = stsz_iter.next() else {
+                        return Err(crate::Error::new(
+                            ErrorKind::InvalidSampleTable,
+                            "Missing sample table sample size (stsz) item",
+                        ));
+                    };
+                    *size
+                };
+                let Some(duration) = stts_iter.next() else {
+                    return Err(crate::Error::new(
+                        ErrorKind::InvalidSampleTable,
+                        "Missing sample time to sample (stts) duration",
+                    ));
+                };
+
+                samples.push(ChapterSample { offset: current_offset, size: size as u64, time });
+
+                time += duration as u64;
+
+                current_offset += size as u64;
+            }
+        }
+
+        stco_idx = stco_end_idx;
+    }
+
+    Ok(samples)
+}
+
+/// The maximum number of bytes that are read at once when reading chapter titles.
+const MAX_CHAPTER_READ_LEN: u64 = 1 << 17;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct ChapterSample {
+    offset: u64,
+    size: u64,
+    time: u64,
+}
+
+/// Reads the titles of all chapter samples. Samples are sorted by their offset and adjacent
+/// samples are coalesced into a single read, to avoid a seek per chapter.
+fn read_chapter_titles(
+    reader: &mut (impl Read + Seek),
+    samples: &[ChapterSample],
+) -> crate::Result<Vec<String>> {
+    let mut order: Vec<usize> = (0..samples.len()).collect();
+    order.sort_by_key(|&i| samples[i].offset);
+
+    let mut titles = vec![String::new(); samples.len()];
+    let mut buf = Vec::new();
+    let mut i = 0;
+    while i < order.len() {
+        let start = samples[order[i]].offset;
+        let mut end = start + samples[order[i]].size;
+        let mut run_end = i + 1;
+        while let Some(next) = order.get(run_end).map(|&j| samples[j])
+            && next.offset == end
+            && next.offset + next.size - start <= MAX_CHAPTER_READ_LEN
+        {
+            end += next.size;
+            run_end += 1;
+        }
+
+        if end - start > MAX_CHAPTER_READ_LEN {
+            // the sample is too big, only read the title
+            titles[order[i]] = read_chapter_title(reader, start)?;
+            i = run_end;
+            continue;
+        }
+
+        buf.resize((end - start) as usize, 0);
+        reader.seek(SeekFrom::Start(start))?;
+        reader.read_exact(&mut buf)?;
+
+        for &j in order[i..run_end].iter() {
+            let sample_start = samples[j].offset - start;
+            let mut cursor = Cursor::new(&buf[..]);
+            titles[j] = match read_chapter_title(&mut cursor, sample_start) {
+                Ok(t) => t,
+                // the title exceeds the sample size, read it directly
+                Err(_) => read_chapter_title(reader, samples[j].offset)?,
+            };
+        }
+
+        i = run_end;
+    }
+
+    Ok(titles)
+}
+
+fn read_chapter_title(reader: &mut (impl Read + Seek), offset: u64) -> crate::Result<String> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let len = reader.read_be_u16()?;
+    let bom = reader.read_be_u16()?;
+
+    // check BOM (byte order mark) for encoding
+    let title = match bom {
+        0xfeff => reader.read_be_utf16(len as u64 - 2)?,
+        0xfffe => reader.read_le_utf16(len as u64 - 2)?,
+        _ => {
+            reader.skip(-2)?;
+            reader.read_utf8(len as u64)?
+        }
+    };
+
+    Ok(title)
+}
+
+/// The chapter list and chapter track that were written, if any.
+pub(super) type WrittenChapters<'a> = (Option<&'a [Chapter]>, Option<&'a [Chapter]>);
+
+/// Compares the chapters read after writing against the written ones.
+pub(super) fn verify_chapters(
+    cfg: &WriteConfig,
+    tag: &Tag,
+    moov: &Moov<'_>,
+    (chapter_list, chapter_track): WrittenChapters<'_>,
+) -> crate::Result<()> {
+    if let Some(chapter_list) = chapter_list {
+        let timescale = cfg.chpl_timescale.fixed_or_mvhd(moov.mvhd.timescale);
+        let mut expected: Vec<Chapter> = (chapter_list.iter())
+            .map(|c| Chapter {
+                start: scale_duration(timescale, unscale_duration(timescale, c.start)),
+                title: c.title[..chpl::title_len(&c.title)].to_owned(),
+            })
+            .collect();
+        expected.sort_by_key(|c| c.start);
+
+        if tag.userdata.chapter_list != expected {
+            return Err(crate::Error::new(
+                ErrorKind::VerificationFailed,
+                "Chapter list read after writing differs from the written one",
+            ));
+        }
+    }
+
+    if let Some(chapter_track) = chapter_track {
+        let chapter_trak = match cfg.chapter_track_id {
+            Some(id) => moov.trak.iter().find(|t| t.tkhd.id == id),
+            None => find_chapter_trak(&moov.trak),
+        };
+        let mdhd = chapter_trak.and_then(|t| t.mdia.as_ref()).map(|m| &m.mdhd);
+        let (timescale, duration) = match mdhd {
+            Some(mdhd) => (mdhd.timescale, mdhd.duration),
+            None => (moov.mvhd.timescale, moov.mvhd.duration),
+        };
+        let mut time = 0;
+        let expected = chapter_track_durations(chapter_track, timescale, duration)
+            .zip(chapter_track.iter())
+            .map(|(duration, c)| {
+                let start = scale_duration(timescale, time);
+                time += duration as u64;
+                Chapter {
+                    start,
+                    title: c.title[..chapter_title_len(&c.title, cfg.chapter_text_encoding)]
+                        .to_owned(),
+                }
+            });
+
+        let written = chapter_trak
+            .and_then(|trak| tag.chapter_tracks.iter().find(|t| t.track_id == trak.tkhd.id))
+            .map_or(&[][..], |t| &t.chapters);
+        if !written.iter().eq(expected.collect::<Vec<_>>().iter()) {
+            return Err(crate::Error::new(
+                ErrorKind::VerificationFailed,
+                "Chapter track read after writing differs from the written one",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the durations of the chapters in the timescale of the chapter track. The last chapter
+/// lasts until the end of the track.
+fn chapter_track_durations(
+    chapters: &[Chapter],
+    timescale: u32,
+    duration: u64,
+) -> impl Iterator<Item = u32> + '_ {
+    let mut chapters_iter = chapters.iter().peekable();
+    std::iter::from_fn(move || {
+        let c = chapters_iter.next()?;
+        let start = unscale_duration(timescale, c.start);
+        let end = match chapters_iter.peek() {
+            Some(next) => unscale_duration(timescale, next.start),
+            None => duration,
+        };
+        let c_duration = end.saturating_sub(start);
+        Some(c_duration as u32)
+    })
+}
+
+/// Converts the duration of the movie header into the timescale of a track.
+fn media_duration(mvhd: &Mvhd, timescale: u32) -> u64 {
+    if timescale == mvhd.timescale {
+        return mvhd.duration;
+    }
+    unscale_duration(timescale, scale_duration(mvhd.timescale, mvhd.duration))
+}
+
+/// Finds the chapter track referenced by another track.
+fn find_chapter_trak(traks: &[Trak]) -> Option<&Trak> {
+    // https://developer.apple.com/documentation/quicktime-file-format/chapter_lists
+    // > If more than one enabled track includes a 'chap' track reference,
+    // > QuickTime uses the first chapter list that it finds.
+    chapter_traks(traks).into_iter().next()
+}
+
+/// Finds all chapter tracks referenced by other tracks, in the order they're referenced.
+pub(super) fn chapter_traks(traks: &[Trak]) -> Vec<&Trak> {
+    let mut chapter_traks: Vec<&Trak> = Vec::new();
+    for chap in traks.iter().filter_map(|t| t.tref.as_ref().and_then(|tref| tref.chap.as_ref())) {
+        for trak in traks.iter().filter(|t| chap.chapter_ids.contains(&t.tkhd.id)) {
+            if !chapter_traks.iter().any(|t| t.tkhd.id == trak.tkhd.id) {
+                chapter_traks.push(trak);
+            }
+        }
+    }
+    chapter_traks
+}
+
+/// Returns an id for a new track. The next track id of the movie header (`mvhd`) is preferred, if
+/// it's larger than the ids of all existing tracks. Otherwise the id following the largest one is
+/// used, or if that overflows, the smallest unused one.
+fn next_track_id(moov: &Moov<'_>) -> u32 {
+    let max_id = moov.trak.iter().map(|t| t.tkhd.id).max().unwrap_or(0);
+    let next_id = moov.mvhd.next_track_id;
+    if next_id > max_id && next_id != u32::MAX {
+        return next_id;
+    }
+    if let Some(id) = max_id.checked_add(1) {
+        return id;
+    }
+    (1..u32::MAX).find(|id| !moov.trak.iter().any(|t| t.tkhd.id == *id)).unwrap_or(u32::MAX)
+}
+
+/// Returns the length of the longest prefix of the title in bytes, that fits into a chapter track
+/// sample when encoded.
+fn chapter_title_len(title: &str, encoding: TextEncoding) -> usize {
+    match encoding {
+        TextEncoding::Utf8 => floor_char_boundary(title, Chapter::MAX_TITLE_LEN),
+        TextEncoding::Utf16Be => {
+            // the byte order mark
+            let mut encoded_len = 2;
+            for (i, c) in title.char_indices() {
+                encoded_len += 2 * c.len_utf16();
+                if encoded_len > Chapter::MAX_TITLE_LEN {
+                    return i;
+                }
+            }
+            title.len()
+        }
+    }
+}
+
+/// Returns true if the existing chapter list contains exactly the chapters, when it's read with
+/// the detected timescale.
+fn chpl_contains(chpl: &Chpl<'_>, mvhd: &Mvhd, chapters: &[Chapter]) -> bool {
+    let ChplData::Owned(items) = &chpl.data else {
+        return false;
+    };
+    let timescale = ChplTimescale::Auto.resolve(mvhd, items);
+    let mut items = items.iter().collect::<Vec<_>>();
+    items.sort_by_key(|c| c.start);
+    items.len() == chapters.len()
+        && items
+            .iter()
+            .zip(chapters)
+            .all(|(i, c)| scale_duration(timescale, i.start) == c.start && i.title == c.title)
+}
+
+/// Updates the chapter list and chapter track, depending on [`WriteConfig::write_chapters`].
+/// Returns whether the chapter list and chapter track were written.
+pub(super) fn update_chapters<'a>(
+    reader: &mut (impl Read + Seek),
+    changes: &mut Vec<Change<'a>>,
+    moov: &mut Moov<'a>,
+    (mdat_bounds, file_len): (Option<&'a AtomBounds>, u64),
+    userdata: &'a Userdata,
+    cfg: &WriteConfig,
+    mut warnings: Option<&mut Vec<ParseWarning>>,
+) -> crate::Result<WrittenChapters<'a>> {
+    let chapter_trak_ids: Vec<u32> = chapter_traks(&moov.trak).iter().map(|t| t.tkhd.id).collect();
+    let chapter_trak_id = match cfg.chapter_track_id {
+        Some(id) => {
+            let is_trak = moov.trak.iter().any(|t| t.tkhd.id == id);
+            if is_trak && !chapter_trak_ids.contains(&id) {
+                return Err(crate::Error::new(
+                    ErrorKind::InvalidChapterTrack,
+                    format!("Track with id {id} is not a chapter track"),
+                ));
+            }
+            Some(id)
+        }
+        None => chapter_trak_ids.first().copied(),
+    };
+    let chapter_trak_idx =
+        chapter_trak_id.and_then(|id| moov.trak.iter().position(|t| t.tkhd.id == id));
+
+    let has_chapter_list = moov.udta.as_ref().is_some_and(|a| a.chpl.is_some());
+    let (mut write_chapter_list, mut write_chapter_track) =
+        cfg.write_chapters.resolve(has_chapter_list, chapter_trak_idx.is_some());
+    let mut chapter_list = &userdata.chapter_list[..];
+    let mut chapter_track = &userdata.chapter_track[..];
+
+    // remove the representation that isn't written, by writing it without any chapters
+    if cfg.remove_other_chapter_format && write_chapter_list != write_chapter_track {
+        match write_chapter_list {
+            true => chapter_track = &[],
+            false => chapter_list = &[],
+        }
+        (write_chapter_list, write_chapter_track) = (true, true);
+    }
+
+    // the representation that isn't written may now contain other chapters
+    if let Some(w) = &mut warnings {
+        if write_chapter_track
+            && !write_chapter_list
+            && let Some(chpl) = moov.udta.as_ref().and_then(|a| a.chpl.as_ref())
+            && !chpl_contains(chpl, &moov.mvhd, chapter_track)
+        {
+            w.push(ParseWarning::UnchangedChapterList);
+        }
+        if write_chapter_list
+            && !write_chapter_track
+            && let Some(track_id) = chapter_trak_id.filter(|_| chapter_trak_idx.is_some())
+        {
+            w.push(ParseWarning::UnchangedChapterTrack { track_id });
+        }
+    }
+
+    // chapter list
+    if write_chapter_list {
+        match moov.udta.as_mut().and_then(|a| a.chpl.as_mut()) {
+            None if chapter_list.is_empty() => (),
+            Some(chpl) if chapter_list.is_empty() => {
+                chpl.state.remove_existing();
+            }
+            Some(chpl) if cfg.preserve_layout && chpl_contains(chpl, &moov.mvhd, chapter_list) => {}
+            _ => {
+                let chpl_timescale = cfg.chpl_timescale.fixed_or_mvhd(moov.mvhd.timescale);
+                let chpl = moov.udta.get_or_insert_default().chpl.get_or_insert_default();
+                chpl.state.replace_existing();
+                chpl.data = ChplData::Borrowed(chpl_timescale, chapter_list);
+            }
+        }
+    }
+
+    // chapter tracks
+    'chapter_track: {
+        if !write_chapter_track {
+            break 'chapter_track;
+        }
+
+        if chapter_track.is_empty() {
+            let Some(idx) = chapter_trak_idx else {
+                // avoid doing redundant work
+                break 'chapter_track;
+            };
+
+            // remove chapter track
+            let chapter_trak = &mut moov.trak[idx];
+            chapter_trak.state.remove_existing();
+            let removed_id = chapter_trak.tkhd.id;
+
+            // remove all chap track references
+            for trak in moov.trak.iter_mut() {
+                let Some(tref) = &mut trak.tref else {
+                    continue;
+                };
+                let State::Existing(tref_bounds) = &tref.state else {
+                    continue;
+                };
+
+                let Some(chap) = &mut tref.chap else {
+                    continue;
+                };
+                let State::Existing(chap_bounds) = &chap.state else {
+                    continue;
+                };
+
+                if !chap.chapter_ids.contains(&removed_id) {
+                    continue;
+                }
+                if chap.chapter_ids.iter().any(|id| *id != removed_id) {
+                    // keep references to other chapter tracks
+                    chap.chapter_ids.retain(|id| *id != removed_id);
+                    chap.state.replace_existing();
+                } else if tref_bounds.content_len() == chap_bounds.len() {
+                    tref.state.remove_existing();
+                } else {
+                    chap.state.remove_existing();
+                }
+            }
+
+            break 'chapter_track;
+        }
+
+        // generate chapter track sample table
+        let mut new_chapter_media_data = Vec::new();
+        let duration = moov.mvhd.duration;
+        let existing_mdhd =
+            chapter_trak_idx.and_then(|i| moov.trak[i].mdia.as_ref()).map(|m| &m.mdhd);
+        let chapter_timescale = match existing_mdhd {
+            Some(mdhd) => mdhd.timescale,
+            None => cfg.chapter_track_timescale.unwrap_or(moov.mvhd.timescale),
+        };
+        let chapter_duration = media_duration(&moov.mvhd, chapter_timescale);
+        let mut sample_sizes = Vec::with_capacity(chapter_track.len());
+        let mut time_to_samples = Vec::with_capacity(chapter_track.len());
+        let durations = chapter_track_durations(chapter_track, chapter_timescale, chapter_duration);
+        for (c, c_duration) in chapter_track.iter().zip(durations) {
+            time_to_samples.push(SttsItem { sample_count: 1, sample_duration: c_duration });
+
+            const ENCD: [u8; 12] = [
+                0, 0, 0, 12, // size
+                b'e', b'n', b'c', b'd', // fourcc
+                0, 0, 1, 0, // content
+            ];
+            let title_len = chapter_title_len(&c.title, cfg.chapter_text_encoding);
+            if title_len < c.title.len() && !cfg.truncate_chapter_titles {
+                return Err(crate::Error::new(
+                    ErrorKind::ChapterTitleTooLong,
+                    format!(
+                        "Chapter title exceeds the maximum of {} bytes when encoded",
+                        Chapter::MAX_TITLE_LEN,
+                    ),
+                ));
+            }
+            let title = &c.title[..title_len];
+
+            let sample_start = new_chapter_media_data.len();
+            match cfg.chapter_text_encoding {
+                TextEncoding::Utf8 => {
+                    new_chapter_media_data.write_be_u16(title.len() as u16).ok();
+                    new_chapter_media_data.write_utf8(title).ok();
+                }
+                TextEncoding::Utf16Be => {
+                    let encoded_len = 2 + 2 * title.encode_utf16().count();
+                    new_chapter_media_data.write_be_u16(encoded_len as u16).ok();
+                    new_chapter_media_data.write_be_u16(0xfeff).ok();
+                    new_chapter_media_data.write_be_utf16(title).ok();
+                }
+            }
+            new_chapter_media_data.extend(ENCD);
+            sample_sizes.push((new_chapter_media_data.len() - sample_start) as u32);
+        }
+
+        let chapter_trak = match chapter_trak_idx {
+            Some(idx) => &mut moov.trak[idx],
+            None => {
+                let new_id = match chapter_trak_id {
+                    Some(id) => id,
+                    None => next_track_id(moov),
+                };
+
+                // add chap track reference to all other tracks, keep references to other chapter
+                // tracks
+                for trak in moov.trak.iter_mut() {
+                    if chapter_trak_ids.contains(&trak.tkhd.id) {
+                        continue;
+                    }
+                    let tref = trak.tref.get_or_insert_default();
+                    let chap = tref.chap.get_or_insert_default();
+                    chap.state.replace_existing();
+                    if chapter_trak_ids.is_empty() {
+                        chap.chapter_ids = vec![new_id];
+                    } else {
+                        chap.chapter_ids.push(new_id);
+                    }
+                }
+
+                // add chapter track
+                moov.trak.push_and_get(Trak {
+                    state: State::Insert,
+                    tkhd: Tkhd { id: new_id, duration, ..Default::default() },
+                    edts: Some(Edts::full_duration(duration)),
+                    ..Default::default()
+                })
+            }
+        };
+
+        let flags = cfg.chapter_track_flags.to_bytes();
+        if chapter_trak.tkhd.flags != flags {
+            chapter_trak.tkhd.flags = flags;
+            chapter_trak.tkhd.state.replace_existing();
+        }
+        // the chapter track should span the whole movie
+        if chapter_trak.tkhd.duration != duration {
+            chapter_trak.tkhd.duration = duration;
+            chapter_trak.tkhd.state.replace_existing();
+        }
+
+        let track_id = chapter_trak.tkhd.id;
+        let mdia = chapter_trak.mdia.get_or_insert_with(|| Mdia {
+            state: State::Insert,
+            mdhd: Mdhd {
+                timescale: chapter_timescale,
+                duration: chapter_duration,
+                language: mdhd::pack_language(cfg.chapter_track_language),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        if mdia.mdhd.duration != chapter_duration {
+            mdia.mdhd.duration = chapter_duration;
+            mdia.mdhd.state.replace_existing();
+        }
+
+        mdia.hdlr.get_or_insert_with(Hdlr::text_mdia);
+        let minf = mdia.minf.get_or_insert_default();
+
+        let gmhd = minf.gmhd.get_or_insert_default();
+        gmhd.gmin.get_or_insert_with(Gmin::chapter);
+        gmhd.text.get_or_insert_with(Text::media_information_chapter);
+
+        let dinf = minf.dinf.get_or_insert_default();
+        let dref = dinf.dref.get_or_insert_default();
+        dref.url.get_or_insert_with(Url::track);
+
+        // append the chapter titles to the media data atom, or create a new one at the end of the
+        // file, if there is none
+        let new_mdat_size = Size::from(new_chapter_media_data.len() as u64);
+        let chunk_offsets = match mdat_bounds {
+            Some(bounds) => vec![bounds.end()],
+            None => vec![file_len + new_mdat_size.head_len()],
+        };
+
+        let stbl = minf.stbl.get_or_insert_default();
+        let stsd = stbl.stsd.get_or_insert_default();
+        match &cfg.chapter_text_description {
+            Some(description) => {
+                let text = Text::media_chapter(description);
+                if stsd.text.as_ref().is_none_or(|t| t.data != text.data) {
+                    stsd.state.replace_existing();
+                    stsd.text = Some(text);
+                }
+            }
+            // preserve the existing description
+            None => {
+                stsd.text
+                    .get_or_insert_with(|| Text::media_chapter(&TextSampleDescription::DEFAULT));
+            }
+        }
+
+        let stts = stbl.stts.get_or_insert_default();
+        stts.state.replace_existing();
+        stts.items = Table::Full(time_to_samples);
+
+        // overwrite the samples in place if their sizes didn't change, instead of moving them to
+        // the end of the media data
+        if let Some(chunks) = in_place_chunks(reader, stbl, &sample_sizes, mdat_bounds)? {
+            let mut data = &new_chapter_media_data[..];
+            for (offset, size) in chunks {
+                let (chunk, rest) = data.split_at(size as usize);
+                changes.push(Change::Overwrite(offset, chunk.to_vec()));
+                data = rest;
+            }
+            break 'chapter_track;
+        }
+
+        // the sample tables are rewritten, so atoms that describe the previous samples, such as
+        // sync samples (`stss`) or sample groups (`sgpd`, `sbgp`), would become inconsistent
+        for other in stbl.other.iter_mut().filter(|a| a.state.is_existing()) {
+            other.state.remove_existing();
+            if let Some(w) = &mut warnings {
+                w.push(ParseWarning::RemovedSampleTableAtom { track_id, fourcc: other.fourcc });
+            }
+        }
+
+        let stsc = stbl.stsc.get_or_insert_default();
+        stsc.state.replace_existing();
+        let prev_stsc = std::mem::replace(
+            &mut stsc.items,
+            Table::Full(vec![StscItem {
+                first_chunk: 1,
+                samples_per_chunk: sample_sizes.len() as u32,
+                sample_description_id: 1,
+            }]),
+        );
+
+        let stsz = stbl.stsz.get_or_insert_default();
+        stsz.state.replace_existing();
+        let prev_stsz_uniform_sample_size = std::mem::replace(&mut stsz.uniform_sample_size, 0);
+        let prev_stsz_sizes = std::mem::replace(&mut stsz.sizes, Table::Full(sample_sizes));
+
+        let prev_stsc = prev_stsc.get_or_read(reader)?;
+        let prev_stsz_sizes = prev_stsz_sizes.get_or_read(reader)?;
+
+        let prev_stco = stbl.stco.as_mut().map(|stco| {
+            stco.state.remove_existing();
+            std::mem::take(&mut stco.offsets)
+        });
+
+        let co64 = stbl.co64.get_or_insert_default();
+        co64.state.replace_existing();
+        let prev_co64 = std::mem::replace(&mut co64.offsets, Table::Full(chunk_offsets));
+
+        // remove previous chapter data from the mdat atom
+        if co64.state.has_existed() {
+            let prev_co64 = prev_co64.get_or_read(reader)?;
+            remove_chapter_media_data(
+                changes,
+                &prev_co64,
+                &prev_stsc,
+                prev_stsz_uniform_sample_size,
+                &prev_stsz_sizes,
+            )?;
+        } else if let Some(prev_stco) = prev_stco {
+            let prev_stco = prev_stco.get_or_read(reader)?;
+            remove_chapter_media_data(
+                changes,
+                &prev_stco,
+                &prev_stsc,
+                prev_stsz_uniform_sample_size,
+                &prev_stsz_sizes,
+            )?;
+        }
+
+        match mdat_bounds {
+            Some(bounds) => {
+                if !new_chapter_media_data.is_empty() {
+                    changes.push(Change::AppendMdat(bounds.end(), new_chapter_media_data));
+                }
+
+                let len_diff = changes.iter().map(|c| c.len_diff()).sum();
+                if len_diff != 0 {
+                    changes.push(Change::UpdateLen(UpdateAtomLen {
+                        bounds,
+                        fourcc: MEDIA_DATA,
+                        len_diff,
+                    }));
+                }
+            }
+            None => {
+                let head = Head::new(new_mdat_size.ext(), new_mdat_size.len(), MEDIA_DATA);
+                let mut mdat = Vec::with_capacity(new_mdat_size.len() as usize);
+                head::write(&mut mdat, head)?;
+                mdat.extend(new_chapter_media_data);
+                changes.push(Change::AppendMdat(file_len, mdat));
+            }
+        }
+    }
+
+    Ok((write_chapter_list.then_some(chapter_list), write_chapter_track.then_some(chapter_track)))
+}
+
+/// Returns the position and size of the chunks of the existing chapter track samples, if they
+/// have the same sizes as the new samples and lie inside the media data.
+fn in_place_chunks(
+    reader: &mut (impl Read + Seek),
+    stbl: &Stbl,
+    sample_sizes: &[u32],
+    mdat_bounds: Option<&AtomBounds>,
+) -> crate::Result<Option<Vec<(u64, u64)>>> {
+    let (Some(mdat_bounds), Some(stsc), Some(stsz)) = (mdat_bounds, &stbl.stsc, &stbl.stsz) else {
+        return Ok(None);
+    };
+    if !stsc.state.is_existing() || !stsz.state.is_existing() {
+        return Ok(None);
+    }
+
+    let uniform_size = stsz.uniform_sample_size;
+    let prev_sizes = stsz.sizes.get_or_read(reader)?;
+    let same_sizes = if uniform_size != 0 {
+        stsz.uniform_sample_count as usize == sample_sizes.len()
+            && sample_sizes.iter().all(|s| *s == uniform_size)
+    } else {
+        *prev_sizes == *sample_sizes
+    };
+    if !same_sizes {
+        return Ok(None);
+    }
+
+    let stsc = stsc.items.get_or_read(reader)?;
+    let mut chunks = Vec::new();
+    let push_chunk = |offset, size| chunks.push((offset, size));
+    match (&stbl.co64, &stbl.stco) {
+        (Some(co64), _) if co64.state.is_existing() => {
+            let offsets = co64.offsets.get_or_read(reader)?;
+            stbl::for_each_chunk(&offsets, &stsc, uniform_size, &prev_sizes, push_chunk)?;
+        }
+        (None, Some(stco)) if stco.state.is_existing() => {
+            let offsets = stco.offsets.get_or_read(reader)?;
+            stbl::for_each_chunk(&offsets, &stsc, uniform_size, &prev_sizes, push_chunk)?;
+        }
+        _ => return Ok(None),
+    }
+
+    let total_size: u64 = chunks.iter().map(|(_, size)| size).sum();
+    let new_size: u64 = sample_sizes.iter().map(|&s| s as u64).sum();
+    let in_mdat = chunks.iter().all(|&(offset, size)| {
+        offset >= mdat_bounds.content_pos() && offset + size <= mdat_bounds.end()
+    });
+    if total_size != new_size || !in_mdat {
+        return Ok(None);
+    }
+    Ok(Some(chunks))
+}
+
+fn remove_chapter_media_data<T: ChunkOffsetInt>(
+    changes: &mut Vec<Change<'_>>,
+    offsets: &[T],
+    stsc: &[StscItem],
+    stsz_uniform_size: u32,
+    stsz_sizes: &[u32],
+) -> crate::Result<()> {
+    stbl::for_each_chunk(offsets, stsc, stsz_uniform_size, stsz_sizes, |offset, size| {
+        changes.push(Change::RemoveMdat(offset, size));
+    })
+}
@@ -1,7 +1,5 @@
 use super::*;
 
-pub const DEFAULT_TIMESCALE: NonZeroU32 = NonZeroU32::new(10_000_000).unwrap();
-
 pub const HEADER_SIZE_V0: u64 = 5;
 pub const HEADER_SIZE_V1: u64 = 9;
 pub const ITEM_HEADER_SIZE: u64 = 9;
@@ -37,7 +35,7 @@ impl Atom for Chpl<'_> {
 impl ParseAtom for Chpl<'_> {
     fn parse_atom(
         reader: &mut (impl Read + Seek),
-        _cfg: &ParseConfig<'_>,
+        cfg: &ParseConfig<'_>,
         size: Size,
     ) -> crate::Result<Self> {
         let bounds = find_bounds(reader, size)?;
@@ -56,9 +54,14 @@ impl ParseAtom for Chpl<'_> {
         expect_min_size("Chapter list (chpl)", size, header_size)?;
 
         let num_entries = reader.read_u8()?;
+        if num_entries as usize > cfg.cfg.max_chapter_count {
+            return Err(crate::Error::new(
+                crate::ErrorKind::TooManyChapters,
+                "Chapter list (chpl) exceeds the maximum chapter count",
+            ));
+        }
         let table_size = size.content_len() - header_size;
-        let mut buf = vec![0; table_size as usize];
-        reader.read_exact(&mut buf)?;
+        let buf = reader.read_u8_vec(table_size)?;
 
         let mut cursor = std::io::Cursor::new(buf);
 
@@ -146,6 +149,6 @@ impl Chpl<'_> {
     }
 }
 
-fn title_len(title: &str) -> usize {
-    title.len().min(u8::MAX as usize)
+pub fn title_len(title: &str) -> usize {
+    floor_char_boundary(title, u8::MAX as usize)
 }
@@ -19,6 +19,8 @@ const UTF8_SORT: u32 = 4;
 /// UTF-16 variant storage of a string for sorting only.
 #[allow(unused)]
 const UTF16_SORT: u32 = 5;
+/// GIF image.
+const GIF: u32 = 12;
 /// JPEG in a JFIF wrapper.
 const JPEG: u32 = 13;
 /// PNG in a PNG wrapper.
@@ -87,6 +89,9 @@ const BE_U64: u32 = 78;
 #[allow(unused)]
 const AFFINE_TRANSFORM_F64: u32 = 79;
 
+/// The type codes that are parsed into a [`Data`] variant other than [`Data::Unknown`].
+pub const KNOWN_TYPES: [u32; 8] = [RESERVED, UTF8, UTF16, GIF, JPEG, PNG, BE_SIGNED, BMP];
+
 /// Different types of data defined by [Table 3-5 Well-known data types](https://developer.apple.com/documentation/quicktime-file-format/well-known_types).
 #[derive(Clone, PartialEq, Eq)]
 pub enum Data {
@@ -95,7 +100,8 @@ pub enum Data {
     Reserved(Vec<u8>),
     /// A utf-8 encoded string.
     Utf8(String),
-    /// A utf-16 encoded string.
+    /// A utf-16 encoded string. It's written as big endian with a byte order mark, and read as
+    /// either big or little endian depending on the byte order mark.
     Utf16(String),
     /// A JPEG image. Note that this type is more of a hint and many encoders use any of the image
     /// formats for all kinds of image data.
@@ -106,6 +112,22 @@ pub enum Data {
     /// A BMP image. Note that this type is more of a hint and many encoders use any of the image
     /// formats for all kinds of image data.
     Bmp(Vec<u8>),
+    /// A GIF image. Note that this type is more of a hint and many encoders use any of the image
+    /// formats for all kinds of image data.
+    Gif(Vec<u8>),
+    /// An image stored with a data type code other than the well-known image types, for example a
+    /// HEIC or WebP image. The format is detected from the data, if it's stored as [`Reserved`] or
+    /// an unknown type code.
+    ///
+    /// [`Reserved`]: Data::Reserved
+    Image {
+        /// The data type code.
+        code: u32,
+        /// The detected image format.
+        fmt: ImgFmt,
+        /// The image data.
+        data: Vec<u8>,
+    },
     /// A big-endian signed integer.
     /// This is often used for track/disc numbers and standard genre codes.
     BeSigned(Vec<u8>),
@@ -116,6 +138,17 @@ pub enum Data {
         /// The data.
         data: Vec<u8>,
     },
+    /// The content of a data atom larger than
+    /// [`ReadConfig::shallow_data_size`](crate::ReadConfig::shallow_data_size), which wasn't read.
+    /// It can be read on demand using [`Data::get_or_read`].
+    Shallow {
+        /// The data type code.
+        code: u32,
+        /// The position of the content in the file.
+        pos: u64,
+        /// The length of the content.
+        len: u64,
+    },
 }
 
 impl fmt::Debug for Data {
@@ -128,9 +161,19 @@ impl fmt::Debug for Data {
             Self::Png(_) => write!(f, "Data::Png"),
             Self::BeSigned(d) => write!(f, "Data::BeSigned({d:?})"),
             Self::Bmp(_) => write!(f, "Data::Bmp"),
+            Self::Gif(_) => write!(f, "Data::Gif"),
+            Self::Image { code, fmt, .. } => {
+                f.debug_struct("Data::Image").field("code", code).field("fmt", fmt).finish()
+            }
             Self::Unknown { code, data } => {
                 f.debug_struct("Data::Unknown").field("code", code).field("data", data).finish()
             }
+            Self::Shallow { code, pos, len } => f
+                .debug_struct("Data::Shallow")
+                .field("code", code)
+                .field("pos", pos)
+                .field("len", len)
+                .finish(),
         }
     }
 }
@@ -141,10 +184,30 @@ impl<T: Into<Vec<u8>>> From<Img<T>> for Data {
             ImgFmt::Bmp => Self::Bmp(image.data.into()),
             ImgFmt::Jpeg => Self::Jpeg(image.data.into()),
             ImgFmt::Png => Self::Png(image.data.into()),
+            ImgFmt::Gif => Self::Gif(image.data.into()),
+            ImgFmt::Heic | ImgFmt::WebP => Self::Image {
+                code: RESERVED,
+                fmt: image.fmt,
+                data: image.data.into(),
+            },
         }
     }
 }
 
+impl From<String> for Data {
+    /// Creates a [`Data::Utf8`] string.
+    fn from(string: String) -> Self {
+        Self::Utf8(string)
+    }
+}
+
+impl From<&str> for Data {
+    /// Creates a [`Data::Utf8`] string.
+    fn from(string: &str) -> Self {
+        Self::Utf8(string.to_owned())
+    }
+}
+
 impl Atom for Data {
     const FOURCC: Fourcc = DATA;
 }
@@ -171,19 +234,70 @@ impl Data {
         expect_min_size("Data (data)", size, HEADER_SIZE)?;
 
         let len = size.content_len() - HEADER_SIZE;
-        Ok(match datatype {
-            RESERVED => Data::Reserved(reader.read_u8_vec(len)?),
-            UTF8 => Data::Utf8(reader.read_utf8(len)?),
-            UTF16 => Data::Utf16(reader.read_be_utf16(len)?),
-            JPEG => Data::Jpeg(read_image(reader, cfg.cfg.read_image_data, len)?),
-            PNG => Data::Png(read_image(reader, cfg.cfg.read_image_data, len)?),
-            BE_SIGNED => Data::BeSigned(reader.read_u8_vec(len)?),
-            BMP => Data::Bmp(read_image(reader, cfg.cfg.read_image_data, len)?),
-            _ => {
-                // TODO: maybe log warning (optional log dependency behind feature flag)
-                Data::Unknown { code: datatype, data: reader.read_u8_vec(len)? }
+        if cfg.warnings_enabled() {
+            let offset = reader.stream_position()? - HEADER_SIZE - size.head_len();
+            if len == 0 {
+                cfg.warn(ParseWarning::EmptyData { offset });
+            }
+            if !KNOWN_TYPES.contains(&datatype) {
+                cfg.warn(ParseWarning::UnknownDataType { code: datatype, offset });
+            }
+        }
+
+        let skipped = matches!(datatype, GIF | JPEG | PNG | BMP) && !cfg.cfg.read_image_data;
+        if len > cfg.cfg.shallow_data_size && !skipped {
+            let pos = reader.stream_position()?;
+            reader.skip(len as i64)?;
+            return Ok(Data::Shallow { code: datatype, pos, len });
+        }
+        if len > cfg.cfg.max_data_size && !skipped {
+            return Err(crate::Error::new(
+                crate::ErrorKind::DataTooLarge,
+                "Data atom (data) exceeds the maximum data size",
+            )
+            .with_sizes(cfg.cfg.max_data_size, len));
+        }
+
+        read_content(reader, datatype, len, cfg.cfg.read_image_data)
+    }
+
+    /// Returns the data, reading the content of [`Data::Shallow`] from the reader, which has to
+    /// be the file the data was read from.
+    pub fn get_or_read(&self, reader: &mut (impl Read + Seek)) -> crate::Result<Cow<'_, Data>> {
+        let &Self::Shallow { code, pos, len } = self else {
+            return Ok(Cow::Borrowed(self));
+        };
+
+        // make sure the data atom is still at the same position, its head may have an extended size
+        let mut matches = false;
+        for head_len in [Head::NORMAL_SIZE, Head::EXT_SIZE] {
+            let Some(head_pos) = pos.checked_sub(head_len + HEADER_SIZE) else {
+                continue;
+            };
+            reader.seek(SeekFrom::Start(head_pos))?;
+            let Ok(head) = head::parse(reader, u64::MAX) else {
+                continue;
+            };
+            if head.fourcc() != DATA
+                || head.head_len() != head_len
+                || head.content_len() != HEADER_SIZE + len
+            {
+                continue;
             }
-        })
+            let mut buf = [0; 4];
+            reader.read_exact(&mut buf)?;
+            matches = buf == code.to_be_bytes();
+            break;
+        }
+        if !matches {
+            return Err(crate::Error::new(
+                crate::ErrorKind::InvalidShallowData,
+                "Shallow data doesn't match the data atom (data) at its position",
+            ));
+        }
+        reader.seek(SeekFrom::Start(pos))?;
+
+        Ok(Cow::Owned(read_content(reader, code, len, true)?))
     }
 
     pub fn write(&self, writer: &mut impl Write) -> crate::Result<()> {
@@ -197,7 +311,15 @@ impl Data {
             Self::Png(_) => PNG,
             Self::BeSigned(_) => BE_SIGNED,
             Self::Bmp(_) => BMP,
+            Self::Gif(_) => GIF,
+            Self::Image { code, .. } => *code,
             Self::Unknown { code, .. } => *code,
+            Self::Shallow { .. } => {
+                return Err(crate::Error::new(
+                    crate::ErrorKind::InvalidShallowData,
+                    "Shallow data has to be read before it can be written",
+                ));
+            }
         };
 
         writer.write_all(&datatype.to_be_bytes())?;
@@ -205,12 +327,18 @@ impl Data {
         match self {
             Self::Reserved(v) => writer.write_all(v)?,
             Self::Utf8(s) => writer.write_utf8(s)?,
-            Self::Utf16(s) => writer.write_be_utf16(s)?,
+            Self::Utf16(s) => {
+                writer.write_be_u16(0xfeff)?;
+                writer.write_be_utf16(s)?;
+            }
             Self::Jpeg(v) => writer.write_all(v)?,
             Self::Png(v) => writer.write_all(v)?,
             Self::BeSigned(v) => writer.write_all(v)?,
             Self::Bmp(v) => writer.write_all(v)?,
+            Self::Gif(v) => writer.write_all(v)?,
+            Self::Image { data, .. } => writer.write_all(data)?,
             Self::Unknown { data, .. } => writer.write_all(data)?,
+            Self::Shallow { .. } => (),
         }
 
         Ok(())
@@ -227,12 +355,15 @@ impl Data {
         (match self {
             Self::Reserved(v) => v.len(),
             Self::Utf8(s) => s.len(),
-            Self::Utf16(s) => 2 * s.encode_utf16().count(),
+            Self::Utf16(s) => 2 + 2 * s.encode_utf16().count(),
             Self::Jpeg(v) => v.len(),
             Self::Png(v) => v.len(),
             Self::BeSigned(v) => v.len(),
             Self::Bmp(v) => v.len(),
+            Self::Gif(v) => v.len(),
+            Self::Image { data, .. } => data.len(),
             Self::Unknown { data, .. } => data.len(),
+            Self::Shallow { len, .. } => return *len,
         }) as u64
     }
 
@@ -257,13 +388,26 @@ impl Data {
         matches!(self, Self::Utf8(_) | Self::Utf16(_))
     }
 
-    /// Returns true if the data is of type [`Jpeg`], [`Png`] or [`Bmp`].
+    /// Returns true if the data is of type [`Jpeg`], [`Png`], [`Bmp`], [`Gif`] or
+    /// [`Image`].
     ///
     /// [`Jpeg`]: Data::Jpeg
     /// [`Png`]: Data::Png
     /// [`Bmp`]: Data::Bmp
+    /// [`Gif`]: Data::Gif
+    /// [`Image`]: Data::Image
     pub const fn is_image(&self) -> bool {
-        matches!(self, Self::Jpeg(_) | Self::Png(_) | Self::Bmp(_))
+        matches!(
+            self,
+            Self::Jpeg(_) | Self::Png(_) | Self::Bmp(_) | Self::Gif(_) | Self::Image { .. }
+        )
+    }
+
+    /// Returns true if the data is of type [`Shallow`], and its content hasn't been read.
+    ///
+    /// [`Shallow`]: Data::Shallow
+    pub const fn is_shallow(&self) -> bool {
+        matches!(self, Self::Shallow { .. })
     }
 
     /// Returns true if the data is of type [`Reserved`].
@@ -308,6 +452,13 @@ impl Data {
         matches!(self, Self::Bmp(_))
     }
 
+    /// Returns true if the data is of type [`Gif`].
+    ///
+    /// [`Gif`]: Data::Gif
+    pub const fn is_gif(&self) -> bool {
+        matches!(self, Self::Gif(_))
+    }
+
     /// Returns true if the data is of type [`BeSigned`].
     ///
     /// [`BeSigned`]: Data::BeSigned
@@ -363,6 +514,26 @@ impl Data {
         }
     }
 
+    /// Interprets the data as a boolean flag. Besides the integer types of any width, which are
+    /// true if they are non-zero, some taggers write flags as strings like `"1"` or `"true"`.
+    pub(crate) fn flag(&self) -> Option<bool> {
+        match self {
+            Self::Reserved(v) | Self::BeSigned(v) => Some(v.iter().any(|&b| b != 0)),
+            Self::Unknown {
+                code: BE_UNSIGNED | I8 | BE_I16 | BE_I32 | BE_I64 | U8 | BE_U16 | BE_U32 | BE_U64,
+                data,
+            } => Some(data.iter().any(|&b| b != 0)),
+            Self::Utf8(s) | Self::Utf16(s) => match s.trim() {
+                "1" => Some(true),
+                "0" => Some(false),
+                s if s.eq_ignore_ascii_case("true") || s.eq_ignore_ascii_case("yes") => Some(true),
+                s if s.eq_ignore_ascii_case("false") || s.eq_ignore_ascii_case("no") => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Returns a mutable string reference if the data is of type [`Utf8`] or [`Utf16`].
     ///
     /// [`Utf8`]: Data::Utf8
@@ -387,71 +558,95 @@ impl Data {
         }
     }
 
-    /// Returns an image reference the data is of type [`Jpeg`], [`Png`] or [`Bmp`].
+    /// Returns an image reference the data is of type [`Jpeg`], [`Png`], [`Bmp`], [`Gif`] or
+    /// [`Image`].
     ///
     /// [`Jpeg`]: Data::Jpeg
     /// [`Png`]: Data::Png
     /// [`Bmp`]: Data::Bmp
+    /// [`Gif`]: Data::Gif
+    /// [`Image`]: Data::Image
     pub fn image(&self) -> Option<ImgRef<'_>> {
         match self {
             Self::Jpeg(v) => Some(Img::new(ImgFmt::Jpeg, v)),
             Self::Png(v) => Some(Img::new(ImgFmt::Png, v)),
             Self::Bmp(v) => Some(Img::new(ImgFmt::Bmp, v)),
+            Self::Gif(v) => Some(Img::new(ImgFmt::Gif, v)),
+            Self::Image { fmt, data, .. } => Some(Img::new(*fmt, data)),
             _ => None,
         }
     }
 
-    /// Returns a mutable image reference if the data is of type [`Jpeg`], [`Png`] or [`Bmp`].
+    /// Returns a mutable image reference if the data is of type [`Jpeg`], [`Png`], [`Bmp`],
+    /// [`Gif`] or [`Image`].
     ///
     /// [`Jpeg`]: Data::Jpeg
     /// [`Png`]: Data::Png
     /// [`Bmp`]: Data::Bmp
+    /// [`Gif`]: Data::Gif
+    /// [`Image`]: Data::Image
     pub fn image_mut(&mut self) -> Option<ImgMut<'_>> {
         match self {
             Self::Jpeg(v) => Some(Img::new(ImgFmt::Jpeg, v)),
             Self::Png(v) => Some(Img::new(ImgFmt::Png, v)),
             Self::Bmp(v) => Some(Img::new(ImgFmt::Bmp, v)),
+            Self::Gif(v) => Some(Img::new(ImgFmt::Gif, v)),
+            Self::Image { fmt, data, .. } => Some(Img::new(*fmt, data)),
             _ => None,
         }
     }
 
-    /// Returns the owned image if the data is of type [`Jpeg`], [`Png`] or [`Bmp`].
+    /// Returns the owned image if the data is of type [`Jpeg`], [`Png`], [`Bmp`], [`Gif`] or
+    /// [`Image`].
     ///
     /// [`Jpeg`]: Data::Jpeg
     /// [`Png`]: Data::Png
     /// [`Bmp`]: Data::Bmp
+    /// [`Gif`]: Data::Gif
+    /// [`Image`]: Data::Image
     pub fn into_image(self) -> Option<ImgBuf> {
         match self {
             Self::Jpeg(v) => Some(Img::new(ImgFmt::Jpeg, v)),
             Self::Png(v) => Some(Img::new(ImgFmt::Png, v)),
             Self::Bmp(v) => Some(Img::new(ImgFmt::Bmp, v)),
+            Self::Gif(v) => Some(Img::new(ImgFmt::Gif, v)),
+            Self::Image { fmt, data, .. } => Some(Img::new(fmt, data)),
             _ => None,
         }
     }
 
-    /// Returns an image data reference if the data is of type [`Jpeg`], [`Png`] or [`Bmp`].
+    /// Returns an image data reference if the data is of type [`Jpeg`], [`Png`], [`Bmp`],
+    /// [`Gif`] or [`Image`].
     ///
     /// [`Jpeg`]: Data::Jpeg
     /// [`Png`]: Data::Png
     /// [`Bmp`]: Data::Bmp
+    /// [`Gif`]: Data::Gif
+    /// [`Image`]: Data::Image
     pub fn image_data(&self) -> Option<&[u8]> {
         self.image().map(|i| i.data)
     }
 
-    /// Returns a mutable image data reference if the data is of type [`Jpeg`], [`Png`] or [`Bmp`].
+    /// Returns a mutable image data reference if the data is of type [`Jpeg`], [`Png`], [`Bmp`],
+    /// [`Gif`] or [`Image`].
     ///
     /// [`Jpeg`]: Data::Jpeg
     /// [`Png`]: Data::Png
     /// [`Bmp`]: Data::Bmp
+    /// [`Gif`]: Data::Gif
+    /// [`Image`]: Data::Image
     pub fn image_data_mut(&mut self) -> Option<&mut Vec<u8>> {
         self.image_mut().map(|i| i.data)
     }
 
-    /// Returns the owned image data if the data is of type [`Jpeg`], [`Png`] or [`Bmp`].
+    /// Returns the owned image data if the data is of type [`Jpeg`], [`Png`], [`Bmp`], [`Gif`] or
+    /// [`Image`].
     ///
     /// [`Jpeg`]: Data::Jpeg
     /// [`Png`]: Data::Png
     /// [`Bmp`]: Data::Bmp
+    /// [`Gif`]: Data::Gif
+    /// [`Image`]: Data::Image
     pub fn into_image_data(self) -> Option<Vec<u8>> {
         self.into_image().map(|i| i.data)
     }
@@ -516,6 +711,16 @@ impl Data {
         }
     }
 
+    /// Returns an image data reference if the data is of type [`Gif`].
+    ///
+    /// [`Gif`]: Data::Gif
+    pub fn gif(&self) -> Option<&[u8]> {
+        match self {
+            Self::Gif(v) => Some(v),
+            _ => None,
+        }
+    }
+
     /// Returns a byte reference if the data is of type [`BeSigned`].
     ///
     /// [`BeSigned`]: Data::BeSigned
@@ -527,6 +732,144 @@ impl Data {
     }
 }
 
+/// ### Integers
+impl Data {
+    /// Creates a [`Data::BeSigned`] integer of 1 byte.
+    pub fn be_u8(value: u8) -> Self {
+        Self::BeSigned(vec![value])
+    }
+
+    /// Creates a [`Data::BeSigned`] integer of 2 bytes.
+    pub fn be_u16(value: u16) -> Self {
+        Self::BeSigned(value.to_be_bytes().to_vec())
+    }
+
+    /// Creates a [`Data::BeSigned`] integer of 4 bytes.
+    pub fn be_u32(value: u32) -> Self {
+        Self::BeSigned(value.to_be_bytes().to_vec())
+    }
+
+    /// Creates a [`Data::BeSigned`] integer of 8 bytes.
+    pub fn be_u64(value: u64) -> Self {
+        Self::BeSigned(value.to_be_bytes().to_vec())
+    }
+
+    /// Interprets the data as a big endian unsigned integer of 1 to 8 bytes, if it's of type
+    /// [`Reserved`], [`BeSigned`] or an integer type code.
+    ///
+    /// [`Reserved`]: Data::Reserved
+    /// [`BeSigned`]: Data::BeSigned
+    fn be_uint(&self) -> Option<u64> {
+        let bytes = match self {
+            Self::Reserved(v) | Self::BeSigned(v) => v,
+            Self::Unknown {
+                code: BE_UNSIGNED | I8 | BE_I16 | BE_I32 | BE_I64 | U8 | BE_U16 | BE_U32 | BE_U64,
+                data,
+            } => data,
+            _ => return None,
+        };
+        match bytes.len() {
+            1..=8 => Some(bytes.iter().fold(0, |n, &b| (n << 8) | b as u64)),
+            _ => None,
+        }
+    }
+}
+
+fn type_mismatch(data: &Data, ty: &str) -> crate::Error {
+    crate::Error::new(ErrorKind::DataTypeMismatch, format!("{data:?} can't be converted to {ty}"))
+}
+
+macro_rules! impl_try_from_data_for_uint {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<&Data> for $ty {
+                type Error = crate::Error;
+
+                /// Converts a big endian integer of 1 to 8 bytes, if its value fits.
+                fn try_from(data: &Data) -> Result<Self, Self::Error> {
+                    (data.be_uint())
+                        .and_then(|v| <$ty>::try_from(v).ok())
+                        .ok_or_else(|| type_mismatch(data, stringify!($ty)))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_data_for_uint!(u8, u16, u32, u64);
+
+impl<'a> TryFrom<&'a Data> for &'a str {
+    type Error = crate::Error;
+
+    /// Converts a [`Data::Utf8`] or [`Data::Utf16`] string.
+    fn try_from(data: &'a Data) -> Result<Self, Self::Error> {
+        data.string().ok_or_else(|| type_mismatch(data, "a string"))
+    }
+}
+
+impl TryFrom<&Data> for String {
+    type Error = crate::Error;
+
+    /// Converts a [`Data::Utf8`] or [`Data::Utf16`] string.
+    fn try_from(data: &Data) -> Result<Self, Self::Error> {
+        <&str>::try_from(data).map(str::to_owned)
+    }
+}
+
+impl TryFrom<Data> for String {
+    type Error = crate::Error;
+
+    /// Converts a [`Data::Utf8`] or [`Data::Utf16`] string.
+    fn try_from(data: Data) -> Result<Self, Self::Error> {
+        match data {
+            Data::Utf8(s) | Data::Utf16(s) => Ok(s),
+            _ => Err(type_mismatch(&data, "a string")),
+        }
+    }
+}
+
+/// Reads the content of a data atom based on the data type code.
+fn read_content(
+    reader: &mut (impl Read + Seek),
+    datatype: u32,
+    len: u64,
+    read_image_data: bool,
+) -> crate::Result<Data> {
+    Ok(match datatype {
+        RESERVED => {
+            let data = reader.read_u8_vec(len)?;
+            image_or(datatype, data, read_image_data, Data::Reserved)
+        }
+        UTF8 => Data::Utf8(reader.read_utf8(len)?),
+        UTF16 => Data::Utf16(reader.read_utf16(len)?),
+        JPEG => Data::Jpeg(read_image(reader, read_image_data, len)?),
+        PNG => Data::Png(read_image(reader, read_image_data, len)?),
+        BE_SIGNED => Data::BeSigned(reader.read_u8_vec(len)?),
+        BMP => Data::Bmp(read_image(reader, read_image_data, len)?),
+        GIF => Data::Gif(read_image(reader, read_image_data, len)?),
+        _ => {
+            let data = reader.read_u8_vec(len)?;
+            image_or(datatype, data, read_image_data, |data| Data::Unknown { code: datatype, data })
+        }
+    })
+}
+
+/// Detects images without a well-known data type code, like HEIC or WebP images.
+fn image_or(
+    code: u32,
+    data: Vec<u8>,
+    read_image_data: bool,
+    other: impl FnOnce(Vec<u8>) -> Data,
+) -> Data {
+    match ImgFmt::detect(&data) {
+        Some(fmt @ (ImgFmt::Heic | ImgFmt::WebP)) => {
+            let data = if read_image_data { data } else { Vec::new() };
+            Data::Image { code, fmt, data }
+        }
+        _ => other(data),
+    }
+}
+
 fn read_image(reader: &mut (impl Read + Seek), parse: bool, len: u64) -> crate::Result<Vec<u8>> {
     if parse {
         Ok(reader.read_u8_vec(len)?)
@@ -0,0 +1,95 @@
+use super::*;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Edts {
+    pub state: State,
+    pub elst: Option<Elst>,
+}
+
+impl Atom for Edts {
+    const FOURCC: Fourcc = EDIT;
+}
+
+impl ParseAtom for Edts {
+    fn parse_atom(
+        reader: &mut (impl Read + Seek),
+        cfg: &ParseConfig<'_>,
+        size: Size,
+    ) -> crate::Result<Self> {
+        let bounds = find_bounds(reader, size)?;
+        let mut edts = Self {
+            state: State::Existing(bounds),
+            ..Default::default()
+        };
+        let mut parsed_bytes = 0;
+
+        while parsed_bytes < size.content_len() {
+            let remaining_bytes = size.content_len() - parsed_bytes;
+            let head = head::parse(reader, remaining_bytes)?;
+
+            match head.fourcc() {
+                EDIT_LIST => edts.elst = Some(Elst::parse(reader, cfg, head.size())?),
+                _ => reader.skip(head.content_len() as i64)?,
+            }
+
+            parsed_bytes += head.len();
+        }
+
+        Ok(edts)
+    }
+}
+
+impl AtomSize for Edts {
+    fn size(&self) -> Size {
+        let content_len = self.elst.len_or_zero();
+        Size::from(content_len)
+    }
+}
+
+impl WriteAtom for Edts {
+    fn write_atom(&self, writer: &mut impl Write, changes: &[Change<'_>]) -> crate::Result<()> {
+        self.write_head(writer)?;
+        if let Some(a) = &self.elst {
+            a.write(writer, changes)?;
+        }
+        Ok(())
+    }
+}
+
+impl SimpleCollectChanges for Edts {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn existing<'a>(
+        &'a self,
+        level: u8,
+        bounds: &'a AtomBounds,
+        changes: &mut Vec<Change<'a>>,
+    ) -> i64 {
+        self.elst.collect_changes(bounds.end(), level, changes)
+    }
+
+    fn atom_ref(&self) -> AtomRef<'_> {
+        AtomRef::Edts(self)
+    }
+}
+
+#[cfg(feature = "chapters")]
+impl Edts {
+    /// An edit list that presents the whole media of a track, starting at the beginning.
+    pub fn full_duration(duration: u64) -> Self {
+        Self {
+            state: State::Insert,
+            elst: Some(Elst {
+                state: State::Insert,
+                version: if duration > u32::MAX as u64 { 1 } else { 0 },
+                entries: vec![ElstEntry {
+                    segment_duration: duration,
+                    media_time: 0,
+                    media_rate: 1 << 16,
+                }],
+            }),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use super::*;
+
+pub const HEADER_SIZE: u64 = 8;
+pub const ENTRY_SIZE_V0: u64 = 12;
+pub const ENTRY_SIZE_V1: u64 = 20;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Elst {
+    pub state: State,
+    pub version: u8,
+    pub entries: Vec<ElstEntry>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElstEntry {
+    /// The duration of the edit segment in mvhd timescale units.
+    pub segment_duration: u64,
+    /// The start time of the edit segment in mdhd timescale units, or -1 for an empty edit.
+    pub media_time: i64,
+    /// The playback rate as a 16.16 fixed point number.
+    pub media_rate: u32,
+}
+
+impl Atom for Elst {
+    const FOURCC: Fourcc = EDIT_LIST;
+}
+
+impl ParseAtom for Elst {
+    fn parse_atom(
+        reader: &mut (impl Read + Seek),
+        _cfg: &ParseConfig<'_>,
+        size: Size,
+    ) -> crate::Result<Self> {
+        let bounds = find_bounds(reader, size)?;
+        let (version, _) = head::parse_full(reader)?;
+
+        let entry_size = match version {
+            0 => ENTRY_SIZE_V0,
+            1 => ENTRY_SIZE_V1,
+            _ => return unknown_version("edit list (elst)", version),
+        };
+
+        let num_entries = reader.read_be_u32()?;
+        let table_size = entry_size * num_entries as u64;
+        expect_size("Edit list (elst)", size, HEADER_SIZE + table_size)?;
+
+        let mut entries = Vec::with_capacity(prealloc_capacity::<ElstEntry>(num_entries as u64));
+        for _ in 0..num_entries {
+            let entry = match version {
+                0 => ElstEntry {
+                    segment_duration: reader.read_be_u32()? as u64,
+                    media_time: reader.read_be_u32()? as i32 as i64,
+                    media_rate: reader.read_be_u32()?,
+                },
+                _ => ElstEntry {
+                    segment_duration: reader.read_be_u64()?,
+                    media_time: reader.read_be_u64()? as i64,
+                    media_rate: reader.read_be_u32()?,
+                },
+            };
+            entries.push(entry);
+        }
+
+        Ok(Self { state: State::Existing(bounds), version, entries })
+    }
+}
+
+impl AtomSize for Elst {
+    fn size(&self) -> Size {
+        let entry_size = match self.version {
+            0 => ENTRY_SIZE_V0,
+            _ => ENTRY_SIZE_V1,
+        };
+        let content_len = HEADER_SIZE + entry_size * self.entries.len() as u64;
+        Size::from(content_len)
+    }
+}
+
+impl WriteAtom for Elst {
+    fn write_atom(&self, writer: &mut impl Write, _changes: &[Change<'_>]) -> crate::Result<()> {
+        self.write_head(writer)?;
+        head::write_full(writer, self.version, [0; 3])?;
+
+        writer.write_be_u32(self.entries.len() as u32)?;
+        for e in self.entries.iter() {
+            match self.version {
+                0 => {
+                    writer.write_be_u32(e.segment_duration as u32)?;
+                    writer.write_be_u32(e.media_time as i32 as u32)?;
+                }
+                _ => {
+                    writer.write_be_u64(e.segment_duration)?;
+                    writer.write_be_u64(e.media_time as u64)?;
+                }
+            }
+            writer.write_be_u32(e.media_rate)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl LeafAtomCollectChanges for Elst {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn atom_ref(&self) -> AtomRef<'_> {
+        AtomRef::Elst(self)
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+/// A free space atom (`free`) that is used as padding after the item list (`ilst`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Free {
+    pub state: State,
+    pub content_len: u64,
+}
+
+impl Atom for Free {
+    const FOURCC: Fourcc = FREE;
+}
+
+impl ParseAtom for Free {
+    fn parse_atom(
+        reader: &mut (impl Read + Seek),
+        _cfg: &ParseConfig<'_>,
+        size: Size,
+    ) -> crate::Result<Self> {
+        let bounds = find_bounds(reader, size)?;
+        reader.skip(size.content_len() as i64)?;
+        Ok(Self {
+            state: State::Existing(bounds),
+            content_len: size.content_len(),
+        })
+    }
+}
+
+impl AtomSize for Free {
+    fn size(&self) -> Size {
+        Size::from(self.content_len)
+    }
+}
+
+impl WriteAtom for Free {
+    fn write_atom(&self, writer: &mut impl Write, _changes: &[Change<'_>]) -> crate::Result<()> {
+        self.write_head(writer)?;
+        std::io::copy(&mut std::io::repeat(0).take(self.content_len), writer)?;
+        Ok(())
+    }
+}
+
+impl LeafAtomCollectChanges for Free {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn atom_ref(&self) -> AtomRef<'_> {
+        AtomRef::Free(self)
+    }
+}
+
+impl Free {
+    pub fn new(content_len: u64) -> Self {
+        Self { state: State::Insert, content_len }
+    }
+}
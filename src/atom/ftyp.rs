@@ -1,11 +1,42 @@
 use super::*;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// The atoms a classic QuickTime file without a filetype atom may start with.
+const QUICKTIME_FIRST_ATOMS: [Fourcc; 5] = [MOVIE, MEDIA_DATA, FREE, SKIP, WIDE];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Ftyp {
-    pub size: Size,
+    pub state: State,
     pub string: String,
 }
 
+impl Atom for Ftyp {
+    const FOURCC: Fourcc = FILETYPE;
+}
+
+impl AtomSize for Ftyp {
+    fn size(&self) -> Size {
+        Size::from(self.string.len() as u64)
+    }
+}
+
+impl WriteAtom for Ftyp {
+    fn write_atom(&self, writer: &mut impl Write, _changes: &[Change<'_>]) -> crate::Result<()> {
+        self.write_head(writer)?;
+        writer.write_utf8(&self.string)?;
+        Ok(())
+    }
+}
+
+impl LeafAtomCollectChanges for Ftyp {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn atom_ref(&self) -> AtomRef<'_> {
+        AtomRef::Ftyp(self)
+    }
+}
+
 impl Ftyp {
     pub fn parse(reader: &mut (impl Read + Seek), file_len: u64) -> crate::Result<Self> {
         let head = head::parse(reader, file_len)?;
@@ -13,8 +44,81 @@ impl Ftyp {
             return Err(crate::Error::new(ErrorKind::NoFtyp, "No filetype atom found."));
         }
 
+        let bounds = find_bounds(reader, head.size())?;
         let string = reader.read_utf8(head.content_len())?;
 
-        Ok(Ftyp { size: head.size(), string })
+        Ok(Ftyp { state: State::Existing(bounds), string })
+    }
+
+    /// Parses the filetype atom if it's the first atom. Classic QuickTime files don't contain one,
+    /// if the file starts with an atom typical for them, `None` is returned and the reader is reset
+    /// to the start of the file.
+    pub fn parse_optional(
+        reader: &mut (impl Read + Seek),
+        file_len: u64,
+    ) -> crate::Result<Option<Self>> {
+        let head = head::parse(reader, file_len)?;
+        reader.seek(SeekFrom::Start(0))?;
+        if QUICKTIME_FIRST_ATOMS.contains(&head.fourcc()) {
+            return Ok(None);
+        }
+        Self::parse(reader, file_len).map(Some)
+    }
+
+    /// Returns the major brand followed by the compatible brands.
+    pub fn brands(&self) -> impl Iterator<Item = Fourcc> + '_ {
+        let data = self.string.as_bytes();
+        let major = data.get(..4).into_iter();
+        let compatible = data.get(8..).unwrap_or_default().chunks_exact(4);
+        major.chain(compatible).map(|b| Fourcc(b.try_into().unwrap()))
+    }
+
+    /// Returns an error of the kind [`ErrorKind::UnsupportedBrand`] if the brands aren't allowed
+    /// by the policy.
+    pub fn check_brands(&self, policy: &BrandPolicy) -> crate::Result<()> {
+        let BrandPolicy::Strict(allowed) = policy else {
+            return Ok(());
+        };
+        if self.brands().any(|b| allowed.contains(&b)) {
+            return Ok(());
+        }
+
+        let major = self.brands().next().unwrap_or_default();
+        Err(crate::Error::new(
+            ErrorKind::UnsupportedBrand(major),
+            format!("Unsupported major brand {major} and compatible brands"),
+        ))
+    }
+
+    /// Replaces the major brand and adds it to the compatible brands, if it isn't already present.
+    ///
+    /// ```md
+    /// 4 bytes major brand
+    /// 4 bytes minor version
+    /// 4 bytes compatible brand
+    /// ...
+    /// ```
+    pub fn set_major_brand(&mut self, brand: Fourcc) -> crate::Result<()> {
+        let mut data = self.string.as_bytes().to_vec();
+        if data.len() < 8 {
+            data.resize(8, 0);
+        }
+
+        let compatible = data[8..].chunks_exact(4).any(|b| b == *brand);
+        if data[..4] == *brand && compatible {
+            return Ok(());
+        }
+
+        data[..4].copy_from_slice(&*brand);
+        if !compatible {
+            data.extend_from_slice(&*brand);
+        }
+
+        self.string = String::from_utf8(data).map_err(|_| {
+            crate::Error::new(ErrorKind::Utf8StringDecoding, "Major brand is not valid utf-8")
+        })?;
+        self.state.replace_existing();
+
+        Ok(())
     }
 }
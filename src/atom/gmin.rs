@@ -76,6 +76,7 @@ impl LeafAtomCollectChanges for Gmin {
     }
 }
 
+#[cfg(feature = "chapters")]
 impl Gmin {
     pub fn chapter() -> Self {
         Self {
@@ -65,6 +65,22 @@ impl Hdlr {
         }
     }
 
+    pub fn mdta() -> Self {
+        Self {
+            state: State::Insert,
+            data: Cow::Borrowed(&[
+                0x00, 0x00, 0x00, 0x00, // version + flags
+                0x00, 0x00, 0x00, 0x00, // component type
+                0x6d, 0x64, 0x74, 0x61, // component subtype
+                0x00, 0x00, 0x00, 0x00, // component manufacturer
+                0x00, 0x00, 0x00, 0x00, // component flags
+                0x00, 0x00, 0x00, 0x00, // component flags mask
+                0x00, // component name
+            ]),
+        }
+    }
+
+    #[cfg(feature = "chapters")]
     pub fn text_mdia() -> Self {
         Self {
             state: State::Insert,
@@ -79,4 +95,29 @@ impl Hdlr {
             ]),
         }
     }
+
+    /// Returns the component subtype, which specifies the type of media of a track, for example
+    /// `soun` for audio.
+    pub fn component_subtype(&self) -> Option<Fourcc> {
+        let bytes = self.data.get(8..12)?;
+        Some(Fourcc(bytes.try_into().unwrap()))
+    }
+
+    /// Returns the component name, which is either stored as a null terminated string, or as a
+    /// pascal string in QuickTime files.
+    pub fn name(&self) -> Option<String> {
+        let bytes = self.data.get(24..)?;
+        let name = match bytes.split_first() {
+            Some((&len, rest)) if len > 0 && len as usize == rest.len() => rest,
+            _ => bytes.split(|&b| b == 0).next()?,
+        };
+        let name = String::from_utf8_lossy(name);
+        let name = name.trim_end_matches('\0');
+        (!name.is_empty()).then(|| name.to_owned())
+    }
+
+    /// Returns a handler with the same data, that is inserted.
+    pub fn to_inserted(&self) -> Self {
+        Self { state: State::Insert, data: self.data.clone() }
+    }
 }
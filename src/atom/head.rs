@@ -121,7 +121,8 @@ pub fn parse(reader: &mut impl Read, remaining_bytes: u64) -> crate::Result<Head
                     format!(
                         "Read extended length of '{fourcc}' which is less than 16 bytes: {ext_len}"
                     ),
-                ));
+                )
+                .with_sizes(16, ext_len));
             }
             Ok(ext_len) => len = ext_len,
             Err(e) => {
@@ -136,7 +137,8 @@ pub fn parse(reader: &mut impl Read, remaining_bytes: u64) -> crate::Result<Head
         return Err(crate::Error::new(
             crate::ErrorKind::InvalidAtomSize,
             format!("Read length of '{fourcc}' which is less than 8 bytes: {len}"),
-        ));
+        )
+        .with_sizes(8, len));
     } else {
         false
     };
@@ -147,7 +149,8 @@ pub fn parse(reader: &mut impl Read, remaining_bytes: u64) -> crate::Result<Head
             format!(
                 "Atom size {len} of {fourcc} out larger than the remaining number of bytes {remaining_bytes}"
             ),
-        ));
+        )
+        .with_sizes(remaining_bytes, len));
     }
 
     Ok(Head::new(ext, len, fourcc))
@@ -6,6 +6,8 @@ use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
+pub use crate::tag::KNOWN_IDENTS;
+
 /// (`ftyp`) Identifier of an atom information about the filetype.
 pub(crate) const FILETYPE: Fourcc = Fourcc(*b"ftyp");
 /// (`mdat`)
@@ -14,10 +16,18 @@ pub(crate) const MEDIA_DATA: Fourcc = Fourcc(*b"mdat");
 pub(crate) const MOVIE: Fourcc = Fourcc(*b"moov");
 /// (`mvhd`) Identifier of an atom containing information about the whole movie (or audio file).
 pub(crate) const MOVIE_HEADER: Fourcc = Fourcc(*b"mvhd");
+/// (`mvex`)
+pub(crate) const MOVIE_EXTENDS: Fourcc = Fourcc(*b"mvex");
+/// (`mehd`)
+pub(crate) const MOVIE_EXTENDS_HEADER: Fourcc = Fourcc(*b"mehd");
 /// (`trak`) Identifier of an atom containing information about a single track.
 pub(crate) const TRACK: Fourcc = Fourcc(*b"trak");
 /// (`tkhd`)
 pub(crate) const TRACK_HEADER: Fourcc = Fourcc(*b"tkhd");
+/// (`edts`)
+pub(crate) const EDIT: Fourcc = Fourcc(*b"edts");
+/// (`elst`)
+pub(crate) const EDIT_LIST: Fourcc = Fourcc(*b"elst");
 /// (`tref`)
 pub(crate) const TRACK_REFERENCE: Fourcc = Fourcc(*b"tref");
 /// (`chap`)
@@ -52,10 +62,19 @@ pub(crate) const SAMPLE_TABLE_CHUNK_OFFSET_64: Fourcc = Fourcc(*b"co64");
 pub(crate) const SAMPLE_TABLE_TIME_TO_SAMPLE: Fourcc = Fourcc(*b"stts");
 /// (`stsd`)
 pub(crate) const SAMPLE_TABLE_SAMPLE_DESCRIPTION: Fourcc = Fourcc(*b"stsd");
+#[cfg(feature = "audio-info")]
 /// (`mp4a`)
 pub(crate) const MP4_AUDIO: Fourcc = Fourcc(*b"mp4a");
+#[cfg(feature = "audio-info")]
+/// (`alac`)
+pub(crate) const APPLE_LOSSLESS_AUDIO: Fourcc = Fourcc(*b"alac");
 /// (`text`)
 pub(crate) const TEXT_MEDIA: Fourcc = Fourcc(*b"text");
+/// (`soun`) The component subtype of audio tracks.
+pub(crate) const SOUND_MEDIA: Fourcc = Fourcc(*b"soun");
+/// (`vide`) The component subtype of video tracks.
+pub(crate) const VIDEO_MEDIA: Fourcc = Fourcc(*b"vide");
+#[cfg(feature = "audio-info")]
 /// (`esds`)
 pub(crate) const ELEMENTARY_STREAM_DESCRIPTION: Fourcc = Fourcc(*b"esds");
 /// (`udta`) Identifier of an atom containing user metadata.
@@ -68,6 +87,10 @@ pub(crate) const METADATA: Fourcc = Fourcc(*b"meta");
 pub(crate) const HANDLER_REFERENCE: Fourcc = Fourcc(*b"hdlr");
 /// (`ilst`) Identifier of an atom containing a list of metadata atoms.
 pub(crate) const ITEM_LIST: Fourcc = Fourcc(*b"ilst");
+/// (`keys`) Identifier of an atom containing QuickTime metadata keys.
+pub(crate) const METADATA_KEYS: Fourcc = Fourcc(*b"keys");
+/// (`mdta`) The namespace of QuickTime metadata keys.
+pub(crate) const MDTA: Fourcc = Fourcc(*b"mdta");
 /// (`data`) Identifier of an atom containing typed data.
 pub(crate) const DATA: Fourcc = Fourcc(*b"data");
 /// (`mean`)
@@ -76,6 +99,10 @@ pub(crate) const MEAN: Fourcc = Fourcc(*b"mean");
 pub(crate) const NAME: Fourcc = Fourcc(*b"name");
 /// (`free`)
 pub(crate) const FREE: Fourcc = Fourcc(*b"free");
+/// (`skip`)
+pub(crate) const SKIP: Fourcc = Fourcc(*b"skip");
+/// (`wide`)
+pub(crate) const WIDE: Fourcc = Fourcc(*b"wide");
 
 /// (`----`)
 pub const FREEFORM: Fourcc = Fourcc(*b"----");
@@ -157,11 +184,25 @@ pub const TV_SHOW_NAME: Fourcc = Fourcc(*b"tvsh");
 // iTunes 6.0.2
 /// (`purd`)
 pub const PURCHASE_DATE: Fourcc = Fourcc(*b"purd");
+/// (`ownr`)
+pub const OWNER: Fourcc = Fourcc(*b"ownr");
 
 // iTunes 7.0
 /// (`pgap`)
 pub const GAPLESS_PLAYBACK: Fourcc = Fourcc(*b"pgap");
 
+// iTunes 9.0
+/// (`ldes`)
+pub const LONG_DESCRIPTION: Fourcc = Fourcc(*b"ldes");
+/// (`sdes`)
+pub const STORE_DESCRIPTION: Fourcc = Fourcc(*b"sdes");
+
+// Credits
+/// (`©dir`)
+pub const DIRECTOR: Fourcc = Fourcc(*b"\xa9dir");
+/// (`©prd`)
+pub const PRODUCER: Fourcc = Fourcc(*b"\xa9prd");
+
 // Work, Movement
 /// (`©mvn`)
 pub const MOVEMENT: Fourcc = Fourcc(*b"\xa9mvn");
@@ -188,6 +229,14 @@ pub const TITLE_SORT_ORDER: Fourcc = Fourcc(*b"sonm");
 /// (`sosn`)
 pub const TV_SHOW_NAME_SORT_ORDER: Fourcc = Fourcc(*b"sosn");
 
+// Audiobooks
+/// (`©nrt`)
+pub const NARRATOR: Fourcc = Fourcc(*b"\xa9nrt");
+
+// Location
+/// (`©xyz`)
+pub const LOCATION: Fourcc = Fourcc(*b"\xa9xyz");
+
 // Freeform
 /// Mean string of most freeform identifiers (`com.apple.iTunes`)
 pub const APPLE_ITUNES_MEAN: &str = "com.apple.iTunes";
@@ -198,6 +247,144 @@ pub const ISRC: FreeformIdentStatic = FreeformIdent::new_static(APPLE_ITUNES_MEA
 pub const LYRICIST: FreeformIdentStatic = FreeformIdent::new_static(APPLE_ITUNES_MEAN, "LYRICIST");
 /// (`----:com.apple.iTunes:LABEL`)
 pub const LABEL: FreeformIdentStatic = FreeformIdent::new_static(APPLE_ITUNES_MEAN, "LABEL");
+/// (`----:com.apple.iTunes:ASIN`)
+pub const ASIN: FreeformIdentStatic = FreeformIdent::new_static(APPLE_ITUNES_MEAN, "ASIN");
+/// (`----:com.apple.iTunes:SERIES`)
+pub const SERIES: FreeformIdentStatic = FreeformIdent::new_static(APPLE_ITUNES_MEAN, "SERIES");
+/// (`----:com.apple.iTunes:SERIES-PART`)
+pub const SERIES_PART: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "SERIES-PART");
+/// (`----:com.apple.iTunes:NARRATOR`), written by some taggers instead of [`NARRATOR`].
+pub const FREEFORM_NARRATOR: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "NARRATOR");
+/// (`----:com.apple.iTunes:LANGUAGE`)
+pub const LANGUAGE: FreeformIdentStatic = FreeformIdent::new_static(APPLE_ITUNES_MEAN, "LANGUAGE");
+/// (`----:com.apple.iTunes:iTunes_CDDB_1`)
+pub const CDDB_1: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "iTunes_CDDB_1");
+/// (`----:com.apple.iTunes:iTunes_CDDB_IDs`)
+pub const CDDB_IDS: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "iTunes_CDDB_IDs");
+/// (`----:com.apple.iTunes:iTunes_CDDB_TrackNumber`)
+pub const CDDB_TRACK_NUMBER: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "iTunes_CDDB_TrackNumber");
+/// (`----:com.apple.iTunes:iTunNORM`)
+pub const ITUNES_NORMALIZATION: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "iTunNORM");
+/// (`----:com.apple.iTunes:replaygain_track_gain`)
+pub const REPLAY_GAIN_TRACK_GAIN: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "replaygain_track_gain");
+/// (`----:com.apple.iTunes:replaygain_track_peak`)
+pub const REPLAY_GAIN_TRACK_PEAK: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "replaygain_track_peak");
+/// (`----:com.apple.iTunes:replaygain_album_gain`)
+pub const REPLAY_GAIN_ALBUM_GAIN: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "replaygain_album_gain");
+/// (`----:com.apple.iTunes:replaygain_album_peak`)
+pub const REPLAY_GAIN_ALBUM_PEAK: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "replaygain_album_peak");
+/// (`----:com.apple.iTunes:R128_TRACK_GAIN`)
+pub const R128_TRACK_GAIN: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "R128_TRACK_GAIN");
+/// (`----:com.apple.iTunes:R128_ALBUM_GAIN`)
+pub const R128_ALBUM_GAIN: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "R128_ALBUM_GAIN");
+/// (`----:com.apple.iTunes:ORIGINAL ENCODER`)
+pub const ORIGINAL_ENCODER: FreeformIdentStatic =
+    FreeformIdent::new_static(APPLE_ITUNES_MEAN, "ORIGINAL ENCODER");
+/// The [`DataIdent`] equivalent of [`ORIGINAL_ENCODER`], used as the default of
+/// [`WriteConfig::original_encoder_ident`](crate::WriteConfig::original_encoder_ident).
+pub const ORIGINAL_ENCODER_IDENT: DataIdent = DataIdent::Freeform {
+    mean: Cow::Borrowed(APPLE_ITUNES_MEAN),
+    name: Cow::Borrowed(ORIGINAL_ENCODER.name),
+};
+
+/// Mean string of `mp4ameta` specific freeform identifiers.
+pub const MP4AMETA_MEAN: &str = "com.github.mp4ameta";
+
+/// (`----:com.github.mp4ameta:REV`)
+pub const REVISION: FreeformIdentStatic = FreeformIdent::new_static(MP4AMETA_MEAN, "REV");
+/// The [`DataIdent`] equivalent of [`REVISION`], which can be used as
+/// [`WriteConfig::revision_ident`](crate::WriteConfig::revision_ident).
+pub const REVISION_IDENT: DataIdent = DataIdent::Freeform {
+    mean: Cow::Borrowed(MP4AMETA_MEAN),
+    name: Cow::Borrowed(REVISION.name),
+};
+
+// QuickTime metadata keys
+/// (`mdta:com.apple.quicktime.location.ISO6709`)
+pub const QUICKTIME_LOCATION: DataIdent =
+    DataIdent::Mdta(Cow::Borrowed("com.apple.quicktime.location.ISO6709"));
+
+/// Well known freeform mean strings, which aren't allocated when read.
+pub(crate) const KNOWN_FREEFORM_MEANS: &[&str] = &[APPLE_ITUNES_MEAN, MP4AMETA_MEAN];
+/// Well known freeform name strings, which aren't allocated when read.
+pub(crate) const KNOWN_FREEFORM_NAMES: &[&str] = &[
+    ISRC.name,
+    LYRICIST.name,
+    LABEL.name,
+    ORIGINAL_ENCODER.name,
+    REVISION.name,
+    ITUNES_NORMALIZATION.name,
+    "iTunSMPB",
+    "iTunMOVI",
+    CDDB_1.name,
+    CDDB_IDS.name,
+    CDDB_TRACK_NUMBER.name,
+    ASIN.name,
+    SERIES.name,
+    SERIES_PART.name,
+    FREEFORM_NARRATOR.name,
+    LANGUAGE.name,
+    "Encoding Params",
+    "BARCODE",
+    "CATALOGNUMBER",
+    "MEDIA",
+    "SCRIPT",
+    "MusicBrainz Album Artist Id",
+    "MusicBrainz Album Id",
+    "MusicBrainz Artist Id",
+    "MusicBrainz Release Group Id",
+    "MusicBrainz Track Id",
+    REPLAY_GAIN_ALBUM_GAIN.name,
+    REPLAY_GAIN_ALBUM_PEAK.name,
+    REPLAY_GAIN_TRACK_GAIN.name,
+    REPLAY_GAIN_TRACK_PEAK.name,
+    R128_TRACK_GAIN.name,
+    R128_ALBUM_GAIN.name,
+];
+
+/// Information about an identifier with dedicated accessors, see [`KNOWN_IDENTS`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdentInfo {
+    /// The identifier of the item.
+    pub ident: DataIdent,
+    /// The canonical name, which is also the name of the accessor, for example `"album_artist"`.
+    pub name: &'static str,
+    /// The kind of value stored in the item.
+    pub kind: ValueKind,
+    /// Whether the item can contain multiple values.
+    pub multiple: bool,
+}
+
+/// The kind of value stored in an item, see [`IdentInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    /// A UTF-8 string.
+    String,
+    /// A flag, which is either present or not.
+    Flag,
+    /// An 8 bit unsigned integer, which is mapped to an enum like the media type.
+    U8,
+    /// A 16 bit unsigned integer.
+    U16,
+    /// A 32 bit unsigned integer.
+    U32,
+    /// A number and a total, like the track number and total tracks.
+    NumberPair,
+    /// An image.
+    Image,
+}
 
 /// A trait providing information about an identifier.
 pub trait Ident: PartialEq<DataIdent> {
@@ -205,12 +392,38 @@ pub trait Ident: PartialEq<DataIdent> {
     fn fourcc(&self) -> Option<Fourcc>;
     /// Returns a freeform identifier.
     fn freeform(&self) -> Option<FreeformIdentBorrowed<'_>>;
+    /// Returns a QuickTime metadata key of the `mdta` namespace.
+    fn mdta(&self) -> Option<&str> {
+        None
+    }
 }
 
 // TODO: figure out how to implement PartialEq for Ident or require an implementation as a trait bound.
 /// Returns wheter the identifiers match.
 pub fn idents_match(a: &impl Ident, b: &impl Ident) -> bool {
-    a.fourcc() == b.fourcc() && a.freeform() == b.freeform()
+    a.fourcc() == b.fourcc() && a.freeform() == b.freeform() && a.mdta() == b.mdta()
+}
+
+/// Creates a [`Fourcc`] from a string literal, which has to consist of 4 Latin-1 characters.
+/// The `©` character is stored as the `0xA9` byte, so `fourcc!("©ART")` is equivalent to
+/// `Fourcc(*b"\xa9ART")`. Invalid literals fail to compile.
+///
+/// ```
+/// use mp4ameta::{Fourcc, fourcc, ident};
+///
+/// assert_eq!(fourcc!("©ART"), ident::ARTIST);
+/// assert_eq!(fourcc!("aART"), Fourcc(*b"aART"));
+/// ```
+///
+/// ```compile_fail
+/// let artist = mp4ameta::fourcc!("ART");
+/// ```
+#[macro_export]
+macro_rules! fourcc {
+    ($s:literal) => {{
+        const FOURCC: $crate::Fourcc = $crate::Fourcc::from_literal($s);
+        FOURCC
+    }};
 }
 
 /// A 4 byte atom identifier (four character code).
@@ -235,7 +448,7 @@ impl PartialEq<DataIdent> for Fourcc {
     fn eq(&self, other: &DataIdent) -> bool {
         match other {
             DataIdent::Fourcc(f) => self == f,
-            DataIdent::Freeform { .. } => false,
+            DataIdent::Freeform { .. } | DataIdent::Mdta(_) => false,
         }
     }
 }
@@ -250,6 +463,36 @@ impl Ident for Fourcc {
     }
 }
 
+impl Fourcc {
+    /// Decodes the 4 Latin-1 characters of a string, used by the [`fourcc!`] macro.
+    ///
+    /// # Panics
+    /// If the string doesn't consist of 4 Latin-1 characters.
+    #[doc(hidden)]
+    pub const fn from_literal(s: &str) -> Self {
+        let s = s.as_bytes();
+        let mut bytes = [0; 4];
+        let mut i = 0;
+        let mut len = 0;
+        while i < s.len() {
+            assert!(len < 4, "a fourcc has to be 4 characters long");
+            bytes[len] = match s[i] {
+                b @ 0x00..=0x7F => b,
+                // 2 byte UTF-8 sequences of the code points 0x80..=0xFF
+                lead @ (0xC2 | 0xC3) => {
+                    i += 1;
+                    ((lead & 0x1F) << 6) | (s[i] & 0x3F)
+                }
+                _ => panic!("a fourcc can only contain Latin-1 characters"),
+            };
+            i += 1;
+            len += 1;
+        }
+        assert!(len == 4, "a fourcc has to be 4 characters long");
+        Self(bytes)
+    }
+}
+
 impl FromStr for Fourcc {
     type Err = TryFromSliceError;
 
@@ -329,7 +572,7 @@ pub struct FreeformIdent<'a, T> {
 impl<'a, T: StrLifetime<'a>> PartialEq<DataIdent> for FreeformIdent<'a, T> {
     fn eq(&self, other: &DataIdent) -> bool {
         match other {
-            DataIdent::Fourcc(_) => false,
+            DataIdent::Fourcc(_) | DataIdent::Mdta(_) => false,
             DataIdent::Freeform { mean, name } => self.mean == mean && self.name == name,
         }
     }
@@ -379,7 +622,7 @@ impl<'a> FreeformIdentBorrowed<'a> {
 }
 
 /// The identifier used to store metadata inside an item list.
-/// Either a [`Fourcc`] or an freeform identifier.
+/// Either a [`Fourcc`], a freeform identifier, or a QuickTime metadata key.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum DataIdent {
     /// A standard identifier containing a 4 byte atom identifier.
@@ -394,24 +637,35 @@ pub enum DataIdent {
         /// The name string used to identify the freeform atom.
         name: Cow<'static, str>,
     },
+    /// A QuickTime metadata key of the `mdta` namespace, for example
+    /// `"com.apple.quicktime.make"`. The keys are stored inside a separate keys (`keys`) atom,
+    /// and referenced by their index from the item list.
+    Mdta(Cow<'static, str>),
 }
 
 impl Ident for DataIdent {
     fn fourcc(&self) -> Option<Fourcc> {
         match self {
             Self::Fourcc(i) => Some(*i),
-            Self::Freeform { .. } => None,
+            Self::Freeform { .. } | Self::Mdta(_) => None,
         }
     }
 
     fn freeform(&self) -> Option<FreeformIdentBorrowed<'_>> {
         match self {
-            Self::Fourcc(_) => None,
+            Self::Fourcc(_) | Self::Mdta(_) => None,
             Self::Freeform { mean, name } => {
                 Some(FreeformIdent::new_borrowed(mean.as_ref(), name.as_ref()))
             }
         }
     }
+
+    fn mdta(&self) -> Option<&str> {
+        match self {
+            Self::Fourcc(_) | Self::Freeform { .. } => None,
+            Self::Mdta(key) => Some(key),
+        }
+    }
 }
 
 impl fmt::Display for DataIdent {
@@ -419,7 +673,70 @@ impl fmt::Display for DataIdent {
         match self {
             Self::Fourcc(ident) => write!(f, "{ident}"),
             Self::Freeform { mean, name } => write!(f, "----:{mean}:{name}"),
+            Self::Mdta(key) => write!(f, "mdta:{key}"),
+        }
+    }
+}
+
+/// Parses an identifier in the same syntax as [`Display`](fmt::Display):
+/// - A fourcc like `"aART"`. The `©` (`0xA9`) byte can also be spelled as `"(c)"`, for example
+///   `"(c)ART"`.
+/// - A freeform identifier like `"----:com.apple.iTunes:ISRC"`, as used by AtomicParsley and
+///   mutagen. The mean ends at the first colon, so the name may contain colons.
+/// - A QuickTime metadata key like `"mdta:com.apple.quicktime.make"`.
+///
+/// ```
+/// use mp4ameta::{DataIdent, ident};
+///
+/// assert_eq!("©ART".parse::<DataIdent>().unwrap(), DataIdent::Fourcc(ident::ARTIST));
+/// assert_eq!("(c)ART".parse::<DataIdent>().unwrap(), DataIdent::Fourcc(ident::ARTIST));
+///
+/// let isrc = DataIdent::from(ident::ISRC);
+/// assert_eq!("----:com.apple.iTunes:ISRC".parse::<DataIdent>().unwrap(), isrc);
+/// assert_eq!(isrc.to_string(), "----:com.apple.iTunes:ISRC");
+/// ```
+impl FromStr for DataIdent {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| {
+            crate::Error::new(
+                crate::ErrorKind::InvalidIdent,
+                format!("Invalid identifier {s:?}: {reason}"),
+            )
+        };
+
+        if let Some(rest) = s.strip_prefix("----:") {
+            let Some((mean, name)) = rest.split_once(':') else {
+                return Err(invalid("expected a freeform identifier like ----:<mean>:<name>"));
+            };
+            if mean.is_empty() || name.is_empty() {
+                return Err(invalid("the mean and name of a freeform identifier can't be empty"));
+            }
+            return Ok(Self::freeform(mean.to_owned(), name.to_owned()));
+        }
+        if let Some(key) = s.strip_prefix("mdta:") {
+            if key.is_empty() {
+                return Err(invalid("the key of a QuickTime metadata identifier can't be empty"));
+            }
+            return Ok(Self::mdta(key.to_owned()));
         }
+
+        let (prefix, rest) = match s.strip_prefix("(c)") {
+            Some(rest) => (Some('\u{a9}'), rest),
+            None => (None, s),
+        };
+        let mut chars = prefix.into_iter().chain(rest.chars());
+        let mut bytes = [0; 4];
+        for b in bytes.iter_mut() {
+            let c = chars.next().ok_or_else(|| invalid("a fourcc has to be 4 characters long"))?;
+            *b = u8::try_from(c)
+                .map_err(|_| invalid("a fourcc can only contain Latin-1 characters"))?;
+        }
+        if chars.next().is_some() {
+            return Err(invalid("a fourcc has to be 4 characters long"));
+        }
+        Ok(Self::Fourcc(Fourcc(bytes)))
     }
 }
 
@@ -451,6 +768,11 @@ impl DataIdent {
         Self::Freeform { mean: mean.into(), name: name.into() }
     }
 
+    /// Creates a new identifier of type [`DataIdent::Mdta`] containing the QuickTime metadata key.
+    pub fn mdta(key: impl Into<Cow<'static, str>>) -> Self {
+        Self::Mdta(key.into())
+    }
+
     /// Creates a new identifier of type [`DataIdent::Fourcc`] containing an atom identifier with
     /// the 4-byte identifier.
     pub const fn fourcc(bytes: [u8; 4]) -> Self {
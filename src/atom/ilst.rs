@@ -3,7 +3,7 @@ use super::*;
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Ilst<'a> {
     pub state: State,
-    pub data: Cow<'a, [MetaItem]>,
+    pub data: Cow<'a, [Arc<MetaItem>]>,
 }
 
 impl Atom for Ilst<'_> {
@@ -42,7 +42,7 @@ impl ParseAtom for Ilst<'_> {
 
         Ok(Self {
             state: State::Existing(bounds),
-            data: Cow::Owned(ilst),
+            data: Cow::Owned(ilst.into_iter().map(Arc::new).collect()),
         })
     }
 }
@@ -57,8 +57,13 @@ impl AtomSize for Ilst<'_> {
 impl WriteAtom for Ilst<'_> {
     fn write_atom(&self, writer: &mut impl Write, _changes: &[Change<'_>]) -> crate::Result<()> {
         self.write_head(writer)?;
+        // keys are referenced in order of appearance, see `Keys::from_items`
+        let mut key_index = 0;
         for a in self.data.iter() {
-            a.write(writer)?;
+            if let DataIdent::Mdta(_) = a.ident {
+                key_index += 1;
+            }
+            a.write(writer, key_index)?;
         }
         Ok(())
     }
@@ -74,3 +79,19 @@ impl LeafAtomCollectChanges for Ilst<'_> {
         AtomRef::Ilst(self)
     }
 }
+
+impl Ilst<'_> {
+    /// Replaces the identifiers of items that reference a QuickTime metadata key by their index.
+    pub fn resolve_keys(&mut self, keys: &Keys<'_>) {
+        for a in self.data.to_mut().iter_mut() {
+            let DataIdent::Fourcc(fourcc) = a.ident else {
+                continue;
+            };
+            let index = u32::from_be_bytes(*fourcc) as usize;
+            let Some(key) = index.checked_sub(1).and_then(|i| keys.keys.get(i)?.as_ref()) else {
+                continue;
+            };
+            Arc::make_mut(a).ident = DataIdent::Mdta(Cow::Owned(key.to_string()));
+        }
+    }
+}
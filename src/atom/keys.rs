@@ -0,0 +1,107 @@
+use super::*;
+
+pub const HEADER_SIZE: u64 = 8;
+pub const ENTRY_HEADER_SIZE: u64 = 8;
+
+/// A struct representing a QuickTime metadata keys atom (`keys`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Keys<'a> {
+    pub state: State,
+    /// The keys in the order they are referenced by the item list, starting with index 1. Keys of
+    /// namespaces other than `mdta` are `None`.
+    pub keys: Vec<Option<Cow<'a, str>>>,
+}
+
+impl Atom for Keys<'_> {
+    const FOURCC: Fourcc = METADATA_KEYS;
+}
+
+impl ParseAtom for Keys<'_> {
+    fn parse_atom(
+        reader: &mut (impl Read + Seek),
+        _cfg: &ParseConfig<'_>,
+        size: Size,
+    ) -> crate::Result<Self> {
+        let bounds = find_bounds(reader, size)?;
+        let (version, _) = head::parse_full(reader)?;
+
+        if version != 0 {
+            return unknown_version("metadata keys (keys)", version);
+        }
+
+        expect_min_size("Metadata keys (keys)", size, HEADER_SIZE)?;
+
+        let num_entries = reader.read_be_u32()?;
+        let mut keys = Vec::with_capacity(prealloc_capacity::<Option<String>>(num_entries as u64));
+        let mut parsed_bytes = HEADER_SIZE;
+        for _ in 0..num_entries {
+            let remaining_bytes = size.content_len() - parsed_bytes;
+            let key_size = reader.read_be_u32()? as u64;
+            if key_size < ENTRY_HEADER_SIZE || key_size > remaining_bytes {
+                return Err(crate::Error::new(
+                    ErrorKind::InvalidAtomSize,
+                    "Metadata keys (keys) entry size is out of bounds",
+                )
+                .with_sizes(remaining_bytes, key_size));
+            }
+
+            let mut namespace = Fourcc::default();
+            reader.read_exact(&mut *namespace)?;
+            let value_len = key_size - ENTRY_HEADER_SIZE;
+            if namespace == MDTA {
+                keys.push(Some(Cow::Owned(reader.read_utf8(value_len)?)));
+            } else {
+                reader.skip(value_len as i64)?;
+                keys.push(None);
+            }
+
+            parsed_bytes += key_size;
+        }
+
+        expect_size("Metadata keys (keys)", size, parsed_bytes)?;
+
+        Ok(Self { state: State::Existing(bounds), keys })
+    }
+}
+
+impl AtomSize for Keys<'_> {
+    fn size(&self) -> Size {
+        let entries_len = self.keys.iter().flatten().map(|k| ENTRY_HEADER_SIZE + k.len() as u64);
+        Size::from(HEADER_SIZE + entries_len.sum::<u64>())
+    }
+}
+
+impl WriteAtom for Keys<'_> {
+    fn write_atom(&self, writer: &mut impl Write, _changes: &[Change<'_>]) -> crate::Result<()> {
+        self.write_head(writer)?;
+        head::write_full(writer, 0, [0; 3])?;
+
+        writer.write_be_u32(self.keys.iter().flatten().count() as u32)?;
+        for k in self.keys.iter().flatten() {
+            writer.write_be_u32((ENTRY_HEADER_SIZE + k.len() as u64) as u32)?;
+            writer.write_all(&*MDTA)?;
+            writer.write_utf8(k)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl LeafAtomCollectChanges for Keys<'_> {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn atom_ref(&self) -> AtomRef<'_> {
+        AtomRef::Keys(self)
+    }
+}
+
+impl<'a> Keys<'a> {
+    /// Creates a keys atom containing the keys of all [`DataIdent::Mdta`] items, in the order in
+    /// which [`Ilst`] references them when written.
+    pub fn from_items(items: &'a [Arc<MetaItem>]) -> Self {
+        let keys = items.iter().filter_map(|a| a.ident.mdta()).map(|k| Some(Cow::Borrowed(k)));
+        Self { state: State::Insert, keys: keys.collect() }
+    }
+}
@@ -8,14 +8,18 @@ const BUF_SIZE_V1: usize = HEADER_SIZE_V1 - 4;
 const_assert!(std::mem::size_of::<MdhdBufV0>() == BUF_SIZE_V0);
 const_assert!(std::mem::size_of::<MdhdBufV1>() == BUF_SIZE_V1);
 
-const UNSPECIFIED_LANGUAGE: u16 = i16::MAX as u16;
-
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Mdhd {
+    pub state: State,
     pub version: u8,
     pub flags: [u8; 3],
     pub timescale: u32,
     pub duration: u64,
+    /// The packed ISO 639-2/T language code.
+    pub language: u16,
+    /// The content of an existing atom after the flags, which is preserved when it's rewritten.
+    /// Only the timescale, duration and language are updated.
+    pub data: Vec<u8>,
 }
 
 #[derive(Default)]
@@ -61,10 +65,14 @@ impl Atom for Mdhd {
 impl ParseAtom for Mdhd {
     fn parse_atom(
         reader: &mut (impl Read + Seek),
-        _cfg: &ParseConfig<'_>,
+        cfg: &ParseConfig<'_>,
         size: Size,
     ) -> crate::Result<Self> {
-        let mut mdhd = Self::default();
+        let bounds = find_bounds(reader, size)?;
+        let mut mdhd = Self {
+            state: State::Existing(bounds),
+            ..Default::default()
+        };
 
         let (version, flags) = head::parse_full(reader)?;
         mdhd.version = version;
@@ -78,6 +86,10 @@ impl ParseAtom for Mdhd {
                 reader.read_exact(buf.bytes_mut())?;
                 mdhd.timescale = u32::from_be_bytes(buf.timescale);
                 mdhd.duration = u32::from_be_bytes(buf.duration) as u64;
+                mdhd.language = u16::from_be_bytes(buf.language);
+                if cfg.write {
+                    mdhd.data = buf.bytes_mut().to_vec();
+                }
             }
             1 => {
                 expect_size("Media header (mdhd) version 1", size, HEADER_SIZE_V1 as u64)?;
@@ -86,6 +98,10 @@ impl ParseAtom for Mdhd {
                 reader.read_exact(buf.bytes_mut())?;
                 mdhd.timescale = u32::from_be_bytes(buf.timescale);
                 mdhd.duration = u64::from_be_bytes(buf.duration);
+                mdhd.language = u16::from_be_bytes(buf.language);
+                if cfg.write {
+                    mdhd.data = buf.bytes_mut().to_vec();
+                }
             }
             _ => {
                 return unknown_version("media header (mdhd)", version);
@@ -113,21 +129,23 @@ impl WriteAtom for Mdhd {
 
         match self.version {
             0 => {
-                let mut buf = MdhdBufV0 {
-                    timescale: u32::to_be_bytes(self.timescale),
-                    duration: u32::to_be_bytes(self.duration as u32),
-                    language: u16::to_be_bytes(UNSPECIFIED_LANGUAGE),
-                    ..Default::default()
-                };
+                let mut buf = MdhdBufV0::default();
+                if !self.data.is_empty() {
+                    buf.bytes_mut().copy_from_slice(&self.data);
+                }
+                buf.timescale = u32::to_be_bytes(self.timescale);
+                buf.duration = u32::to_be_bytes(self.duration as u32);
+                buf.language = u16::to_be_bytes(self.language);
                 writer.write_all(buf.bytes_mut())?;
             }
             1 => {
-                let mut buf = MdhdBufV1 {
-                    timescale: u32::to_be_bytes(self.timescale),
-                    duration: u64::to_be_bytes(self.duration),
-                    language: u16::to_be_bytes(UNSPECIFIED_LANGUAGE),
-                    ..Default::default()
-                };
+                let mut buf = MdhdBufV1::default();
+                if !self.data.is_empty() {
+                    buf.bytes_mut().copy_from_slice(&self.data);
+                }
+                buf.timescale = u32::to_be_bytes(self.timescale);
+                buf.duration = u64::to_be_bytes(self.duration);
+                buf.language = u16::to_be_bytes(self.language);
                 writer.write_all(buf.bytes_mut())?;
             }
             v => {
@@ -141,3 +159,28 @@ impl WriteAtom for Mdhd {
         Ok(())
     }
 }
+
+impl LeafAtomCollectChanges for Mdhd {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn atom_ref(&self) -> AtomRef<'_> {
+        AtomRef::Mdhd(self)
+    }
+}
+
+/// Packs an ISO 639-2/T language code into 3 times 5 bits, each character offset by `0x60`.
+pub fn pack_language(language: Language) -> u16 {
+    language.code().iter().fold(0, |packed, c| (packed << 5) | (c - 0x60) as u16)
+}
+
+/// Unpacks an ISO 639-2/T language code. Returns [`Language::UNDETERMINED`] if the language is
+/// unspecified or invalid.
+pub fn unpack_language(packed: u16) -> Language {
+    let code = [(packed >> 10) & 0x1f, (packed >> 5) & 0x1f, packed & 0x1f].map(|c| c as u8 + 0x60);
+    if packed >> 15 != 0 {
+        return Language::UNDETERMINED;
+    }
+    Language::new(code).unwrap_or_default()
+}
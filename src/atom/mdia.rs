@@ -30,7 +30,7 @@ impl ParseAtom for Mdia {
 
             match head.fourcc() {
                 MEDIA_HEADER => mdhd = Some(Mdhd::parse(reader, cfg, head.size())?),
-                HANDLER_REFERENCE if cfg.write => {
+                HANDLER_REFERENCE if cfg.write || cfg.cfg.read_video_info => {
                     hdlr = Some(Hdlr::parse(reader, cfg, head.size())?)
                 }
                 MEDIA_INFORMATION => minf = Some(Minf::parse(reader, cfg, head.size())?),
@@ -85,7 +85,8 @@ impl SimpleCollectChanges for Mdia {
         bounds: &'a AtomBounds,
         changes: &mut Vec<Change<'a>>,
     ) -> i64 {
-        self.hdlr.collect_changes(bounds.end(), level, changes)
+        self.mdhd.collect_changes(bounds.end(), level, changes)
+            + self.hdlr.collect_changes(bounds.end(), level, changes)
             + self.minf.collect_changes(bounds.end(), level, changes)
     }
 
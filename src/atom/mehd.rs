@@ -0,0 +1,43 @@
+use super::*;
+
+/// The movie extends header atom, containing the duration of all fragments.
+///
+/// ```md
+/// 1 byte version
+/// 3 bytes flags
+/// 4 or 8 bytes fragment duration
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mehd {
+    pub version: u8,
+    pub flags: [u8; 3],
+    /// The duration of the whole movie including all fragments in mvhd timescale units.
+    pub fragment_duration: u64,
+}
+
+impl Atom for Mehd {
+    const FOURCC: Fourcc = MOVIE_EXTENDS_HEADER;
+}
+
+impl ParseAtom for Mehd {
+    fn parse_atom(
+        reader: &mut (impl Read + Seek),
+        _cfg: &ParseConfig<'_>,
+        size: Size,
+    ) -> crate::Result<Self> {
+        let (version, flags) = head::parse_full(reader)?;
+        let fragment_duration = match version {
+            0 => {
+                expect_size("Movie extends header (mehd) version 0", size, 8)?;
+                reader.read_be_u32()? as u64
+            }
+            1 => {
+                expect_size("Movie extends header (mehd) version 1", size, 12)?;
+                reader.read_be_u64()?
+            }
+            _ => return unknown_version("movie extends header (mehd)", version),
+        };
+
+        Ok(Self { version, flags, fragment_duration })
+    }
+}
@@ -5,8 +5,13 @@ pub const HEADER_SIZE: u64 = 4;
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Meta<'a> {
     pub state: State,
+    /// QuickTime omits the version and flags of the metadata atom.
+    pub quicktime_header: bool,
     pub hdlr: Option<Hdlr>,
+    pub keys: Option<Keys<'a>>,
     pub ilst: Option<Ilst<'a>>,
+    /// Padding directly following the item list.
+    pub free: Option<Free>,
 }
 
 impl Atom for Meta<'_> {
@@ -20,33 +25,52 @@ impl ParseAtom for Meta<'_> {
         size: Size,
     ) -> crate::Result<Self> {
         let bounds = find_bounds(reader, size)?;
-        let (version, _) = head::parse_full(reader)?;
+        expect_min_size("Metadata (meta)", size, HEADER_SIZE)?;
 
-        if version != 0 {
-            return unknown_version("metadata (meta)", version);
+        let mut buf = [0; 8];
+        let quicktime_header = size.content_len() >= 8 && {
+            reader.read_exact(&mut buf)?;
+            reader.skip(-8)?;
+            buf[4..8] == *HANDLER_REFERENCE
+        };
+        if !quicktime_header {
+            let (version, _) = head::parse_full(reader)?;
+            if version != 0 {
+                return unknown_version("metadata (meta)", version);
+            }
         }
 
-        expect_min_size("Metadata (meta)", size, HEADER_SIZE)?;
-
         let mut meta = Self {
             state: State::Existing(bounds),
+            quicktime_header,
             ..Default::default()
         };
-        let mut parsed_bytes = HEADER_SIZE;
+        let mut parsed_bytes = meta.header_len();
+        let mut prev = None;
 
         while parsed_bytes < size.content_len() {
             let remaining_bytes = size.content_len() - parsed_bytes;
             let head = head::parse(reader, remaining_bytes)?;
 
             match head.fourcc() {
-                HANDLER_REFERENCE if cfg.write => {
+                FREE if cfg.write && prev == Some(ITEM_LIST) && meta.free.is_none() => {
+                    meta.free = Some(Free::parse(reader, cfg, head.size())?)
+                }
+                HANDLER_REFERENCE if meta.hdlr.is_none() => {
                     meta.hdlr = Some(Hdlr::parse(reader, cfg, head.size())?)
                 }
+                METADATA_KEYS => meta.keys = Some(Keys::parse(reader, cfg, head.size())?),
                 ITEM_LIST => meta.ilst = Some(Ilst::parse(reader, cfg, head.size())?),
-                _ => reader.skip(head.content_len() as i64)?,
+                HANDLER_REFERENCE | FREE => reader.skip(head.content_len() as i64)?,
+                _ => skip_unknown(reader, cfg, head)?,
             }
 
             parsed_bytes += head.len();
+            prev = Some(head.fourcc());
+        }
+
+        if let (Some(keys), Some(ilst)) = (&meta.keys, &mut meta.ilst) {
+            ilst.resolve_keys(keys);
         }
 
         Ok(meta)
@@ -55,7 +79,11 @@ impl ParseAtom for Meta<'_> {
 
 impl AtomSize for Meta<'_> {
     fn size(&self) -> Size {
-        let content_len = HEADER_SIZE + self.hdlr.len_or_zero() + self.ilst.len_or_zero();
+        let content_len = self.header_len()
+            + self.hdlr.len_or_zero()
+            + self.keys.len_or_zero()
+            + self.ilst.len_or_zero()
+            + self.free.len_or_zero();
         Size::from(content_len)
     }
 }
@@ -63,13 +91,21 @@ impl AtomSize for Meta<'_> {
 impl WriteAtom for Meta<'_> {
     fn write_atom(&self, writer: &mut impl Write, changes: &[Change<'_>]) -> crate::Result<()> {
         self.write_head(writer)?;
-        head::write_full(writer, 0, [0; 3])?;
+        if !self.quicktime_header {
+            head::write_full(writer, 0, [0; 3])?;
+        }
         if let Some(a) = &self.hdlr {
             a.write(writer, changes)?;
         }
+        if let Some(a) = &self.keys {
+            a.write(writer, changes)?;
+        }
         if let Some(a) = &self.ilst {
             a.write(writer, changes)?;
         }
+        if let Some(a) = &self.free {
+            a.write(writer, changes)?;
+        }
         Ok(())
     }
 }
@@ -85,11 +121,24 @@ impl SimpleCollectChanges for Meta<'_> {
         bounds: &AtomBounds,
         changes: &mut Vec<Change<'a>>,
     ) -> i64 {
-        self.hdlr.collect_changes(bounds.content_pos() + HEADER_SIZE, level, changes)
+        // the keys have to precede the item list that references them
+        let (ilst_pos, ilst_end) = match self.ilst.as_ref().map(|a| &a.state) {
+            Some(State::Existing(b) | State::Replace(b) | State::Remove(b)) => (b.pos(), b.end()),
+            _ => (bounds.end(), bounds.end()),
+        };
+        self.hdlr.collect_changes(bounds.content_pos() + self.header_len(), level, changes)
+            + self.keys.collect_changes(ilst_pos, level, changes)
             + self.ilst.collect_changes(bounds.end(), level, changes)
+            + self.free.collect_changes(ilst_end, level, changes)
     }
 
     fn atom_ref(&self) -> AtomRef<'_> {
         AtomRef::Meta(self)
     }
 }
+
+impl Meta<'_> {
+    fn header_len(&self) -> u64 {
+        if self.quicktime_header { 0 } else { HEADER_SIZE }
+    }
+}
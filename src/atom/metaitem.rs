@@ -7,6 +7,8 @@
 //! ├─ mean
 //! ├─ name
 //! └─ data
+use std::fmt;
+
 use super::*;
 
 /// A struct representing a metadata item, containing data that is associated with an identifier.
@@ -15,12 +17,119 @@ pub struct MetaItem {
     /// The identifier of the atom.
     pub ident: DataIdent,
     /// The data contained in the atom.
-    pub data: Vec<Data>,
+    pub data: ItemData,
+}
+
+/// The data of a metadata item. Most items contain exactly one data atom, which is stored inline
+/// to avoid an allocation.
+#[derive(Clone)]
+pub enum ItemData {
+    One(Data),
+    Many(Vec<Data>),
+}
+
+impl Default for ItemData {
+    fn default() -> Self {
+        Self::Many(Vec::new())
+    }
+}
+
+impl fmt::Debug for ItemData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl PartialEq for ItemData {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for ItemData {}
+
+impl Deref for ItemData {
+    type Target = [Data];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::One(d) => std::slice::from_ref(d),
+            Self::Many(v) => v,
+        }
+    }
+}
+
+impl DerefMut for ItemData {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::One(d) => std::slice::from_mut(d),
+            Self::Many(v) => v,
+        }
+    }
+}
+
+impl Extend<Data> for ItemData {
+    fn extend<T: IntoIterator<Item = Data>>(&mut self, iter: T) {
+        for d in iter {
+            self.push(d);
+        }
+    }
+}
+
+impl FromIterator<Data> for ItemData {
+    fn from_iter<T: IntoIterator<Item = Data>>(iter: T) -> Self {
+        let mut data = Self::default();
+        data.extend(iter);
+        data
+    }
+}
+
+impl IntoIterator for ItemData {
+    type Item = Data;
+    type IntoIter = std::iter::Chain<std::option::IntoIter<Data>, std::vec::IntoIter<Data>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Self::One(d) => Some(d).into_iter().chain(Vec::new()),
+            Self::Many(v) => None.into_iter().chain(v),
+        }
+    }
+}
+
+impl ItemData {
+    pub fn push(&mut self, data: Data) {
+        match self {
+            Self::Many(v) if v.is_empty() => *self = Self::One(data),
+            Self::Many(v) => v.push(data),
+            Self::One(_) => {
+                let Self::One(first) = std::mem::take(self) else { unreachable!() };
+                *self = Self::Many(vec![first, data]);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        match self {
+            Self::One(_) => *self = Self::default(),
+            Self::Many(v) => v.clear(),
+        }
+    }
+
+    pub fn retain(&mut self, mut predicate: impl FnMut(&Data) -> bool) {
+        match self {
+            Self::One(d) => {
+                if !predicate(d) {
+                    *self = Self::default();
+                }
+            }
+            Self::Many(v) => v.retain(predicate),
+        }
+    }
 }
 
 impl MetaItem {
     /// Creates a metadata item with the identifier and data.
-    pub const fn new(ident: DataIdent, data: Vec<Data>) -> Self {
+    pub const fn new(ident: DataIdent, data: ItemData) -> Self {
         Self { ident, data }
     }
 
@@ -30,7 +139,7 @@ impl MetaItem {
         let data_len: u64 = self.data.iter().map(Data::len).sum();
 
         match &self.ident {
-            DataIdent::Fourcc(_) => parent_len + data_len,
+            DataIdent::Fourcc(_) | DataIdent::Mdta(_) => parent_len + data_len,
             DataIdent::Freeform { mean, name } => {
                 let mean_len = 12 + mean.len() as u64;
                 let name_len = 12 + name.len() as u64;
@@ -45,9 +154,9 @@ impl MetaItem {
         cfg: &ParseConfig<'_>,
         head: Head,
     ) -> crate::Result<Self> {
-        let mut data = Vec::new();
-        let mut mean: Option<String> = None;
-        let mut name: Option<String> = None;
+        let mut data = ItemData::default();
+        let mut mean: Option<Cow<'static, str>> = None;
+        let mut name: Option<Cow<'static, str>> = None;
         let mut parsed_bytes = 0;
 
         while parsed_bytes < head.content_len() {
@@ -63,7 +172,8 @@ impl MetaItem {
                     }
                     expect_min_size("Mean (mean)", head.size(), 4)?;
 
-                    mean = Some(reader.read_utf8(head.content_len() - 4)?);
+                    let len = head.content_len() - 4;
+                    mean = Some(reader.read_interned_utf8(len, KNOWN_FREEFORM_MEANS)?);
                 }
                 NAME => {
                     let (version, _) = head::parse_full(reader)?;
@@ -72,9 +182,10 @@ impl MetaItem {
                     }
                     expect_min_size("Name (name)", head.size(), 4)?;
 
-                    name = Some(reader.read_utf8(head.content_len() - 4)?);
+                    let len = head.content_len() - 4;
+                    name = Some(reader.read_interned_utf8(len, KNOWN_FREEFORM_NAMES)?);
                 }
-                _ => reader.skip(head.content_len() as i64)?,
+                _ => skip_unknown(reader, cfg, head)?,
             }
 
             parsed_bytes += head.len();
@@ -88,17 +199,15 @@ impl MetaItem {
         Ok(MetaItem { ident, data })
     }
 
-    /// Attempts to write the metadata item to the writer.
-    pub fn write(&self, writer: &mut impl Write) -> crate::Result<()> {
+    /// Attempts to write the metadata item to the writer. The `key_index` is the 1-based index of
+    /// the key inside the keys (`keys`) atom, it's only used for [`DataIdent::Mdta`] identifiers.
+    pub fn write(&self, writer: &mut impl Write, key_index: u32) -> crate::Result<()> {
         writer.write_be_u32(self.len() as u32)?;
 
         match &self.ident {
             DataIdent::Fourcc(ident) => writer.write_all(ident.deref())?,
-            _ => {
-                let (mean, name) = match &self.ident {
-                    DataIdent::Freeform { mean, name } => (mean.as_ref(), name.as_ref()),
-                    DataIdent::Fourcc(_) => unreachable!(),
-                };
+            DataIdent::Mdta(_) => writer.write_be_u32(key_index)?,
+            DataIdent::Freeform { mean, name } => {
                 writer.write_all(FREEFORM.deref())?;
 
                 let mean_len: u32 = 12 + mean.len() as u32;
@@ -122,3 +231,27 @@ impl MetaItem {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn item_data() {
+        let mut data = ItemData::default();
+        data.push(Data::Utf8("1".into()));
+        assert!(matches!(data, ItemData::One(_)));
+
+        data.extend([Data::Utf8("2".into()), Data::Utf8("3".into())]);
+        assert_eq!(data.len(), 3);
+        assert_eq!(data, [1, 2, 3].map(|i| Data::Utf8(i.to_string())).into_iter().collect());
+
+        data.retain(|d| d.string() != Some("2"));
+        let strings: Vec<_> = data.iter().filter_map(Data::string).collect();
+        assert_eq!(strings, ["1", "3"]);
+
+        data.clear();
+        assert!(data.is_empty());
+        assert_eq!(data.into_iter().count(), 0);
+    }
+}
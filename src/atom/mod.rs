@@ -7,6 +7,8 @@
 //! ├─ mvhd
 //! ├─ trak
 //! │  ├─ tkhd
+//! │  ├─ edts
+//! │  │  └─ elst
 //! │  ├─ tref
 //! │  │  └─ chap
 //! │  └─ mdia
@@ -29,27 +31,41 @@
 //! │           ├─ stsz
 //! │           ├─ stco
 //! │           └─ co64
-//! └─ udta
-//!    ├─ chpl
-//!    └─ meta
-//!       ├─ hdlr
-//!       └─ ilst
-//!          ├─ **** (any fourcc)
-//!          │  └─ data
-//!          └─ ---- (freeform fourcc)
-//!             ├─ mean
-//!             ├─ name
-//!             └─ data
+//! ├─ udta
+//! │  ├─ chpl
+//! │  └─ meta
+//! │     ├─ hdlr
+//! │     ├─ keys (QuickTime metadata keys)
+//! │     ├─ ilst
+//! │     │  ├─ **** (any fourcc)
+//! │     │  │  └─ data
+//! │     │  └─ ---- (freeform fourcc)
+//! │     │     ├─ mean
+//! │     │     ├─ name
+//! │     │     └─ data
+//! │     └─ free (padding)
+//! └─ meta (written by some applications instead of udta/meta)
+//!    ├─ hdlr
+//!    └─ ilst
 //! ```
 
 use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "audio-info")]
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::num::NonZeroU32;
-use std::ops::Deref;
-
-use crate::{AudioInfo, Chapter, ErrorKind, Tag, Userdata};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::inspect::{FileLayout, Probe, SampleTable, TableBounds};
+use crate::track::TrackLanguage;
+use crate::{
+    AudioInfo, Chapter, ChapterSources, ChapterTrack, ErrorKind, Language, ParseWarning, Tag,
+    TextEncoding, TextSampleDescription, TrackFlags, Userdata, VideoInfo,
+};
 
 use change::{
     AtomRef, Change, ChunkOffsetInt, ChunkOffsets, CollectChanges, LeafAtomCollectChanges,
@@ -61,22 +77,32 @@ use state::State;
 use util::*;
 
 use chap::Chap;
+#[cfg(feature = "chapters")]
 use chpl::{Chpl, ChplData};
 use co64::Co64;
 use dinf::Dinf;
 use dref::Dref;
+use edts::Edts;
+use elst::Elst;
+#[cfg(feature = "chapters")]
+use elst::ElstEntry;
+use free::Free;
 use ftyp::Ftyp;
 use gmhd::Gmhd;
 use gmin::Gmin;
 use hdlr::Hdlr;
 use ilst::Ilst;
+use keys::Keys;
 use mdat::Mdat;
 use mdhd::Mdhd;
 use mdia::Mdia;
+use mehd::Mehd;
 use meta::Meta;
 use minf::Minf;
 use moov::Moov;
+#[cfg(feature = "audio-info")]
 use mp4a::Mp4a;
+use mvex::Mvex;
 use mvhd::Mvhd;
 use stbl::{Stbl, Table};
 use stco::Stco;
@@ -90,38 +116,55 @@ use trak::Trak;
 use tref::Tref;
 use udta::Udta;
 use url::*;
+use visual::Visual;
 
 pub use data::Data;
-pub use metaitem::MetaItem;
+pub(crate) use data::KNOWN_TYPES as KNOWN_DATA_TYPES;
+pub use metaitem::{ItemData, MetaItem};
 
 /// A module for working with identifiers.
 pub mod ident;
 
 #[macro_use]
 mod util;
+pub(crate) use util::{scale_duration, unscale_duration};
 mod change;
+#[cfg(feature = "chapters")]
+mod chapter;
+#[cfg(feature = "chapters")]
+pub use chapter::ChapterTrackIter;
+#[cfg(feature = "chapters")]
+pub(crate) use chapter::read_chapter_track_lazy;
 mod head;
 mod state;
 
 mod chap;
+#[cfg(feature = "chapters")]
 mod chpl;
 mod co64;
 mod data;
 mod dinf;
 mod dref;
+mod edts;
+mod elst;
+mod free;
 mod ftyp;
 mod gmhd;
 mod gmin;
 mod hdlr;
 mod ilst;
+mod keys;
 mod mdat;
 mod mdhd;
 mod mdia;
+mod mehd;
 mod meta;
 mod metaitem;
 mod minf;
 mod moov;
+#[cfg(feature = "audio-info")]
 mod mp4a;
+mod mvex;
 mod mvhd;
 mod stbl;
 mod stco;
@@ -135,6 +178,7 @@ mod trak;
 mod tref;
 mod udta;
 mod url;
+mod visual;
 
 trait Atom: Sized {
     const FOURCC: Fourcc;
@@ -146,11 +190,22 @@ trait ParseAtom: Atom {
         cfg: &ParseConfig<'_>,
         size: Size,
     ) -> crate::Result<Self> {
-        match Self::parse_atom(reader, cfg, size) {
+        let depth = cfg.depth.get();
+        let res = if depth >= cfg.cfg.max_depth {
+            Err(crate::Error::new(ErrorKind::NestingTooDeep, "Atoms are nested too deep"))
+        } else {
+            cfg.depth.set(depth + 1);
+            let res = Self::parse_atom(reader, cfg, size);
+            cfg.depth.set(depth);
+            res
+        };
+
+        match res {
             Err(mut e) => {
                 let mut d = e.description.into_owned();
                 insert_str(&mut d, "Error parsing ", Self::FOURCC);
                 e.description = d.into();
+                e.add_context(Self::FOURCC, reader.stream_position().ok());
                 Err(e)
             }
             a => a,
@@ -179,6 +234,7 @@ trait WriteAtom: AtomSize + Atom {
                 let mut d = e.description.into_owned();
                 insert_str(&mut d, "Error writing ", Self::FOURCC);
                 e.description = d.into();
+                e.add_context(Self::FOURCC, None);
                 Err(e)
             }
             a => a,
@@ -212,9 +268,11 @@ impl<T: AtomSize> LenOrZero for Option<T> {
     }
 }
 
+#[cfg(feature = "chapters")]
 trait PushAndGet<T> {
     fn push_and_get(&mut self, item: T) -> &mut T;
 }
+#[cfg(feature = "chapters")]
 impl<T> PushAndGet<T> for Vec<T> {
     fn push_and_get(&mut self, item: T) -> &mut T {
         self.push(item);
@@ -224,19 +282,28 @@ impl<T> PushAndGet<T> for Vec<T> {
 
 /// The timescale which is used for the chapter list (`chpl`).
 ///
-/// | library          | timescale  |
-/// |------------------|------------|
-/// | FFMpeg (default) | 10,000,000 |
-/// | mp4v2            |      1,000 |
-/// | mutagen          |       mvhd |
+/// | library          | timescale  | preset                      |
+/// |------------------|------------|-----------------------------|
+/// | FFMpeg (default) | 10,000,000 | [`ChplTimescale::FFMPEG`]   |
+/// | mp4v2            |      1,000 | [`ChplTimescale::MP4V2`]    |
+/// | mutagen          |       mvhd | [`ChplTimescale::MUTAGEN`]  |
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ChplTimescale {
     /// Use a fixed timescale: the number of units that pass per second.
     Fixed(NonZeroU32),
     /// Use the timescale defined in the movie header (mvhd) atom.
     Mvhd,
+    /// Detect the timescale when reading, by checking which of the timescales above places all
+    /// chapters inside the movie duration. If multiple ones do, the smallest one is used. The
+    /// detected timescale is reported by [`Tag::chpl_timescale`].
+    ///
+    /// When writing, this is equivalent to [`ChplTimescale::DEFAULT`].
+    Auto,
 }
 
+/// The timescale used by FFMpeg.
+const DEFAULT_CHPL_TIMESCALE: NonZeroU32 = NonZeroU32::new(10_000_000).unwrap();
+
 impl Default for ChplTimescale {
     fn default() -> Self {
         Self::DEFAULT
@@ -244,12 +311,140 @@ impl Default for ChplTimescale {
 }
 
 impl ChplTimescale {
-    pub const DEFAULT: Self = Self::Fixed(chpl::DEFAULT_TIMESCALE);
+    pub const DEFAULT: Self = Self::FFMPEG;
+    /// The timescale used by FFMpeg, and players based on it such as VLC and mpv.
+    pub const FFMPEG: Self = Self::Fixed(DEFAULT_CHPL_TIMESCALE);
+    /// The timescale used by mp4v2, and tools based on it such as mp4chaps.
+    pub const MP4V2: Self = Self::Fixed(NonZeroU32::new(1_000).unwrap());
+    /// The timescale used by mutagen, the timescale of the movie header (mvhd).
+    pub const MUTAGEN: Self = Self::Mvhd;
+}
+
+/// Which brands of the filetype (`ftyp`) atom are accepted when reading.
+///
+/// The brands aren't needed to read the metadata, so by default any file with a filetype atom is
+/// read, including unusual brands like `dash`, `M4VH` or `qt  `.
+///
+/// ```
+/// use mp4ameta::{BrandPolicy, ErrorKind, Fourcc, ReadConfig, Tag};
+///
+/// let cfg = ReadConfig {
+///     brand_policy: BrandPolicy::Strict(vec![Fourcc(*b"M4V ")].into()),
+///     ..ReadConfig::DEFAULT
+/// };
+/// let err = Tag::read_with_path("files/sample.m4a", &cfg).unwrap_err();
+/// assert!(matches!(err.kind, ErrorKind::UnsupportedBrand(Fourcc(b)) if b == *b"M4A "));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum BrandPolicy {
+    /// Only accept files whose major brand or any of the compatible brands is contained in the
+    /// list, otherwise an error of the kind [`ErrorKind::UnsupportedBrand`] is returned.
+    Strict(Cow<'static, [Fourcc]>),
+    /// Accept any file with a filetype atom, regardless of its brands.
+    #[default]
+    AnyIsoBmff,
+    /// Also accept classic QuickTime files (`.mov`) without a filetype atom, which start with a
+    /// `moov`, `mdat`, `free`, `skip` or `wide` atom instead. In that case
+    /// [`Tag::ftyp`](crate::Tag::ftyp) is empty.
+    ///
+    /// Such files can always be written, without adding a filetype atom, unless
+    /// [`WriteConfig::set_major_brand`] is set.
+    Skip,
+}
+
+/// Which representations of chapters are (over)written.
+///
+/// Chapters can either be stored inside a chapter list (`chpl`), or a chapter track. Some players
+/// only support one of them.
+///
+/// The chapter list is written from [`Userdata::chapter_list`](crate::Userdata::chapter_list) and
+/// the chapter track from [`Userdata::chapter_track`](crate::Userdata::chapter_track), so both
+/// can contain distinct chapters. The chapter list is stored entirely inside the movie (`moov`)
+/// atom, only the titles of the chapter track are stored in the media data (`mdat`) atom, so
+/// writing both in one pass never affects the other one.
+///
+/// If only one of them is written, while the file also contains the other one, they may contain
+/// different chapters afterwards. [`Userdata::write_with_warnings`] reports this as
+/// [`ParseWarning::UnchangedChapterList`] or [`ParseWarning::UnchangedChapterTrack`], and
+/// [`WriteConfig::remove_other_chapter_format`] removes the other one instead.
+///
+/// [`Userdata::write_with_warnings`]: crate::Userdata::write_with_warnings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteChapters {
+    /// Leave all chapter information intact.
+    None,
+    /// Only overwrite the chapter list (`chpl`).
+    ChapterList,
+    /// Only overwrite the chapter track.
+    ChapterTrack,
+    /// Overwrite both, the chapter list (`chpl`) and the chapter track.
+    #[default]
+    Both,
+    /// Only overwrite the representations that are already present in the file. If the file
+    /// contains neither a chapter list nor a chapter track, both are written.
+    PreferExisting,
+}
+
+impl WriteChapters {
+    /// Returns whether the chapter list and chapter track should be written, given which of them
+    /// are already present.
+    #[cfg(feature = "chapters")]
+    fn resolve(self, has_chapter_list: bool, has_chapter_track: bool) -> (bool, bool) {
+        match self {
+            Self::None => (false, false),
+            Self::ChapterList => (true, false),
+            Self::ChapterTrack => (false, true),
+            Self::Both => (true, true),
+            Self::PreferExisting if has_chapter_list || has_chapter_track => {
+                (has_chapter_list, has_chapter_track)
+            }
+            Self::PreferExisting => (true, true),
+        }
+    }
+}
+
+/// A player, or family of players, which a file is written for, see [`WriteConfig::for_player`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetPlayer {
+    /// Apple Books and iTunes, which only read the chapter track, and only list a file as an
+    /// audiobook if its major brand is `M4B `.
+    AppleBooks,
+    /// VLC, and other players based on FFMpeg, which read both the chapter list and the chapter
+    /// track.
+    Vlc,
+    /// Audiobookshelf, which reads chapters using ffprobe, and detects audiobooks by the file
+    /// extension.
+    Audiobookshelf,
+}
+
+/// Where the metadata item list (`ilst`) is written.
+///
+/// Most files store the item list inside `moov/udta/meta`, but some applications, notably on
+/// Android, store it directly inside `moov/meta`. When reading, both locations are considered. If
+/// both contain an item list, the one inside the user data (`udta`) atom takes precedence.
+///
+/// An item list in a location that isn't written to is removed, so no stale data is left behind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ItemListLocation {
+    /// Write the item list to `moov/udta/meta`.
+    UserData,
+    /// Write the item list to `moov/meta`.
+    Movie,
+    /// Write the item list to the locations that already contain one. If the file contains none,
+    /// it's written to `moov/udta/meta`.
+    #[default]
+    PreferExisting,
+}
 
-    fn fixed_or_mvhd(self, mvhd_timescale: u32) -> u32 {
+impl ItemListLocation {
+    /// Returns whether the item list should be written to `moov/udta/meta` and `moov/meta`, given
+    /// which of them already contain one.
+    fn resolve(self, has_udta_ilst: bool, has_moov_ilst: bool) -> (bool, bool) {
         match self {
-            Self::Fixed(v) => v.get(),
-            Self::Mvhd => mvhd_timescale,
+            Self::UserData => (true, false),
+            Self::Movie => (false, true),
+            Self::PreferExisting if has_moov_ilst => (has_udta_ilst, true),
+            Self::PreferExisting => (true, false),
         }
     }
 }
@@ -273,9 +468,27 @@ pub struct ReadConfig {
     pub read_chapter_track: bool,
     /// Wheter audio information will be read.
     /// Even if disabled, the [`AudioInfo::duration`] will be read.
+    /// Without the `audio-info` feature this has no effect.
     pub read_audio_info: bool,
+    /// Wheter video information will be read.
+    pub read_video_info: bool,
     /// The timescale that is used to scale time for chapter list (chpl) atoms.
     pub chpl_timescale: ChplTimescale,
+    /// Which brands of the filetype (`ftyp`) atom are accepted.
+    pub brand_policy: BrandPolicy,
+    /// The maximum nesting depth of atoms. If exceeded, an error of the kind
+    /// [`ErrorKind::NestingTooDeep`] is returned.
+    pub max_depth: u8,
+    /// The maximum size in bytes of a single data atom inside the metadata item list, for example
+    /// an image. If exceeded, an error of the kind [`ErrorKind::DataTooLarge`] is returned.
+    pub max_data_size: u64,
+    /// The size in bytes above which the content of a data atom inside the metadata item list
+    /// isn't read, to bound the memory usage. Instead it's represented as [`Data::Shallow`] and
+    /// can be read on demand using [`Data::get_or_read`] or [`Userdata::read_shallow_data`].
+    pub shallow_data_size: u64,
+    /// The maximum number of chapters inside the chapter list or chapter track. If exceeded, an
+    /// error of the kind [`ErrorKind::TooManyChapters`] is returned.
+    pub max_chapter_count: usize,
 }
 
 impl ReadConfig {
@@ -286,7 +499,21 @@ impl ReadConfig {
         read_chapter_list: true,
         read_chapter_track: true,
         read_audio_info: true,
+        read_video_info: true,
         chpl_timescale: ChplTimescale::DEFAULT,
+        brand_policy: BrandPolicy::AnyIsoBmff,
+        max_depth: 32,
+        max_data_size: u64::MAX,
+        shallow_data_size: u64::MAX,
+        max_chapter_count: usize::MAX,
+    };
+
+    /// The default configuration with stricter limits, for reading files from untrusted sources.
+    pub const UNTRUSTED: ReadConfig = ReadConfig {
+        max_depth: 16,
+        max_data_size: 16 * 1024 * 1024,
+        max_chapter_count: 10_000,
+        ..ReadConfig::DEFAULT
     };
 
     /// A configuration that would read no data at all.
@@ -306,7 +533,13 @@ impl ReadConfig {
         read_chapter_list: false,
         read_chapter_track: false,
         read_audio_info: false,
+        read_video_info: false,
         chpl_timescale: ChplTimescale::DEFAULT,
+        brand_policy: BrandPolicy::AnyIsoBmff,
+        max_depth: 32,
+        max_data_size: u64::MAX,
+        shallow_data_size: u64::MAX,
+        max_chapter_count: usize::MAX,
     };
 }
 
@@ -319,260 +552,243 @@ impl Default for ReadConfig {
 pub struct ParseConfig<'a> {
     cfg: &'a ReadConfig,
     write: bool,
+    warnings: Option<&'a RefCell<Vec<ParseWarning>>>,
+    depth: Cell<u8>,
+}
+
+impl<'a> ParseConfig<'a> {
+    fn new(
+        cfg: &'a ReadConfig,
+        write: bool,
+        warnings: Option<&'a RefCell<Vec<ParseWarning>>>,
+    ) -> Self {
+        Self { cfg, write, warnings, depth: Cell::new(0) }
+    }
+
+    fn warnings_enabled(&self) -> bool {
+        self.warnings.is_some()
+    }
+
+    fn warn(&self, warning: ParseWarning) {
+        if let Some(w) = self.warnings {
+            w.borrow_mut().push(warning);
+        }
+    }
+}
+
+/// The chapter information read from a file.
+#[derive(Default)]
+struct ReadChapters {
+    sources: ChapterSources,
+    list: Vec<Chapter>,
+    chpl_timescale: Option<u32>,
+    track: Vec<Chapter>,
+    tracks: Vec<ChapterTrack>,
+}
+
+/// Skips an atom that isn't known, and reports a warning.
+fn skip_unknown(
+    reader: &mut (impl Read + Seek),
+    cfg: &ParseConfig<'_>,
+    head: Head,
+) -> crate::Result<()> {
+    if cfg.warnings_enabled() {
+        let offset = reader.stream_position()? - head.head_len();
+        cfg.warn(ParseWarning::UnknownAtom { fourcc: head.fourcc(), offset });
+    }
+    reader.skip(head.content_len() as i64)?;
+    Ok(())
 }
 
-pub(crate) fn read_tag(reader: &mut (impl Read + Seek), cfg: &ReadConfig) -> crate::Result<Tag> {
-    let parse_cfg = ParseConfig { cfg, write: false };
+pub(crate) fn read_tag(
+    reader: &mut (impl Read + Seek),
+    cfg: &ReadConfig,
+    warnings: Option<&RefCell<Vec<ParseWarning>>>,
+) -> crate::Result<Tag> {
+    let parse_cfg = ParseConfig::new(cfg, false, warnings);
 
     let file_len = reader.seek(SeekFrom::End(0))?;
     reader.seek(SeekFrom::Start(0))?;
 
-    let ftyp = Ftyp::parse(reader, file_len)?;
+    let ftyp = parse_ftyp(reader, file_len, &cfg.brand_policy)?;
+    let ftyp_len = ftyp.as_ref().map_or(0, |f| f.len());
+    let mut moov = parse_moov(reader, &parse_cfg, file_len, ftyp_len)?;
 
-    let mut parsed_bytes = ftyp.size.len();
-    let mut moov = loop {
-        if parsed_bytes >= file_len {
-            return Err(crate::Error::new(
-                ErrorKind::AtomNotFound(MOVIE),
-                "Missing necessary data, no movie (moov) atom found",
-            ));
-        }
+    let mvhd = movie_header(&moov);
 
-        let remaining_bytes = file_len - parsed_bytes;
-        let head = head::parse(reader, remaining_bytes)?;
-        if head.fourcc() == MOVIE {
-            break Moov::parse(reader, &parse_cfg, head.size())?;
-        }
+    // prefer the item list inside the user data atom, if both exist
+    let udta_meta = moov.udta.as_mut().and_then(|a| a.meta.take());
+    let meta = match udta_meta {
+        Some(a) if a.ilst.is_some() => Some(a),
+        _ => moov.meta.take().filter(|a| a.ilst.is_some()).or(udta_meta),
+    };
+    let handler_name = meta.as_ref().and_then(|a| a.hdlr.as_ref()?.name());
+    let meta_items = meta.and_then(|a| a.ilst).map(|a| a.data.into_owned()).unwrap_or_default();
 
-        reader.skip(head.content_len() as i64)?;
-        parsed_bytes += head.len();
+    #[cfg(feature = "chapters")]
+    let chapters = {
+        let duration = scale_duration(mvhd.timescale, mvhd.duration);
+        chapter::read_chapters(reader, &parse_cfg, &mut moov, &mvhd, duration)?
     };
+    #[cfg(not(feature = "chapters"))]
+    let chapters = ReadChapters::default();
 
-    let mvhd = moov.mvhd;
-    let duration = scale_duration(mvhd.timescale, mvhd.duration);
-
-    let meta_items = moov
-        .udta
-        .as_mut()
-        .and_then(|a| a.meta.take())
-        .and_then(|a| a.ilst)
-        .map(|a| a.data.into_owned())
-        .unwrap_or_default();
-
-    // chapter list atom
-    let mut chapter_list = Vec::new();
-    if cfg.read_chapter_list
-        && let Some(udta) = moov.udta
-        && let Some(mut chpl) = udta.chpl.and_then(Chpl::into_owned)
-    {
-        let chpl_timescale = cfg.chpl_timescale.fixed_or_mvhd(mvhd.timescale);
+    let video_info = match cfg.read_video_info {
+        true => read_video_info(&moov.trak),
+        false => None,
+    };
 
-        chpl.sort_by_key(|c| c.start);
-        chapter_list.extend(chpl.into_iter().map(|c| Chapter {
-            start: scale_duration(chpl_timescale, c.start),
-            title: c.title,
-        }));
+    let info = audio_info(&mvhd, moov.trak, cfg.read_audio_info);
+
+    let userdata = Userdata {
+        meta_items,
+        chapter_list: chapters.list,
+        chapter_track: chapters.track,
+        #[cfg(feature = "image")]
+        thumbnail_cache: Default::default(),
+    };
+    Ok(Tag {
+        ftyp: ftyp.map(|f| f.string).unwrap_or_default(),
+        info,
+        chapter_sources: chapters.sources,
+        chapter_tracks: chapters.tracks,
+        chpl_timescale: chapters.chpl_timescale,
+        handler_name,
+        video_info,
+        userdata,
+    })
+}
+
+/// Parses the filetype atom (`ftyp`) and checks its brands according to the policy. Classic
+/// QuickTime files without one are only accepted by [`BrandPolicy::Skip`], in which case `None` is
+/// returned.
+fn parse_ftyp(
+    reader: &mut (impl Read + Seek),
+    file_len: u64,
+    policy: &BrandPolicy,
+) -> crate::Result<Option<Ftyp>> {
+    let ftyp = match policy {
+        BrandPolicy::Skip => Ftyp::parse_optional(reader, file_len)?,
+        _ => Some(Ftyp::parse(reader, file_len)?),
+    };
+    if let Some(ftyp) = &ftyp {
+        ftyp.check_brands(policy)?;
     }
+    Ok(ftyp)
+}
 
-    // chapter tracks
-    let mut chapter_track = Vec::new();
-    if cfg.read_chapter_track {
-        // https://developer.apple.com/documentation/quicktime-file-format/chapter_lists
-        // > If more than one enabled track includes a 'chap' track reference,
-        // > QuickTime uses the first chapter list that it finds.
-        let traks = &moov.trak;
-        let chapter_trak = traks.iter().find_map(|trak| {
-            let chap = trak.tref.as_ref().and_then(|tref| tref.chap.as_ref())?;
-            traks.iter().find(|trak| chap.chapter_ids.contains(&trak.tkhd.id))
-        });
-        if let Some(trak) = chapter_trak {
-            let Some(mdia) = &trak.mdia else {
-                return Err(crate::Error::new(
-                    ErrorKind::AtomNotFound(MEDIA),
-                    "Media (mdia) atom of chapter track not found",
-                ));
-            };
-            let Some(stbl) = mdia.minf.as_ref().and_then(|a| a.stbl.as_ref()) else {
-                return Err(crate::Error::new(
-                    ErrorKind::AtomNotFound(SAMPLE_TABLE),
-                    "Sample table (stbl) of chapter track not found",
-                ));
-            };
-            let Some(stsc) = &stbl.stsc else {
-                return Err(crate::Error::new(
-                    ErrorKind::AtomNotFound(SAMPLE_TABLE_SAMPLE_TO_CHUNK),
-                    "Sample table sample to chunk (stsc) atom of chapter track not found",
-                ));
-            };
-            let Some(stsz) = &stbl.stsz else {
-                return Err(crate::Error::new(
-                    ErrorKind::AtomNotFound(SAMPLE_TABLE_SAMPLE_SIZE),
-                    "Sample table sample size (stsz) atom of chapter track not found",
-                ));
-            };
-            let Some(stts) = &stbl.stts else {
-                return Err(crate::Error::new(
-                    ErrorKind::AtomNotFound(SAMPLE_TABLE_TIME_TO_SAMPLE),
-                    "Sample table time to sample (stts) atom of chapter track not found",
-                ));
-            };
-            let timescale = mdia.mdhd.timescale;
-
-            let stsc_items = stsc.items.get_or_read(reader)?;
-            let stsz_sizes = stsz.sizes.get_or_read(reader)?;
-            let stts_items = stts.items.get_or_read(reader)?;
-
-            chapter_track.reserve(stsz_sizes.len());
-
-            if let Some(co64) = &stbl.co64 {
-                let co64_offsets = co64.offsets.get_or_read(reader)?;
-
-                read_track_chapters(
-                    reader,
-                    &mut chapter_track,
-                    timescale,
-                    &co64_offsets,
-                    &stsc_items,
-                    stsz.uniform_sample_size,
-                    &stsz_sizes,
-                    &stts_items,
-                )
-                .map_err(|mut e| {
-                    let mut desc = e.description.into_owned();
-                    desc.insert_str(0, "Error reading chapters: ");
-                    e.description = desc.into();
-                    e
-                })?;
-            } else if let Some(stco) = &stbl.stco {
-                let stco_offsets = stco.offsets.get_or_read(reader)?;
-
-                chapter_track.reserve(stco.offsets.len());
-                read_track_chapters(
-                    reader,
-                    &mut chapter_track,
-                    timescale,
-                    &stco_offsets,
-                    stsc_items.as_ref(),
-                    stsz.uniform_sample_size,
-                    stsz_sizes.as_ref(),
-                    stts_items.as_ref(),
-                )
-                .map_err(|mut e| {
-                    let mut desc = e.description.into_owned();
-                    desc.insert_str(0, "Error reading chapters: ");
-                    e.description = desc.into();
-                    e
-                })?;
-            }
+/// Returns the video information of the first video track.
+fn read_video_info(traks: &[Trak]) -> Option<VideoInfo> {
+    traks.iter().find_map(|trak| {
+        let mdia = trak.mdia.as_ref()?;
+        if mdia.hdlr.as_ref().and_then(Hdlr::component_subtype) != Some(VIDEO_MEDIA) {
+            return None;
         }
+        let stbl = mdia.minf.as_ref()?.stbl.as_ref()?;
+        let visual = stbl.stsd.as_ref()?.visual.as_ref()?;
+        let (width, height) = match (trak.tkhd.width, trak.tkhd.height) {
+            (0, _) | (_, 0) => (visual.width as u32, visual.height as u32),
+            size => size,
+        };
+        Some(VideoInfo {
+            width,
+            height,
+            codec: visual.fourcc,
+            frame_count: stbl.stsz.as_ref().map_or(0, Stsz::sample_count),
+        })
+    })
+}
+
+/// Skips atoms until the movie atom (`moov`) is found, and parses it.
+/// Reads only the audio information, without the metadata, see [`crate::read_audio_info`].
+pub(crate) fn read_audio_info(reader: &mut (impl Read + Seek)) -> crate::Result<AudioInfo> {
+    let cfg = ReadConfig {
+        read_audio_info: true,
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::NONE
+    };
+    let parse_cfg = ParseConfig::new(&cfg, false, None);
+
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let ftyp = parse_ftyp(reader, file_len, &cfg.brand_policy)?;
+    let moov = parse_moov(reader, &parse_cfg, file_len, ftyp.map_or(0, |f| f.len()))?;
+
+    let mvhd = movie_header(&moov);
+    Ok(audio_info(&mvhd, moov.trak, cfg.read_audio_info))
+}
+
+/// Returns the movie header, with the duration of the movie extends header (`mehd`) if the movie
+/// header doesn't specify one.
+fn movie_header(moov: &Moov<'_>) -> Mvhd {
+    let mut mvhd = moov.mvhd.clone();
+    // fragmented files, like DASH initialization segments, may only specify the duration in the
+    // movie extends header (mehd)
+    if mvhd.duration == 0
+        && let Some(mehd) = moov.mvex.as_ref().and_then(|mvex| mvex.mehd.as_ref())
+    {
+        mvhd.duration = mehd.fragment_duration;
     }
+    mvhd
+}
 
-    let mut info = AudioInfo { duration, ..Default::default() };
-    if cfg.read_audio_info {
-        let mp4a = moov.trak.into_iter().find_map(|trak| {
-            trak.mdia
-                .and_then(|a| a.minf)
-                .and_then(|a| a.stbl)
-                .and_then(|a| a.stsd)
-                .and_then(|a| a.mp4a)
+#[cfg_attr(not(feature = "audio-info"), allow(unused_variables))]
+fn audio_info(mvhd: &Mvhd, trak: Vec<Trak>, read_audio_info: bool) -> AudioInfo {
+    #[cfg_attr(not(feature = "audio-info"), allow(unused_mut))]
+    let mut info = AudioInfo {
+        duration: scale_duration(mvhd.timescale, mvhd.duration),
+        timescale: mvhd.timescale,
+        raw_duration: mvhd.duration,
+        ..Default::default()
+    };
+    #[cfg(feature = "audio-info")]
+    if read_audio_info {
+        let mp4a = trak.into_iter().find_map(|trak| {
+            let mdia = trak.mdia?;
+            let mp4a = mdia.minf.and_then(|a| a.stbl).and_then(|a| a.stsd).and_then(|a| a.mp4a)?;
+            Some((mdia.mdhd, mp4a))
         });
-        if let Some(i) = mp4a {
+        if let Some((mdhd, i)) = mp4a {
+            info.media_timescale = Some(mdhd.timescale);
+            info.media_raw_duration = Some(mdhd.duration);
             info.channel_config = i.channel_config;
             info.sample_rate = i.sample_rate;
+            info.sample_rate_hz = i.sample_rate_hz;
+            info.aac_profile = i.aac_profile;
+            info.sbr = i.sbr;
+            info.ps = i.ps;
+            info.bit_depth = i.bit_depth;
             info.max_bitrate = i.max_bitrate;
             info.avg_bitrate = i.avg_bitrate;
         }
     }
-
-    let userdata = Userdata { meta_items, chapter_list, chapter_track };
-    Ok(Tag { ftyp: ftyp.string, info, userdata })
+    info
 }
 
-fn read_track_chapters<T: ChunkOffsetInt>(
+fn parse_moov(
     reader: &mut (impl Read + Seek),
-    chapters: &mut Vec<Chapter>,
-    timescale: u32,
-    offsets: &[T],
-    stsc: &[StscItem],
-    stsz_uniform_size: u32,
-    stsz_sizes: &[u32],
-    stts: &[SttsItem],
-) -> crate::Result<()> {
-    let mut time = 0;
-    let mut stco_idx = 0;
-    let mut stsz_iter = stsz_sizes.iter();
-    let mut stts_iter = stts.iter().flat_map(|stts_item| {
-        std::iter::repeat_n(stts_item.sample_duration, stts_item.sample_count as usize)
-    });
-
-    for (stsc_idx, stsc_item) in stsc.iter().enumerate() {
-        let stco_end_idx = match stsc.get(stsc_idx + 1) {
-            Some(next_stsc_item) => {
-                let end_idx = next_stsc_item.first_chunk as usize;
-                if end_idx > offsets.len() {
-                    return Err(crate::Error::new(
-                        ErrorKind::InvalidSampleTable,
-                        "Sample table sample to chunk (stsc) first chunk index is out of bounds",
-                    ));
-                }
-                end_idx
-            }
-            None => offsets.len(),
-        };
-
-        for o in offsets[stco_idx..stco_end_idx].iter().copied() {
-            let mut current_offset = o.into();
-
-            for _ in 0..stsc_item.samples_per_chunk {
-                let size = if stsz_uniform_size != 0 {
-                    stsz_uniform_size
-                } else {
-                    let Some(size) = stsz_iter.next() else {
-                        return Err(crate::Error::new(
-                            ErrorKind::InvalidSampleTable,
-                            "Missing sample table sample size (stsz) item",
-                        ));
-                    };
-                    *size
-                };
-                let Some(duration) = stts_iter.next() else {
-                    return Err(crate::Error::new(
-                        ErrorKind::InvalidSampleTable,
-                        "Missing sample time to sample (stts) duration",
-                    ));
-                };
-
-                let title = read_chapter_title(reader, current_offset)?;
-                chapters.push(Chapter { start: scale_duration(timescale, time), title });
-
-                time += duration as u64;
-
-                current_offset += size as u64;
-            }
+    cfg: &ParseConfig<'_>,
+    file_len: u64,
+    mut parsed_bytes: u64,
+) -> crate::Result<Moov<'static>> {
+    loop {
+        if parsed_bytes >= file_len {
+            return Err(crate::Error::new(
+                ErrorKind::AtomNotFound(MOVIE),
+                "Missing necessary data, no movie (moov) atom found",
+            ));
         }
 
-        stco_idx = stco_end_idx;
-    }
-
-    Ok(())
-}
-
-fn read_chapter_title(reader: &mut (impl Read + Seek), offset: u64) -> crate::Result<String> {
-    reader.seek(SeekFrom::Start(offset))?;
-    let len = reader.read_be_u16()?;
-    let bom = reader.read_be_u16()?;
-
-    // check BOM (byte order mark) for encoding
-    let title = match bom {
-        0xfeff => reader.read_be_utf16(len as u64 - 2)?,
-        0xfffe => reader.read_le_utf16(len as u64 - 2)?,
-        _ => {
-            reader.skip(-2)?;
-            reader.read_utf8(len as u64)?
+        let remaining_bytes = file_len - parsed_bytes;
+        let head = head::parse(reader, remaining_bytes)?;
+        if head.fourcc() == MOVIE {
+            return Moov::parse(reader, cfg, head.size());
         }
-    };
 
-    Ok(title)
+        reader.skip(head.content_len() as i64)?;
+        parsed_bytes += head.len();
+    }
 }
 
 /// Configure which metadata is (over)written.
@@ -585,21 +801,120 @@ fn read_chapter_title(reader: &mut (impl Read + Seek), offset: u64) -> crate::Re
 pub struct WriteConfig {
     /// Whether to overwrite the metadata item list.
     pub write_meta_items: bool,
-    /// Whether to overwrite chapter list information.
-    pub write_chapter_list: bool,
-    /// Whether to overwrite chapter track information.
-    pub write_chapter_track: bool,
+    /// Where the metadata item list is written.
+    pub item_list_location: ItemListLocation,
+    /// Which representations of chapter information to overwrite.
+    pub write_chapters: WriteChapters,
+    /// Whether to remove the chapter representation that isn't overwritten, if only one of them is
+    /// overwritten, so the file doesn't contain two sets of chapters that could differ. For
+    /// example with [`WriteChapters::ChapterTrack`] an existing chapter list (`chpl`) is removed.
+    pub remove_other_chapter_format: bool,
     /// The timescale that is used to scale time for chapter list (chpl) atoms.
     pub chpl_timescale: ChplTimescale,
+    /// Replace the major brand of the filetype (ftyp) atom, for example to convert an audio file
+    /// (`M4A `) into an audiobook (`M4B `). The brand is also added to the compatible brands.
+    pub set_major_brand: Option<Fourcc>,
+    /// Whether to read the file again after writing and verify that the written data matches,
+    /// and that all chunk offsets point inside the media data (`mdat`). If verification fails an
+    /// error of the kind [`ErrorKind::VerificationFailed`] is returned.
+    pub verify: bool,
+    /// Whether to truncate chapter track titles that exceed [`Chapter::MAX_TITLE_LEN`] bytes.
+    /// If disabled an error of the kind [`ErrorKind::ChapterTitleTooLong`] is returned instead.
+    pub truncate_chapter_titles: bool,
+    /// The text encoding used for chapter track titles.
+    pub chapter_text_encoding: TextEncoding,
+    /// The track header (`tkhd`) flags of the chapter track.
+    pub chapter_track_flags: TrackFlags,
+    /// The sample description of the chapter track's text samples, which specifies how chapter
+    /// titles are displayed. If `None` the description of an existing chapter track is preserved,
+    /// and a new chapter track uses [`TextSampleDescription::DEFAULT`]. Otherwise an existing
+    /// description is replaced, if it differs.
+    pub chapter_text_description: Option<TextSampleDescription>,
+    /// The language of a newly created chapter track. Existing chapter tracks keep their
+    /// language, as well as their track id.
+    pub chapter_track_language: Language,
+    /// The timescale of a newly created chapter track. If `None` the timescale of the movie
+    /// header (`mvhd`) is used, as ffmpeg does. Existing chapter tracks keep their timescale.
+    pub chapter_track_timescale: Option<u32>,
+    /// The track id of the chapter track to overwrite, if the file contains multiple chapter
+    /// tracks. See [`Tag::chapter_tracks`]. If `None` the first chapter track is overwritten.
+    ///
+    /// If no track with the id exists, an additional chapter track with this id is created, for
+    /// example to store chapters in another language. If the id belongs to a track that isn't a
+    /// chapter track, an error of the kind [`ErrorKind::InvalidChapterTrack`] is returned.
+    pub chapter_track_id: Option<u32>,
+    /// Replace the encoder (`©too`) with this value, for example the name and version of the
+    /// application writing the file. Only the written data is changed, the [`Userdata`] itself
+    /// isn't modified.
+    ///
+    /// If the encoder differs, the previous one is preserved, see
+    /// [`WriteConfig::original_encoder_ident`].
+    pub update_encoder: Option<String>,
+    /// The identifier under which the previous encoder is preserved, when the encoder is replaced
+    /// by [`WriteConfig::update_encoder`]. An original encoder that is already present isn't
+    /// overwritten, so the first encoder of a file is kept across multiple writes. If `None` the
+    /// previous encoder is discarded.
+    pub original_encoder_ident: Option<DataIdent>,
+    /// The number of bytes of padding, that is inserted after the metadata item list (`ilst`) as
+    /// a free atom (`free`), if the media data (`mdat`) has to be moved because the size of the
+    /// movie atom (`moov`) changes. If `0` no padding is inserted.
+    ///
+    /// Existing padding directly after the item list is used to absorb size changes of the movie
+    /// atom, so later writes don't have to move the media data again, as long as the padding is
+    /// large enough.
+    pub padding: u64,
+    /// The identifier of a revision counter, which is incremented on every write, so tools that
+    /// synchronize tags can detect concurrent edits. The counter is stored as a decimal string
+    /// and the written value is one more than the highest of the one in the file and the one
+    /// in the [`Userdata`]. If `None`, no revision counter is written.
+    ///
+    /// See [`ident::REVISION_IDENT`] for a default identifier.
+    pub revision_ident: Option<DataIdent>,
+    /// The text encoding of the written strings of items with 4 byte identifiers, which includes
+    /// all items set using the generated accessors, such as [`Userdata::set_title`]. If
+    /// [`TextEncoding::Utf16Be`] [`Data::Utf8`] strings are written as [`Data::Utf16`] instead,
+    /// which some legacy players require. [`Data::Utf16`] strings are always preserved. Only the
+    /// written data is changed, the [`Userdata`] itself isn't modified.
+    pub string_encoding: TextEncoding,
+    /// Aim for byte-identical output if nothing changed, so files can be diffed before and after
+    /// writing. No empty metadata item list (`ilst`) is created if there are no items to write,
+    /// and an unchanged chapter list (`chpl`) is kept as it is, instead of being rewritten with
+    /// [`WriteConfig::chpl_timescale`]. Items are always written in the order of the
+    /// [`Userdata`] with their original data type codes, and free atoms and handler (`hdlr`)
+    /// atoms are always kept, unless they have to be resized.
+    pub preserve_layout: bool,
+    /// Normalize all strings of the written items and chapter titles to this Unicode form, see
+    /// [`Userdata::normalize_strings`]. Only the written data is changed, the [`Userdata`] itself
+    /// isn't modified. If `None` strings are written as they are.
+    #[cfg(feature = "normalization")]
+    pub normalize_strings: Option<crate::UnicodeForm>,
 }
 
 impl WriteConfig {
     /// The default configuration for writing tags.
     pub const DEFAULT: WriteConfig = WriteConfig {
         write_meta_items: true,
-        write_chapter_list: true,
-        write_chapter_track: true,
+        item_list_location: ItemListLocation::PreferExisting,
+        write_chapters: WriteChapters::Both,
+        remove_other_chapter_format: false,
         chpl_timescale: ChplTimescale::DEFAULT,
+        set_major_brand: None,
+        verify: false,
+        truncate_chapter_titles: true,
+        chapter_text_encoding: TextEncoding::Utf8,
+        chapter_track_flags: TrackFlags::CHAPTER,
+        chapter_text_description: None,
+        chapter_track_language: Language::UNDETERMINED,
+        chapter_track_timescale: None,
+        chapter_track_id: None,
+        update_encoder: None,
+        original_encoder_ident: Some(ident::ORIGINAL_ENCODER_IDENT),
+        padding: 0,
+        revision_ident: None,
+        string_encoding: TextEncoding::Utf8,
+        preserve_layout: false,
+        #[cfg(feature = "normalization")]
+        normalize_strings: None,
     };
 
     /// A configuration that would write no data at all.
@@ -614,10 +929,65 @@ impl WriteConfig {
     /// ```
     pub const NONE: WriteConfig = WriteConfig {
         write_meta_items: false,
-        write_chapter_list: false,
-        write_chapter_track: false,
+        item_list_location: ItemListLocation::PreferExisting,
+        write_chapters: WriteChapters::None,
+        remove_other_chapter_format: false,
         chpl_timescale: ChplTimescale::DEFAULT,
+        set_major_brand: None,
+        verify: false,
+        truncate_chapter_titles: true,
+        chapter_text_encoding: TextEncoding::Utf8,
+        chapter_track_flags: TrackFlags::CHAPTER,
+        chapter_text_description: None,
+        chapter_track_language: Language::UNDETERMINED,
+        chapter_track_timescale: None,
+        chapter_track_id: None,
+        update_encoder: None,
+        original_encoder_ident: Some(ident::ORIGINAL_ENCODER_IDENT),
+        padding: 0,
+        revision_ident: None,
+        string_encoding: TextEncoding::Utf8,
+        preserve_layout: false,
+        #[cfg(feature = "normalization")]
+        normalize_strings: None,
     };
+
+    /// Returns the default configuration, adjusted for the quirks of the player:
+    ///
+    /// | player                           | chapters                | chpl timescale            |
+    /// |----------------------------------|-------------------------|---------------------------|
+    /// | [`TargetPlayer::AppleBooks`]     | chapter track, no chpl  | -                         |
+    /// | [`TargetPlayer::Vlc`]            | both                    | [`ChplTimescale::FFMPEG`] |
+    /// | [`TargetPlayer::Audiobookshelf`] | both                    | [`ChplTimescale::FFMPEG`] |
+    ///
+    /// For [`TargetPlayer::AppleBooks`] the major brand is also set to `M4B `.
+    ///
+    /// ```
+    /// use mp4ameta::{Fourcc, TargetPlayer, WriteChapters, WriteConfig};
+    ///
+    /// let cfg = WriteConfig {
+    ///     verify: true,
+    ///     ..WriteConfig::for_player(TargetPlayer::AppleBooks)
+    /// };
+    /// assert_eq!(cfg.write_chapters, WriteChapters::ChapterTrack);
+    /// assert!(cfg.remove_other_chapter_format);
+    /// assert_eq!(cfg.set_major_brand, Some(Fourcc(*b"M4B ")));
+    /// ```
+    pub const fn for_player(player: TargetPlayer) -> WriteConfig {
+        match player {
+            TargetPlayer::AppleBooks => WriteConfig {
+                write_chapters: WriteChapters::ChapterTrack,
+                remove_other_chapter_format: true,
+                set_major_brand: Some(Fourcc(*b"M4B ")),
+                ..WriteConfig::DEFAULT
+            },
+            TargetPlayer::Vlc | TargetPlayer::Audiobookshelf => WriteConfig {
+                write_chapters: WriteChapters::Both,
+                chpl_timescale: ChplTimescale::FFMPEG,
+                ..WriteConfig::DEFAULT
+            },
+        }
+    }
 }
 
 impl Default for WriteConfig {
@@ -636,13 +1006,63 @@ struct MovedData {
 ///
 /// This trait is the combination of the [`std::io`]
 /// stream traits with an additional method to resize the file.
+/// It's implemented for [`File`], in-memory buffers ([`Cursor<Vec<u8>>`]) and can be implemented
+/// for custom storage backends.
+///
+/// # Example
+/// ```
+/// use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+///
+/// use mp4ameta::StorageFile;
+///
+/// struct Backend(Cursor<Vec<u8>>);
+///
+/// impl Read for Backend {
+///     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+///         self.0.read(buf)
+///     }
+/// }
+///
+/// impl Write for Backend {
+///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+///         self.0.write(buf)
+///     }
+///
+///     fn flush(&mut self) -> std::io::Result<()> {
+///         self.0.flush()
+///     }
+/// }
+///
+/// impl Seek for Backend {
+///     fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+///         self.0.seek(pos)
+///     }
+/// }
+///
+/// impl StorageFile for Backend {
+///     fn set_len(&mut self, new_size: u64) -> mp4ameta::Result<()> {
+///         self.0.get_mut().resize(new_size as usize, 0);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut file: Box<dyn StorageFile> = Box::new(Backend(Cursor::new(Vec::new())));
+/// file.set_len(8).unwrap();
+/// assert_eq!(file.seek(SeekFrom::End(0)).unwrap(), 8);
+/// ```
 pub trait StorageFile: Read + Write + Seek {
     /// Resize the file. This method behaves the same as
     /// [`File::set_len`](std::fs::File::set_len).
     fn set_len(&mut self, new_size: u64) -> crate::Result<()>;
 }
 
-impl<T: StorageFile> StorageFile for &mut T {
+impl<T: StorageFile + ?Sized> StorageFile for &mut T {
+    fn set_len(&mut self, new_size: u64) -> crate::Result<()> {
+        T::set_len(self, new_size)
+    }
+}
+
+impl<T: StorageFile + ?Sized> StorageFile for Box<T> {
     fn set_len(&mut self, new_size: u64) -> crate::Result<()> {
         T::set_len(self, new_size)
     }
@@ -674,68 +1094,787 @@ impl StorageFile for Cursor<&mut Vec<u8>> {
     }
 }
 
-pub(crate) fn write_tag(
-    file: &mut impl StorageFile,
-    cfg: &WriteConfig,
-    userdata: &Userdata,
+pub(crate) fn read_layout(reader: &mut (impl Read + Seek)) -> crate::Result<FileLayout> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut layout = FileLayout { file_len, ..Default::default() };
+    let mut mdat_found = false;
+    let mut parsed_bytes = 0;
+    while parsed_bytes < file_len {
+        let head = match head::parse(reader, file_len - parsed_bytes) {
+            Ok(h) => h,
+            Err(e)
+                if matches!(
+                    e.kind,
+                    ErrorKind::InvalidAtomSize | ErrorKind::AtomSizeOutOfBounds
+                ) =>
+            {
+                layout.unparsed_len = file_len - parsed_bytes;
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+
+        match head.fourcc() {
+            MOVIE => {
+                layout.moov_len += head.len();
+                layout.moov_before_mdat |= !mdat_found;
+                read_container_layout(reader, &mut layout, MOVIE, head.content_len())?;
+            }
+            MEDIA_DATA => {
+                layout.mdat_len += head.len();
+                mdat_found = true;
+                reader.skip(head.content_len() as i64)?;
+            }
+            FREE | SKIP => {
+                layout.free_len += head.len();
+                reader.skip(head.content_len() as i64)?;
+            }
+            _ => reader.skip(head.content_len() as i64)?,
+        }
+
+        parsed_bytes += head.len();
+    }
+
+    Ok(layout)
+}
+
+fn read_container_layout(
+    reader: &mut (impl Read + Seek),
+    layout: &mut FileLayout,
+    parent: Fourcc,
+    len: u64,
 ) -> crate::Result<()> {
-    let mut reader = BufReader::new(&mut *file);
+    let mut parsed_bytes = 0;
+    while parsed_bytes < len {
+        let head = head::parse(reader, len - parsed_bytes)?;
+
+        match head.fourcc() {
+            FREE | SKIP => {
+                layout.free_len += head.len();
+                reader.skip(head.content_len() as i64)?;
+            }
+            CHAPTER_LIST => {
+                layout.chpl_len += head.len();
+                reader.skip(head.content_len() as i64)?;
+            }
+            ITEM_LIST => {
+                layout.ilst_len += head.len();
+                read_container_layout(reader, layout, ITEM_LIST, head.content_len())?;
+            }
+            METADATA => {
+                head::parse_full(reader)?;
+                read_container_layout(reader, layout, METADATA, head.content_len() - 4)?;
+            }
+            DATA if parent == ARTWORK => {
+                layout.artwork_len += head.content_len().saturating_sub(8);
+                reader.skip(head.content_len() as i64)?;
+            }
+            TRACK | MEDIA | MEDIA_INFORMATION | SAMPLE_TABLE | USER_DATA | ARTWORK => {
+                read_container_layout(reader, layout, head.fourcc(), head.content_len())?;
+            }
+            _ => reader.skip(head.content_len() as i64)?,
+        }
 
-    let old_file_len = reader.seek(SeekFrom::End(0))?;
+        parsed_bytes += head.len();
+    }
+
+    Ok(())
+}
+
+/// Reads the positions of the sample tables of all tracks, without reading their entries.
+pub(crate) fn read_sample_tables(
+    reader: &mut (impl Read + Seek),
+) -> crate::Result<Vec<SampleTable>> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
     reader.seek(SeekFrom::Start(0))?;
 
-    let ftyp = Ftyp::parse(&mut reader, old_file_len)?;
+    // the tables are only read shallowly when not parsing in write mode
+    let read_cfg = ReadConfig {
+        read_chapter_track: true,
+        read_video_info: true,
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::NONE
+    };
+    let cfg = ParseConfig::new(&read_cfg, false, None);
+    let ftyp = parse_ftyp(reader, file_len, &read_cfg.brand_policy)?;
+    let moov = parse_moov(reader, &cfg, file_len, ftyp.map_or(0, |f| f.len()))?;
 
-    let mut moov = None;
-    let mut mdat_bounds = None;
-    {
-        let read_cfg = ReadConfig {
-            read_meta_items: cfg.write_meta_items,
-            read_chapter_list: cfg.write_chapter_list,
-            read_chapter_track: cfg.write_chapter_track,
-            read_audio_info: false,
-            read_image_data: false,
-            chpl_timescale: ChplTimescale::default(),
+    let mut tables = Vec::new();
+    for trak in moov.trak.iter() {
+        let Some(mdia) = &trak.mdia else {
+            continue;
+        };
+        let Some(stbl) = mdia.minf.as_ref().and_then(|a| a.stbl.as_ref()) else {
+            continue;
         };
+        let stsz = stbl.stsz.as_ref();
+        let (chunk_offsets, co64) = match (&stbl.co64, &stbl.stco) {
+            (Some(co64), _) => (table_bounds(&co64.offsets), true),
+            (None, Some(stco)) => (table_bounds(&stco.offsets), false),
+            (None, None) => (TableBounds::default(), false),
+        };
+        tables.push(SampleTable {
+            track_id: trak.tkhd.id,
+            handler: mdia.hdlr.as_ref().and_then(Hdlr::component_subtype),
+            timescale: mdia.mdhd.timescale,
+            stts: stbl.stts.as_ref().map(|a| table_bounds(&a.items)).unwrap_or_default(),
+            stsc: stbl.stsc.as_ref().map(|a| table_bounds(&a.items)).unwrap_or_default(),
+            stsz: stsz.map(|a| table_bounds(&a.sizes)).unwrap_or_default(),
+            uniform_sample_size: stsz.map_or(0, |a| a.uniform_sample_size),
+            uniform_sample_count: stsz.map_or(0, |a| a.uniform_sample_count),
+            chunk_offsets,
+            co64,
+        });
+    }
 
-        let mut parsed_bytes = ftyp.size.len();
-        while parsed_bytes < old_file_len {
-            let remaining_bytes = old_file_len - parsed_bytes;
-            let head = head::parse(&mut reader, remaining_bytes)?;
-            let parse_cfg = ParseConfig { cfg: &read_cfg, write: true };
-            match head.fourcc() {
-                MOVIE => moov = Some(Moov::parse(&mut reader, &parse_cfg, head.size())?),
-                MEDIA_DATA => mdat_bounds = Some(Mdat::read_bounds(&mut reader, head.size())?),
-                _ => reader.skip(head.content_len() as i64)?,
-            }
+    Ok(tables)
+}
 
-            parsed_bytes += head.len();
+/// Returns the position of a shallow table. Tables are only fully read in write mode, or if
+/// they're empty.
+fn table_bounds<T>(table: &Table<T>) -> TableBounds {
+    match *table {
+        Table::Shallow { pos, num_entries } => TableBounds { pos, num_entries },
+        Table::Full(_) => TableBounds::default(),
+    }
+}
+
+/// Collects the offsets and sizes of the media data chunks of all audio tracks, in the order of
+/// the tracks and their chunk offset tables.
+pub(crate) fn read_audio_chunks(reader: &mut (impl Read + Seek)) -> crate::Result<Vec<(u64, u64)>> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    // parsing in write mode reads the handler (hdlr) and chunk offsets of all tracks
+    let read_cfg = ReadConfig {
+        read_chapter_track: true,
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::NONE
+    };
+    let cfg = ParseConfig::new(&read_cfg, true, None);
+    let ftyp = parse_ftyp(reader, file_len, &read_cfg.brand_policy)?;
+    let moov = parse_moov(reader, &cfg, file_len, ftyp.map_or(0, |f| f.len()))?;
+
+    let mut chunks = Vec::new();
+    for trak in moov.trak.iter() {
+        let Some(mdia) = &trak.mdia else {
+            continue;
+        };
+        if mdia.hdlr.as_ref().and_then(Hdlr::component_subtype) != Some(SOUND_MEDIA) {
+            continue;
         }
+        let Some(stbl) = mdia.minf.as_ref().and_then(|a| a.stbl.as_ref()) else {
+            continue;
+        };
+        for_each_trak_chunk(reader, stbl, |offset, size| chunks.push((offset, size)))?;
     }
 
-    let Some(mut moov) = moov else {
+    Ok(chunks)
+}
+
+/// Calls the function with the position and size of every chunk of the track's sample table.
+fn for_each_trak_chunk(
+    reader: &mut (impl Read + Seek),
+    stbl: &Stbl,
+    f: impl FnMut(u64, u64),
+) -> crate::Result<()> {
+    let Some(stsc) = &stbl.stsc else {
         return Err(crate::Error::new(
-            crate::ErrorKind::AtomNotFound(MOVIE),
-            "Missing necessary data, no movie (moov) atom found",
+            ErrorKind::AtomNotFound(SAMPLE_TABLE_SAMPLE_TO_CHUNK),
+            "Sample table sample to chunk (stsc) atom of track not found",
         ));
     };
-    let Some(mdat_bounds) = mdat_bounds else {
+    let Some(stsz) = &stbl.stsz else {
         return Err(crate::Error::new(
-            crate::ErrorKind::AtomNotFound(MEDIA_DATA),
-            "Missing necessary data, no media data (mdat) atom found",
+            ErrorKind::AtomNotFound(SAMPLE_TABLE_SAMPLE_SIZE),
+            "Sample table sample size (stsz) atom of track not found",
         ));
     };
 
-    // update atom hierarchy
-    let mut changes = Vec::new();
-    if cfg.write_meta_items || cfg.write_chapter_list || cfg.write_chapter_track {
-        update_userdata(&mut reader, &mut changes, &mut moov, &mdat_bounds, userdata, cfg)?;
+    let stsc_items = stsc.items.get_or_read(reader)?;
+    let stsz_sizes = stsz.sizes.get_or_read(reader)?;
+    let uniform_size = stsz.uniform_sample_size;
+    if let Some(co64) = &stbl.co64 {
+        let offsets = co64.offsets.get_or_read(reader)?;
+        stbl::for_each_chunk(&offsets, &stsc_items, uniform_size, &stsz_sizes, f)?;
+    } else if let Some(stco) = &stbl.stco {
+        let offsets = stco.offsets.get_or_read(reader)?;
+        stbl::for_each_chunk(&offsets, &stsc_items, uniform_size, &stsz_sizes, f)?;
+    } else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(SAMPLE_TABLE_CHUNK_OFFSET),
+            "Sample table chunk offset (stco or co64) atom of track not found",
+        ));
     }
+    Ok(())
+}
 
-    for trak in moov.trak.iter() {
-        if !trak.state.is_existing() {
-            continue;
-        }
+/// Removes all ranges of the media data (`mdat`) that aren't referenced by the sample table of any
+/// track, and returns the number of removed bytes. Fragmented files are left untouched.
+pub(crate) fn compact(file: &mut impl StorageFile) -> crate::Result<u64> {
+    let mut reader = BufReader::new(&mut *file);
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    // parsing in write mode reads the chunk offsets of all tracks
+    let read_cfg = ReadConfig {
+        read_chapter_track: true,
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::NONE
+    };
+    let cfg = ParseConfig::new(&read_cfg, true, None);
+    let ftyp = parse_ftyp(&mut reader, file_len, &read_cfg.brand_policy)?;
+
+    let mut moov = None;
+    let mut mdat_bounds = Vec::new();
+    let mut parsed_bytes = ftyp.map_or(0, |f| f.len());
+    while parsed_bytes < file_len {
+        let remaining_bytes = file_len - parsed_bytes;
+        let head = head::parse(&mut reader, remaining_bytes)?;
+        match head.fourcc() {
+            MOVIE => moov = Some(Moov::parse(&mut reader, &cfg, head.size())?),
+            MEDIA_DATA => mdat_bounds.push(Mdat::read_bounds(&mut reader, head.size())?),
+            _ => reader.skip(head.content_len() as i64)?,
+        }
+        parsed_bytes += head.len();
+    }
+
+    let Some(moov) = moov else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(MOVIE),
+            "Missing necessary data, no movie (moov) atom found",
+        ));
+    };
+    // the media data of fragments is referenced by the fragments themselves
+    if moov.mvex.is_some() {
+        return Ok(0);
+    }
+
+    // without the complete sample table of every track, referenced data can't be distinguished
+    // from unreferenced data
+    let stbls = moov.trak.iter().map(|trak| {
+        let stbl = trak.mdia.as_ref()?.minf.as_ref()?.stbl.as_ref()?;
+        (stbl.co64.is_some() || stbl.stco.is_some()).then_some(stbl)
+    });
+    let Some(stbls) = stbls.collect::<Option<Vec<_>>>() else {
+        return Ok(0);
+    };
+    let mut chunks = Vec::new();
+    for stbl in stbls.iter() {
+        for_each_trak_chunk(&mut reader, stbl, |offset, size| chunks.push((offset, size)))?;
+    }
+    chunks.sort_unstable();
+
+    let mut changes = Vec::new();
+    let mut removed = 0;
+    for bounds in mdat_bounds.iter() {
+        let mut unreferenced = Vec::new();
+        let mut pos = bounds.content_pos();
+        for &(offset, size) in chunks.iter() {
+            if offset + size <= pos || offset >= bounds.end() {
+                continue;
+            }
+            if offset > pos {
+                unreferenced.push((pos, offset - pos));
+            }
+            pos = pos.max(offset + size);
+        }
+        if pos < bounds.end() {
+            unreferenced.push((pos, bounds.end() - pos));
+        }
+
+        let len: u64 = unreferenced.iter().map(|(_, len)| len).sum();
+        if len == 0 {
+            continue;
+        }
+        removed += len;
+        changes.extend(unreferenced.into_iter().map(|(pos, len)| Change::RemoveMdat(pos, len)));
+        changes.push(Change::UpdateLen(UpdateAtomLen {
+            bounds,
+            fourcc: MEDIA_DATA,
+            len_diff: -(len as i64),
+        }));
+    }
+    if changes.is_empty() {
+        return Ok(0);
+    }
+
+    for stbl in stbls {
+        if let Some(co64) = &stbl.co64
+            && let State::Existing(bounds) = &co64.state
+        {
+            let offsets = ChunkOffsets::Co64(co64.offsets.get_or_read(&mut reader)?);
+            changes.push(Change::UpdateChunkOffset(UpdateChunkOffsets { bounds, offsets }));
+        }
+        if let Some(stco) = &stbl.stco
+            && let State::Existing(bounds) = &stco.state
+        {
+            let offsets = ChunkOffsets::Stco(stco.offsets.get_or_read(&mut reader)?);
+            changes.push(Change::UpdateChunkOffset(UpdateChunkOffsets { bounds, offsets }));
+        }
+    }
+
+    drop(reader);
+    write_changes(file, &mut changes)?;
+    Ok(removed)
+}
+
+pub(crate) fn read_probe(reader: &mut (impl Read + Seek)) -> crate::Result<Probe> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let ftyp = parse_ftyp(reader, file_len, &BrandPolicy::Skip)?;
+    let mut parsed_bytes = ftyp.as_ref().map_or(0, |f| f.len());
+    while parsed_bytes < file_len {
+        let head = head::parse(reader, file_len - parsed_bytes)?;
+        if head.fourcc() == MOVIE {
+            let read_cfg = ReadConfig::DEFAULT;
+            let cfg = ParseConfig::new(&read_cfg, false, None);
+            let mut state = ProbeState {
+                cfg: &cfg,
+                probe: Probe::default(),
+                mvhd: None,
+                mehd: None,
+            };
+            probe_container(reader, &mut state, MOVIE, head.content_len())?;
+
+            let mut probe = state.probe;
+            if let Some(brand) = ftyp.as_ref().and_then(|f| f.string.as_bytes().get(..4)) {
+                probe.brand = Fourcc(brand.try_into().unwrap());
+            }
+            let mut mvhd = state.mvhd.unwrap_or_default();
+            // fragmented files may only specify the duration in the movie extends header (mehd)
+            if mvhd.duration == 0
+                && let Some(mehd) = state.mehd
+            {
+                mvhd.duration = mehd.fragment_duration;
+            }
+            probe.duration = scale_duration(mvhd.timescale, mvhd.duration);
+            return Ok(probe);
+        }
+
+        reader.skip(head.content_len() as i64)?;
+        parsed_bytes += head.len();
+    }
+
+    Err(crate::Error::new(
+        ErrorKind::AtomNotFound(MOVIE),
+        "Missing necessary data, no movie (moov) atom found",
+    ))
+}
+
+/// Reads only the movie duration, see [`crate::read_duration`].
+pub(crate) fn read_duration(reader: &mut (impl Read + Seek)) -> crate::Result<Duration> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let read_cfg = ReadConfig {
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::NONE
+    };
+    let ftyp = parse_ftyp(reader, file_len, &read_cfg.brand_policy)?;
+    let ftyp_len = ftyp.map_or(0, |f| f.len());
+    let Some(moov) = find_child(reader, ftyp_len, file_len - ftyp_len, MOVIE)? else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(MOVIE),
+            "Missing necessary data, no movie (moov) atom found",
+        ));
+    };
+
+    let cfg = ParseConfig::new(&read_cfg, false, None);
+    let Some(bounds) = find_atom(reader, moov.clone(), MOVIE, &[MOVIE_HEADER])? else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(MOVIE_HEADER),
+            "Missing necessary data, no movie header (mvhd) atom found",
+        ));
+    };
+    let mut mvhd = Mvhd::parse(reader, &cfg, *bounds)?;
+
+    // fragmented files may only specify the duration in the movie extends header (mehd)
+    if mvhd.duration == 0
+        && let Some(bounds) =
+            find_atom(reader, moov, MOVIE, &[MOVIE_EXTENDS, MOVIE_EXTENDS_HEADER])?
+    {
+        mvhd.duration = Mehd::parse(reader, &cfg, *bounds)?.fragment_duration;
+    }
+    Ok(scale_duration(mvhd.timescale, mvhd.duration))
+}
+
+struct ProbeState<'a> {
+    cfg: &'a ParseConfig<'a>,
+    probe: Probe,
+    mvhd: Option<Mvhd>,
+    mehd: Option<Mehd>,
+}
+
+fn probe_container(
+    reader: &mut (impl Read + Seek),
+    state: &mut ProbeState<'_>,
+    parent: Fourcc,
+    len: u64,
+) -> crate::Result<()> {
+    let mut parsed_bytes = 0;
+    while parsed_bytes < len {
+        let head = head::parse(reader, len - parsed_bytes)?;
+
+        match head.fourcc() {
+            MOVIE_HEADER if parent == MOVIE => {
+                state.mvhd = Some(Mvhd::parse(reader, state.cfg, head.size())?);
+            }
+            MOVIE_EXTENDS_HEADER if parent == MOVIE_EXTENDS => {
+                state.mehd = Some(Mehd::parse(reader, state.cfg, head.size())?);
+            }
+            CHAPTER_REFERENCE if parent == TRACK_REFERENCE => {
+                state.probe.has_chapter_track = true;
+                reader.skip(head.content_len() as i64)?;
+            }
+            CHAPTER_LIST if parent == USER_DATA => {
+                state.probe.has_chpl = true;
+                reader.skip(head.content_len() as i64)?;
+            }
+            ITEM_LIST if parent == METADATA => {
+                state.probe.has_ilst |= head.content_len() > 0;
+                reader.skip(head.content_len() as i64)?;
+            }
+            METADATA => {
+                let header_len = skip_meta_header(reader, head.content_len())?;
+                probe_container(reader, state, METADATA, head.content_len() - header_len)?;
+            }
+            MOVIE_EXTENDS | TRACK | TRACK_REFERENCE | USER_DATA => {
+                probe_container(reader, state, head.fourcc(), head.content_len())?;
+            }
+            _ => reader.skip(head.content_len() as i64)?,
+        }
+
+        parsed_bytes += head.len();
+    }
+
+    Ok(())
+}
+
+/// Overwrites the artwork item (`covr`) of the item list with a single image, if it fits into the
+/// space of the existing one. Remaining space is filled with a free atom (`free`). Returns whether
+/// the artwork was replaced, the file is left untouched otherwise.
+pub(crate) fn replace_artwork_in_place(
+    file: &mut impl StorageFile,
+    image: Data,
+) -> crate::Result<bool> {
+    let mut reader = BufReader::new(&mut *file);
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    // like when writing, files without a filetype atom are accepted
+    let ftyp = parse_ftyp(&mut reader, file_len, &BrandPolicy::Skip)?;
+    let ftyp_len = ftyp.map_or(0, |f| f.len());
+    let Some(moov) = find_child(&mut reader, ftyp_len, file_len - ftyp_len, MOVIE)? else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(MOVIE),
+            "Missing necessary data, no movie (moov) atom found",
+        ));
+    };
+
+    // prefer the item list inside the user data atom, if both exist
+    let mut covr = None;
+    for path in [&[USER_DATA, METADATA, ITEM_LIST, ARTWORK][..], &[METADATA, ITEM_LIST, ARTWORK]] {
+        covr = find_atom(&mut reader, moov.clone(), MOVIE, path)?;
+        if covr.is_some() {
+            break;
+        }
+    }
+    let Some(covr) = covr else {
+        return Ok(false);
+    };
+
+    let item = MetaItem::new(DataIdent::Fourcc(ARTWORK), ItemData::One(image));
+    let gap = match covr.len().checked_sub(item.len()) {
+        Some(gap) if gap == 0 || gap >= Head::NORMAL_SIZE => gap,
+        _ => return Ok(false),
+    };
+
+    let mut writer = BufWriter::new(file);
+    writer.seek(SeekFrom::Start(covr.pos()))?;
+    item.write(&mut writer, 0)?;
+    if gap > 0 {
+        Free::new(gap - Head::NORMAL_SIZE).write(&mut writer, &[])?;
+    }
+    writer.flush()?;
+
+    Ok(true)
+}
+
+/// Reads the media language of all tracks, see [`crate::track_languages`].
+pub(crate) fn read_track_languages(
+    reader: &mut (impl Read + Seek),
+) -> crate::Result<Vec<TrackLanguage>> {
+    let moov = parse_tracks(reader)?;
+    let languages = moov.trak.iter().filter_map(|trak| {
+        let mdia = trak.mdia.as_ref()?;
+        Some(TrackLanguage {
+            track_id: trak.tkhd.id,
+            handler: mdia.hdlr.as_ref().and_then(Hdlr::component_subtype),
+            language: mdhd::unpack_language(mdia.mdhd.language),
+        })
+    });
+    Ok(languages.collect())
+}
+
+/// Overwrites the language of the media header (`mdhd`) of the track. Returns whether a track with
+/// the id exists, the file is left untouched otherwise.
+pub(crate) fn set_track_language(
+    file: &mut impl StorageFile,
+    track_id: u32,
+    language: Language,
+) -> crate::Result<bool> {
+    let moov = parse_tracks(&mut BufReader::new(&mut *file))?;
+    let mdhd = moov.trak.iter().find(|t| t.tkhd.id == track_id).and_then(|t| t.mdia.as_ref());
+    let Some(Mdhd { state: State::Existing(bounds), version, .. }) = mdhd.map(|m| &m.mdhd) else {
+        return Ok(false);
+    };
+
+    // version and flags, followed by the creation and modification time, timescale and duration
+    let offset = match version {
+        0 => 4 + 16,
+        _ => 4 + 28,
+    };
+    file.seek(SeekFrom::Start(bounds.content_pos() + offset))?;
+    file.write_all(&mdhd::pack_language(language).to_be_bytes())?;
+    file.flush()?;
+
+    Ok(true)
+}
+
+/// Parses the track header (`tkhd`), media header (`mdhd`) and handler (`hdlr`) of all tracks.
+fn parse_tracks(reader: &mut (impl Read + Seek)) -> crate::Result<Moov<'static>> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let read_cfg = ReadConfig {
+        read_video_info: true,
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::NONE
+    };
+    let cfg = ParseConfig::new(&read_cfg, false, None);
+    let ftyp = parse_ftyp(reader, file_len, &read_cfg.brand_policy)?;
+    parse_moov(reader, &cfg, file_len, ftyp.map_or(0, |f| f.len()))
+}
+
+/// Finds the atom at the path of identifiers, starting with the children of the parent atom.
+fn find_atom(
+    reader: &mut (impl Read + Seek),
+    mut bounds: AtomBounds,
+    mut parent: Fourcc,
+    path: &[Fourcc],
+) -> crate::Result<Option<AtomBounds>> {
+    for fourcc in path.iter().copied() {
+        reader.seek(SeekFrom::Start(bounds.content_pos()))?;
+        let mut content_pos = bounds.content_pos();
+        let mut content_len = bounds.content_len();
+        if parent == METADATA {
+            let header_len = skip_meta_header(reader, content_len)?;
+            content_pos += header_len;
+            content_len -= header_len;
+        }
+
+        match find_child(reader, content_pos, content_len, fourcc)? {
+            Some(b) => bounds = b,
+            None => return Ok(None),
+        }
+        parent = fourcc;
+    }
+    Ok(Some(bounds))
+}
+
+/// Finds the first atom with the identifier, among the atoms in the range.
+fn find_child(
+    reader: &mut (impl Read + Seek),
+    pos: u64,
+    len: u64,
+    fourcc: Fourcc,
+) -> crate::Result<Option<AtomBounds>> {
+    reader.seek(SeekFrom::Start(pos))?;
+    let mut parsed_bytes = 0;
+    while parsed_bytes < len {
+        let head = head::parse(reader, len - parsed_bytes)?;
+        if head.fourcc() == fourcc {
+            return Ok(Some(find_bounds(reader, head.size())?));
+        }
+
+        reader.skip(head.content_len() as i64)?;
+        parsed_bytes += head.len();
+    }
+    Ok(None)
+}
+
+/// Skips the version and flags of a metadata atom (`meta`), which QuickTime omits, and returns
+/// the number of skipped bytes.
+fn skip_meta_header(reader: &mut (impl Read + Seek), content_len: u64) -> crate::Result<u64> {
+    if content_len < meta::HEADER_SIZE {
+        return Err(crate::Error::new(
+            ErrorKind::InvalidAtomSize,
+            "Metadata (meta) atom is smaller than its header",
+        ));
+    }
+
+    let mut buf = [0; 8];
+    if content_len >= 8 {
+        reader.read_exact(&mut buf)?;
+        reader.skip(-8)?;
+        if buf[4..8] == *HANDLER_REFERENCE {
+            return Ok(0);
+        }
+    }
+    reader.skip(meta::HEADER_SIZE as i64)?;
+    Ok(meta::HEADER_SIZE)
+}
+
+pub(crate) fn write_tag(
+    file: &mut impl StorageFile,
+    cfg: &WriteConfig,
+    userdata: &Userdata,
+    #[cfg_attr(not(feature = "chapters"), allow(unused_variables))] warnings: Option<
+        &mut Vec<ParseWarning>,
+    >,
+) -> crate::Result<()> {
+    let updated_userdata;
+    let revised_userdata;
+    let userdata = match &cfg.update_encoder {
+        Some(encoder) if cfg.write_meta_items => {
+            updated_userdata =
+                update_encoder(userdata, encoder, cfg.original_encoder_ident.as_ref());
+            &updated_userdata
+        }
+        _ => userdata,
+    };
+    let encoded_userdata;
+    let userdata = match cfg.string_encoding {
+        TextEncoding::Utf16Be if cfg.write_meta_items => {
+            encoded_userdata = encode_utf16(userdata);
+            &encoded_userdata
+        }
+        _ => userdata,
+    };
+    #[cfg(feature = "normalization")]
+    let normalized_userdata;
+    #[cfg(feature = "normalization")]
+    let userdata = match cfg.normalize_strings {
+        Some(form) => {
+            let mut u = userdata.clone();
+            u.normalize_strings(form);
+            normalized_userdata = u;
+            &normalized_userdata
+        }
+        None => userdata,
+    };
+
+    let mut reader = BufReader::new(&mut *file);
+
+    // shallow data is read from the file that is written, before it's modified
+    let loaded_userdata;
+    let userdata = match userdata.has_shallow_data() {
+        true => {
+            let mut u = userdata.clone();
+            u.read_shallow_data(&mut reader)?;
+            loaded_userdata = u;
+            &loaded_userdata
+        }
+        false => userdata,
+    };
+
+    let old_file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut ftyp = Ftyp::parse_optional(&mut reader, old_file_len)?;
+
+    let mut moov = None;
+    let mut mdat_bounds = None;
+    {
+        let read_cfg = ReadConfig {
+            read_meta_items: cfg.write_meta_items,
+            read_chapter_list: cfg.write_chapters != WriteChapters::None,
+            read_chapter_track: cfg.write_chapters != WriteChapters::None,
+            read_audio_info: false,
+            read_video_info: false,
+            read_image_data: false,
+            chpl_timescale: ChplTimescale::default(),
+            ..ReadConfig::DEFAULT
+        };
+
+        let mut parsed_bytes = ftyp.as_ref().map_or(0, |f| f.len());
+        while parsed_bytes < old_file_len {
+            let remaining_bytes = old_file_len - parsed_bytes;
+            let head = head::parse(&mut reader, remaining_bytes)?;
+            let parse_cfg = ParseConfig::new(&read_cfg, true, None);
+            match head.fourcc() {
+                MOVIE => moov = Some(Moov::parse(&mut reader, &parse_cfg, head.size())?),
+                MEDIA_DATA => mdat_bounds = Some(Mdat::read_bounds(&mut reader, head.size())?),
+                _ => reader.skip(head.content_len() as i64)?,
+            }
+
+            parsed_bytes += head.len();
+        }
+    }
+
+    let Some(mut moov) = moov else {
+        return Err(crate::Error::new(
+            crate::ErrorKind::AtomNotFound(MOVIE),
+            "Missing necessary data, no movie (moov) atom found",
+        ));
+    };
+
+    let userdata = match &cfg.revision_ident {
+        Some(ident) if cfg.write_meta_items => {
+            revised_userdata = update_revision(userdata, ident, &moov);
+            &revised_userdata
+        }
+        _ => userdata,
+    };
+
+    // update atom hierarchy
+    let mut changes = Vec::new();
+    if let Some(brand) = cfg.set_major_brand {
+        ftyp.get_or_insert_default().set_major_brand(brand)?;
+    }
+    let had_mdat = mdat_bounds.is_some();
+    if cfg.write_meta_items && !(cfg.preserve_layout && is_unchanged_empty(&moov, userdata)) {
+        update_meta_items(&mut moov, userdata, cfg.item_list_location);
+    }
+    #[cfg(feature = "chapters")]
+    let written_chapters = match cfg.write_chapters {
+        WriteChapters::None => (None, None),
+        _ => chapter::update_chapters(
+            &mut reader,
+            &mut changes,
+            &mut moov,
+            (mdat_bounds.as_ref(), old_file_len),
+            userdata,
+            cfg,
+            warnings,
+        )?,
+    };
+    #[cfg(not(feature = "chapters"))]
+    let written_chapters: (Option<&[Chapter]>, Option<&[Chapter]>) = (None, None);
+
+    if cfg.write_meta_items {
+        let udta_meta = moov.udta.as_mut().and_then(|a| a.meta.as_mut());
+        for ilst in [udta_meta, moov.meta.as_mut()].into_iter().flatten().flat_map(|a| &mut a.ilst)
+        {
+            diff_item_list(&mut reader, &mut changes, ilst)?;
+        }
+    }
+    if cfg.write_meta_items
+        && let (State::Existing(moov_bounds), Some(mdat_bounds)) = (&moov.state, &mdat_bounds)
+        && moov_bounds.pos() < mdat_bounds.pos()
+    {
+        let len_diff = moov.collect_changes(0, 0, &mut Vec::new());
+        update_padding(&mut moov, len_diff, cfg.padding);
+    }
+
+    for trak in moov.trak.iter() {
+        if !trak.state.is_existing() {
+            continue;
+        }
 
         let Some(stbl) = (trak.mdia.as_ref())
             .filter(|mdia| mdia.state.is_existing())
@@ -766,8 +1905,34 @@ pub(crate) fn write_tag(
     }
 
     // collect changes
+    if let Some(ftyp) = &ftyp {
+        ftyp.collect_changes(0, 0, &mut changes);
+    }
     moov.collect_changes(0, 0, &mut changes);
 
+    // no more reading from here on
+    drop(reader);
+    write_changes(file, &mut changes)?;
+
+    if cfg.verify {
+        let mut reader = BufReader::new(file);
+        verify_tag(&mut reader, cfg, userdata, written_chapters, had_mdat).map_err(|mut e| {
+            if !matches!(e.kind, ErrorKind::VerificationFailed) {
+                let mut desc = e.description.into_owned();
+                desc.insert_str(0, "Error verifying written data: ");
+                e.description = desc.into();
+            }
+            e
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Sorts the changes and applies them to the file, moving all data in between.
+fn write_changes(file: &mut impl StorageFile, changes: &mut [Change<'_>]) -> crate::Result<()> {
+    let mut reader = BufReader::new(&mut *file);
+
     changes.sort_by(|a, b| {
         a.old_pos().cmp(&b.old_pos()).then_with(|| {
             // Fix sorting of zero-sized changes in child atoms.
@@ -816,7 +1981,8 @@ pub(crate) fn write_tag(
     let new_file_len = (old_file_len as i64 + len_diff) as u64;
     file.set_len(new_file_len)?;
 
-    let writer = &mut BufWriter::new(file);
+    let mut buf_writer = BufWriter::new(&mut *file);
+    let writer = &mut buf_writer;
 
     // write moved data
     for d in moved_data {
@@ -842,306 +2008,338 @@ pub(crate) fn write_tag(
             Change::Insert(i) => i.atom.write(writer, shifting_changes)?,
             Change::RemoveMdat(_, _) => (),
             Change::AppendMdat(_, d) => writer.write_all(d)?,
+            Change::Overwrite(_, d) => writer.write_all(d)?,
         }
 
         pos_shift += c.len_diff();
     }
 
     writer.flush()?;
+    drop(buf_writer);
 
     Ok(())
 }
 
-fn update_userdata<'a>(
+/// Re-reads the written file and compares it against the userdata. Also checks that all chunk
+/// offsets point inside a media data (`mdat`) atom. If the file didn't contain a media data atom
+/// before writing, only the chunk offsets of chapter tracks are checked.
+fn verify_tag(
     reader: &mut (impl Read + Seek),
-    changes: &mut Vec<Change<'a>>,
-    moov: &mut Moov<'a>,
-    mdat_bounds: &'a AtomBounds,
-    userdata: &'a Userdata,
     cfg: &WriteConfig,
+    userdata: &Userdata,
+    (chapter_list, chapter_track): (Option<&[Chapter]>, Option<&[Chapter]>),
+    had_mdat: bool,
 ) -> crate::Result<()> {
-    let udta = moov.udta.get_or_insert_default();
+    let read_cfg = ReadConfig {
+        read_meta_items: cfg.write_meta_items,
+        read_image_data: true,
+        read_chapter_list: chapter_list.is_some(),
+        read_chapter_track: chapter_track.is_some(),
+        read_audio_info: false,
+        read_video_info: false,
+        chpl_timescale: match cfg.chpl_timescale {
+            ChplTimescale::Auto => ChplTimescale::DEFAULT,
+            t => t,
+        },
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::DEFAULT
+    };
+    let tag = read_tag(reader, &read_cfg, None)?;
 
-    // item list (ilst)
-    if cfg.write_meta_items {
-        let meta = udta.meta.get_or_insert_default();
-        meta.hdlr.get_or_insert_with(Hdlr::meta);
+    // parse the atom hierarchy again, to check the chunk offsets
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let ftyp = Ftyp::parse_optional(reader, file_len)?;
+
+    // track references are needed to find the chapter track
+    let moov_cfg = ReadConfig {
+        read_chapter_track: chapter_track.is_some(),
+        ..ReadConfig::NONE
+    };
+    let mut moov = None;
+    let mut mdat_bounds = Vec::new();
+    let mut parsed_bytes = ftyp.as_ref().map_or(0, |f| f.len());
+    while parsed_bytes < file_len {
+        let remaining_bytes = file_len - parsed_bytes;
+        let head = head::parse(reader, remaining_bytes)?;
+        let parse_cfg = ParseConfig::new(&moov_cfg, true, None);
+        match head.fourcc() {
+            MOVIE => moov = Some(Moov::parse(reader, &parse_cfg, head.size())?),
+            MEDIA_DATA => mdat_bounds.push(Mdat::read_bounds(reader, head.size())?),
+            _ => reader.skip(head.content_len() as i64)?,
+        }
 
-        let ilst = meta.ilst.get_or_insert_default();
-        ilst.state.replace_existing();
-        ilst.data = Cow::Borrowed(&userdata.meta_items);
+        parsed_bytes += head.len();
     }
 
-    // chapter list
-    if cfg.write_chapter_list {
-        match udta.chpl.as_mut() {
-            None if userdata.chapter_list.is_empty() => (),
-            Some(chpl) if userdata.chapter_list.is_empty() => {
-                chpl.state.remove_existing();
-            }
-            _ => {
-                let chpl_timescale = cfg.chpl_timescale.fixed_or_mvhd(moov.mvhd.timescale);
-                let chpl = udta.chpl.get_or_insert_default();
-                chpl.state.replace_existing();
-                chpl.data = ChplData::Borrowed(chpl_timescale, &userdata.chapter_list);
-            }
-        }
+    let Some(moov) = moov else {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(MOVIE),
+            "Missing necessary data, no movie (moov) atom found",
+        ));
+    };
+
+    if let Some(brand) = cfg.set_major_brand
+        && !ftyp.is_some_and(|f| f.string.as_bytes().starts_with(&*brand))
+    {
+        return Err(crate::Error::new(
+            ErrorKind::VerificationFailed,
+            "Major brand of the filetype (ftyp) atom differs from the written one",
+        ));
     }
 
-    // chapter tracks
-    'chapter_track: {
-        if !cfg.write_chapter_track {
-            break 'chapter_track;
+    #[cfg(feature = "chapters")]
+    let chapter_trak_ids: Vec<u32> =
+        chapter::chapter_traks(&moov.trak).iter().map(|t| t.tkhd.id).collect();
+    #[cfg(not(feature = "chapters"))]
+    let chapter_trak_ids: Vec<u32> = Vec::new();
+    for trak in moov.trak.iter() {
+        if !had_mdat && !chapter_trak_ids.contains(&trak.tkhd.id) {
+            continue;
         }
+        let Some(stbl) = (trak.mdia.as_ref())
+            .and_then(|mdia| mdia.minf.as_ref())
+            .and_then(|minf| minf.stbl.as_ref())
+        else {
+            continue;
+        };
 
-        // https://developer.apple.com/documentation/quicktime-file-format/chapter_lists
-        // > If more than one enabled track includes a 'chap' track reference,
-        // > QuickTime uses the first chapter list that it finds.
-        let chapter_trak_idx = moov.trak.iter().find_map(|trak| {
-            let chap = trak.tref.as_ref().and_then(|tref| tref.chap.as_ref())?;
-            moov.trak.iter().position(|trak| chap.chapter_ids.contains(&trak.tkhd.id))
-        });
+        if let Some(co64) = &stbl.co64 {
+            let offsets = co64.offsets.get_or_read(reader)?;
+            verify_chunk_offsets(trak.tkhd.id, &offsets, &mdat_bounds)?;
+        }
+        if let Some(stco) = &stbl.stco {
+            let offsets = stco.offsets.get_or_read(reader)?;
+            verify_chunk_offsets(trak.tkhd.id, &offsets, &mdat_bounds)?;
+        }
+    }
 
-        if userdata.chapter_track.is_empty() {
-            let Some(idx) = chapter_trak_idx else {
-                // avoid doing redundant work
-                break 'chapter_track;
-            };
+    if cfg.write_meta_items && tag.userdata.meta_items != userdata.meta_items {
+        return Err(crate::Error::new(
+            ErrorKind::VerificationFailed,
+            "Metadata items read after writing differ from the written ones",
+        ));
+    }
 
-            // remove chapter track
-            let chapter_trak = &mut moov.trak[idx];
-            chapter_trak.state.remove_existing();
-
-            // remove all chap track references
-            for trak in moov.trak.iter_mut() {
-                let Some(tref) = &mut trak.tref else {
-                    continue;
-                };
-                let State::Existing(tref_bounds) = &tref.state else {
-                    continue;
-                };
-
-                let Some(chap) = &mut tref.chap else {
-                    continue;
-                };
-                let State::Existing(chap_bounds) = &chap.state else {
-                    continue;
-                };
-
-                if tref_bounds.content_len() == chap_bounds.len() {
-                    tref.state.remove_existing();
-                } else {
-                    chap.state.remove_existing();
-                }
-            }
+    #[cfg(feature = "chapters")]
+    chapter::verify_chapters(cfg, &tag, &moov, (chapter_list, chapter_track))?;
 
-            break 'chapter_track;
+    Ok(())
+}
+
+fn verify_chunk_offsets<T: ChunkOffsetInt>(
+    track_id: u32,
+    offsets: &[T],
+    mdat_bounds: &[AtomBounds],
+) -> crate::Result<()> {
+    for o in offsets.iter().copied() {
+        let o = o.into();
+        if !mdat_bounds.iter().any(|b| b.content_pos() <= o && o < b.end()) {
+            return Err(crate::Error::new(
+                ErrorKind::VerificationFailed,
+                format!(
+                    "Chunk offset {o} of track {track_id} points outside of the media data (mdat)"
+                ),
+            ));
         }
+    }
+    Ok(())
+}
 
-        // generate chapter track sample table
-        let mut new_chapter_media_data = Vec::new();
-        let duration = moov.mvhd.duration;
-        let chapter_timescale = moov.mvhd.timescale;
-        let chunk_offsets = vec![mdat_bounds.end()];
-        let mut sample_sizes = Vec::with_capacity(userdata.chapter_track.len());
-        let mut time_to_samples = Vec::with_capacity(userdata.chapter_track.len());
-        let mut chapters_iter = userdata.chapter_track.iter().peekable();
-        while let Some(c) = chapters_iter.next() {
-            let c_duration = match chapters_iter.peek() {
-                Some(next) => {
-                    let c_duration = next.start.saturating_sub(c.start);
-                    unscale_duration(chapter_timescale, c_duration)
-                }
-                None => {
-                    let start = unscale_duration(chapter_timescale, c.start);
-                    duration.saturating_sub(start)
-                }
-            };
+/// Returns a copy of the userdata with the encoder replaced, preserving the previous one.
+fn update_encoder(
+    userdata: &Userdata,
+    encoder: &str,
+    original_ident: Option<&DataIdent>,
+) -> Userdata {
+    let mut userdata = userdata.clone();
+    if let Some(original_ident) = original_ident
+        && let Some(previous) = userdata.encoder()
+        && previous != encoder
+        && userdata.strings_of(original_ident).next().is_none()
+    {
+        let previous = Data::Utf8(previous.to_owned());
+        userdata.set_data(original_ident.clone(), previous);
+    }
+    userdata.set_encoder(encoder);
+    userdata
+}
 
-            time_to_samples.push(SttsItem {
-                sample_count: 1,
-                sample_duration: c_duration as u32,
-            });
-
-            const ENCD: [u8; 12] = [
-                0, 0, 0, 12, // size
-                b'e', b'n', b'c', b'd', // fourcc
-                0, 0, 1, 0, // content
-            ];
-            let title_len = c.title.len().min(u16::MAX as usize);
-            let sample_size = 2 + title_len + ENCD.len();
-            sample_sizes.push(sample_size as u32);
-
-            new_chapter_media_data.write_be_u16(title_len as u16).ok();
-            new_chapter_media_data.write_utf8(&c.title[..title_len]).ok();
-            new_chapter_media_data.extend(ENCD);
+/// Returns a copy of the userdata with all utf-8 strings of items with 4 byte identifiers
+/// converted to utf-16.
+fn encode_utf16(userdata: &Userdata) -> Userdata {
+    let mut userdata = userdata.clone();
+    for item in userdata.meta_items.iter_mut() {
+        let is_utf8 = |d: &Data| matches!(d, Data::Utf8(_));
+        if item.ident.fourcc().is_none() || !item.data.iter().any(is_utf8) {
+            continue;
         }
-
-        let chapter_trak = match chapter_trak_idx {
-            Some(idx) => &mut moov.trak[idx],
-            None => {
-                let new_id = moov.trak.iter().map(|t| t.tkhd.id).max().unwrap() + 1;
-
-                // add chap track reference to all other tracks
-                for trak in moov.trak.iter_mut() {
-                    let tref = trak.tref.get_or_insert_default();
-                    let chap = tref.chap.get_or_insert_default();
-                    chap.state.replace_existing();
-                    chap.chapter_ids = vec![new_id];
-                }
-
-                // add chapter track
-                moov.trak.push_and_get(Trak {
-                    state: State::Insert,
-                    tkhd: Tkhd { version: 0, flags: [0, 0, 0], id: new_id, duration },
-                    ..Default::default()
-                })
+        for d in Arc::make_mut(item).data.iter_mut() {
+            if let Data::Utf8(s) = d {
+                *d = Data::Utf16(std::mem::take(s));
             }
-        };
+        }
+    }
+    userdata
+}
 
-        let mdia = chapter_trak.mdia.get_or_insert_with(|| Mdia {
-            state: State::Insert,
-            mdhd: Mdhd {
-                timescale: chapter_timescale,
-                duration,
-                ..Default::default()
-            },
-            ..Default::default()
-        });
+fn update_revision(userdata: &Userdata, ident: &DataIdent, moov: &Moov<'_>) -> Userdata {
+    let udta_ilst = moov.udta.as_ref().and_then(|a| a.meta.as_ref()?.ilst.as_ref());
+    let moov_ilst = moov.meta.as_ref().and_then(|a| a.ilst.as_ref());
+    let existing = [udta_ilst, moov_ilst]
+        .into_iter()
+        .flatten()
+        .flat_map(|a| a.data.iter())
+        .filter(|a| a.ident == *ident)
+        .flat_map(|a| a.data.iter().filter_map(Data::string));
+    let revision = existing
+        .chain(userdata.strings_of(ident))
+        .filter_map(|s| s.trim().parse::<u64>().ok())
+        .max()
+        .unwrap_or(0);
+
+    let mut userdata = userdata.clone();
+    userdata.set_data(ident.clone(), Data::Utf8(revision.saturating_add(1).to_string()));
+    userdata
+}
 
-        mdia.hdlr.get_or_insert_with(Hdlr::text_mdia);
-        let minf = mdia.minf.get_or_insert_default();
-
-        let gmhd = minf.gmhd.get_or_insert_default();
-        gmhd.gmin.get_or_insert_with(Gmin::chapter);
-        gmhd.text.get_or_insert_with(Text::media_information_chapter);
-
-        let dinf = minf.dinf.get_or_insert_default();
-        let dref = dinf.dref.get_or_insert_default();
-        dref.url.get_or_insert_with(Url::track);
-
-        let stbl = minf.stbl.get_or_insert_default();
-        let stsd = stbl.stsd.get_or_insert_default();
-        stsd.text.get_or_insert_with(Text::media_chapter);
-
-        let stts = stbl.stts.get_or_insert_default();
-        stts.state.replace_existing();
-        stts.items = Table::Full(time_to_samples);
-
-        let stsc = stbl.stsc.get_or_insert_default();
-        stsc.state.replace_existing();
-        let prev_stsc = std::mem::replace(
-            &mut stsc.items,
-            Table::Full(vec![StscItem {
-                first_chunk: 1,
-                samples_per_chunk: sample_sizes.len() as u32,
-                sample_description_id: 1,
-            }]),
-        );
-
-        let stsz = stbl.stsz.get_or_insert_default();
-        stsz.state.replace_existing();
-        let prev_stsz_uniform_sample_size = std::mem::replace(&mut stsz.uniform_sample_size, 0);
-        let prev_stsz_sizes = std::mem::replace(&mut stsz.sizes, Table::Full(sample_sizes));
-
-        let prev_stsc = prev_stsc.get_or_read(reader)?;
-        let prev_stsz_sizes = prev_stsz_sizes.get_or_read(reader)?;
-
-        let prev_stco = stbl.stco.as_mut().map(|stco| {
-            stco.state.remove_existing();
-            std::mem::take(&mut stco.offsets)
-        });
+fn update_meta_items<'a>(moov: &mut Moov<'a>, userdata: &'a Userdata, location: ItemListLocation) {
+    let has_udta_ilst =
+        moov.udta.as_ref().and_then(|a| a.meta.as_ref()).is_some_and(|a| a.ilst.is_some());
+    let has_moov_ilst = moov.meta.as_ref().is_some_and(|a| a.ilst.is_some());
+    let (write_udta, write_moov) = location.resolve(has_udta_ilst, has_moov_ilst);
+
+    // a newly created meta atom reuses the handler of the other one, to preserve vendor names
+    let udta_hdlr = moov.udta.as_ref().and_then(|a| a.meta.as_ref()?.hdlr.as_ref());
+    let moov_hdlr = moov.meta.as_ref().and_then(|a| a.hdlr.as_ref());
+    let hdlr = udta_hdlr.or(moov_hdlr).map(Hdlr::to_inserted);
+
+    if write_udta {
+        let udta = moov.udta.get_or_insert_default();
+        update_item_list(udta.meta.get_or_insert_default(), userdata, hdlr.as_ref());
+    } else if let Some(meta) = moov.udta.as_mut().and_then(|a| a.meta.as_mut()) {
+        remove_item_list(meta);
+    }
 
-        let co64 = stbl.co64.get_or_insert_default();
-        co64.state.replace_existing();
-        let prev_co64 = std::mem::replace(&mut co64.offsets, Table::Full(chunk_offsets));
-
-        // remove previous chapter data from the mdat atom
-        if co64.state.has_existed() {
-            let prev_co64 = prev_co64.get_or_read(reader)?;
-            remove_chapter_media_data(
-                changes,
-                &prev_co64,
-                &prev_stsc,
-                prev_stsz_uniform_sample_size,
-                &prev_stsz_sizes,
-            )?;
-        } else if let Some(prev_stco) = prev_stco {
-            let prev_stco = prev_stco.get_or_read(reader)?;
-            remove_chapter_media_data(
-                changes,
-                &prev_stco,
-                &prev_stsc,
-                prev_stsz_uniform_sample_size,
-                &prev_stsz_sizes,
-            )?;
-        }
+    if write_moov {
+        update_item_list(moov.meta.get_or_insert_default(), userdata, hdlr.as_ref());
+    } else if let Some(meta) = &mut moov.meta {
+        remove_item_list(meta);
+    }
+}
+
+/// Returns true if there are no items to write and the file doesn't contain an item list.
+fn is_unchanged_empty(moov: &Moov<'_>, userdata: &Userdata) -> bool {
+    let udta_meta = moov.udta.as_ref().and_then(|a| a.meta.as_ref());
+    let has_ilst = [udta_meta, moov.meta.as_ref()].into_iter().flatten().any(|a| a.ilst.is_some());
+    userdata.meta_items.is_empty() && !has_ilst
+}
 
-        if !new_chapter_media_data.is_empty() {
-            changes.push(Change::AppendMdat(mdat_bounds.end(), new_chapter_media_data));
+fn update_item_list<'a>(meta: &mut Meta<'a>, userdata: &'a Userdata, other_hdlr: Option<&Hdlr>) {
+    let items = &userdata.meta_items;
+    let has_keys = items.iter().any(|a| a.ident.mdta().is_some());
+    meta.hdlr.get_or_insert_with(|| {
+        // QuickTime expects the mdta handler, if all items reference keys
+        let hdlr = if has_keys && items.iter().all(|a| a.ident.mdta().is_some()) {
+            Hdlr::mdta()
+        } else {
+            Hdlr::meta()
+        };
+        match other_hdlr {
+            Some(h) if h.component_subtype() == hdlr.component_subtype() => h.clone(),
+            _ => hdlr,
         }
+    });
 
-        let len_diff = changes.iter().map(|c| c.len_diff()).sum();
-        if len_diff != 0 {
-            changes.push(Change::UpdateLen(UpdateAtomLen {
-                bounds: mdat_bounds,
-                fourcc: MEDIA_DATA,
-                len_diff,
-            }));
+    match &mut meta.keys {
+        Some(keys) if has_keys => {
+            keys.state.replace_existing();
+            keys.keys = Keys::from_items(items).keys;
         }
+        Some(keys) => keys.state.remove_existing(),
+        None if has_keys => meta.keys = Some(Keys::from_items(items)),
+        None => (),
     }
 
-    Ok(())
+    let ilst = meta.ilst.get_or_insert_default();
+    ilst.state.replace_existing();
+    ilst.data = Cow::Borrowed(items);
 }
 
-fn remove_chapter_media_data<T: ChunkOffsetInt>(
+/// If the replaced item list has the same length as the existing one, only the items that differ
+/// from the ones in the file are overwritten, instead of the whole item list.
+fn diff_item_list(
+    reader: &mut (impl Read + Seek),
     changes: &mut Vec<Change<'_>>,
-    offsets: &[T],
-    stsc: &[StscItem],
-    stsz_uniform_size: u32,
-    stsz_sizes: &[u32],
+    ilst: &mut Ilst<'_>,
 ) -> crate::Result<()> {
-    let mut stco_idx = 0;
-    let mut stsz_iter = stsz_sizes.iter();
-
-    for (stsc_idx, stsc_item) in stsc.iter().enumerate() {
-        let stco_end_idx = match stsc.get(stsc_idx + 1) {
-            Some(next_stsc_item) => {
-                let end_idx = next_stsc_item.first_chunk as usize;
-                if end_idx > offsets.len() {
-                    return Err(crate::Error::new(
-                        ErrorKind::InvalidSampleTable,
-                        "Sample table sample to chunk (stsc) first chunk index is out of bounds",
-                    ));
-                }
-                end_idx
-            }
-            None => offsets.len(),
-        };
-
-        for o in offsets[stco_idx..stco_end_idx].iter().copied() {
-            let offset = o.into();
-            let chunk_size = if stsz_uniform_size != 0 {
-                stsc_item.samples_per_chunk as u64 * stsz_uniform_size as u64
-            } else {
-                let mut chunk_size = 0;
-                for _ in 0..stsc_item.samples_per_chunk {
-                    let Some(size) = stsz_iter.next() else {
-                        return Err(crate::Error::new(
-                            ErrorKind::InvalidSampleTable,
-                            "Missing sample table sample size (stsz) item",
-                        ));
-                    };
-                    chunk_size += *size as u64;
-                }
-                chunk_size
-            };
+    let State::Replace(bounds) = &ilst.state else {
+        return Ok(());
+    };
+    if ilst.len() != bounds.len() || ilst.size().head_len() != bounds.head_len() {
+        return Ok(());
+    }
 
-            changes.push(Change::RemoveMdat(offset, chunk_size));
+    let mut new = Vec::with_capacity(ilst.len() as usize);
+    ilst.write(&mut new, &[])?;
+    let mut old = vec![0; bounds.len() as usize];
+    reader.seek(SeekFrom::Start(bounds.pos()))?;
+    reader.read_exact(&mut old)?;
+
+    let mut pos = bounds.head_len() as usize;
+    for item in ilst.data.iter() {
+        let end = pos + item.len() as usize;
+        if new[pos..end] != old[pos..end] {
+            changes.push(Change::Overwrite(bounds.pos() + pos as u64, new[pos..end].to_vec()));
         }
-
-        stco_idx = stco_end_idx;
+        pos = end;
     }
 
+    ilst.state = State::Existing(bounds.clone());
     Ok(())
 }
+
+/// Resizes the padding after the written item list to absorb the length difference of the movie
+/// atom, so the following media data doesn't have to be moved. If that isn't possible, the
+/// padding is replaced by a free atom of the configured size.
+fn update_padding(moov: &mut Moov<'_>, len_diff: i64, padding: u64) {
+    if len_diff == 0 {
+        return;
+    }
+
+    let udta_meta = moov.udta.as_mut().and_then(|a| a.meta.as_mut());
+    let Some(meta) = [udta_meta, moov.meta.as_mut()]
+        .into_iter()
+        .flatten()
+        .find(|a| a.ilst.as_ref().is_some_and(|a| !matches!(a.state, State::Remove(_))))
+    else {
+        return;
+    };
+
+    match &mut meta.free {
+        Some(free) if free.state.is_existing() => {
+            let new_len = free.len() as i64 - len_diff;
+            if new_len == 0 {
+                free.state.remove_existing();
+            } else if new_len >= Head::NORMAL_SIZE as i64 {
+                free.state.replace_existing();
+                free.content_len = new_len as u64 - Head::NORMAL_SIZE;
+            } else if padding > 0 {
+                free.state.replace_existing();
+                free.content_len = padding;
+            }
+        }
+        Some(_) => (),
+        None if padding > 0 => meta.free = Some(Free::new(padding)),
+        None => (),
+    }
+}
+
+fn remove_item_list(meta: &mut Meta<'_>) {
+    if let Some(keys) = &mut meta.keys {
+        keys.state.remove_existing();
+    }
+    if let Some(ilst) = &mut meta.ilst {
+        ilst.state.remove_existing();
+    }
+}
@@ -4,8 +4,12 @@ use super::*;
 pub struct Moov<'a> {
     pub state: State,
     pub mvhd: Mvhd,
+    pub mvex: Option<Mvex>,
     pub trak: Vec<Trak>,
     pub udta: Option<Udta<'a>>,
+    /// Some applications store the item list directly inside the movie atom, instead of the user
+    /// data atom.
+    pub meta: Option<Meta<'a>>,
 }
 
 impl Atom for Moov<'_> {
@@ -21,8 +25,10 @@ impl ParseAtom for Moov<'_> {
         let bounds = find_bounds(reader, size)?;
         let mut parsed_bytes = 0;
         let mut mvhd = None;
+        let mut mvex = None;
         let mut trak = Vec::new();
         let mut udta = None;
+        let mut meta = None;
 
         while parsed_bytes < size.content_len() {
             let remaining_bytes = size.content_len() - parsed_bytes;
@@ -30,12 +36,24 @@ impl ParseAtom for Moov<'_> {
 
             match head.fourcc() {
                 MOVIE_HEADER => mvhd = Some(Mvhd::parse(reader, cfg, head.size())?),
-                TRACK if cfg.write || cfg.cfg.read_chapter_track || cfg.cfg.read_audio_info => {
-                    trak.push(Trak::parse(reader, cfg, head.size())?)
+                MOVIE_EXTENDS if !cfg.write => mvex = Some(Mvex::parse(reader, cfg, head.size())?),
+                TRACK
+                    if cfg.write
+                        || cfg.cfg.read_chapter_track
+                        || cfg.cfg.read_audio_info
+                        || cfg.cfg.read_video_info =>
+                {
+                    let index = trak.len();
+                    trak.push(
+                        Trak::parse(reader, cfg, head.size()).map_err(|e| e.with_index(index))?,
+                    )
                 }
                 USER_DATA if cfg.cfg.read_meta_items || cfg.cfg.read_chapter_list => {
                     udta = Some(Udta::parse(reader, cfg, head.size())?)
                 }
+                METADATA if cfg.cfg.read_meta_items => {
+                    meta = Some(Meta::parse(reader, cfg, head.size())?)
+                }
                 _ => reader.skip(head.content_len() as i64)?,
             }
 
@@ -49,7 +67,14 @@ impl ParseAtom for Moov<'_> {
             )
         })?;
 
-        let moov = Self { state: State::Existing(bounds), mvhd, trak, udta };
+        let moov = Self {
+            state: State::Existing(bounds),
+            mvhd,
+            mvex,
+            trak,
+            udta,
+            meta,
+        };
 
         Ok(moov)
     }
@@ -59,7 +84,8 @@ impl AtomSize for Moov<'_> {
     fn size(&self) -> Size {
         let content_len = self.mvhd.len()
             + self.trak.iter().map(Trak::len).sum::<u64>()
-            + self.udta.len_or_zero();
+            + self.udta.len_or_zero()
+            + self.meta.len_or_zero();
         Size::from(content_len)
     }
 }
@@ -77,6 +103,7 @@ impl SimpleCollectChanges for Moov<'_> {
     ) -> i64 {
         self.trak.iter().map(|a| a.collect_changes(bounds.end(), level, changes)).sum::<i64>()
             + self.udta.collect_changes(bounds.end(), level, changes)
+            + self.meta.collect_changes(bounds.end(), level, changes)
     }
 
     fn atom_ref(&self) -> AtomRef<'_> {
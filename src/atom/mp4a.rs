@@ -34,10 +34,7 @@
 //!       │  └─ decoder specific descriptor
 //!       │     1 byte tag (0x05)
 //!       │     1~4 bytes len
-//!       │     5 bits profile
-//!       │     4 bits frequency index
-//!       │     4 bits channel config
-//!       │     3 bits ?
+//!       │     audio specific config
 //!       │
 //!       └─ sl config descriptor
 //!          1 byte tag (0x06)
@@ -47,7 +44,7 @@
 
 use std::cmp::min;
 
-use crate::{ChannelConfig, SampleRate};
+use crate::{AacProfile, ChannelConfig, SampleRate};
 
 use super::*;
 
@@ -60,10 +57,17 @@ const DECODER_CONFIG_DESCRIPTOR: u8 = 0x04;
 /// Decoder specific descriptor tag
 const DECODER_SPECIFIC_DESCRIPTOR: u8 = 0x05;
 
+/// The audio information of an MPEG-4 audio (`mp4a`) or Apple lossless audio (`alac`) sample
+/// entry.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Mp4a {
     pub channel_config: Option<ChannelConfig>,
     pub sample_rate: Option<SampleRate>,
+    pub sample_rate_hz: Option<u32>,
+    pub aac_profile: Option<AacProfile>,
+    pub sbr: bool,
+    pub ps: bool,
+    pub bit_depth: Option<u8>,
     pub max_bitrate: Option<u32>,
     pub avg_bitrate: Option<u32>,
 }
@@ -82,8 +86,7 @@ impl ParseAtom for Mp4a {
         let mut mp4a = Self::default();
 
         // use cursor over a buffer to avoid syscalls
-        let mut buf = vec![0; bounds.content_len() as usize];
-        reader.read_exact(&mut buf)?;
+        let mut buf = reader.read_u8_vec(bounds.content_len())?;
 
         let mut cursor = std::io::Cursor::new(&mut buf);
         cursor.skip(HEADER_SIZE as i64)?;
@@ -99,10 +102,80 @@ impl ParseAtom for Mp4a {
 
         parse_esds(&mut cursor, &mut mp4a, head.size())?;
 
+        // the sample rate of the sample entry is a 16.16 fixed point number, which can't
+        // represent rates above 65535Hz
+        if let (None, Some(&[h0, h1])) = (mp4a.sample_rate_hz, buf.get(24..26)) {
+            let hz = u16::from_be_bytes([h0, h1]) as u32;
+            if hz != 0 {
+                mp4a.sample_rate_hz = Some(hz);
+                mp4a.sample_rate = SampleRate::from_hz(hz);
+            }
+        }
+
         Ok(mp4a)
     }
 }
 
+impl Mp4a {
+    /// Parses an Apple lossless audio sample entry (`alac`).
+    ///
+    /// ```md
+    /// 28 bytes sample entry header
+    /// │
+    /// └─ alac atom
+    ///    4 bytes len
+    ///    4 bytes ident
+    ///    1 byte version
+    ///    3 bytes flags
+    ///    4 bytes frame length
+    ///    1 byte compatible version
+    ///    1 byte bit depth
+    ///    3 bytes rice parameters
+    ///    1 byte channel count
+    ///    2 bytes maximum run
+    ///    4 bytes maximum frame size
+    ///    4 bytes average bitrate
+    ///    4 bytes sample rate
+    /// ```
+    pub fn parse_alac(reader: &mut (impl Read + Seek), size: Size) -> crate::Result<Self> {
+        let bounds = find_bounds(reader, size)?;
+        expect_min_size("Apple lossless audio (alac)", size, HEADER_SIZE)?;
+
+        // use cursor over a buffer to avoid syscalls
+        let buf = reader.read_u8_vec(bounds.content_len())?;
+
+        let mut cursor = std::io::Cursor::new(&buf);
+        cursor.skip(HEADER_SIZE as i64)?;
+
+        let remaining_bytes = size.content_len() - HEADER_SIZE;
+        let head = head::parse(&mut cursor, remaining_bytes)?;
+        if head.fourcc() != APPLE_LOSSLESS_AUDIO {
+            return Err(crate::Error::new(
+                crate::ErrorKind::AtomNotFound(APPLE_LOSSLESS_AUDIO),
+                "Missing alac atom",
+            ));
+        }
+        expect_min_size("Apple lossless audio (alac)", head.size(), 28)?;
+
+        cursor.skip(4 + 5)?;
+        let bit_depth = cursor.read_u8()?;
+        cursor.skip(3)?;
+        let channel_count = cursor.read_u8()?;
+        cursor.skip(6)?;
+        let avg_bitrate = cursor.read_be_u32()?;
+        let sample_rate = cursor.read_be_u32()?;
+
+        Ok(Self {
+            channel_config: ChannelConfig::from_channel_count(channel_count),
+            sample_rate: SampleRate::from_hz(sample_rate),
+            sample_rate_hz: Some(sample_rate),
+            bit_depth: Some(bit_depth),
+            avg_bitrate: (avg_bitrate != 0).then_some(avg_bitrate),
+            ..Default::default()
+        })
+    }
+}
+
 /// esds atom
 ///
 /// ```md
@@ -215,22 +288,148 @@ fn parse_dc_desc(reader: &mut (impl Read + Seek), info: &mut Mp4a, len: u64) ->
 /// ```md
 /// 1 byte tag (0x05)
 /// 1~4 bytes len
-/// 5 bits profile
+/// audio specific config
+/// ```
+fn parse_ds_desc(reader: &mut (impl Read + Seek), info: &mut Mp4a, len: u64) -> crate::Result<()> {
+    let buf = reader.read_u8_vec(len)?;
+    let mut bits = BitReader { data: &buf, pos: 0 };
+    parse_audio_specific_config(&mut bits, info);
+    Ok(())
+}
+
+/// Audio object types
+const AAC_MAIN: u32 = 1;
+const AAC_LTP: u32 = 4;
+const SBR: u32 = 5;
+const PS: u32 = 29;
+/// Sync extension types
+const SBR_SYNC: u32 = 0x2b7;
+const PS_SYNC: u32 = 0x548;
+
+/// audio specific config
+///
+/// ```md
+/// 5 bits object type (6 more bits if 31)
 /// 4 bits frequency index
+/// 24 bits frequency (if the frequency index is 15)
 /// 4 bits channel config
-/// 3 bits ?
+/// if the object type is SBR (5) or PS (29), explicit signaling:
+///     4 bits extension frequency index
+///     24 bits extension frequency (if the extension frequency index is 15)
+///     5 bits object type (6 more bits if 31)
+/// if the object type is 1 to 4:
+///     3 bits general audio specific config
+/// if there are at least 16 bits left, backwards compatible signaling:
+///     11 bits sync extension type (0x2b7)
+///     5 bits extension object type (6 more bits if 31)
+///     1 bit SBR present flag
+///     if SBR is present:
+///         4 bits extension frequency index
+///         24 bits extension frequency (if the extension frequency index is 15)
+///         if there are at least 12 bits left:
+///             11 bits sync extension type (0x548)
+///             1 bit PS present flag
 /// ```
-fn parse_ds_desc(reader: &mut (impl Read + Seek), info: &mut Mp4a, len: u64) -> crate::Result<()> {
-    let num = reader.read_be_u16()?;
+fn parse_audio_specific_config(bits: &mut BitReader<'_>, info: &mut Mp4a) -> Option<()> {
+    let mut object_type = bits.read_object_type()?;
+
+    let freq_index = bits.read(4)?;
+    let hz = match freq_index {
+        15 => bits.read(24),
+        _ => SampleRate::try_from(freq_index as u8).ok().map(|r| r.hz()),
+    };
+    info.sample_rate_hz = hz;
+    info.sample_rate = hz.and_then(SampleRate::from_hz);
+
+    let channel_config = bits.read(4)?;
+    info.channel_config = ChannelConfig::try_from(channel_config as u8).ok();
+
+    let explicit = matches!(object_type, SBR | PS);
+    if explicit {
+        info.sbr = true;
+        info.ps = object_type == PS;
+        bits.read_frequency()?;
+        object_type = bits.read_object_type()?;
+    }
+    info.aac_profile = aac_profile(object_type, info.sbr, info.ps);
 
-    let freq_index = ((num >> 7) & 0x0F) as u8;
-    info.sample_rate = SampleRate::try_from(freq_index).ok();
+    // the general audio specific config of other object types, or one with a program config
+    // element, has a variable length
+    if !(AAC_MAIN..=AAC_LTP).contains(&object_type) || channel_config == 0 {
+        return Some(());
+    }
+    let _frame_length_flag = bits.read(1)?;
+    if bits.read(1)? == 1 {
+        let _core_coder_delay = bits.read(14)?;
+    }
+    let _extension_flag = bits.read(1)?;
 
-    let channel_config = ((num >> 3) & 0x0F) as u8;
-    info.channel_config = ChannelConfig::try_from(channel_config).ok();
+    if explicit || bits.remaining() < 16 || bits.read(11)? != SBR_SYNC {
+        return Some(());
+    }
+    if bits.read_object_type()? != SBR {
+        return Some(());
+    }
+    info.sbr = bits.read(1)? == 1;
+    if info.sbr {
+        bits.read_frequency()?;
+        if bits.remaining() >= 12 && bits.read(11)? == PS_SYNC {
+            info.ps = bits.read(1)? == 1;
+        }
+    }
+    info.aac_profile = aac_profile(object_type, info.sbr, info.ps);
 
-    reader.skip((len - 2) as i64)?;
-    Ok(())
+    Some(())
+}
+
+fn aac_profile(object_type: u32, sbr: bool, ps: bool) -> Option<AacProfile> {
+    let profile = match object_type {
+        1 => AacProfile::Main,
+        2 if ps => AacProfile::HeV2,
+        2 if sbr => AacProfile::He,
+        2 => AacProfile::Lc,
+        3 => AacProfile::Ssr,
+        4 => AacProfile::Ltp,
+        _ => return None,
+    };
+    Some(profile)
+}
+
+/// Reads big endian bit fields.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, num_bits: u8) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..num_bits {
+            let byte = self.data.get(self.pos / 8)?;
+            let bit = (byte >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | bit as u32;
+            self.pos += 1;
+        }
+        Some(value)
+    }
+
+    fn remaining(&self) -> usize {
+        (8 * self.data.len()).saturating_sub(self.pos)
+    }
+
+    fn read_object_type(&mut self) -> Option<u32> {
+        match self.read(5)? {
+            31 => Some(32 + self.read(6)?),
+            t => Some(t),
+        }
+    }
+
+    fn read_frequency(&mut self) -> Option<u32> {
+        match self.read(4)? {
+            15 => self.read(24),
+            i => SampleRate::try_from(i as u8).ok().map(|r| r.hz()),
+        }
+    }
 }
 
 fn parse_desc_head(reader: &mut impl Read) -> crate::Result<(u8, u64, u64)> {
@@ -0,0 +1,36 @@
+use super::*;
+
+/// The movie extends atom of a fragmented file, for example a DASH initialization segment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mvex {
+    pub mehd: Option<Mehd>,
+}
+
+impl Atom for Mvex {
+    const FOURCC: Fourcc = MOVIE_EXTENDS;
+}
+
+impl ParseAtom for Mvex {
+    fn parse_atom(
+        reader: &mut (impl Read + Seek),
+        cfg: &ParseConfig<'_>,
+        size: Size,
+    ) -> crate::Result<Self> {
+        let mut mvex = Self::default();
+        let mut parsed_bytes = 0;
+
+        while parsed_bytes < size.content_len() {
+            let remaining_bytes = size.content_len() - parsed_bytes;
+            let head = head::parse(reader, remaining_bytes)?;
+
+            match head.fourcc() {
+                MOVIE_EXTENDS_HEADER => mvex.mehd = Some(Mehd::parse(reader, cfg, head.size())?),
+                _ => reader.skip(head.content_len() as i64)?,
+            }
+
+            parsed_bytes += head.len();
+        }
+
+        Ok(mvex)
+    }
+}
@@ -14,6 +14,8 @@ pub struct Mvhd {
     pub flags: [u8; 3],
     pub timescale: u32,
     pub duration: u64,
+    /// The id the next added track should use. Zero if unknown.
+    pub next_track_id: u32,
 }
 
 #[derive(Default)]
@@ -94,6 +96,7 @@ impl ParseAtom for Mvhd {
                 reader.read_exact(buf.bytes_mut())?;
                 mvhd.timescale = u32::from_be_bytes(buf.timescale);
                 mvhd.duration = u32::from_be_bytes(buf.duration) as u64;
+                mvhd.next_track_id = u32::from_be_bytes(buf.next_track_id);
             }
             1 => {
                 expect_size("Movie header (mvhd) version 1", size, HEADER_SIZE_V1 as u64)?;
@@ -102,6 +105,7 @@ impl ParseAtom for Mvhd {
                 reader.read_exact(buf.bytes_mut())?;
                 mvhd.timescale = u32::from_be_bytes(buf.timescale);
                 mvhd.duration = u64::from_be_bytes(buf.duration);
+                mvhd.next_track_id = u32::from_be_bytes(buf.next_track_id);
             }
             _ => {
                 return unknown_version("movie header (mvhd)", version);
@@ -15,6 +15,7 @@ pub enum State {
 }
 
 impl State {
+    #[cfg_attr(not(feature = "chapters"), allow(dead_code))]
     pub fn has_existed(&self) -> bool {
         matches!(self, Self::Existing(_) | Self::Replace(_) | Self::Remove(_))
     }
@@ -1,3 +1,4 @@
+use super::change::RemoveAtom;
 use super::*;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -9,6 +10,53 @@ pub struct Stbl {
     pub stsz: Option<Stsz>,
     pub stco: Option<Stco>,
     pub co64: Option<Co64>,
+    /// Children that aren't modeled, such as the sync sample (`stss`) or sample group (`sgpd`,
+    /// `sbgp`) atoms. These are only parsed when writing, so they can be removed if the sample
+    /// tables change.
+    pub other: Vec<OtherAtom>,
+}
+
+/// A child of the sample table atom (`stbl`) whose content isn't parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OtherAtom {
+    pub state: State,
+    pub fourcc: Fourcc,
+}
+
+impl OtherAtom {
+    fn parse(reader: &mut (impl Read + Seek), head: Head) -> crate::Result<Self> {
+        let bounds = find_bounds(reader, head.size())?;
+        reader.skip(head.content_len() as i64)?;
+        Ok(Self {
+            state: State::Existing(bounds),
+            fourcc: head.fourcc(),
+        })
+    }
+
+    /// The length of the atom, or zero if it is removed.
+    fn len(&self) -> u64 {
+        match &self.state {
+            State::Existing(bounds) | State::Replace(bounds) => bounds.len(),
+            State::Remove(_) | State::Insert => 0,
+        }
+    }
+}
+
+impl CollectChanges for OtherAtom {
+    fn collect_changes<'a>(
+        &'a self,
+        _insert_pos: u64,
+        level: u8,
+        changes: &mut Vec<Change<'a>>,
+    ) -> i64 {
+        match &self.state {
+            State::Remove(bounds) => {
+                changes.push(Change::Remove(RemoveAtom { bounds, level: level + 1 }));
+                -(bounds.len() as i64)
+            }
+            _ => 0,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -48,7 +96,7 @@ impl<T: ReadItem> Table<T> {
     }
 
     pub fn read_items(reader: &mut impl Read, num_entries: u32) -> Result<Vec<T>, crate::Error> {
-        let mut items = Vec::with_capacity(num_entries as usize);
+        let mut items = Vec::with_capacity(prealloc_capacity::<T>(num_entries as u64));
         for _ in 0..num_entries {
             items.push(T::read_item(reader)?);
         }
@@ -56,6 +104,65 @@ impl<T: ReadItem> Table<T> {
     }
 }
 
+/// Calls `f` with the offset and size of every chunk referenced by the chunk offsets, in the order
+/// of the chunk offset table.
+pub fn for_each_chunk<T: ChunkOffsetInt>(
+    offsets: &[T],
+    stsc: &[StscItem],
+    stsz_uniform_size: u32,
+    stsz_sizes: &[u32],
+    mut f: impl FnMut(u64, u64),
+) -> crate::Result<()> {
+    let mut stco_idx = 0;
+    let mut stsz_iter = stsz_sizes.iter();
+
+    for (stsc_idx, stsc_item) in stsc.iter().enumerate() {
+        let stco_end_idx = match stsc.get(stsc_idx + 1) {
+            Some(next_stsc_item) => {
+                let end_idx = next_stsc_item.first_chunk as usize;
+                if end_idx > offsets.len() {
+                    return Err(crate::Error::new(
+                        ErrorKind::InvalidSampleTable,
+                        "Sample table sample to chunk (stsc) first chunk index is out of bounds",
+                    ));
+                }
+                if end_idx < stco_idx {
+                    return Err(crate::Error::new(
+                        ErrorKind::InvalidSampleTable,
+                        "Sample table sample to chunk (stsc) first chunk indices are decreasing",
+                    ));
+                }
+                end_idx
+            }
+            None => offsets.len(),
+        };
+
+        for o in offsets[stco_idx..stco_end_idx].iter().copied() {
+            let chunk_size = if stsz_uniform_size != 0 {
+                stsc_item.samples_per_chunk as u64 * stsz_uniform_size as u64
+            } else {
+                let mut chunk_size = 0;
+                for _ in 0..stsc_item.samples_per_chunk {
+                    let Some(size) = stsz_iter.next() else {
+                        return Err(crate::Error::new(
+                            ErrorKind::InvalidSampleTable,
+                            "Missing sample table sample size (stsz) item",
+                        ));
+                    };
+                    chunk_size += *size as u64;
+                }
+                chunk_size
+            };
+
+            f(o.into(), chunk_size);
+        }
+
+        stco_idx = stco_end_idx;
+    }
+
+    Ok(())
+}
+
 pub trait ReadItem: Sized + Clone {
     fn read_item(reader: &mut impl Read) -> std::io::Result<Self>;
 }
@@ -113,7 +220,9 @@ impl ParseAtom for Stbl {
             let head = head::parse(reader, remaining_bytes)?;
 
             match head.fourcc() {
-                SAMPLE_TABLE_SAMPLE_DESCRIPTION if cfg.write || cfg.cfg.read_audio_info => {
+                SAMPLE_TABLE_SAMPLE_DESCRIPTION
+                    if cfg.write || cfg.cfg.read_audio_info || cfg.cfg.read_video_info =>
+                {
                     stbl.stsd = Some(Stsd::parse(reader, cfg, head.size())?)
                 }
                 SAMPLE_TABLE_TIME_TO_SAMPLE if cfg.cfg.read_chapter_track => {
@@ -122,7 +231,9 @@ impl ParseAtom for Stbl {
                 SAMPLE_TABLE_SAMPLE_TO_CHUNK if cfg.cfg.read_chapter_track => {
                     stbl.stsc = Some(Stsc::parse(reader, cfg, head.size())?)
                 }
-                SAMPLE_TABLE_SAMPLE_SIZE if cfg.cfg.read_chapter_track => {
+                SAMPLE_TABLE_SAMPLE_SIZE
+                    if cfg.cfg.read_chapter_track || cfg.cfg.read_video_info =>
+                {
                     stbl.stsz = Some(Stsz::parse(reader, cfg, head.size())?)
                 }
                 SAMPLE_TABLE_CHUNK_OFFSET if cfg.write || cfg.cfg.read_chapter_track => {
@@ -131,6 +242,7 @@ impl ParseAtom for Stbl {
                 SAMPLE_TABLE_CHUNK_OFFSET_64 if cfg.write || cfg.cfg.read_chapter_track => {
                     stbl.co64 = Some(Co64::parse(reader, cfg, head.size())?)
                 }
+                _ if cfg.write => stbl.other.push(OtherAtom::parse(reader, head)?),
                 _ => reader.skip(head.content_len() as i64)?,
             }
 
@@ -148,7 +260,8 @@ impl AtomSize for Stbl {
             + self.stsc.len_or_zero()
             + self.stsz.len_or_zero()
             + self.stco.len_or_zero()
-            + self.co64.len_or_zero();
+            + self.co64.len_or_zero()
+            + self.other.iter().map(OtherAtom::len).sum::<u64>();
         Size::from(content_len)
     }
 }
@@ -195,6 +308,11 @@ impl SimpleCollectChanges for Stbl {
             + self.stsz.collect_changes(bounds.end(), level, changes)
             + self.stco.collect_changes(bounds.end(), level, changes)
             + self.co64.collect_changes(bounds.end(), level, changes)
+            + self
+                .other
+                .iter()
+                .map(|a| a.collect_changes(bounds.end(), level, changes))
+                .sum::<i64>()
     }
 
     fn atom_ref(&self) -> AtomRef<'_> {
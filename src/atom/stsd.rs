@@ -5,8 +5,11 @@ pub const HEADER_SIZE: u64 = 8;
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Stsd {
     pub state: State,
+    #[cfg(feature = "audio-info")]
     pub mp4a: Option<Mp4a>,
     pub text: Option<Text>,
+    /// Only parsed when reading, if the track is a video track.
+    pub visual: Option<Visual>,
 }
 
 impl Atom for Stsd {
@@ -40,8 +43,20 @@ impl ParseAtom for Stsd {
             let head = head::parse(reader, remaining_bytes)?;
 
             match head.fourcc() {
+                #[cfg(feature = "audio-info")]
                 MP4_AUDIO if !cfg.write => stsd.mp4a = Some(Mp4a::parse(reader, cfg, head.size())?),
+                #[cfg(feature = "audio-info")]
+                APPLE_LOSSLESS_AUDIO if !cfg.write => {
+                    stsd.mp4a = Some(Mp4a::parse_alac(reader, head.size())?)
+                }
                 TEXT_MEDIA if cfg.write => stsd.text = Some(Text::parse(reader, cfg, head.size())?),
+                _ if !cfg.write
+                    && cfg.cfg.read_video_info
+                    && stsd.visual.is_none()
+                    && head.content_len() >= visual::HEADER_SIZE =>
+                {
+                    stsd.visual = Some(Visual::parse(reader, head)?)
+                }
                 _ => reader.skip(head.content_len() as i64)?,
             }
 
@@ -8,9 +8,21 @@ pub struct Stsz {
     pub state: State,
     /// If this field is set to zero, a list of sizes is read instead.
     pub uniform_sample_size: u32,
+    /// The number of samples, if the uniform sample size is set.
+    pub uniform_sample_count: u32,
     pub sizes: Table<u32>,
 }
 
+impl Stsz {
+    /// Returns the number of samples.
+    pub fn sample_count(&self) -> u32 {
+        match self.uniform_sample_size {
+            0 => self.sizes.len() as u32,
+            _ => self.uniform_sample_count,
+        }
+    }
+}
+
 impl Atom for Stsz {
     const FOURCC: Fourcc = SAMPLE_TABLE_SAMPLE_SIZE;
 }
@@ -31,6 +43,7 @@ impl ParseAtom for Stsz {
         let uniform_sample_size = reader.read_be_u32()?;
 
         let num_entries = reader.read_be_u32()?;
+        let mut uniform_sample_count = 0;
         let sizes = if uniform_sample_size == 0 {
             let table_size = ENTRY_SIZE * num_entries as u64;
             expect_size("Sample table sample size (stsz)", size, HEADER_SIZE + table_size)?;
@@ -42,12 +55,14 @@ impl ParseAtom for Stsz {
             }
         } else {
             expect_size("Sample table sample size (stsz)", size, HEADER_SIZE)?;
+            uniform_sample_count = num_entries;
             Table::Full(Vec::new())
         };
 
         Ok(Self {
             state: State::Existing(bounds),
             uniform_sample_size,
+            uniform_sample_count,
             sizes,
         })
     }
@@ -66,7 +81,11 @@ impl WriteAtom for Stsz {
         head::write_full(writer, 0, [0; 3])?;
 
         writer.write_be_u32(self.uniform_sample_size)?;
-        writer.write_be_u32(self.sizes.len() as u32)?;
+        if self.uniform_sample_size != 0 {
+            writer.write_be_u32(self.uniform_sample_count)?;
+        } else {
+            writer.write_be_u32(self.sizes.len() as u32)?;
+        }
 
         match &self.sizes {
             Table::Shallow { .. } => unreachable!(),
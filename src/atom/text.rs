@@ -49,35 +49,12 @@ impl LeafAtomCollectChanges for Text {
     }
 }
 
+#[cfg(feature = "chapters")]
 impl Text {
-    pub fn media_chapter() -> Self {
+    pub fn media_chapter(description: &TextSampleDescription) -> Self {
         Self {
             state: State::Insert,
-            data: Cow::Borrowed(&[
-                // Text Sample Entry
-                0x00, 0x00, 0x00, 0x01, // displayFlags
-                0x00, 0x00, // horizontal and vertical justification
-                0x00, 0x00, 0x00, 0x00, // bg color rgba
-                // Box Record
-                0x00, 0x00, // def text box top
-                0x00, 0x00, // def text box left
-                0x00, 0x00, // def text box bottom
-                0x00, 0x00, // def text box right
-                // Style Record
-                0x00, 0x00, // start char
-                0x00, 0x00, // end char
-                0x00, 0x01, // font ID
-                0x00, // font style flags
-                0x00, // font size
-                0x00, 0x00, 0x00, 0x00, // fg color rgba
-                // Font Table Box
-                0x00, 0x00, 0x00, 0x0D, // box size
-                b'f', b't', b'a', b'b', // box atom name
-                0x00, 0x01, // entry count
-                // Font Record
-                0x00, 0x01, // font ID
-                0x00, // font name length
-            ]),
+            data: Cow::Owned(description.to_bytes()),
         }
     }
 
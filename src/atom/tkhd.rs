@@ -16,11 +16,19 @@ const MATRIX: [[[u8; 4]; 3]; 3] = [
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Tkhd {
+    pub state: State,
     pub version: u8,
     pub flags: [u8; 3],
     pub id: u32,
     /// The duration in mvhd timescale units
     pub duration: u64,
+    /// The integer part of the 16.16 fixed point presentation width.
+    pub width: u32,
+    /// The integer part of the 16.16 fixed point presentation height.
+    pub height: u32,
+    /// The content of an existing atom after the flags, which is preserved when it's rewritten.
+    /// Only the id and duration are updated.
+    pub data: Vec<u8>,
 }
 
 #[derive(Default)]
@@ -80,10 +88,14 @@ impl Atom for Tkhd {
 impl ParseAtom for Tkhd {
     fn parse_atom(
         reader: &mut (impl Read + Seek),
-        _cfg: &ParseConfig<'_>,
+        cfg: &ParseConfig<'_>,
         size: Size,
     ) -> crate::Result<Self> {
-        let mut tkhd = Self::default();
+        let bounds = find_bounds(reader, size)?;
+        let mut tkhd = Self {
+            state: State::Existing(bounds),
+            ..Default::default()
+        };
 
         let (version, flags) = head::parse_full(reader)?;
         tkhd.version = version;
@@ -97,6 +109,11 @@ impl ParseAtom for Tkhd {
                 reader.read_exact(buf.bytes_mut())?;
                 tkhd.id = u32::from_be_bytes(buf.id);
                 tkhd.duration = u32::from_be_bytes(buf.duration) as u64;
+                tkhd.width = u32::from_be_bytes(buf.track_width) >> 16;
+                tkhd.height = u32::from_be_bytes(buf.track_height) >> 16;
+                if cfg.write {
+                    tkhd.data = buf.bytes_mut().to_vec();
+                }
             }
             1 => {
                 expect_size("Track header (tkhd) version 1", size, HEADER_SIZE_V1 as u64)?;
@@ -105,6 +122,11 @@ impl ParseAtom for Tkhd {
                 reader.read_exact(buf.bytes_mut())?;
                 tkhd.id = u32::from_be_bytes(buf.id);
                 tkhd.duration = u64::from_be_bytes(buf.duration);
+                tkhd.width = u32::from_be_bytes(buf.track_width) >> 16;
+                tkhd.height = u32::from_be_bytes(buf.track_height) >> 16;
+                if cfg.write {
+                    tkhd.data = buf.bytes_mut().to_vec();
+                }
             }
             _ => {
                 return unknown_version("track header (tkhd)", version);
@@ -132,21 +154,21 @@ impl WriteAtom for Tkhd {
 
         match self.version {
             0 => {
-                let mut buf = TkhdBufV0 {
-                    id: u32::to_be_bytes(self.id),
-                    duration: u32::to_be_bytes(self.duration as u32),
-                    matrix: MATRIX,
-                    ..Default::default()
-                };
+                let mut buf = TkhdBufV0 { matrix: MATRIX, ..Default::default() };
+                if !self.data.is_empty() {
+                    buf.bytes_mut().copy_from_slice(&self.data);
+                }
+                buf.id = u32::to_be_bytes(self.id);
+                buf.duration = u32::to_be_bytes(self.duration as u32);
                 writer.write_all(buf.bytes_mut())?;
             }
             1 => {
-                let mut buf = TkhdBufV1 {
-                    id: u32::to_be_bytes(self.id),
-                    duration: u64::to_be_bytes(self.duration),
-                    matrix: MATRIX,
-                    ..Default::default()
-                };
+                let mut buf = TkhdBufV1 { matrix: MATRIX, ..Default::default() };
+                if !self.data.is_empty() {
+                    buf.bytes_mut().copy_from_slice(&self.data);
+                }
+                buf.id = u32::to_be_bytes(self.id);
+                buf.duration = u64::to_be_bytes(self.duration);
                 writer.write_all(buf.bytes_mut())?;
             }
             v => {
@@ -160,3 +182,13 @@ impl WriteAtom for Tkhd {
         Ok(())
     }
 }
+
+impl LeafAtomCollectChanges for Tkhd {
+    fn state(&self) -> &State {
+        &self.state
+    }
+
+    fn atom_ref(&self) -> AtomRef<'_> {
+        AtomRef::Tkhd(self)
+    }
+}
@@ -4,6 +4,7 @@ use super::*;
 pub struct Trak {
     pub state: State,
     pub tkhd: Tkhd,
+    pub edts: Option<Edts>,
     pub tref: Option<Tref>,
     pub mdia: Option<Mdia>,
 }
@@ -21,6 +22,7 @@ impl ParseAtom for Trak {
         let bounds = find_bounds(reader, size)?;
         let mut parsed_bytes = 0;
         let mut tkhd = None;
+        let mut edts = None;
         let mut tref = None;
         let mut mdia = None;
 
@@ -30,10 +32,16 @@ impl ParseAtom for Trak {
 
             match head.fourcc() {
                 TRACK_HEADER => tkhd = Some(Tkhd::parse(reader, cfg, head.size())?),
+                EDIT if cfg.write => edts = Some(Edts::parse(reader, cfg, head.size())?),
                 TRACK_REFERENCE if cfg.cfg.read_chapter_track => {
                     tref = Some(Tref::parse(reader, cfg, head.size())?)
                 }
-                MEDIA if cfg.write || cfg.cfg.read_chapter_track || cfg.cfg.read_audio_info => {
+                MEDIA
+                    if cfg.write
+                        || cfg.cfg.read_chapter_track
+                        || cfg.cfg.read_audio_info
+                        || cfg.cfg.read_video_info =>
+                {
                     mdia = Some(Mdia::parse(reader, cfg, head.size())?)
                 }
                 _ => reader.skip(head.content_len() as i64)?,
@@ -49,7 +57,13 @@ impl ParseAtom for Trak {
             )
         })?;
 
-        let trak = Self { state: State::Existing(bounds), tkhd, tref, mdia };
+        let trak = Self {
+            state: State::Existing(bounds),
+            tkhd,
+            edts,
+            tref,
+            mdia,
+        };
 
         Ok(trak)
     }
@@ -57,7 +71,10 @@ impl ParseAtom for Trak {
 
 impl AtomSize for Trak {
     fn size(&self) -> Size {
-        let content_len = self.tkhd.len() + self.tref.len_or_zero() + self.mdia.len_or_zero();
+        let content_len = self.tkhd.len()
+            + self.edts.len_or_zero()
+            + self.tref.len_or_zero()
+            + self.mdia.len_or_zero();
         Size::from(content_len)
     }
 }
@@ -66,6 +83,9 @@ impl WriteAtom for Trak {
     fn write_atom(&self, writer: &mut impl Write, changes: &[Change<'_>]) -> crate::Result<()> {
         self.write_head(writer)?;
         self.tkhd.write(writer, changes)?;
+        if let Some(a) = &self.edts {
+            a.write(writer, changes)?;
+        }
         if let Some(a) = &self.tref {
             a.write(writer, changes)?;
         }
@@ -87,7 +107,9 @@ impl SimpleCollectChanges for Trak {
         bounds: &'a AtomBounds,
         changes: &mut Vec<Change<'a>>,
     ) -> i64 {
-        self.tref.collect_changes(bounds.end(), level, changes)
+        self.tkhd.collect_changes(bounds.end(), level, changes)
+            + self.edts.collect_changes(bounds.end(), level, changes)
+            + self.tref.collect_changes(bounds.end(), level, changes)
             + self.mdia.collect_changes(bounds.end(), level, changes)
     }
 
@@ -3,6 +3,7 @@ use super::*;
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Udta<'a> {
     pub state: State,
+    #[cfg(feature = "chapters")]
     pub chpl: Option<Chpl<'a>>,
     pub meta: Option<Meta<'a>>,
 }
@@ -29,13 +30,17 @@ impl ParseAtom for Udta<'_> {
             let head = head::parse(reader, remaining_bytes)?;
 
             match head.fourcc() {
+                #[cfg(feature = "chapters")]
                 CHAPTER_LIST if cfg.cfg.read_chapter_list => {
                     udta.chpl = Some(Chpl::parse(reader, cfg, head.size())?);
                 }
                 METADATA if cfg.cfg.read_meta_items => {
                     udta.meta = Some(Meta::parse(reader, cfg, head.size())?)
                 }
-                _ => reader.skip(head.content_len() as i64)?,
+                #[cfg(feature = "chapters")]
+                CHAPTER_LIST => reader.skip(head.content_len() as i64)?,
+                METADATA => reader.skip(head.content_len() as i64)?,
+                _ => skip_unknown(reader, cfg, head)?,
             }
 
             parsed_bytes += head.len();
@@ -47,7 +52,9 @@ impl ParseAtom for Udta<'_> {
 
 impl AtomSize for Udta<'_> {
     fn size(&self) -> Size {
-        let content_len = self.meta.len_or_zero() + self.chpl.len_or_zero();
+        let content_len = self.meta.len_or_zero();
+        #[cfg(feature = "chapters")]
+        let content_len = content_len + self.chpl.len_or_zero();
         Size::from(content_len)
     }
 }
@@ -55,6 +62,7 @@ impl AtomSize for Udta<'_> {
 impl WriteAtom for Udta<'_> {
     fn write_atom(&self, writer: &mut impl Write, changes: &[Change<'_>]) -> crate::Result<()> {
         self.write_head(writer)?;
+        #[cfg(feature = "chapters")]
         if let Some(a) = &self.chpl {
             a.write(writer, changes)?;
         }
@@ -76,8 +84,10 @@ impl SimpleCollectChanges for Udta<'_> {
         bounds: &AtomBounds,
         changes: &mut Vec<Change<'a>>,
     ) -> i64 {
-        self.chpl.collect_changes(bounds.end(), level, changes)
-            + self.meta.collect_changes(bounds.end(), level, changes)
+        let len_diff = self.meta.collect_changes(bounds.end(), level, changes);
+        #[cfg(feature = "chapters")]
+        let len_diff = len_diff + self.chpl.collect_changes(bounds.end(), level, changes);
+        len_diff
     }
 
     fn atom_ref(&self) -> AtomRef<'_> {
@@ -39,6 +39,7 @@ impl WriteAtom for Url {
     }
 }
 
+#[cfg(feature = "chapters")]
 impl Url {
     pub fn track() -> Self {
         Self {
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::io::{self, Read, Seek, Write};
 use std::time::Duration;
 
@@ -6,6 +7,7 @@ use crate::atom::head::Size;
 
 pub trait ReadUtil: Read {
     /// Attempts to read an unsigned 8 bit integer from the reader.
+    #[cfg(any(feature = "chapters", feature = "audio-info"))]
     fn read_u8(&mut self) -> io::Result<u8> {
         let mut buf = [0];
         self.read_exact(&mut buf)?;
@@ -34,9 +36,20 @@ pub trait ReadUtil: Read {
     }
 
     /// Attempts to read 8 bit unsigned integers from the reader to a vector of size length.
+    /// Lengths above [`MAX_PREALLOC_LEN`] aren't allocated upfront, instead the vector grows as
+    /// data is read, so it never exceeds what the stream actually contains.
     fn read_u8_vec(&mut self, len: u64) -> io::Result<Vec<u8>> {
-        let mut buf = vec![0; len as usize];
-        self.read_exact(&mut buf)?;
+        if len <= MAX_PREALLOC_LEN as u64 {
+            let mut buf = vec![0; len as usize];
+            self.read_exact(&mut buf)?;
+            return Ok(buf);
+        }
+
+        let mut buf = Vec::with_capacity(MAX_PREALLOC_LEN);
+        Read::take(&mut *self, len).read_to_end(&mut buf)?;
+        if (buf.len() as u64) < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
         Ok(buf)
     }
 
@@ -48,7 +61,32 @@ pub trait ReadUtil: Read {
             .map_err(|_| crate::Error::new(ErrorKind::Utf8StringDecoding, "invalid utf-8 data"))
     }
 
+    /// Attempts to read a utf-8 string from the reader. If it matches one of the known strings, no
+    /// allocation is made and the known string is returned instead.
+    fn read_interned_utf8(
+        &mut self,
+        len: u64,
+        known: &[&'static str],
+    ) -> crate::Result<Cow<'static, str>> {
+        let mut buf = [0; 64];
+        if len > buf.len() as u64 {
+            return self.read_utf8(len).map(Cow::Owned);
+        }
+
+        let data = &mut buf[..len as usize];
+        self.read_exact(data)?;
+        if let Some(s) = known.iter().find(|s| s.as_bytes() == data) {
+            return Ok(Cow::Borrowed(s));
+        }
+
+        match std::str::from_utf8(data) {
+            Ok(s) => Ok(Cow::Owned(s.to_owned())),
+            Err(_) => Err(crate::Error::new(ErrorKind::Utf8StringDecoding, "invalid utf-8 data")),
+        }
+    }
+
     /// Attempts to read a big endian utf-16 string from the reader.
+    #[cfg(feature = "chapters")]
     fn read_be_utf16(&mut self, len: u64) -> crate::Result<String> {
         let data = self.read_u8_vec(len)?;
         let code_units = data.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]]));
@@ -57,7 +95,26 @@ pub trait ReadUtil: Read {
         Ok(ret)
     }
 
+    /// Attempts to read a utf-16 string from the reader, which may start with a byte order mark.
+    /// Without a byte order mark the string is read as big endian.
+    fn read_utf16(&mut self, len: u64) -> crate::Result<String> {
+        let data = self.read_u8_vec(len)?;
+        let mut ret = String::with_capacity(data.len() / 2);
+        match data.as_slice() {
+            [0xff, 0xfe, rest @ ..] => {
+                let code_units = rest.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]]));
+                decode_utf16(&mut ret, code_units)?;
+            }
+            [0xfe, 0xff, rest @ ..] | rest => {
+                let code_units = rest.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]]));
+                decode_utf16(&mut ret, code_units)?;
+            }
+        }
+        Ok(ret)
+    }
+
     /// Attempts to read a little endian utf-16 string from the reader.
+    #[cfg(feature = "chapters")]
     fn read_le_utf16(&mut self, len: u64) -> crate::Result<String> {
         let data = self.read_u8_vec(len)?;
         let code_units = data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]]));
@@ -90,6 +147,7 @@ pub trait SeekUtil: Seek {
 impl<T: Seek> SeekUtil for T {}
 
 pub trait WriteUtil: Write {
+    #[cfg(feature = "chapters")]
     fn write_u8(&mut self, val: u8) -> io::Result<()> {
         self.write_all(&[val])
     }
@@ -118,6 +176,17 @@ pub trait WriteUtil: Write {
     }
 }
 
+/// The maximum number of bytes that are allocated upfront, based on a length or number of entries
+/// read from a file. Anything larger has to be backed by data that was actually read.
+pub const MAX_PREALLOC_LEN: usize = 64 * 1024;
+
+/// Returns the capacity that should be preallocated for `num_entries` items of type `T`, read from
+/// a file, see [`MAX_PREALLOC_LEN`].
+pub fn prealloc_capacity<T>(num_entries: u64) -> usize {
+    let max_entries = MAX_PREALLOC_LEN / std::mem::size_of::<T>().max(1);
+    num_entries.min(max_entries as u64) as usize
+}
+
 pub fn expect_size(name: &str, head_size: Size, content_size: u64) -> crate::Result<()> {
     let head_content_size = head_size.content_len();
     if content_size != head_content_size {
@@ -126,7 +195,8 @@ pub fn expect_size(name: &str, head_size: Size, content_size: u64) -> crate::Res
             format!(
                 "{name} size from atom head {head_content_size} differs from the content size {content_size}",
             ),
-        ));
+        )
+        .with_sizes(content_size, head_content_size));
     }
     Ok(())
 }
@@ -139,7 +209,8 @@ pub fn expect_min_size(name: &str, head_size: Size, min_size: u64) -> crate::Res
             format!(
                 "{name} size from atom head {head_content_size} is smaller than the minimum size {min_size}",
             ),
-        ));
+        )
+        .with_sizes(min_size, head_content_size));
     }
     Ok(())
 }
@@ -159,12 +230,25 @@ pub fn scale_duration(timescale: u32, duration: u64) -> Duration {
     Duration::new(secs, nanos as u32)
 }
 
+/// Converts the duration into the timescale, rounding to the nearest unit. Since
+/// [`scale_duration`] rounds down to whole nanoseconds, converting a value back and forth yields
+/// the same value for all timescales below 500,000,000.
 pub fn unscale_duration(timescale: u32, duration: Duration) -> u64 {
     let secs = duration.as_secs() * timescale as u64;
-    let nanos = duration.subsec_nanos() as u64 * timescale as u64 / 1_000_000_000;
+    let nanos = (duration.subsec_nanos() as u64 * timescale as u64 + 500_000_000) / 1_000_000_000;
     secs + nanos
 }
 
+/// Returns the largest char boundary of the string that is less than or equal to the index.
+#[cfg(feature = "chapters")]
+pub fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut i = index.min(s.len());
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
 /// Attempts to read a big endian integer at the specified index from a byte slice.
 macro_rules! be_int {
     ($bytes:expr, $index:expr, $type:ty) => {{
@@ -219,6 +303,35 @@ macro_rules! const_assert {
 
 #[cfg(test)]
 mod test {
+    use super::*;
+
+    #[test]
+    fn read_u8_vec_exceeding_stream() {
+        let mut reader = io::Cursor::new([1, 2, 3]);
+        let err = reader.read_u8_vec(u64::MAX).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let data = vec![7; MAX_PREALLOC_LEN + 1];
+        let mut reader = io::Cursor::new(&data);
+        assert_eq!(reader.read_u8_vec(data.len() as u64).unwrap(), data);
+    }
+
+    #[test]
+    fn prealloc_capacity_is_capped() {
+        assert_eq!(prealloc_capacity::<u32>(10), 10);
+        assert_eq!(prealloc_capacity::<u32>(u32::MAX as u64), MAX_PREALLOC_LEN / 4);
+        assert_eq!(prealloc_capacity::<u8>(u64::MAX), MAX_PREALLOC_LEN);
+    }
+
+    #[test]
+    #[cfg(feature = "chapters")]
+    fn floor_char_boundary_of_multibyte_chars() {
+        assert_eq!(floor_char_boundary("aäb", 2), 1);
+        assert_eq!(floor_char_boundary("aäb", 3), 3);
+        assert_eq!(floor_char_boundary("aäb", 10), 4);
+        assert_eq!(floor_char_boundary("", 1), 0);
+    }
+
     #[test]
     fn be_int() {
         let bytes = [0x00, 0x00, 0x00, 0x00, 0x2D, 0x34, 0xD0, 0x5E];
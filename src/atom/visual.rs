@@ -0,0 +1,35 @@
+//! visual sample entry, for example `avc1`, `hvc1` or `mp4v`
+//!
+//! ```md
+//! 6 bytes reserved
+//! 2 bytes data reference index
+//! 16 bytes ?
+//! 2 bytes width
+//! 2 bytes height
+//! ...
+//! ```
+
+use super::*;
+
+pub const HEADER_SIZE: u64 = 28;
+
+/// The codec and coded size of a visual sample entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Visual {
+    pub fourcc: Fourcc,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Visual {
+    pub fn parse(reader: &mut (impl Read + Seek), head: Head) -> crate::Result<Self> {
+        expect_min_size("Visual sample entry", head.size(), HEADER_SIZE)?;
+
+        reader.skip(24)?;
+        let width = reader.read_be_u16()?;
+        let height = reader.read_be_u16()?;
+        reader.skip((head.content_len() - HEADER_SIZE) as i64)?;
+
+        Ok(Self { fourcc: head.fourcc(), width, height })
+    }
+}
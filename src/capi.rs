@@ -0,0 +1,381 @@
+//! A minimal C ABI, enabled by the `capi` feature.
+//!
+//! The shared library can be built using:
+//! ```sh
+//! cargo rustc --release --features capi --crate-type cdylib
+//! ```
+//! The matching declarations can be found in `include/mp4ameta.h`.
+//!
+//! All functions returning an `int` return `0` on success and `-1` on failure. The message of the
+//! error that occurred during the last call on the current thread can be retrieved using
+//! [`mp4ameta_last_error`]. Panics are caught and reported as errors. Identifiers are passed as
+//! pointers to 4 bytes, for example the bytes `\xa9nam` for the title.
+//!
+//! Strings are expected to be nul terminated and utf-8 encoded. Strings returned by this library
+//! have to be released using [`mp4ameta_string_free`].
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use crate::{Chapter, Data, Fourcc, Img, ImgFmt, Tag};
+
+/// The chapter list (`chpl`).
+pub const MP4AMETA_CHAPTER_LIST: c_int = 0;
+/// The chapter track.
+pub const MP4AMETA_CHAPTER_TRACK: c_int = 1;
+
+/// A bmp image.
+pub const MP4AMETA_IMG_BMP: c_int = 0;
+/// A jpeg image.
+pub const MP4AMETA_IMG_JPEG: c_int = 1;
+/// A png image.
+pub const MP4AMETA_IMG_PNG: c_int = 2;
+/// A gif image.
+pub const MP4AMETA_IMG_GIF: c_int = 3;
+/// A heic image.
+pub const MP4AMETA_IMG_HEIC: c_int = 4;
+/// A webp image.
+pub const MP4AMETA_IMG_WEBP: c_int = 5;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(msg: impl ToString) {
+    let msg = CString::new(msg.to_string().replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+/// Runs the body of an entry point. The last error is cleared first, errors and panics are stored
+/// as the last error, in which case the fallback is returned. Panics must not unwind across the
+/// FFI boundary.
+fn ffi_call<T>(fallback: T, f: impl FnOnce() -> Result<T, String>) -> T {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => {
+            set_last_error(e);
+            fallback
+        }
+        Err(payload) => {
+            let msg = (payload.downcast_ref::<&str>().copied())
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown panic");
+            set_last_error(format!("panicked: {msg}"));
+            fallback
+        }
+    }
+}
+
+fn status(f: impl FnOnce() -> Result<(), String>) -> c_int {
+    ffi_call(-1, || f().map(|()| 0))
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{name} is null"));
+    }
+    let s = unsafe { CStr::from_ptr(ptr) };
+    s.to_str().map_err(|_| format!("{name} is not valid utf-8"))
+}
+
+unsafe fn ident_arg(ptr: *const u8) -> Result<Fourcc, String> {
+    if ptr.is_null() {
+        return Err("ident is null".to_owned());
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(ptr, 4) };
+    Ok(Fourcc([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+unsafe fn tag_arg<'a>(tag: *const Tag) -> Result<&'a Tag, String> {
+    unsafe { tag.as_ref() }.ok_or_else(|| "tag is null".to_owned())
+}
+
+unsafe fn tag_arg_mut<'a>(tag: *mut Tag) -> Result<&'a mut Tag, String> {
+    unsafe { tag.as_mut() }.ok_or_else(|| "tag is null".to_owned())
+}
+
+fn chapters(tag: &Tag, kind: c_int) -> Result<&[Chapter], String> {
+    match kind {
+        MP4AMETA_CHAPTER_LIST => Ok(tag.chapter_list()),
+        MP4AMETA_CHAPTER_TRACK => Ok(tag.chapter_track()),
+        _ => Err(format!("unknown chapter kind {kind}")),
+    }
+}
+
+fn chapters_mut(tag: &mut Tag, kind: c_int) -> Result<&mut Vec<Chapter>, String> {
+    match kind {
+        MP4AMETA_CHAPTER_LIST => Ok(tag.chapter_list_mut()),
+        MP4AMETA_CHAPTER_TRACK => Ok(tag.chapter_track_mut()),
+        _ => Err(format!("unknown chapter kind {kind}")),
+    }
+}
+
+fn into_c_string(s: &str) -> Result<*mut c_char, String> {
+    CString::new(s).map(CString::into_raw).map_err(|e| e.to_string())
+}
+
+/// Returns the message of the error that occurred during the last call on the current thread, or
+/// null if it succeeded. The string is owned by the library and valid until the next call on this
+/// thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn mp4ameta_last_error() -> *const c_char {
+    // this doesn't go through `ffi_call`, which would clear the error
+    let last_error =
+        || LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |e| e.as_ptr()));
+    panic::catch_unwind(last_error).unwrap_or(std::ptr::null())
+}
+
+/// Reads the tag of the file at the path. Returns null on failure.
+/// The tag has to be released using [`mp4ameta_tag_free`].
+///
+/// # Safety
+/// `path` has to be a valid nul terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_read(path: *const c_char) -> *mut Tag {
+    ffi_call(std::ptr::null_mut(), || {
+        let path = unsafe { str_arg(path, "path") }?;
+        let tag = Tag::read_from_path(path).map_err(|e| e.to_string())?;
+        Ok(Box::into_raw(Box::new(tag)))
+    })
+}
+
+/// Writes the tag to the file at the path.
+///
+/// # Safety
+/// `tag` has to be a pointer returned by [`mp4ameta_tag_read`] and `path` a valid nul terminated
+/// string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_write(tag: *const Tag, path: *const c_char) -> c_int {
+    status(|| {
+        let tag = unsafe { tag_arg(tag) }?;
+        let path = unsafe { str_arg(path, "path") }?;
+        tag.write_to_path(path).map_err(|e| e.to_string())
+    })
+}
+
+/// Releases the tag. Passing null is a no-op.
+///
+/// # Safety
+/// `tag` has to be a pointer returned by [`mp4ameta_tag_read`] that hasn't been released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_free(tag: *mut Tag) {
+    ffi_call((), || {
+        if !tag.is_null() {
+            drop(unsafe { Box::from_raw(tag) });
+        }
+        Ok(())
+    })
+}
+
+/// Releases a string returned by this library. Passing null is a no-op.
+///
+/// # Safety
+/// `s` has to be a string returned by this library that hasn't been released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_string_free(s: *mut c_char) {
+    ffi_call((), || {
+        if !s.is_null() {
+            drop(unsafe { CString::from_raw(s) });
+        }
+        Ok(())
+    })
+}
+
+/// Returns the first string stored under the identifier, or null if there is none.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer and `ident` has to point to 4 bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_get_string(tag: *const Tag, ident: *const u8) -> *mut c_char {
+    ffi_call(std::ptr::null_mut(), || {
+        let tag = unsafe { tag_arg(tag) }?;
+        let ident = unsafe { ident_arg(ident) }?;
+        match tag.strings_of(&ident).next() {
+            Some(s) => into_c_string(s),
+            None => Ok(std::ptr::null_mut()),
+        }
+    })
+}
+
+/// Sets the string stored under the identifier, replacing all other data.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer, `ident` has to point to 4 bytes and `value` has to be a
+/// valid nul terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_set_string(
+    tag: *mut Tag,
+    ident: *const u8,
+    value: *const c_char,
+) -> c_int {
+    status(|| {
+        let tag = unsafe { tag_arg_mut(tag) }?;
+        let ident = unsafe { ident_arg(ident) }?;
+        let value = unsafe { str_arg(value, "value") }?;
+        tag.set_data(ident, Data::Utf8(value.to_owned()));
+        Ok(())
+    })
+}
+
+/// Removes all data stored under the identifier.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer and `ident` has to point to 4 bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_remove(tag: *mut Tag, ident: *const u8) -> c_int {
+    status(|| {
+        let tag = unsafe { tag_arg_mut(tag) }?;
+        let ident = unsafe { ident_arg(ident) }?;
+        tag.remove_data_of(&ident);
+        Ok(())
+    })
+}
+
+/// Returns the data of the first artwork image, or null if there is none. The data is owned by
+/// the tag and valid until the tag is modified or released. The length is written to `len` and
+/// the format, one of the `MP4AMETA_IMG_*` constants, to `fmt`.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer, `len` and `fmt` valid pointers or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_get_artwork(
+    tag: *const Tag,
+    len: *mut usize,
+    fmt: *mut c_int,
+) -> *const u8 {
+    ffi_call(std::ptr::null(), || {
+        let tag = unsafe { tag_arg(tag) }?;
+        let Some(img) = tag.artwork() else {
+            return Ok(std::ptr::null());
+        };
+        unsafe {
+            if let Some(len) = len.as_mut() {
+                *len = img.data.len();
+            }
+            if let Some(fmt) = fmt.as_mut() {
+                *fmt = match img.fmt {
+                    ImgFmt::Bmp => MP4AMETA_IMG_BMP,
+                    ImgFmt::Jpeg => MP4AMETA_IMG_JPEG,
+                    ImgFmt::Png => MP4AMETA_IMG_PNG,
+                    ImgFmt::Gif => MP4AMETA_IMG_GIF,
+                    ImgFmt::Heic => MP4AMETA_IMG_HEIC,
+                    ImgFmt::WebP => MP4AMETA_IMG_WEBP,
+                };
+            }
+        }
+        Ok(img.data.as_ptr())
+    })
+}
+
+/// Sets the artwork image, replacing all other artworks. The data is copied.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer and `data` has to point to `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_set_artwork(
+    tag: *mut Tag,
+    data: *const u8,
+    len: usize,
+    fmt: c_int,
+) -> c_int {
+    status(|| {
+        let tag = unsafe { tag_arg_mut(tag) }?;
+        if data.is_null() {
+            return Err("data is null".to_owned());
+        }
+        let fmt = match fmt {
+            MP4AMETA_IMG_BMP => ImgFmt::Bmp,
+            MP4AMETA_IMG_JPEG => ImgFmt::Jpeg,
+            MP4AMETA_IMG_PNG => ImgFmt::Png,
+            MP4AMETA_IMG_GIF => ImgFmt::Gif,
+            MP4AMETA_IMG_HEIC => ImgFmt::Heic,
+            MP4AMETA_IMG_WEBP => ImgFmt::WebP,
+            _ => return Err(format!("unknown image format {fmt}")),
+        };
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        tag.set_artwork(Img::new(fmt, data.to_vec()));
+        Ok(())
+    })
+}
+
+/// Removes all artwork images.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_remove_artworks(tag: *mut Tag) -> c_int {
+    status(|| {
+        unsafe { tag_arg_mut(tag) }?.remove_artworks();
+        Ok(())
+    })
+}
+
+/// Returns the number of chapters of the kind, one of the `MP4AMETA_CHAPTER_*` constants, or `-1`
+/// on failure.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_chapter_count(tag: *const Tag, kind: c_int) -> isize {
+    ffi_call(-1, || {
+        let chapters = unsafe { tag_arg(tag) }.and_then(|t| chapters(t, kind))?;
+        Ok(chapters.len() as isize)
+    })
+}
+
+/// Returns the title of the chapter at the index, or null on failure. The start in milliseconds
+/// is written to `start_ms`.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer and `start_ms` a valid pointer or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_chapter_get(
+    tag: *const Tag,
+    kind: c_int,
+    index: usize,
+    start_ms: *mut u64,
+) -> *mut c_char {
+    ffi_call(std::ptr::null_mut(), || {
+        let chapters = unsafe { tag_arg(tag) }.and_then(|t| chapters(t, kind))?;
+        let c =
+            chapters.get(index).ok_or_else(|| format!("chapter index {index} out of bounds"))?;
+        if let Some(start_ms) = unsafe { start_ms.as_mut() } {
+            *start_ms = c.start.as_millis() as u64;
+        }
+        into_c_string(&c.title)
+    })
+}
+
+/// Appends a chapter starting at `start_ms` milliseconds.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer and `title` a valid nul terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_chapter_add(
+    tag: *mut Tag,
+    kind: c_int,
+    start_ms: u64,
+    title: *const c_char,
+) -> c_int {
+    status(|| {
+        let chapters = unsafe { tag_arg_mut(tag) }.and_then(|t| chapters_mut(t, kind))?;
+        let title = unsafe { str_arg(title, "title") }?;
+        chapters.push(Chapter::new(Duration::from_millis(start_ms), title));
+        Ok(())
+    })
+}
+
+/// Removes all chapters of the kind.
+///
+/// # Safety
+/// `tag` has to be a valid tag pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mp4ameta_tag_chapter_clear(tag: *mut Tag, kind: c_int) -> c_int {
+    status(|| {
+        unsafe { tag_arg_mut(tag) }.and_then(|t| chapters_mut(t, kind))?.clear();
+        Ok(())
+    })
+}
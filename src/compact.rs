@@ -0,0 +1,26 @@
+//! Removal of unreferenced media data.
+//!
+//! Third-party tools sometimes leave ranges in the media data (`mdat`) that no sample table refers
+//! to anymore, for example the samples of a removed track. These functions remove such ranges and
+//! update the chunk offsets of all tracks.
+
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use crate::{StorageFile, atom};
+
+/// Removes all ranges of the media data (`mdat`) of the file at the indicated path, that aren't
+/// referenced by any track, see [`compact_in`].
+pub fn compact(path: impl AsRef<Path>) -> crate::Result<u64> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    compact_in(&mut file)
+}
+
+/// Removes all ranges of the media data (`mdat`) of the file, that aren't referenced by the chunks
+/// of any track, and updates the chunk offsets. Returns the number of removed bytes.
+///
+/// Fragmented files are left untouched, since their media data is referenced by the movie
+/// fragments (`moof`) instead of the sample tables.
+pub fn compact_in(file: &mut impl StorageFile) -> crate::Result<u64> {
+    atom::compact(file)
+}
@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::time::Duration;
 use std::{error, fmt, io};
 
 use crate::Fourcc;
@@ -15,6 +16,10 @@ pub enum ErrorKind {
     DescriptorNotFound(u8),
     /// No filetype (`ftyp`) atom, which indicates na MPEG-4 file, could be found.
     NoFtyp,
+    /// Neither the major brand nor any of the compatible brands of the filetype (`ftyp`) atom are
+    /// allowed by [`ReadConfig::brand_policy`](crate::ReadConfig::brand_policy). Contains the
+    /// major brand.
+    UnsupportedBrand(Fourcc),
     /// The size of an atom is smaller than its header, or otherwise unsound.
     InvalidAtomSize,
     /// The content of an atom suggests another length than its header.
@@ -41,21 +46,171 @@ pub enum ErrorKind {
     Utf8StringDecoding,
     /// An invalid utf-16 string was found.
     Utf16StringDecoding,
+    /// A track that is expected to be a chapter track isn't one.
+    /// See [`WriteConfig::chapter_track_id`](crate::WriteConfig::chapter_track_id).
+    InvalidChapterTrack,
+    /// A chapter title exceeds the maximum length of [`Chapter::MAX_TITLE_LEN`] bytes.
+    ///
+    /// [`Chapter::MAX_TITLE_LEN`]: crate::Chapter::MAX_TITLE_LEN
+    ChapterTitleTooLong,
+    /// The data read after writing differs from the written data.
+    /// See [`WriteConfig::verify`](crate::WriteConfig::verify).
+    VerificationFailed,
+    /// Atoms are nested deeper than [`ReadConfig::max_depth`](crate::ReadConfig::max_depth).
+    NestingTooDeep,
+    /// A data atom is larger than [`ReadConfig::max_data_size`](crate::ReadConfig::max_data_size).
+    DataTooLarge,
+    /// A file contains more chapters than
+    /// [`ReadConfig::max_chapter_count`](crate::ReadConfig::max_chapter_count).
+    TooManyChapters,
+    /// A string isn't a valid ISO 6709 [`Location`](crate::Location).
+    InvalidLocation,
+    /// A string isn't a valid ISO 639-1 or ISO 639-2 [`Language`](crate::Language) code.
+    InvalidLanguage,
+    /// The content of [`Data::Shallow`](crate::Data::Shallow) couldn't be read, because it doesn't
+    /// match the file, or it was written before being read.
+    InvalidShallowData,
+    /// The format of image data couldn't be detected, or it can't be stored as artwork.
+    /// See [`Img::from_bytes`](crate::Img::from_bytes).
+    UnsupportedImgFmt,
+    /// A JSON document couldn't be parsed, or doesn't match the schema.
+    /// See [`Userdata::from_json_str`](crate::Userdata::from_json_str).
+    InvalidJson,
+    /// A template is malformed, or contains a placeholder that can't be resolved.
+    /// See [`Context::render`](crate::template::Context::render).
+    InvalidTemplate,
+    /// A string isn't a valid [`DataIdent`](crate::DataIdent).
+    InvalidIdent,
+    /// [`Data`](crate::Data) can't be converted to the requested type, because it's of another
+    /// type or the value doesn't fit.
+    DataTypeMismatch,
+    /// An artwork image couldn't be decoded.
+    /// See [`Userdata::artwork_thumbnail`](crate::Userdata::artwork_thumbnail).
+    #[cfg(feature = "image")]
+    ImgDecoding(Box<image::ImageError>),
     /// An IO error has occurred.
     Io(io::Error),
 }
 
 /// Any error that may occur while performing metadata operations.
+///
+/// Besides the [`kind`](Self::kind) and [`description`](Self::description), an error may contain
+/// context about where it occurred, which is included when it's displayed. Errors are constructed
+/// using [`Error::new`].
 pub struct Error {
     /// The kind of error that occurred.
     pub kind: ErrorKind,
     /// A human readable string describing the error.
     pub description: Cow<'static, str>,
+    path: Vec<PathSegment>,
+    offset: Option<u64>,
+    sizes: Option<(u64, u64)>,
+}
+
+/// An atom in the [`path`](Error::path) of an error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathSegment {
+    /// The identifier of the atom.
+    pub fourcc: Fourcc,
+    /// The index among the sibling atoms with the same identifier, if there can be multiple of
+    /// them, such as tracks (`trak`).
+    pub index: Option<usize>,
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(i) => write!(f, "{}[{i}]", self.fourcc),
+            None => write!(f, "{}", self.fourcc),
+        }
+    }
 }
 
 impl Error {
     pub fn new(kind: ErrorKind, description: impl Into<Cow<'static, str>>) -> Error {
-        Error { kind, description: description.into() }
+        Error {
+            kind,
+            description: description.into(),
+            path: Vec::new(),
+            offset: None,
+            sizes: None,
+        }
+    }
+
+    /// Returns the atoms that were being parsed or written when the error occurred, starting
+    /// with the outermost atom. For example `moov`, `trak[1]`, `mdia`, `minf`, `stbl`, `stco`.
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Returns the [`path`](Self::path) formatted as a string, for example
+    /// `moov/trak[1]/mdia/minf/stbl/stco`.
+    pub fn path_string(&self) -> String {
+        let mut string = String::new();
+        for (i, segment) in self.path.iter().enumerate() {
+            if i != 0 {
+                string.push('/');
+            }
+            string.push_str(&segment.to_string());
+        }
+        string
+    }
+
+    /// Returns the position in the file at which the error was detected, if known.
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// Returns the size that was expected, if the error was caused by an unexpected size.
+    pub fn expected_size(&self) -> Option<u64> {
+        self.sizes.map(|(expected, _)| expected)
+    }
+
+    /// Returns the size that was found, if the error was caused by an unexpected size.
+    pub fn found_size(&self) -> Option<u64> {
+        self.sizes.map(|(_, found)| found)
+    }
+
+    /// Sets the position in the file at which the error was detected.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the size that was expected and the size that was found.
+    pub fn with_sizes(mut self, expected: u64, found: u64) -> Self {
+        self.sizes = Some((expected, found));
+        self
+    }
+
+    /// Adds an atom to the front of the path and sets the offset, if it isn't already known.
+    pub(crate) fn add_context(&mut self, fourcc: Fourcc, offset: Option<u64>) {
+        self.path.insert(0, PathSegment { fourcc, index: None });
+        if self.offset.is_none() {
+            self.offset = offset;
+        }
+    }
+
+    /// Sets the index of the outermost atom of the path.
+    pub(crate) fn with_index(mut self, index: usize) -> Self {
+        if let Some(segment) = self.path.first_mut() {
+            segment.index = Some(index);
+        }
+        self
+    }
+
+    fn fmt_context(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "\nat {}", self.path_string())?;
+        }
+        if let Some(offset) = self.offset {
+            let sep = if self.path.is_empty() { "\nat" } else { "," };
+            write!(f, "{sep} offset {offset:#x}")?;
+        }
+        if let Some((expected, found)) = self.sizes {
+            write!(f, "\nexpected size {expected}, found {found}")?;
+        }
+        Ok(())
     }
 }
 
@@ -63,6 +218,8 @@ impl error::Error for Error {
     fn cause(&self) -> Option<&dyn error::Error> {
         match self.kind {
             ErrorKind::Io(ref err) => Some(err),
+            #[cfg(feature = "image")]
+            ErrorKind::ImgDecoding(ref err) => Some(err.as_ref()),
             _ => None,
         }
     }
@@ -78,19 +235,128 @@ impl From<io::Error> for Error {
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.description.is_empty() {
-            write!(f, "{:?}", self.kind)
+            write!(f, "{:?}", self.kind)?;
         } else {
-            write!(f, "{}:\n{:?}", self.description, self.kind)
+            write!(f, "{}:\n{:?}", self.description, self.kind)?;
         }
+        self.fmt_context(f)
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.description.is_empty() {
-            write!(f, "{:?}", self.kind)
+            write!(f, "{:?}", self.kind)?;
         } else {
-            write!(f, "{}:\n{:?}", self.description, self.kind)
+            write!(f, "{}:\n{:?}", self.description, self.kind)?;
+        }
+        self.fmt_context(f)
+    }
+}
+
+/// A non-fatal issue that was encountered while parsing or writing a file.
+/// See [`Tag::read_with_warnings`](crate::Tag::read_with_warnings) and
+/// [`Userdata::write_with_warnings`](crate::Userdata::write_with_warnings).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// An atom that isn't known was skipped. Contains the atom's identifier and position.
+    UnknownAtom {
+        /// The identifier of the atom.
+        fourcc: Fourcc,
+        /// The position of the atom in the file.
+        offset: u64,
+    },
+    /// A data atom (`data`) without any content was found. Contains the atom's position.
+    EmptyData {
+        /// The position of the atom in the file.
+        offset: u64,
+    },
+    /// A data atom (`data`) with an unknown type code was found. The data is preserved as
+    /// [`Data::Unknown`](crate::Data::Unknown). The displayed message lists the known type codes.
+    UnknownDataType {
+        /// The type code of the data.
+        code: u32,
+        /// The position of the atom in the file.
+        offset: u64,
+    },
+    /// A chapter starts after the end of the file.
+    ChapterOutOfBounds {
+        /// The start of the chapter.
+        start: Duration,
+        /// The duration of the file.
+        duration: Duration,
+    },
+    /// The duration of a chapter track differs from the movie duration, which may cause players
+    /// to display chapters incorrectly. Rewriting the chapter track corrects it.
+    TrackDurationMismatch {
+        /// The id of the track.
+        track_id: u32,
+        /// The duration of the track.
+        duration: Duration,
+        /// The duration of the movie.
+        movie_duration: Duration,
+    },
+    /// An atom of the sample table (`stbl`) of a chapter track that isn't understood, such as a
+    /// sync sample (`stss`) or sample group (`sgpd`, `sbgp`) atom, was removed when writing,
+    /// because the samples it describes were rewritten.
+    RemovedSampleTableAtom {
+        /// The id of the track.
+        track_id: u32,
+        /// The identifier of the removed atom.
+        fourcc: Fourcc,
+    },
+    /// Only the chapter track was written, but the file also contains a chapter list (`chpl`) with
+    /// other chapters, which was left unchanged. Players that read the chapter list may show
+    /// different chapters, see [`WriteConfig::write_chapters`](crate::WriteConfig::write_chapters).
+    UnchangedChapterList,
+    /// Only the chapter list (`chpl`) was written, but the file also contains a chapter track,
+    /// which was left unchanged and may contain other chapters. Players that read the chapter
+    /// track may show different chapters, see
+    /// [`WriteConfig::write_chapters`](crate::WriteConfig::write_chapters).
+    UnchangedChapterTrack {
+        /// The id of the track.
+        track_id: u32,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownAtom { fourcc, offset } => {
+                write!(f, "Skipped unknown atom '{fourcc}' at offset {offset}")
+            }
+            Self::EmptyData { offset } => write!(f, "Empty data atom at offset {offset}"),
+            Self::UnknownDataType { code, offset } => {
+                write!(f, "Unknown data type code {code} at offset {offset}, known codes are ")?;
+                for (i, known) in crate::atom::KNOWN_DATA_TYPES.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{known}")?;
+                }
+                Ok(())
+            }
+            Self::ChapterOutOfBounds { start, duration } => {
+                write!(f, "Chapter starting at {start:?} exceeds the duration {duration:?}")
+            }
+            Self::TrackDurationMismatch { track_id, duration, movie_duration } => {
+                write!(
+                    f,
+                    "Duration {duration:?} of track {track_id} differs from the movie duration {movie_duration:?}"
+                )
+            }
+            Self::RemovedSampleTableAtom { track_id, fourcc } => {
+                write!(f, "Removed atom '{fourcc}' from the sample table of track {track_id}")
+            }
+            Self::UnchangedChapterList => {
+                write!(f, "The unchanged chapter list differs from the written chapter track")
+            }
+            Self::UnchangedChapterTrack { track_id } => {
+                write!(
+                    f,
+                    "Chapter track {track_id} was left unchanged, and may differ from the written chapter list"
+                )
+            }
         }
     }
 }
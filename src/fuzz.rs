@@ -0,0 +1,23 @@
+//! Entry points for fuzzing, only available with the `fuzz` feature.
+//!
+//! These aren't covered by semver guarantees.
+
+use std::cell::RefCell;
+use std::io::Cursor;
+
+use crate::{ParseWarning, ReadConfig, Tag, WriteConfig, atom};
+
+/// Reads a tag from `data`, collecting all parse warnings on the way.
+pub fn read_tag(data: &[u8], cfg: &ReadConfig) -> crate::Result<(Tag, Vec<ParseWarning>)> {
+    let warnings = RefCell::new(Vec::new());
+    let tag = atom::read_tag(&mut Cursor::new(data), cfg, Some(&warnings))?;
+    Ok((tag, warnings.into_inner()))
+}
+
+/// Reads a tag from `data`, writes it back, and reads the written file again.
+pub fn read_write_tag(data: &[u8], cfg: &ReadConfig) -> crate::Result<Tag> {
+    let (tag, _) = read_tag(data, cfg)?;
+    let written = tag.write_to_vec(data, &WriteConfig::DEFAULT)?;
+    let (tag, _) = read_tag(&written, cfg)?;
+    Ok(tag)
+}
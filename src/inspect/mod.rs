@@ -0,0 +1,211 @@
+//! Utilities for inspecting the layout of a file without reading its metadata.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{AudioInfo, Fourcc, atom};
+
+pub use sample_table::{
+    ChunkOffsets, SampleDurations, SampleSizes, SampleTable, SamplesPerChunk, sample_tables,
+};
+
+pub(crate) use sample_table::TableBounds;
+
+mod sample_table;
+
+/// Size statistics of the atoms making up a file.
+///
+/// All sizes are in bytes and include the atom headers, except for `artwork_len` which only counts
+/// the image data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileLayout {
+    /// The total length of the file.
+    pub file_len: u64,
+    /// The size of the movie atom (`moov`).
+    pub moov_len: u64,
+    /// The combined size of all media data atoms (`mdat`).
+    pub mdat_len: u64,
+    /// The combined size of all free space atoms (`free`, `skip`), including the ones nested
+    /// inside the movie atom.
+    pub free_len: u64,
+    /// The size of the metadata item list atom (`ilst`).
+    pub ilst_len: u64,
+    /// The size of the chapter list atom (`chpl`).
+    pub chpl_len: u64,
+    /// The combined size of all artwork image data.
+    pub artwork_len: u64,
+    /// The number of trailing bytes that couldn't be parsed as an atom.
+    pub unparsed_len: u64,
+    /// Whether the movie atom (`moov`) precedes the first media data atom (`mdat`).
+    pub moov_before_mdat: bool,
+}
+
+/// Inspects the layout of the file at the indicated path.
+pub fn layout(path: impl AsRef<Path>) -> crate::Result<FileLayout> {
+    let mut file = BufReader::new(File::open(path)?);
+    layout_from(&mut file)
+}
+
+/// Inspects the layout of the reader.
+pub fn layout_from(reader: &mut (impl Read + Seek)) -> crate::Result<FileLayout> {
+    atom::read_layout(reader)
+}
+
+/// Which metadata a file contains, see [`probe`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Probe {
+    /// Whether the file contains a non-empty metadata item list (`ilst`).
+    pub has_ilst: bool,
+    /// Whether the file contains a chapter list (`chpl`).
+    pub has_chpl: bool,
+    /// Whether a track of the file references a chapter track (`tref/chap`).
+    pub has_chapter_track: bool,
+    /// The major brand of the filetype (`ftyp`) atom, for example `M4A `. Zeroed for classic
+    /// QuickTime files without one.
+    pub brand: Fourcc,
+    /// The duration of the movie.
+    pub duration: Duration,
+}
+
+/// Probes which metadata the file at the indicated path contains.
+///
+/// This is considerably faster than reading a [`Tag`](crate::Tag), since only atom heads are read
+/// and no metadata is parsed. The only atom contents that are read are the brand of the filetype
+/// atom (`ftyp`) and the movie header (`mvhd`). Files that aren't MPEG-4 files are rejected
+/// as soon as the first atom has been read.
+pub fn probe(path: impl AsRef<Path>) -> crate::Result<Probe> {
+    let mut file = BufReader::new(File::open(path)?);
+    probe_from(&mut file)
+}
+
+/// Probes which metadata the reader contains, see [`probe`].
+pub fn probe_from(reader: &mut (impl Read + Seek)) -> crate::Result<Probe> {
+    atom::read_probe(reader)
+}
+
+/// Reads the audio information of the reader, the same as [`Tag::info`](crate::Tag::info).
+///
+/// Only the movie header (`mvhd`) and the track atoms are parsed, the metadata item list and
+/// chapters are skipped without being read. This is useful if only the duration or bitrate are
+/// needed, for example by players that keep their own database of tags.
+///
+/// ```
+/// let mut file = std::fs::File::open("files/sample.m4a").unwrap();
+/// let info = mp4ameta::read_audio_info(&mut file).unwrap();
+/// assert!(!info.duration.is_zero());
+/// ```
+///
+/// The brands of the filetype atom (`ftyp`) aren't checked, and classic QuickTime files without
+/// one are accepted, see [`BrandPolicy::Skip`](crate::BrandPolicy::Skip).
+pub fn read_audio_info(reader: &mut (impl Read + Seek)) -> crate::Result<AudioInfo> {
+    atom::read_audio_info(reader)
+}
+
+/// Reads the duration of the movie, the same as [`Tag::duration`](crate::Tag::duration).
+///
+/// This is the cheapest way to read the duration, only the filetype atom (`ftyp`) and the movie
+/// header (`mvhd`) are parsed, all other atoms are skipped by their headers. Files without a
+/// filetype atom are accepted, like with [`BrandPolicy::Skip`](crate::BrandPolicy::Skip).
+///
+/// ```
+/// let mut file = std::fs::File::open("files/sample.m4a").unwrap();
+/// let duration = mp4ameta::read_duration(&mut file).unwrap();
+/// assert!(!duration.is_zero());
+/// ```
+pub fn read_duration(reader: &mut (impl Read + Seek)) -> crate::Result<Duration> {
+    atom::read_duration(reader)
+}
+
+/// The algorithm used to compute a checksum, see [`audio_checksum`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    /// CRC-32 (IEEE 802.3), as used by zip and png.
+    #[default]
+    Crc32,
+    /// 64 bit FNV-1a.
+    Fnv1a64,
+}
+
+/// Computes a checksum of the audio data of the reader, which is independent of the metadata.
+///
+/// Only the media data referenced by the sample tables of audio tracks is hashed, so the checksum
+/// stays the same when the metadata is rewritten, even if the media data (`mdat`) is moved or the
+/// chapter track is changed.
+pub fn audio_checksum(
+    reader: &mut (impl Read + Seek),
+    algo: ChecksumAlgorithm,
+) -> crate::Result<u64> {
+    /// The maximum number of bytes that are read at once.
+    const BUF_SIZE: u64 = 1 << 16;
+
+    let chunks = atom::read_audio_chunks(reader)?;
+    let mut checksum = Checksum::new(algo);
+    let mut buf = vec![0; BUF_SIZE as usize];
+    for (offset, size) in chunks {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut remaining = size;
+        while remaining > 0 {
+            let len = remaining.min(BUF_SIZE) as usize;
+            reader.read_exact(&mut buf[..len])?;
+            checksum.update(&buf[..len]);
+            remaining -= len as u64;
+        }
+    }
+    Ok(checksum.finish())
+}
+
+enum Checksum {
+    Crc32(u32),
+    Fnv1a64(u64),
+}
+
+impl Checksum {
+    const CRC32_TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut j = 0;
+            while j < 8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+                j += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    fn new(algo: ChecksumAlgorithm) -> Self {
+        match algo {
+            ChecksumAlgorithm::Crc32 => Self::Crc32(!0),
+            ChecksumAlgorithm::Fnv1a64 => Self::Fnv1a64(Self::FNV_OFFSET_BASIS),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Crc32(crc) => {
+                for b in data {
+                    *crc = Self::CRC32_TABLE[((*crc ^ *b as u32) & 0xff) as usize] ^ (*crc >> 8);
+                }
+            }
+            Self::Fnv1a64(hash) => {
+                for b in data {
+                    *hash = (*hash ^ *b as u64).wrapping_mul(Self::FNV_PRIME);
+                }
+            }
+        }
+    }
+
+    fn finish(self) -> u64 {
+        match self {
+            Self::Crc32(crc) => !crc as u64,
+            Self::Fnv1a64(hash) => hash,
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::{Fourcc, atom};
+
+/// Reads the sample tables (`stbl`) of all tracks of the reader.
+///
+/// Only the positions of the tables are read, their entries are read on demand from the same
+/// reader, using the iterators of [`SampleTable`]. This allows chunk or sample level analysis,
+/// for example of the bitrate over time, without reading whole tables into memory.
+pub fn sample_tables(reader: &mut (impl Read + Seek)) -> crate::Result<Vec<SampleTable>> {
+    atom::read_sample_tables(reader)
+}
+
+/// The sample table (`stbl`) of a track, see [`sample_tables`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SampleTable {
+    /// The id of the track.
+    pub track_id: u32,
+    /// The component subtype of the track's handler (`hdlr`), for example `soun` or `vide`.
+    pub handler: Option<Fourcc>,
+    /// The timescale of the track's media: the number of units that pass per second.
+    pub timescale: u32,
+    pub(crate) stts: TableBounds,
+    pub(crate) stsc: TableBounds,
+    pub(crate) stsz: TableBounds,
+    pub(crate) uniform_sample_size: u32,
+    pub(crate) uniform_sample_count: u32,
+    pub(crate) chunk_offsets: TableBounds,
+    pub(crate) co64: bool,
+}
+
+/// The position and number of entries of a table inside the file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TableBounds {
+    pub pos: u64,
+    pub num_entries: u32,
+}
+
+impl SampleTable {
+    /// Returns the number of samples.
+    pub fn sample_count(&self) -> u32 {
+        match self.uniform_sample_size {
+            0 => self.stsz.num_entries,
+            _ => self.uniform_sample_count,
+        }
+    }
+
+    /// Returns the number of chunks.
+    pub fn chunk_count(&self) -> u32 {
+        self.chunk_offsets.num_entries
+    }
+
+    /// Returns an iterator over the size of every sample in bytes (`stsz`).
+    pub fn sample_sizes<'a, R: Read + Seek>(&self, reader: &'a mut R) -> SampleSizes<'a, R> {
+        SampleSizes {
+            entries: Entries::new(reader, self.stsz),
+            uniform_size: self.uniform_sample_size,
+            uniform_remaining: self.uniform_sample_count,
+        }
+    }
+
+    /// Returns an iterator over the duration of every sample in units of the
+    /// [`timescale`](Self::timescale) (`stts`).
+    pub fn sample_durations<'a, R: Read + Seek>(
+        &self,
+        reader: &'a mut R,
+    ) -> SampleDurations<'a, R> {
+        SampleDurations {
+            entries: Entries::new(reader, self.stts),
+            count: 0,
+            duration: 0,
+        }
+    }
+
+    /// Returns an iterator over the offset of every chunk in the file (`stco` or `co64`).
+    pub fn chunk_offsets<'a, R: Read + Seek>(&self, reader: &'a mut R) -> ChunkOffsets<'a, R> {
+        ChunkOffsets {
+            entries: Entries::new(reader, self.chunk_offsets),
+            co64: self.co64,
+        }
+    }
+
+    /// Returns an iterator over the number of samples of every chunk (`stsc`).
+    pub fn samples_per_chunk<'a, R: Read + Seek>(
+        &self,
+        reader: &'a mut R,
+    ) -> SamplesPerChunk<'a, R> {
+        SamplesPerChunk {
+            entries: Entries::new(reader, self.stsc),
+            chunk: 1,
+            chunk_count: self.chunk_count(),
+            current: 0,
+            next: None,
+        }
+    }
+}
+
+/// The maximum number of bytes of a table that are read at once.
+const BUF_LEN: usize = 4096;
+
+/// Reads the entries of a table sequentially, in chunks of up to [`BUF_LEN`] bytes. After an
+/// error no more entries are returned.
+#[derive(Debug)]
+struct Entries<'a, R> {
+    reader: &'a mut R,
+    /// The position of the table, until the reader has been moved to it.
+    pos: Option<u64>,
+    /// The number of entries that haven't been returned yet.
+    remaining: u32,
+    /// Entries that have been read, but not returned yet, starting at `buf_pos`.
+    buf: Vec<u8>,
+    buf_pos: usize,
+}
+
+impl<'a, R: Read + Seek> Entries<'a, R> {
+    fn new(reader: &'a mut R, table: TableBounds) -> Self {
+        Self {
+            reader,
+            pos: Some(table.pos),
+            remaining: table.num_entries,
+            buf: Vec::new(),
+            buf_pos: 0,
+        }
+    }
+
+    fn next<const N: usize>(&mut self) -> Option<crate::Result<[u8; N]>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if self.buf_pos + N > self.buf.len()
+            && let Err(e) = self.fill_buf::<N>()
+        {
+            self.remaining = 0;
+            return Some(Err(e.into()));
+        }
+        self.remaining -= 1;
+
+        let mut entry = [0; N];
+        entry.copy_from_slice(&self.buf[self.buf_pos..self.buf_pos + N]);
+        self.buf_pos += N;
+        Some(Ok(entry))
+    }
+
+    /// Reads the next chunk of entries. If the table is truncated, the complete entries before
+    /// the end are kept.
+    fn fill_buf<const N: usize>(&mut self) -> io::Result<()> {
+        if let Some(pos) = self.pos.take() {
+            self.reader.seek(SeekFrom::Start(pos))?;
+        }
+
+        let num_entries = (self.remaining as usize).min((BUF_LEN / N).max(1));
+        self.buf.resize(num_entries * N, 0);
+        let mut filled = 0;
+        while filled < self.buf.len() {
+            match self.reader.read(&mut self.buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+        self.buf.truncate(filled / N * N);
+        self.buf_pos = 0;
+        if self.buf.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the sample sizes of a track, see [`SampleTable::sample_sizes`].
+#[derive(Debug)]
+pub struct SampleSizes<'a, R> {
+    entries: Entries<'a, R>,
+    uniform_size: u32,
+    uniform_remaining: u32,
+}
+
+impl<R: Read + Seek> Iterator for SampleSizes<'_, R> {
+    type Item = crate::Result<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.uniform_size == 0 {
+            return self.entries.next().map(|r| r.map(u32::from_be_bytes));
+        }
+        if self.uniform_remaining == 0 {
+            return None;
+        }
+        self.uniform_remaining -= 1;
+        Some(Ok(self.uniform_size))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.uniform_size {
+            0 => self.entries.remaining,
+            _ => self.uniform_remaining,
+        };
+        (0, Some(len as usize))
+    }
+}
+
+/// An iterator over the sample durations of a track, see [`SampleTable::sample_durations`].
+#[derive(Debug)]
+pub struct SampleDurations<'a, R> {
+    entries: Entries<'a, R>,
+    count: u32,
+    duration: u32,
+}
+
+impl<R: Read + Seek> Iterator for SampleDurations<'_, R> {
+    type Item = crate::Result<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.count == 0 {
+            match self.entries.next::<8>()? {
+                Ok(b) => {
+                    self.count = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+                    self.duration = u32::from_be_bytes([b[4], b[5], b[6], b[7]]);
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.count -= 1;
+        Some(Ok(self.duration))
+    }
+}
+
+/// An iterator over the chunk offsets of a track, see [`SampleTable::chunk_offsets`].
+#[derive(Debug)]
+pub struct ChunkOffsets<'a, R> {
+    entries: Entries<'a, R>,
+    co64: bool,
+}
+
+impl<R: Read + Seek> Iterator for ChunkOffsets<'_, R> {
+    type Item = crate::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.co64 {
+            true => self.entries.next().map(|r| r.map(u64::from_be_bytes)),
+            false => self.entries.next().map(|r| r.map(|b| u32::from_be_bytes(b) as u64)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entries.remaining as usize))
+    }
+}
+
+/// An iterator over the number of samples per chunk of a track, see
+/// [`SampleTable::samples_per_chunk`].
+#[derive(Debug)]
+pub struct SamplesPerChunk<'a, R> {
+    entries: Entries<'a, R>,
+    /// The index of the next chunk, starting at 1.
+    chunk: u32,
+    chunk_count: u32,
+    current: u32,
+    /// The first chunk and number of samples per chunk of the next entry.
+    next: Option<(u32, u32)>,
+}
+
+impl<R: Read + Seek> SamplesPerChunk<'_, R> {
+    fn read_entry(&mut self) -> crate::Result<Option<(u32, u32)>> {
+        match self.entries.next::<12>() {
+            Some(Ok(b)) => {
+                let first_chunk = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+                let samples_per_chunk = u32::from_be_bytes([b[4], b[5], b[6], b[7]]);
+                Ok(Some((first_chunk, samples_per_chunk)))
+            }
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<R: Read + Seek> Iterator for SamplesPerChunk<'_, R> {
+    type Item = crate::Result<u32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.chunk > self.chunk_count {
+            return None;
+        }
+        if self.chunk == 1 {
+            match self.read_entry() {
+                Ok(e) => self.next = e,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        while let Some((first_chunk, samples_per_chunk)) = self.next
+            && first_chunk <= self.chunk
+        {
+            self.current = samples_per_chunk;
+            match self.read_entry() {
+                Ok(e) => self.next = e,
+                Err(e) => {
+                    self.chunk = self.chunk_count + 1;
+                    return Some(Err(e));
+                }
+            }
+        }
+        self.chunk += 1;
+        Some(Ok(self.current))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.chunk_count + 1).saturating_sub(self.chunk);
+        (0, Some(len as usize))
+    }
+}
@@ -92,19 +92,66 @@
 //! };
 //! tag.write_with_path("music.m4a", &write_cfg).unwrap();
 //! ```
+//!
+//! # Cargo Features
+//! Both features are enabled by default.
+//!
+//! - `chapters`: Reading and writing of chapter lists (`chpl`) and chapter tracks, including the
+//!   `repair` module. Without it, chapters are neither read nor written and existing ones are
+//!   left untouched.
+//! - `audio-info`: Parsing of the audio sample description (`mp4a`/`esds`). Without it, only the
+//!   movie duration and timescale of [`AudioInfo`] are available.
+//!
+//! The `capi` feature, which is disabled by default, exposes a minimal C ABI, see the `capi`
+//! module. The `fuzz` feature exposes internal entry points for fuzzing, see the `fuzz` module.
+//! The `image` feature decodes artwork thumbnails using the [`image`](https://docs.rs/image)
+//! crate, see `Userdata::artwork_thumbnail`. The `normalization` feature normalizes strings to a
+//! Unicode form, see `Userdata::normalize_strings`. The `testing` feature provides helpers for
+//! verifying that writes don't corrupt the audio data, see the `testing` module.
 #![deny(rust_2018_idioms)]
 
-pub use crate::atom::ident::{self, DataIdent, Fourcc, FreeformIdent, Ident};
-pub use crate::atom::{ChplTimescale, Data, ReadConfig, StorageFile, WriteConfig};
-pub use crate::error::{Error, ErrorKind, Result};
-pub use crate::tag::{STANDARD_GENRES, Tag, Userdata};
+#[cfg(feature = "chapters")]
+pub use crate::atom::ChapterTrackIter;
+pub use crate::atom::ident::{self, DataIdent, Fourcc, FreeformIdent, Ident, IdentInfo, ValueKind};
+pub use crate::atom::{
+    BrandPolicy, ChplTimescale, Data, ItemListLocation, ReadConfig, StorageFile, TargetPlayer,
+    WriteChapters, WriteConfig,
+};
+pub use crate::compact::{compact, compact_in};
+pub use crate::error::{Error, ErrorKind, ParseWarning, PathSegment, Result};
+pub use crate::inspect::{
+    ChecksumAlgorithm, Probe, SampleTable, audio_checksum, probe, probe_from, read_audio_info,
+    read_duration, sample_tables,
+};
+pub use crate::tag::{
+    AudiobookInfo, ClassicalWork, CompatIssue, FormatLayout, FormatStyle, ItemOrder, LengthLimits,
+    LengthViolation, Loudness, ReplayGain, STANDARD_GENRES, Tag, TagDisplay, TvEpisodeInfo,
+    Userdata, UserdataSnapshot,
+};
+pub use crate::track::{TrackLanguage, set_track_language, set_track_language_in, track_languages};
 pub use crate::types::*;
+#[cfg(feature = "image")]
+pub use image;
+
+pub(crate) use crate::atom::{ItemData, MetaItem};
 
-pub(crate) use crate::atom::MetaItem;
+pub mod artwork;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod inspect;
+#[cfg(feature = "chapters")]
+pub mod repair;
+pub mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 #[macro_use]
 mod atom;
+mod compact;
 mod error;
 mod tag;
+mod track;
 mod types;
 mod util;
@@ -0,0 +1,74 @@
+//! Utilities for repairing chapter information.
+//!
+//! Some tools only update one of the two chapter representations, or break the sample table of the
+//! chapter track when rewriting metadata. These functions re-derive one representation purely from
+//! the other and rewrite it.
+
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use crate::{
+    ChapterSync, ErrorKind, ReadConfig, StorageFile, Tag, WriteChapters, WriteConfig, ident,
+};
+
+/// Rebuilds the chapter track of the file at the indicated path from its chapter list (`chpl`).
+/// The sample table and media data of the chapter track are regenerated.
+///
+/// Returns an error if the file doesn't contain a chapter list.
+pub fn rebuild_chapter_track(path: impl AsRef<Path>) -> crate::Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    rebuild_chapter_track_in(&mut file)
+}
+
+/// Rebuilds the chapter track of the file from its chapter list (`chpl`).
+/// The sample table and media data of the chapter track are regenerated.
+///
+/// Returns an error if the file doesn't contain a chapter list.
+pub fn rebuild_chapter_track_in(file: &mut impl StorageFile) -> crate::Result<()> {
+    let cfg = ReadConfig { read_chapter_list: true, ..ReadConfig::NONE };
+    let mut tag = Tag::read_with(file, &cfg)?;
+    if tag.chapter_list().is_empty() {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(ident::CHAPTER_LIST),
+            "No chapter list (chpl) found to rebuild the chapter track from",
+        ));
+    }
+
+    tag.sync_chapters(ChapterSync::ListToTrack);
+
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::NONE
+    };
+    tag.write_with(file, &cfg)
+}
+
+/// Rebuilds the chapter list (`chpl`) of the file at the indicated path from its chapter track.
+///
+/// Returns an error if the file doesn't contain a chapter track.
+pub fn rebuild_chapter_list(path: impl AsRef<Path>) -> crate::Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    rebuild_chapter_list_in(&mut file)
+}
+
+/// Rebuilds the chapter list (`chpl`) of the file from its chapter track.
+///
+/// Returns an error if the file doesn't contain a chapter track.
+pub fn rebuild_chapter_list_in(file: &mut impl StorageFile) -> crate::Result<()> {
+    let cfg = ReadConfig { read_chapter_track: true, ..ReadConfig::NONE };
+    let mut tag = Tag::read_with(file, &cfg)?;
+    if tag.chapter_track().is_empty() {
+        return Err(crate::Error::new(
+            ErrorKind::AtomNotFound(ident::CHAPTER_REFERENCE),
+            "No chapter track found to rebuild the chapter list (chpl) from",
+        ));
+    }
+
+    tag.sync_chapters(ChapterSync::TrackToList);
+
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterList,
+        ..WriteConfig::NONE
+    };
+    tag.write_with(file, &cfg)
+}
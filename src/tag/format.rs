@@ -0,0 +1,251 @@
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+use crate::{Chapter, Tag, util};
+
+/// The layout used by [`Tag::format`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FormatLayout {
+    /// All values on a single line, separated by `|`. Chapters are only counted.
+    Compact,
+    /// One value per line, with multiple values and chapters indented below their name. This is
+    /// the layout used by the [`Display`] implementation of [`Tag`].
+    #[default]
+    Verbose,
+    /// One `key=value` pair per line, where the key is the name of the value with underscores
+    /// instead of spaces. Multiple values repeat the key, flags have the value `true` and chapters
+    /// are formatted as `hh:mm:ss.fff title`. Line breaks and backslashes in values are escaped.
+    KeyValue,
+}
+
+/// The style used by [`Tag::format`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FormatStyle {
+    /// The layout of the values.
+    pub layout: FormatLayout,
+    /// Whether to include the raw identifiers of the metadata items, for example `©nam` for the
+    /// title. In the [`KeyValue`](FormatLayout::KeyValue) layout, the identifier replaces the key.
+    pub raw_idents: bool,
+}
+
+impl FormatStyle {
+    /// All values on a single line.
+    pub const COMPACT: Self = Self { layout: FormatLayout::Compact, raw_idents: false };
+    /// One value per line, the style used by the [`Display`] implementation of [`Tag`].
+    pub const VERBOSE: Self = Self { layout: FormatLayout::Verbose, raw_idents: false };
+    /// One `key=value` pair per line.
+    pub const KEY_VALUE: Self = Self { layout: FormatLayout::KeyValue, raw_idents: false };
+
+    /// Includes the raw identifiers of the metadata items.
+    pub const fn with_raw_idents(mut self) -> Self {
+        self.raw_idents = true;
+        self
+    }
+}
+
+/// A tag formatted in a [`FormatStyle`], created by [`Tag::format`].
+#[derive(Clone, Copy, Debug)]
+pub struct TagDisplay<'a> {
+    tag: &'a Tag,
+    style: FormatStyle,
+}
+
+impl Tag {
+    /// Returns an object that formats the tag in the style, implementing [`Display`].
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::{FormatStyle, Tag};
+    ///
+    /// let mut tag = Tag::default();
+    /// tag.set_title("title");
+    /// tag.add_artist("artist 1");
+    /// tag.add_artist("artist 2");
+    ///
+    /// let compact = tag.format(FormatStyle::COMPACT).to_string();
+    /// assert!(compact.starts_with("artists: artist 1, artist 2 | title: title | duration: "));
+    ///
+    /// let key_value = tag.format(FormatStyle::KEY_VALUE.with_raw_idents()).to_string();
+    /// assert!(key_value.starts_with("©ART=artist 1\n©ART=artist 2\n©nam=title\n"));
+    /// ```
+    pub fn format(&self, style: FormatStyle) -> TagDisplay<'_> {
+        TagDisplay { tag: self, style }
+    }
+}
+
+impl Display for TagDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut f = TagFormatter { f, style: self.style, first: true };
+        self.tag.format_all(&mut f)?;
+        f.value("filetype", None, Some(self.tag.filetype()))?;
+        match self.style.layout {
+            FormatLayout::Compact => writeln!(f.f),
+            FormatLayout::Verbose | FormatLayout::KeyValue => Ok(()),
+        }
+    }
+}
+
+/// Writes the values of a tag in a [`FormatStyle`].
+pub(crate) struct TagFormatter<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    style: FormatStyle,
+    /// Whether no value has been written yet.
+    first: bool,
+}
+
+impl TagFormatter<'_, '_> {
+    /// Writes the name of a value, followed by the separator of the layout.
+    fn name(&mut self, name: &str, ident: Option<&str>) -> fmt::Result {
+        match self.style.layout {
+            FormatLayout::Compact if !self.first => self.f.write_str(" | ")?,
+            FormatLayout::Compact | FormatLayout::Verbose | FormatLayout::KeyValue => (),
+        }
+        self.first = false;
+
+        match (self.style.layout, ident) {
+            (FormatLayout::KeyValue, Some(i)) if self.style.raw_idents => self.f.write_str(i),
+            (FormatLayout::KeyValue, _) => self.f.write_str(&name.replace(' ', "_")),
+            (_, Some(i)) if self.style.raw_idents => write!(self.f, "{name} ({i})"),
+            _ => self.f.write_str(name),
+        }
+    }
+
+    /// Writes a single value, if present.
+    pub(crate) fn value(
+        &mut self,
+        name: &str,
+        ident: Option<&str>,
+        value: Option<impl Display>,
+    ) -> fmt::Result {
+        let Some(value) = value else {
+            return Ok(());
+        };
+        self.name(name, ident)?;
+        match self.style.layout {
+            FormatLayout::Compact => write!(self.f, ": {value}"),
+            FormatLayout::Verbose => writeln!(self.f, ": {value}"),
+            FormatLayout::KeyValue => writeln!(self.f, "={}", Escaped(value)),
+        }
+    }
+
+    /// Writes all values, using the plural name if there is more than one.
+    pub(crate) fn values<T: Display>(
+        &mut self,
+        name: &str,
+        plural: &str,
+        ident: Option<&str>,
+        values: impl Iterator<Item = T>,
+    ) -> fmt::Result {
+        let values = values.collect::<Vec<_>>();
+        if values.len() < 2 {
+            return self.value(name, ident, values.into_iter().next());
+        }
+
+        match self.style.layout {
+            FormatLayout::Compact => {
+                self.name(plural, ident)?;
+                for (i, v) in values.iter().enumerate() {
+                    let sep = if i == 0 { ": " } else { ", " };
+                    write!(self.f, "{sep}{v}")?;
+                }
+            }
+            FormatLayout::Verbose => {
+                self.name(plural, ident)?;
+                writeln!(self.f, ":")?;
+                for v in values.iter() {
+                    writeln!(self.f, "    {v}")?;
+                }
+            }
+            FormatLayout::KeyValue => {
+                for v in values.iter() {
+                    self.value(name, ident, Some(v))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a flag, if it's set.
+    pub(crate) fn flag(&mut self, name: &str, ident: Option<&str>, value: bool) -> fmt::Result {
+        if !value {
+            return Ok(());
+        }
+        match self.style.layout {
+            FormatLayout::Compact => self.name(name, ident),
+            FormatLayout::Verbose => {
+                self.name(name, ident)?;
+                writeln!(self.f)
+            }
+            FormatLayout::KeyValue => self.value(name, ident, Some(true)),
+        }
+    }
+
+    /// Writes chapters, along with their start and duration, if there are any.
+    pub(crate) fn chapters(
+        &mut self,
+        name: &str,
+        ident: Option<&str>,
+        chapters: &[Chapter],
+        duration: Duration,
+    ) -> fmt::Result {
+        if chapters.is_empty() {
+            return Ok(());
+        }
+        match self.style.layout {
+            FormatLayout::Compact => {
+                self.name(name, ident)?;
+                write!(self.f, ": {} chapters", chapters.len())
+            }
+            FormatLayout::Verbose => {
+                self.name(name, ident)?;
+                writeln!(self.f, ":")?;
+                util::format_chapters(self.f, chapters, duration)
+            }
+            FormatLayout::KeyValue => {
+                for c in chapters {
+                    let value = format!("{} {}", Timestamp(c.start), c.title);
+                    self.value(name, ident, Some(value))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Formats a duration in an easily readable way.
+pub(crate) struct DisplayDuration(pub Duration);
+
+impl Display for DisplayDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        util::format_duration(f, self.0)
+    }
+}
+
+/// Formats a duration as `hh:mm:ss.fff`.
+struct Timestamp(Duration);
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let millis = self.0.subsec_millis();
+        write!(f, "{:02}:{:02}:{:02}.{millis:03}", secs / 3600, secs / 60 % 60, secs % 60)
+    }
+}
+
+/// Escapes line breaks and backslashes, so a value stays on a single line.
+struct Escaped<T>(T);
+
+impl<T: Display> Display for Escaped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string = self.0.to_string();
+        for c in string.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
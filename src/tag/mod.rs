@@ -1,22 +1,45 @@
+use std::cell::RefCell;
 use std::fmt;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek};
+use std::io::{BufReader, Cursor, Read, Seek};
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
 
-use crate::{AudioInfo, ReadConfig, atom, util};
+#[cfg(feature = "chapters")]
+use crate::ChapterTrackIter;
+use crate::{
+    AudioInfo, ChapterSources, ChapterTrack, ParseWarning, ReadConfig, SharedImg, VideoInfo, atom,
+};
 
+pub use format::{FormatLayout, FormatStyle, TagDisplay};
 pub use userdata::*;
 
+pub(crate) use format::{DisplayDuration, TagFormatter};
+
+mod format;
 mod readonly;
 mod userdata;
 
 /// A tag containing MPEG-4 audio metadata.
+///
+/// Tags, as well as the iterators returned by their accessors, are [`Send`] and [`Sync`], so they
+/// can be read, modified and written in parallel.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Tag {
     /// The filetype (`ftyp`) atom.
     pub ftyp: String,
     pub info: AudioInfo,
+    /// The chapter representations found in the file.
+    pub chapter_sources: ChapterSources,
+    /// All chapter tracks found in the file.
+    pub chapter_tracks: Vec<ChapterTrack>,
+    /// The timescale that was used to read the chapter list, if one was read.
+    pub chpl_timescale: Option<u32>,
+    /// The component name of the handler (`hdlr`) of the metadata atom (`meta`) containing the
+    /// item list, if present.
+    pub handler_name: Option<String>,
+    /// The video information of the first video track, if present.
+    pub video_info: Option<VideoInfo>,
     pub userdata: Userdata,
 }
 
@@ -36,82 +59,48 @@ impl DerefMut for Tag {
 
 impl fmt::Display for Tag {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.format_album_artists(f)?;
-        self.format_album_artist_sort_orders(f)?;
-        self.format_artists(f)?;
-        self.format_artist_sort_orders(f)?;
-        self.format_composers(f)?;
-        self.format_composer_sort_orders(f)?;
-        self.format_lyricists(f)?;
-        self.format_album(f)?;
-        self.format_album_sort_order(f)?;
-        self.format_title(f)?;
-        self.format_title_sort_order(f)?;
-        self.format_genres(f)?;
-        self.format_year(f)?;
-        self.format_track(f)?;
-        self.format_disc(f)?;
-        self.format_artworks(f)?;
-        self.format_advisory_rating(f)?;
-        self.format_media_type(f)?;
-        self.format_groupings(f)?;
-        self.format_descriptions(f)?;
-        self.format_comments(f)?;
-        self.format_categories(f)?;
-        self.format_keywords(f)?;
-        self.format_copyright(f)?;
-        self.format_encoder(f)?;
-        self.format_publisher(f)?;
-        self.format_tv_show_name(f)?;
-        self.format_tv_show_name_sort_order(f)?;
-        self.format_tv_network_name(f)?;
-        self.format_tv_episode_name(f)?;
-        self.format_tv_episode(f)?;
-        self.format_tv_season(f)?;
-        self.format_bpm(f)?;
-        self.format_movement(f)?;
-        self.format_work(f)?;
-        self.format_movement_count(f)?;
-        self.format_movement_index(f)?;
-        self.format_duration(f)?;
-        self.format_channel_config(f)?;
-        self.format_sample_rate(f)?;
-        self.format_avg_bitrate(f)?;
-        self.format_max_bitrate(f)?;
-        self.format_show_movement(f)?;
-        self.format_gapless_playback(f)?;
-        self.format_compilation(f)?;
-        self.format_isrc(f)?;
-        self.format_label(f)?;
-        self.format_lyrics(f)?;
-        self.format_chapter_list(f)?;
-        self.format_chapter_track(f)?;
-        writeln!(f, "filetype: {}", self.filetype())
+        self.format(FormatStyle::VERBOSE).fmt(f)
     }
 }
 
 impl Tag {
-    fn format_chapter_list(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if !self.userdata.chapter_list.is_empty() {
-            writeln!(f, "chapter list:")?;
-            util::format_chapters(f, &self.chapter_list, self.info.duration)?;
-        }
-        Ok(())
+    fn format_chapter_list(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.chapters("chapter list", Some("chpl"), &self.chapter_list, self.info.duration)
     }
 
-    fn format_chapter_track(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if !self.userdata.chapter_track.is_empty() {
-            writeln!(f, "chapter track:")?;
-            util::format_chapters(f, &self.chapter_track, self.info.duration)?;
-        }
-        Ok(())
+    fn format_chapter_track(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.chapters("chapter track", None, &self.chapter_track, self.info.duration)
     }
 }
 
 impl Tag {
     /// Attempts to read a MPEG-4 audio tag from the reader.
     pub fn read_with(reader: &mut (impl Read + Seek), cfg: &ReadConfig) -> crate::Result<Self> {
-        atom::read_tag(reader, cfg)
+        atom::read_tag(reader, cfg, None)
+    }
+
+    /// Returns an iterator over the chapters of the chapter track of the reader. Chapter titles are
+    /// only read when the iterator is advanced, so only the chapters that are needed are read.
+    ///
+    /// The iterator is empty if no chapter track is found. The brands of the filetype atom
+    /// (`ftyp`) aren't checked, and files without one are accepted, see
+    /// [`BrandPolicy::Skip`](crate::BrandPolicy::Skip).
+    #[cfg(feature = "chapters")]
+    pub fn chapter_track_lazy<R: Read + Seek>(
+        reader: &mut R,
+    ) -> crate::Result<ChapterTrackIter<'_, R>> {
+        atom::read_chapter_track_lazy(reader)
+    }
+
+    /// Attempts to read a MPEG-4 audio tag from the reader. Additionally returns non-fatal issues
+    /// that were encountered, such as unknown atoms or empty data.
+    pub fn read_with_warnings(
+        reader: &mut (impl Read + Seek),
+        cfg: &ReadConfig,
+    ) -> crate::Result<(Self, Vec<ParseWarning>)> {
+        let warnings = RefCell::new(Vec::new());
+        let tag = atom::read_tag(reader, cfg, Some(&warnings))?;
+        Ok((tag, warnings.into_inner()))
     }
 
     /// Attempts to read a MPEG-4 audio tag from the reader.
@@ -119,6 +108,17 @@ impl Tag {
         Self::read_with(reader, &ReadConfig::DEFAULT)
     }
 
+    /// Attempts to read a MPEG-4 audio tag from the bytes of a whole file. No filesystem access is
+    /// necessary, which makes this suitable for environments without one, such as the browser.
+    pub fn read_with_slice(data: &[u8], cfg: &ReadConfig) -> crate::Result<Self> {
+        Self::read_with(&mut Cursor::new(data), cfg)
+    }
+
+    /// Attempts to read a MPEG-4 audio tag from the bytes of a whole file.
+    pub fn read_from_slice(data: &[u8]) -> crate::Result<Self> {
+        Self::read_with_slice(data, &ReadConfig::DEFAULT)
+    }
+
     /// Attempts to read a MPEG-4 audio tag from the file at the indicated path.
     pub fn read_with_path(path: impl AsRef<Path>, cfg: &ReadConfig) -> crate::Result<Self> {
         let mut file = BufReader::new(File::open(path)?);
@@ -130,3 +130,29 @@ impl Tag {
         Self::read_with_path(path, &ReadConfig::DEFAULT)
     }
 }
+
+/// Compile time assertions that tags and their iterators can be sent to and shared between threads.
+const _: () = {
+    const fn assert<T: Send + Sync>() {}
+    fn assert_val<T: Send + Sync>(_: &T) {}
+
+    // the iterators can't be named, so they're checked in a function that is never called
+    fn iterators(tag: &mut Tag) {
+        assert_val(&tag.data());
+        assert_val(&tag.data_mut());
+        assert_val(&tag.images());
+        assert_val(&tag.strings_mut());
+        assert_val(&tag.take_data_of(&crate::ident::ARTWORK));
+        assert_val(&tag.take_artworks());
+        assert_val(&tag.userdata.clone().into_data());
+        assert_val(&tag.userdata.clone().into_images());
+    }
+
+    assert::<Tag>();
+    assert::<UserdataSnapshot>();
+    assert::<SharedImg>();
+    assert::<crate::Error>();
+    #[cfg(feature = "chapters")]
+    assert::<ChapterTrackIter<'_, Cursor<Vec<u8>>>>();
+    let _ = iterators as fn(&mut Tag);
+};
@@ -1,7 +1,10 @@
 use std::fmt;
 use std::time::Duration;
 
-use crate::{AudioInfo, ChannelConfig, SampleRate, Tag, util};
+use crate::tag::{DisplayDuration, TagFormatter};
+use crate::{
+    AacProfile, AudioInfo, ChannelConfig, ChapterSources, ChapterTrack, SampleRate, Tag, VideoInfo,
+};
 
 /// ### Audio information
 impl Tag {
@@ -10,16 +13,43 @@ impl Tag {
         &self.info
     }
 
-    /// Returns the duration in seconds.
+    /// Returns a reference of the video information of the first video track, if present.
+    pub fn video_info(&self) -> Option<&VideoInfo> {
+        self.video_info.as_ref()
+    }
+
+    /// Returns the duration in seconds. For fragmented files, such as DASH initialization
+    /// segments, that don't specify a duration in the movie header, the duration of all
+    /// fragments from the movie extends header (`mehd`) is used.
     pub fn duration(&self) -> Duration {
         self.info.duration
     }
 
     /// Returns the duration formatted in an easily readable way.
-    pub(crate) fn format_duration(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "duration: ")?;
-        util::format_duration(f, self.duration())?;
-        writeln!(f)
+    pub(crate) fn format_duration(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("duration", None, Some(DisplayDuration(self.duration())))
+    }
+
+    /// Returns the timescale of the movie: the number of units that pass per second.
+    pub fn timescale(&self) -> u32 {
+        self.info.timescale
+    }
+
+    /// Returns the duration of the movie in units of the [`timescale`](Self::timescale), without
+    /// any loss of precision.
+    pub fn raw_duration(&self) -> u64 {
+        self.info.raw_duration
+    }
+
+    /// Returns the timescale of the audio track's media.
+    pub fn media_timescale(&self) -> Option<u32> {
+        self.info.media_timescale
+    }
+
+    /// Returns the duration of the audio track's media in units of the
+    /// [`media_timescale`](Self::media_timescale), without any loss of precision.
+    pub fn media_raw_duration(&self) -> Option<u64> {
+        self.info.media_raw_duration
     }
 
     /// Returns the channel configuration.
@@ -27,23 +57,51 @@ impl Tag {
         self.info.channel_config
     }
 
-    pub(crate) fn format_channel_config(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.channel_config() {
-            Some(c) => writeln!(f, "channel config: {c}"),
-            None => Ok(()),
-        }
+    pub(crate) fn format_channel_config(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("channel config", None, self.channel_config())
     }
 
-    /// Returns the channel configuration.
+    /// Returns the sample rate.
     pub fn sample_rate(&self) -> Option<SampleRate> {
         self.info.sample_rate
     }
 
-    pub(crate) fn format_sample_rate(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.sample_rate() {
-            Some(r) => writeln!(f, "sample rate: {r}"),
-            None => Ok(()),
-        }
+    /// Returns the sample rate in Hz. Unlike [`Tag::sample_rate`] this is also present for rates
+    /// that don't correspond to a [`SampleRate`].
+    pub fn sample_rate_hz(&self) -> Option<u32> {
+        self.info.sample_rate_hz
+    }
+
+    pub(crate) fn format_sample_rate(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("sample rate", None, self.sample_rate_hz().map(|r| format!("{r}Hz")))
+    }
+
+    /// Returns the AAC profile.
+    pub fn aac_profile(&self) -> Option<AacProfile> {
+        self.info.aac_profile
+    }
+
+    pub(crate) fn format_aac_profile(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("profile", None, self.aac_profile())
+    }
+
+    /// Returns whether spectral band replication (SBR) is signaled.
+    pub fn sbr(&self) -> bool {
+        self.info.sbr
+    }
+
+    /// Returns whether parametric stereo (PS) is signaled.
+    pub fn ps(&self) -> bool {
+        self.info.ps
+    }
+
+    /// Returns the bit depth, only present for lossless codecs.
+    pub fn bit_depth(&self) -> Option<u8> {
+        self.info.bit_depth
+    }
+
+    pub(crate) fn format_bit_depth(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("bit depth", None, self.bit_depth())
     }
 
     /// Returns the average bitrate.
@@ -51,11 +109,8 @@ impl Tag {
         self.info.avg_bitrate
     }
 
-    pub(crate) fn format_avg_bitrate(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.avg_bitrate() {
-            Some(c) => writeln!(f, "average bitrate: {}kbps", c / 1024),
-            None => Ok(()),
-        }
+    pub(crate) fn format_avg_bitrate(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("average bitrate", None, self.avg_bitrate().map(|c| format!("{}kbps", c / 1024)))
     }
 
     /// Returns the maximum bitrate.
@@ -63,11 +118,43 @@ impl Tag {
         self.info.max_bitrate
     }
 
-    pub(crate) fn format_max_bitrate(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.max_bitrate() {
-            Some(c) => writeln!(f, "maximum bitrate: {}kbps", c / 1024),
-            None => Ok(()),
-        }
+    pub(crate) fn format_max_bitrate(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("maximum bitrate", None, self.max_bitrate().map(|c| format!("{}kbps", c / 1024)))
+    }
+}
+
+/// ### Chapter sources
+impl Tag {
+    /// Returns which chapter representations the file contains, and the track id of the chapter
+    /// track. This is independent of whether the chapter list or track actually contain chapters.
+    pub fn chapter_sources(&self) -> ChapterSources {
+        self.chapter_sources
+    }
+
+    /// Returns all chapter tracks of the file, in the order they're referenced. The chapters of
+    /// the first one are also available through [`Userdata::chapter_track`]. A different chapter
+    /// track can be overwritten by setting
+    /// [`WriteConfig::chapter_track_id`](crate::WriteConfig::chapter_track_id).
+    ///
+    /// [`Userdata::chapter_track`]: crate::Userdata::chapter_track
+    pub fn chapter_tracks(&self) -> &[ChapterTrack] {
+        &self.chapter_tracks
+    }
+
+    /// Returns the timescale that was used to read the chapter list (`chpl`), if one was read.
+    /// This is useful in combination with [`ChplTimescale::Auto`](crate::ChplTimescale::Auto).
+    pub fn chpl_timescale(&self) -> Option<u32> {
+        self.chpl_timescale
+    }
+}
+
+/// ### Handler
+impl Tag {
+    /// Returns the component name of the handler (`hdlr`) of the metadata atom (`meta`) that
+    /// contains the item list, for example `"Apple"` or `"mp4v2"`. The handler is preserved when
+    /// the tag is written.
+    pub fn handler_name(&self) -> Option<&str> {
+        self.handler_name.as_deref()
     }
 }
 
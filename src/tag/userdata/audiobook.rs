@@ -0,0 +1,111 @@
+use crate::{Data, DataIdent, Userdata, ident};
+
+/// The metadata of an audiobook, which is stored in several separate items, see
+/// [`Userdata::audiobook_info`].
+///
+/// The fields are mapped to the following items, which are read by Apple Books, Audiobookshelf
+/// and Plex. The author and description are stored in general items, which are also used by
+/// files that aren't audiobooks, so they are only written but never removed.
+///
+/// | Field         | Item                                                      |
+/// |---------------|-----------------------------------------------------------|
+/// | `author`      | artist (`©ART`)                                           |
+/// | `narrator`    | narrator (`©nrt`), or `----:com.apple.iTunes:NARRATOR`    |
+/// | `series`      | `----:com.apple.iTunes:SERIES`                            |
+/// | `series_part` | `----:com.apple.iTunes:SERIES-PART`                       |
+/// | `description` | description (`desc`)                                      |
+/// | `asin`        | `----:com.apple.iTunes:ASIN`                              |
+///
+/// ```
+/// use mp4ameta::{AudiobookInfo, Tag};
+///
+/// let mut tag = Tag::default();
+/// tag.set_audiobook_info(AudiobookInfo {
+///     author: Some("author".into()),
+///     narrator: Some("narrator".into()),
+///     series: Some("series".into()),
+///     series_part: Some("1".into()),
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(tag.artist(), Some("author"));
+/// assert_eq!(tag.narrator(), Some("narrator"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudiobookInfo {
+    /// The author, which is stored as the artist (`©ART`).
+    pub author: Option<String>,
+    /// The narrator (`©nrt`).
+    pub narrator: Option<String>,
+    /// The name of the series (`----:com.apple.iTunes:SERIES`).
+    pub series: Option<String>,
+    /// The position in the series, which isn't necessarily an integer, for example `"2.5"`
+    /// (`----:com.apple.iTunes:SERIES-PART`).
+    pub series_part: Option<String>,
+    /// The description (`desc`).
+    pub description: Option<String>,
+    /// The Amazon Standard Identification Number (`----:com.apple.iTunes:ASIN`).
+    pub asin: Option<String>,
+}
+
+/// ### Audiobook
+impl Userdata {
+    /// Returns the author, narrator, series, series part, description and ASIN, or `None` if none
+    /// of the audiobook specific items (narrator, series, series part and ASIN) are present. If no
+    /// narrator (`©nrt`) is present, the freeform narrator (`----:com.apple.iTunes:NARRATOR`)
+    /// written by some taggers is returned instead.
+    pub fn audiobook_info(&self) -> Option<AudiobookInfo> {
+        let freeform_narrator = || self.strings_of(&ident::FREEFORM_NARRATOR).next();
+        let narrator = self.narrator().or_else(freeform_narrator);
+        if narrator.is_none()
+            && self.series().is_none()
+            && self.series_part().is_none()
+            && self.asin().is_none()
+        {
+            return None;
+        }
+        Some(AudiobookInfo {
+            author: self.artist().map(str::to_owned),
+            narrator: narrator.map(str::to_owned),
+            series: self.series().map(str::to_owned),
+            series_part: self.series_part().map(str::to_owned),
+            description: self.description().map(str::to_owned),
+            asin: self.asin().map(str::to_owned),
+        })
+    }
+
+    /// Sets the audiobook specific items (narrator, series, series part and ASIN), removing the
+    /// ones that are `None`. The narrator is always stored as `©nrt`, a freeform narrator
+    /// (`----:com.apple.iTunes:NARRATOR`) is removed.
+    ///
+    /// The author and description are general items, which are only set if they are `Some`, and
+    /// never removed.
+    pub fn set_audiobook_info(&mut self, info: AudiobookInfo) {
+        self.remove_data_of(&ident::FREEFORM_NARRATOR);
+        let items: [(DataIdent, _); 4] = [
+            (ident::NARRATOR.into(), info.narrator),
+            (ident::SERIES.into(), info.series),
+            (ident::SERIES_PART.into(), info.series_part),
+            (ident::ASIN.into(), info.asin),
+        ];
+        for (ident, value) in items {
+            match value {
+                Some(v) => self.set_data(ident, Data::Utf8(v)),
+                None => self.remove_data_of(&ident),
+            }
+        }
+
+        if let Some(a) = info.author {
+            self.set_artist(a);
+        }
+        if let Some(d) = info.description {
+            self.set_description(d);
+        }
+    }
+
+    /// Removes the audiobook specific items: the narrator, series, series part and ASIN. The
+    /// author (`©ART`) and description (`desc`) are left untouched.
+    pub fn remove_audiobook_info(&mut self) {
+        self.set_audiobook_info(AudiobookInfo::default());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{ImgFmt, ImgRef, TargetPlayer, Userdata};
+
+/// The maximum width and height of artwork, which Apple Books displays.
+const APPLE_MAX_ARTWORK_SIZE: u32 = 3000;
+
+/// A known compatibility issue of the userdata with a player, see [`Userdata::check_compat`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompatIssue {
+    /// The artwork at the index is larger than the player displays, so it's either downscaled or
+    /// not displayed at all.
+    ArtworkTooLarge {
+        /// The index among the artwork images.
+        index: usize,
+        /// The width in pixels.
+        width: u32,
+        /// The height in pixels.
+        height: u32,
+        /// The maximum width and height in pixels.
+        max_size: u32,
+    },
+    /// The artwork at the index is stored in a format the player doesn't display.
+    UnsupportedArtworkFmt {
+        /// The index among the artwork images.
+        index: usize,
+        /// The image format.
+        fmt: ImgFmt,
+    },
+    /// There are multiple artwork images, but the player only displays the first one.
+    MultipleArtworks,
+    /// The chapters are only stored in the chapter list (`chpl`), which the player ignores,
+    /// because it only reads the chapter track, like all players based on AVFoundation.
+    ChapterListOnly,
+    /// The chapter list (`chpl`) and chapter track contain different chapters. The player reads
+    /// both, but only shows one of them.
+    ChaptersDiffer,
+    /// The title of the chapter at the index exceeds 255 bytes, the maximum length of titles in
+    /// the chapter list (`chpl`), which the player reads. Titles in the chapter list are always
+    /// truncated to 255 bytes when written.
+    ChapterTitleTooLong {
+        /// The index among the chapters of the chapter list.
+        index: usize,
+        /// The length of the title in bytes.
+        len: usize,
+    },
+}
+
+/// ### Compatibility
+impl Userdata {
+    /// Returns the known issues of the userdata with the player, so they can be fixed before the
+    /// file is written. The checks match the representations written by
+    /// [`WriteConfig::for_player`](crate::WriteConfig::for_player).
+    ///
+    /// | issue                                  | player                  |
+    /// |----------------------------------------|-------------------------|
+    /// | [`CompatIssue::ArtworkTooLarge`]       | Apple Books (3000 px)   |
+    /// | [`CompatIssue::UnsupportedArtworkFmt`] | Apple Books (jpeg, png) |
+    /// | [`CompatIssue::MultipleArtworks`]      | Apple Books             |
+    /// | [`CompatIssue::ChapterListOnly`]       | Apple Books             |
+    /// | [`CompatIssue::ChaptersDiffer`]        | VLC, Audiobookshelf     |
+    /// | [`CompatIssue::ChapterTitleTooLong`]   | VLC, Audiobookshelf     |
+    ///
+    /// The text encoding of chapter titles isn't checked, it's configured when writing, see
+    /// [`WriteConfig::chapter_text_encoding`](crate::WriteConfig::chapter_text_encoding).
+    ///
+    /// ```
+    /// use mp4ameta::{Chapter, CompatIssue, TargetPlayer, Userdata};
+    ///
+    /// let mut userdata = Userdata::default();
+    /// userdata.chapter_list_mut().push(Chapter::new(Default::default(), "intro"));
+    ///
+    /// let issues = userdata.check_compat(TargetPlayer::AppleBooks);
+    /// assert_eq!(issues, [CompatIssue::ChapterListOnly]);
+    /// ```
+    pub fn check_compat(&self, player: TargetPlayer) -> Vec<CompatIssue> {
+        let mut issues = Vec::new();
+        match player {
+            TargetPlayer::AppleBooks => {
+                for (index, img) in self.artworks().enumerate() {
+                    if !matches!(img.fmt, ImgFmt::Jpeg | ImgFmt::Png) {
+                        issues.push(CompatIssue::UnsupportedArtworkFmt { index, fmt: img.fmt });
+                        continue;
+                    }
+                    let Some((width, height)) = img_dimensions(img) else {
+                        continue;
+                    };
+                    let max_size = APPLE_MAX_ARTWORK_SIZE;
+                    if width > max_size || height > max_size {
+                        issues.push(CompatIssue::ArtworkTooLarge {
+                            index,
+                            width,
+                            height,
+                            max_size,
+                        });
+                    }
+                }
+                if self.artworks().nth(1).is_some() {
+                    issues.push(CompatIssue::MultipleArtworks);
+                }
+                if !self.chapter_list.is_empty() && self.chapter_track.is_empty() {
+                    issues.push(CompatIssue::ChapterListOnly);
+                }
+            }
+            TargetPlayer::Vlc | TargetPlayer::Audiobookshelf => {
+                let (list, track) = (&self.chapter_list, &self.chapter_track);
+                if !list.is_empty() && !track.is_empty() && list != track {
+                    issues.push(CompatIssue::ChaptersDiffer);
+                }
+                for (index, c) in list.iter().enumerate() {
+                    if c.title.len() > u8::MAX as usize {
+                        issues.push(CompatIssue::ChapterTitleTooLong { index, len: c.title.len() });
+                    }
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Reads the width and height from the header of a png or jpeg image.
+fn img_dimensions(img: ImgRef<'_>) -> Option<(u32, u32)> {
+    let be_u16 = |b: &[u8], i: usize| Some(u16::from_be_bytes(b.get(i..i + 2)?.try_into().ok()?));
+    let be_u32 = |b: &[u8], i: usize| Some(u32::from_be_bytes(b.get(i..i + 4)?.try_into().ok()?));
+
+    let data = img.data;
+    match img.fmt {
+        // signature (8), IHDR length and type (8), width (4), height (4)
+        ImgFmt::Png => Some((be_u32(data, 16)?, be_u32(data, 20)?)),
+        ImgFmt::Jpeg => {
+            // skip the start of image marker and walk the segments until a start of frame
+            let mut pos = 2;
+            loop {
+                if *data.get(pos)? != 0xFF {
+                    return None;
+                }
+                let marker = *data.get(pos + 1)?;
+                let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+                if is_sof {
+                    // length (2), precision (1), height (2), width (2)
+                    let height = be_u16(data, pos + 5)?;
+                    let width = be_u16(data, pos + 7)?;
+                    return Some((width as u32, height as u32));
+                }
+                pos += 2 + be_u16(data, pos + 2)? as usize;
+            }
+        }
+        _ => None,
+    }
+}
@@ -2,7 +2,11 @@
 // Edit the `generate.toml` file and run the code generation from the repository root
 // with the following command: `cargo run --manifest-path=gen/Cargo.toml`
 
-use crate::{ident, Data, Userdata};
+use std::borrow::Cow;
+
+use crate::ident::{IdentInfo, ValueKind};
+use crate::tag::TagFormatter;
+use crate::{ident, Data, DataIdent, Tag, Userdata};
 
 /// ### Album
 impl Userdata {
@@ -28,11 +32,8 @@ impl Userdata {
 
     /// Returns the album formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_album(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.album() {
-            Some(s) => writeln!(f, "album: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_album(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("album", Some("©alb"), self.album())
     }
 }
 
@@ -60,11 +61,8 @@ impl Userdata {
 
     /// Returns the copyright formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_copyright(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.copyright() {
-            Some(s) => writeln!(f, "copyright: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_copyright(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("copyright", Some("cprt"), self.copyright())
     }
 }
 
@@ -92,11 +90,37 @@ impl Userdata {
 
     /// Returns the encoder formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_encoder(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.encoder() {
-            Some(s) => writeln!(f, "encoder: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_encoder(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("encoder", Some("©too"), self.encoder())
+    }
+}
+
+/// ### Long description
+impl Userdata {
+    /// Returns the long description (`ldes`).
+    pub fn long_description(&self) -> Option<&str> {
+        self.strings_of(&ident::LONG_DESCRIPTION).next()
+    }
+
+    /// Removes and returns the long description (`ldes`).
+    pub fn take_long_description(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::LONG_DESCRIPTION).next()
+    }
+
+    /// Sets the long description (`ldes`).
+    pub fn set_long_description(&mut self, long_description: impl Into<String>) {
+        self.set_data(ident::LONG_DESCRIPTION, Data::Utf8(long_description.into()));
+    }
+
+    /// Removes the long description (`ldes`).
+    pub fn remove_long_description(&mut self) {
+        self.remove_data_of(&ident::LONG_DESCRIPTION);
+    }
+
+    /// Returns the long description formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_long_description(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("long description", Some("ldes"), self.long_description())
     }
 }
 
@@ -124,11 +148,8 @@ impl Userdata {
 
     /// Returns the lyrics formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_lyrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.lyrics() {
-            Some(s) => writeln!(f, "lyrics: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_lyrics(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("lyrics", Some("©lyr"), self.lyrics())
     }
 }
 
@@ -156,11 +177,124 @@ impl Userdata {
 
     /// Returns the movement formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_movement(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.movement() {
-            Some(s) => writeln!(f, "movement: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_movement(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("movement", Some("©mvn"), self.movement())
+    }
+}
+
+/// ### Narrator
+impl Userdata {
+    /// Returns the narrator (`©nrt`).
+    pub fn narrator(&self) -> Option<&str> {
+        self.strings_of(&ident::NARRATOR).next()
+    }
+
+    /// Removes and returns the narrator (`©nrt`).
+    pub fn take_narrator(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::NARRATOR).next()
+    }
+
+    /// Sets the narrator (`©nrt`).
+    pub fn set_narrator(&mut self, narrator: impl Into<String>) {
+        self.set_data(ident::NARRATOR, Data::Utf8(narrator.into()));
+    }
+
+    /// Removes the narrator (`©nrt`).
+    pub fn remove_narrator(&mut self) {
+        self.remove_data_of(&ident::NARRATOR);
+    }
+
+    /// Returns the narrator formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_narrator(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("narrator", Some("©nrt"), self.narrator())
+    }
+}
+
+/// ### Owner
+impl Userdata {
+    /// Returns the owner (`ownr`).
+    pub fn owner(&self) -> Option<&str> {
+        self.strings_of(&ident::OWNER).next()
+    }
+
+    /// Removes and returns the owner (`ownr`).
+    pub fn take_owner(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::OWNER).next()
+    }
+
+    /// Sets the owner (`ownr`).
+    pub fn set_owner(&mut self, owner: impl Into<String>) {
+        self.set_data(ident::OWNER, Data::Utf8(owner.into()));
+    }
+
+    /// Removes the owner (`ownr`).
+    pub fn remove_owner(&mut self) {
+        self.remove_data_of(&ident::OWNER);
+    }
+
+    /// Returns the owner formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_owner(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("owner", Some("ownr"), self.owner())
+    }
+}
+
+/// ### Podcast episode global unique id
+impl Userdata {
+    /// Returns the podcast episode global unique id (`egid`).
+    pub fn podcast_episode_global_unique_id(&self) -> Option<&str> {
+        self.strings_of(&ident::PODCAST_EPISODE_GLOBAL_UNIQUE_ID).next()
+    }
+
+    /// Removes and returns the podcast episode global unique id (`egid`).
+    pub fn take_podcast_episode_global_unique_id(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::PODCAST_EPISODE_GLOBAL_UNIQUE_ID).next()
+    }
+
+    /// Sets the podcast episode global unique id (`egid`).
+    pub fn set_podcast_episode_global_unique_id(&mut self, podcast_episode_global_unique_id: impl Into<String>) {
+        self.set_data(ident::PODCAST_EPISODE_GLOBAL_UNIQUE_ID, Data::Utf8(podcast_episode_global_unique_id.into()));
+    }
+
+    /// Removes the podcast episode global unique id (`egid`).
+    pub fn remove_podcast_episode_global_unique_id(&mut self) {
+        self.remove_data_of(&ident::PODCAST_EPISODE_GLOBAL_UNIQUE_ID);
+    }
+
+    /// Returns the podcast episode global unique id formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_podcast_episode_global_unique_id(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("podcast episode global unique id", Some("egid"), self.podcast_episode_global_unique_id())
+    }
+}
+
+/// ### Podcast url
+impl Userdata {
+    /// Returns the podcast url (`purl`).
+    pub fn podcast_url(&self) -> Option<&str> {
+        self.strings_of(&ident::PODCAST_URL).next()
+    }
+
+    /// Removes and returns the podcast url (`purl`).
+    pub fn take_podcast_url(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::PODCAST_URL).next()
+    }
+
+    /// Sets the podcast url (`purl`).
+    pub fn set_podcast_url(&mut self, podcast_url: impl Into<String>) {
+        self.set_data(ident::PODCAST_URL, Data::Utf8(podcast_url.into()));
+    }
+
+    /// Removes the podcast url (`purl`).
+    pub fn remove_podcast_url(&mut self) {
+        self.remove_data_of(&ident::PODCAST_URL);
+    }
+
+    /// Returns the podcast url formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_podcast_url(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("podcast url", Some("purl"), self.podcast_url())
     }
 }
 
@@ -188,11 +322,66 @@ impl Userdata {
 
     /// Returns the publisher formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_publisher(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.publisher() {
-            Some(s) => writeln!(f, "publisher: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_publisher(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("publisher", Some("©pub"), self.publisher())
+    }
+}
+
+/// ### Store description
+impl Userdata {
+    /// Returns the store description (`sdes`).
+    pub fn store_description(&self) -> Option<&str> {
+        self.strings_of(&ident::STORE_DESCRIPTION).next()
+    }
+
+    /// Removes and returns the store description (`sdes`).
+    pub fn take_store_description(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::STORE_DESCRIPTION).next()
+    }
+
+    /// Sets the store description (`sdes`).
+    pub fn set_store_description(&mut self, store_description: impl Into<String>) {
+        self.set_data(ident::STORE_DESCRIPTION, Data::Utf8(store_description.into()));
+    }
+
+    /// Removes the store description (`sdes`).
+    pub fn remove_store_description(&mut self) {
+        self.remove_data_of(&ident::STORE_DESCRIPTION);
+    }
+
+    /// Returns the store description formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_store_description(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("store description", Some("sdes"), self.store_description())
+    }
+}
+
+/// ### Purchase date
+impl Userdata {
+    /// Returns the purchase date (`purd`).
+    pub fn purchase_date(&self) -> Option<&str> {
+        self.strings_of(&ident::PURCHASE_DATE).next()
+    }
+
+    /// Removes and returns the purchase date (`purd`).
+    pub fn take_purchase_date(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::PURCHASE_DATE).next()
+    }
+
+    /// Sets the purchase date (`purd`).
+    pub fn set_purchase_date(&mut self, purchase_date: impl Into<String>) {
+        self.set_data(ident::PURCHASE_DATE, Data::Utf8(purchase_date.into()));
+    }
+
+    /// Removes the purchase date (`purd`).
+    pub fn remove_purchase_date(&mut self) {
+        self.remove_data_of(&ident::PURCHASE_DATE);
+    }
+
+    /// Returns the purchase date formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_purchase_date(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("purchase date", Some("purd"), self.purchase_date())
     }
 }
 
@@ -220,11 +409,8 @@ impl Userdata {
 
     /// Returns the title formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_title(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.title() {
-            Some(s) => writeln!(f, "title: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_title(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("title", Some("©nam"), self.title())
     }
 }
 
@@ -252,11 +438,8 @@ impl Userdata {
 
     /// Returns the tv episode name formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_tv_episode_name(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.tv_episode_name() {
-            Some(s) => writeln!(f, "tv episode name: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_tv_episode_name(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("tv episode name", Some("tven"), self.tv_episode_name())
     }
 }
 
@@ -284,11 +467,8 @@ impl Userdata {
 
     /// Returns the tv network name formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_tv_network_name(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.tv_network_name() {
-            Some(s) => writeln!(f, "tv network name: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_tv_network_name(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("tv network name", Some("tvnn"), self.tv_network_name())
     }
 }
 
@@ -316,11 +496,8 @@ impl Userdata {
 
     /// Returns the tv show name formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_tv_show_name(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.tv_show_name() {
-            Some(s) => writeln!(f, "tv show name: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_tv_show_name(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("tv show name", Some("tvsh"), self.tv_show_name())
     }
 }
 
@@ -348,11 +525,8 @@ impl Userdata {
 
     /// Returns the work formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_work(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.work() {
-            Some(s) => writeln!(f, "work: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_work(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("work", Some("©wrk"), self.work())
     }
 }
 
@@ -380,11 +554,8 @@ impl Userdata {
 
     /// Returns the year formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_year(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.year() {
-            Some(s) => writeln!(f, "year: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_year(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("year", Some("©day"), self.year())
     }
 }
 
@@ -412,11 +583,8 @@ impl Userdata {
 
     /// Returns the isrc formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_isrc(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.isrc() {
-            Some(s) => writeln!(f, "isrc: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_isrc(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("isrc", Some("----:com.apple.iTunes:ISRC"), self.isrc())
     }
 }
 
@@ -444,11 +612,385 @@ impl Userdata {
 
     /// Returns the label formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_label(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.label() {
-            Some(s) => writeln!(f, "label: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_label(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("label", Some("----:com.apple.iTunes:LABEL"), self.label())
+    }
+}
+
+/// ### Asin
+impl Userdata {
+    /// Returns the asin (`----:com.apple.iTunes:ASIN`).
+    pub fn asin(&self) -> Option<&str> {
+        self.strings_of(&ident::ASIN).next()
+    }
+
+    /// Removes and returns the asin (`----:com.apple.iTunes:ASIN`).
+    pub fn take_asin(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::ASIN).next()
+    }
+
+    /// Sets the asin (`----:com.apple.iTunes:ASIN`).
+    pub fn set_asin(&mut self, asin: impl Into<String>) {
+        self.set_data(ident::ASIN, Data::Utf8(asin.into()));
+    }
+
+    /// Removes the asin (`----:com.apple.iTunes:ASIN`).
+    pub fn remove_asin(&mut self) {
+        self.remove_data_of(&ident::ASIN);
+    }
+
+    /// Returns the asin formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_asin(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("asin", Some("----:com.apple.iTunes:ASIN"), self.asin())
+    }
+}
+
+/// ### Series
+impl Userdata {
+    /// Returns the series (`----:com.apple.iTunes:SERIES`).
+    pub fn series(&self) -> Option<&str> {
+        self.strings_of(&ident::SERIES).next()
+    }
+
+    /// Removes and returns the series (`----:com.apple.iTunes:SERIES`).
+    pub fn take_series(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::SERIES).next()
+    }
+
+    /// Sets the series (`----:com.apple.iTunes:SERIES`).
+    pub fn set_series(&mut self, series: impl Into<String>) {
+        self.set_data(ident::SERIES, Data::Utf8(series.into()));
+    }
+
+    /// Removes the series (`----:com.apple.iTunes:SERIES`).
+    pub fn remove_series(&mut self) {
+        self.remove_data_of(&ident::SERIES);
+    }
+
+    /// Returns the series formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_series(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("series", Some("----:com.apple.iTunes:SERIES"), self.series())
+    }
+}
+
+/// ### Series part
+impl Userdata {
+    /// Returns the series part (`----:com.apple.iTunes:SERIES-PART`).
+    pub fn series_part(&self) -> Option<&str> {
+        self.strings_of(&ident::SERIES_PART).next()
+    }
+
+    /// Removes and returns the series part (`----:com.apple.iTunes:SERIES-PART`).
+    pub fn take_series_part(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::SERIES_PART).next()
+    }
+
+    /// Sets the series part (`----:com.apple.iTunes:SERIES-PART`).
+    pub fn set_series_part(&mut self, series_part: impl Into<String>) {
+        self.set_data(ident::SERIES_PART, Data::Utf8(series_part.into()));
+    }
+
+    /// Removes the series part (`----:com.apple.iTunes:SERIES-PART`).
+    pub fn remove_series_part(&mut self) {
+        self.remove_data_of(&ident::SERIES_PART);
+    }
+
+    /// Returns the series part formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_series_part(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("series part", Some("----:com.apple.iTunes:SERIES-PART"), self.series_part())
+    }
+}
+
+/// ### Cddb 1
+impl Userdata {
+    /// Returns the cddb 1 (`----:com.apple.iTunes:iTunes_CDDB_1`).
+    pub fn cddb_1(&self) -> Option<&str> {
+        self.strings_of(&ident::CDDB_1).next()
+    }
+
+    /// Removes and returns the cddb 1 (`----:com.apple.iTunes:iTunes_CDDB_1`).
+    pub fn take_cddb_1(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::CDDB_1).next()
+    }
+
+    /// Sets the cddb 1 (`----:com.apple.iTunes:iTunes_CDDB_1`).
+    pub fn set_cddb_1(&mut self, cddb_1: impl Into<String>) {
+        self.set_data(ident::CDDB_1, Data::Utf8(cddb_1.into()));
+    }
+
+    /// Removes the cddb 1 (`----:com.apple.iTunes:iTunes_CDDB_1`).
+    pub fn remove_cddb_1(&mut self) {
+        self.remove_data_of(&ident::CDDB_1);
+    }
+
+    /// Returns the cddb 1 formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_cddb_1(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("cddb 1", Some("----:com.apple.iTunes:iTunes_CDDB_1"), self.cddb_1())
+    }
+}
+
+/// ### Cddb ids
+impl Userdata {
+    /// Returns the cddb ids (`----:com.apple.iTunes:iTunes_CDDB_IDs`).
+    pub fn cddb_ids(&self) -> Option<&str> {
+        self.strings_of(&ident::CDDB_IDS).next()
+    }
+
+    /// Removes and returns the cddb ids (`----:com.apple.iTunes:iTunes_CDDB_IDs`).
+    pub fn take_cddb_ids(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::CDDB_IDS).next()
+    }
+
+    /// Sets the cddb ids (`----:com.apple.iTunes:iTunes_CDDB_IDs`).
+    pub fn set_cddb_ids(&mut self, cddb_ids: impl Into<String>) {
+        self.set_data(ident::CDDB_IDS, Data::Utf8(cddb_ids.into()));
+    }
+
+    /// Removes the cddb ids (`----:com.apple.iTunes:iTunes_CDDB_IDs`).
+    pub fn remove_cddb_ids(&mut self) {
+        self.remove_data_of(&ident::CDDB_IDS);
+    }
+
+    /// Returns the cddb ids formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_cddb_ids(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("cddb ids", Some("----:com.apple.iTunes:iTunes_CDDB_IDs"), self.cddb_ids())
+    }
+}
+
+/// ### Cddb track number
+impl Userdata {
+    /// Returns the cddb track number (`----:com.apple.iTunes:iTunes_CDDB_TrackNumber`).
+    pub fn cddb_track_number(&self) -> Option<&str> {
+        self.strings_of(&ident::CDDB_TRACK_NUMBER).next()
+    }
+
+    /// Removes and returns the cddb track number (`----:com.apple.iTunes:iTunes_CDDB_TrackNumber`).
+    pub fn take_cddb_track_number(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::CDDB_TRACK_NUMBER).next()
+    }
+
+    /// Sets the cddb track number (`----:com.apple.iTunes:iTunes_CDDB_TrackNumber`).
+    pub fn set_cddb_track_number(&mut self, cddb_track_number: impl Into<String>) {
+        self.set_data(ident::CDDB_TRACK_NUMBER, Data::Utf8(cddb_track_number.into()));
+    }
+
+    /// Removes the cddb track number (`----:com.apple.iTunes:iTunes_CDDB_TrackNumber`).
+    pub fn remove_cddb_track_number(&mut self) {
+        self.remove_data_of(&ident::CDDB_TRACK_NUMBER);
+    }
+
+    /// Returns the cddb track number formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_cddb_track_number(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("cddb track number", Some("----:com.apple.iTunes:iTunes_CDDB_TrackNumber"), self.cddb_track_number())
+    }
+}
+
+/// ### Itunes normalization
+impl Userdata {
+    /// Returns the itunes normalization (`----:com.apple.iTunes:iTunNORM`).
+    pub fn itunes_normalization(&self) -> Option<&str> {
+        self.strings_of(&ident::ITUNES_NORMALIZATION).next()
+    }
+
+    /// Removes and returns the itunes normalization (`----:com.apple.iTunes:iTunNORM`).
+    pub fn take_itunes_normalization(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::ITUNES_NORMALIZATION).next()
+    }
+
+    /// Sets the itunes normalization (`----:com.apple.iTunes:iTunNORM`).
+    pub fn set_itunes_normalization(&mut self, itunes_normalization: impl Into<String>) {
+        self.set_data(ident::ITUNES_NORMALIZATION, Data::Utf8(itunes_normalization.into()));
+    }
+
+    /// Removes the itunes normalization (`----:com.apple.iTunes:iTunNORM`).
+    pub fn remove_itunes_normalization(&mut self) {
+        self.remove_data_of(&ident::ITUNES_NORMALIZATION);
+    }
+
+    /// Returns the itunes normalization formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_itunes_normalization(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("itunes normalization", Some("----:com.apple.iTunes:iTunNORM"), self.itunes_normalization())
+    }
+}
+
+/// ### Replay gain track gain
+impl Userdata {
+    /// Returns the replay gain track gain (`----:com.apple.iTunes:replaygain_track_gain`).
+    pub fn replay_gain_track_gain(&self) -> Option<&str> {
+        self.strings_of(&ident::REPLAY_GAIN_TRACK_GAIN).next()
+    }
+
+    /// Removes and returns the replay gain track gain (`----:com.apple.iTunes:replaygain_track_gain`).
+    pub fn take_replay_gain_track_gain(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::REPLAY_GAIN_TRACK_GAIN).next()
+    }
+
+    /// Sets the replay gain track gain (`----:com.apple.iTunes:replaygain_track_gain`).
+    pub fn set_replay_gain_track_gain(&mut self, replay_gain_track_gain: impl Into<String>) {
+        self.set_data(ident::REPLAY_GAIN_TRACK_GAIN, Data::Utf8(replay_gain_track_gain.into()));
+    }
+
+    /// Removes the replay gain track gain (`----:com.apple.iTunes:replaygain_track_gain`).
+    pub fn remove_replay_gain_track_gain(&mut self) {
+        self.remove_data_of(&ident::REPLAY_GAIN_TRACK_GAIN);
+    }
+
+    /// Returns the replay gain track gain formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_replay_gain_track_gain(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("replay gain track gain", Some("----:com.apple.iTunes:replaygain_track_gain"), self.replay_gain_track_gain())
+    }
+}
+
+/// ### Replay gain track peak
+impl Userdata {
+    /// Returns the replay gain track peak (`----:com.apple.iTunes:replaygain_track_peak`).
+    pub fn replay_gain_track_peak(&self) -> Option<&str> {
+        self.strings_of(&ident::REPLAY_GAIN_TRACK_PEAK).next()
+    }
+
+    /// Removes and returns the replay gain track peak (`----:com.apple.iTunes:replaygain_track_peak`).
+    pub fn take_replay_gain_track_peak(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::REPLAY_GAIN_TRACK_PEAK).next()
+    }
+
+    /// Sets the replay gain track peak (`----:com.apple.iTunes:replaygain_track_peak`).
+    pub fn set_replay_gain_track_peak(&mut self, replay_gain_track_peak: impl Into<String>) {
+        self.set_data(ident::REPLAY_GAIN_TRACK_PEAK, Data::Utf8(replay_gain_track_peak.into()));
+    }
+
+    /// Removes the replay gain track peak (`----:com.apple.iTunes:replaygain_track_peak`).
+    pub fn remove_replay_gain_track_peak(&mut self) {
+        self.remove_data_of(&ident::REPLAY_GAIN_TRACK_PEAK);
+    }
+
+    /// Returns the replay gain track peak formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_replay_gain_track_peak(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("replay gain track peak", Some("----:com.apple.iTunes:replaygain_track_peak"), self.replay_gain_track_peak())
+    }
+}
+
+/// ### Replay gain album gain
+impl Userdata {
+    /// Returns the replay gain album gain (`----:com.apple.iTunes:replaygain_album_gain`).
+    pub fn replay_gain_album_gain(&self) -> Option<&str> {
+        self.strings_of(&ident::REPLAY_GAIN_ALBUM_GAIN).next()
+    }
+
+    /// Removes and returns the replay gain album gain (`----:com.apple.iTunes:replaygain_album_gain`).
+    pub fn take_replay_gain_album_gain(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::REPLAY_GAIN_ALBUM_GAIN).next()
+    }
+
+    /// Sets the replay gain album gain (`----:com.apple.iTunes:replaygain_album_gain`).
+    pub fn set_replay_gain_album_gain(&mut self, replay_gain_album_gain: impl Into<String>) {
+        self.set_data(ident::REPLAY_GAIN_ALBUM_GAIN, Data::Utf8(replay_gain_album_gain.into()));
+    }
+
+    /// Removes the replay gain album gain (`----:com.apple.iTunes:replaygain_album_gain`).
+    pub fn remove_replay_gain_album_gain(&mut self) {
+        self.remove_data_of(&ident::REPLAY_GAIN_ALBUM_GAIN);
+    }
+
+    /// Returns the replay gain album gain formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_replay_gain_album_gain(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("replay gain album gain", Some("----:com.apple.iTunes:replaygain_album_gain"), self.replay_gain_album_gain())
+    }
+}
+
+/// ### Replay gain album peak
+impl Userdata {
+    /// Returns the replay gain album peak (`----:com.apple.iTunes:replaygain_album_peak`).
+    pub fn replay_gain_album_peak(&self) -> Option<&str> {
+        self.strings_of(&ident::REPLAY_GAIN_ALBUM_PEAK).next()
+    }
+
+    /// Removes and returns the replay gain album peak (`----:com.apple.iTunes:replaygain_album_peak`).
+    pub fn take_replay_gain_album_peak(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::REPLAY_GAIN_ALBUM_PEAK).next()
+    }
+
+    /// Sets the replay gain album peak (`----:com.apple.iTunes:replaygain_album_peak`).
+    pub fn set_replay_gain_album_peak(&mut self, replay_gain_album_peak: impl Into<String>) {
+        self.set_data(ident::REPLAY_GAIN_ALBUM_PEAK, Data::Utf8(replay_gain_album_peak.into()));
+    }
+
+    /// Removes the replay gain album peak (`----:com.apple.iTunes:replaygain_album_peak`).
+    pub fn remove_replay_gain_album_peak(&mut self) {
+        self.remove_data_of(&ident::REPLAY_GAIN_ALBUM_PEAK);
+    }
+
+    /// Returns the replay gain album peak formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_replay_gain_album_peak(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("replay gain album peak", Some("----:com.apple.iTunes:replaygain_album_peak"), self.replay_gain_album_peak())
+    }
+}
+
+/// ### R128 track gain
+impl Userdata {
+    /// Returns the r128 track gain (`----:com.apple.iTunes:R128_TRACK_GAIN`).
+    pub fn r128_track_gain(&self) -> Option<&str> {
+        self.strings_of(&ident::R128_TRACK_GAIN).next()
+    }
+
+    /// Removes and returns the r128 track gain (`----:com.apple.iTunes:R128_TRACK_GAIN`).
+    pub fn take_r128_track_gain(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::R128_TRACK_GAIN).next()
+    }
+
+    /// Sets the r128 track gain (`----:com.apple.iTunes:R128_TRACK_GAIN`).
+    pub fn set_r128_track_gain(&mut self, r128_track_gain: impl Into<String>) {
+        self.set_data(ident::R128_TRACK_GAIN, Data::Utf8(r128_track_gain.into()));
+    }
+
+    /// Removes the r128 track gain (`----:com.apple.iTunes:R128_TRACK_GAIN`).
+    pub fn remove_r128_track_gain(&mut self) {
+        self.remove_data_of(&ident::R128_TRACK_GAIN);
+    }
+
+    /// Returns the r128 track gain formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_r128_track_gain(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("r128 track gain", Some("----:com.apple.iTunes:R128_TRACK_GAIN"), self.r128_track_gain())
+    }
+}
+
+/// ### R128 album gain
+impl Userdata {
+    /// Returns the r128 album gain (`----:com.apple.iTunes:R128_ALBUM_GAIN`).
+    pub fn r128_album_gain(&self) -> Option<&str> {
+        self.strings_of(&ident::R128_ALBUM_GAIN).next()
+    }
+
+    /// Removes and returns the r128 album gain (`----:com.apple.iTunes:R128_ALBUM_GAIN`).
+    pub fn take_r128_album_gain(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::R128_ALBUM_GAIN).next()
+    }
+
+    /// Sets the r128 album gain (`----:com.apple.iTunes:R128_ALBUM_GAIN`).
+    pub fn set_r128_album_gain(&mut self, r128_album_gain: impl Into<String>) {
+        self.set_data(ident::R128_ALBUM_GAIN, Data::Utf8(r128_album_gain.into()));
+    }
+
+    /// Removes the r128 album gain (`----:com.apple.iTunes:R128_ALBUM_GAIN`).
+    pub fn remove_r128_album_gain(&mut self) {
+        self.remove_data_of(&ident::R128_ALBUM_GAIN);
+    }
+
+    /// Returns the r128 album gain formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_r128_album_gain(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("r128 album gain", Some("----:com.apple.iTunes:R128_ALBUM_GAIN"), self.r128_album_gain())
     }
 }
 
@@ -476,11 +1018,8 @@ impl Userdata {
 
     /// Returns the album sort order formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_album_sort_order(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.album_sort_order() {
-            Some(s) => writeln!(f, "album sort order: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_album_sort_order(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("album sort order", Some("soal"), self.album_sort_order())
     }
 }
 
@@ -508,11 +1047,8 @@ impl Userdata {
 
     /// Returns the title sort order formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_title_sort_order(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.title_sort_order() {
-            Some(s) => writeln!(f, "title sort order: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_title_sort_order(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("title sort order", Some("sonm"), self.title_sort_order())
     }
 }
 
@@ -540,11 +1076,8 @@ impl Userdata {
 
     /// Returns the tv show name sort order formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_tv_show_name_sort_order(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.tv_show_name_sort_order() {
-            Some(s) => writeln!(f, "tv show name sort order: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_tv_show_name_sort_order(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("tv show name sort order", Some("sosn"), self.tv_show_name_sort_order())
     }
 }
 
@@ -571,8 +1104,8 @@ impl Userdata {
     }
 
     /// Sets all album artists (`aART`). This will remove all other album artists.
-    pub fn set_album_artists(&mut self, album_artists: impl IntoIterator<Item = String>) {
-        let data = album_artists.into_iter().map(Data::Utf8);
+    pub fn set_album_artists(&mut self, album_artists: impl IntoIterator<Item = impl Into<String>>) {
+        let data = album_artists.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::ALBUM_ARTIST, data);
     }
 
@@ -582,8 +1115,8 @@ impl Userdata {
     }
 
     /// Adds all album artists (`aART`).
-    pub fn add_album_artists(&mut self, album_artists: impl IntoIterator<Item = String>) {
-        let data = album_artists.into_iter().map(Data::Utf8);
+    pub fn add_album_artists(&mut self, album_artists: impl IntoIterator<Item = impl Into<String>>) {
+        let data = album_artists.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::ALBUM_ARTIST, data);
     }
 
@@ -599,16 +1132,8 @@ impl Userdata {
 
     /// Returns all album artists formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_album_artists(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.album_artists().count() > 1 {
-            writeln!(f, "album artists:")?;
-            for s in self.album_artists() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.album_artist() {
-            writeln!(f, "album artist: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_album_artists(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("album artist", "album artists", Some("aART"), self.album_artists())
     }
 }
 
@@ -635,8 +1160,8 @@ impl Userdata {
     }
 
     /// Sets all artists (`©ART`). This will remove all other artists.
-    pub fn set_artists(&mut self, artists: impl IntoIterator<Item = String>) {
-        let data = artists.into_iter().map(Data::Utf8);
+    pub fn set_artists(&mut self, artists: impl IntoIterator<Item = impl Into<String>>) {
+        let data = artists.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::ARTIST, data);
     }
 
@@ -646,8 +1171,8 @@ impl Userdata {
     }
 
     /// Adds all artists (`©ART`).
-    pub fn add_artists(&mut self, artists: impl IntoIterator<Item = String>) {
-        let data = artists.into_iter().map(Data::Utf8);
+    pub fn add_artists(&mut self, artists: impl IntoIterator<Item = impl Into<String>>) {
+        let data = artists.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::ARTIST, data);
     }
 
@@ -663,16 +1188,8 @@ impl Userdata {
 
     /// Returns all artists formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_artists(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.artists().count() > 1 {
-            writeln!(f, "artists:")?;
-            for s in self.artists() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.artist() {
-            writeln!(f, "artist: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_artists(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("artist", "artists", Some("©ART"), self.artists())
     }
 }
 
@@ -699,8 +1216,8 @@ impl Userdata {
     }
 
     /// Sets all categories (`catg`). This will remove all other categories.
-    pub fn set_categories(&mut self, categories: impl IntoIterator<Item = String>) {
-        let data = categories.into_iter().map(Data::Utf8);
+    pub fn set_categories(&mut self, categories: impl IntoIterator<Item = impl Into<String>>) {
+        let data = categories.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::CATEGORY, data);
     }
 
@@ -710,8 +1227,8 @@ impl Userdata {
     }
 
     /// Adds all categories (`catg`).
-    pub fn add_categories(&mut self, categories: impl IntoIterator<Item = String>) {
-        let data = categories.into_iter().map(Data::Utf8);
+    pub fn add_categories(&mut self, categories: impl IntoIterator<Item = impl Into<String>>) {
+        let data = categories.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::CATEGORY, data);
     }
 
@@ -727,16 +1244,8 @@ impl Userdata {
 
     /// Returns all categories formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_categories(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.categories().count() > 1 {
-            writeln!(f, "categories:")?;
-            for s in self.categories() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.category() {
-            writeln!(f, "category: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_categories(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("category", "categories", Some("catg"), self.categories())
     }
 }
 
@@ -763,8 +1272,8 @@ impl Userdata {
     }
 
     /// Sets all comments (`©cmt`). This will remove all other comments.
-    pub fn set_comments(&mut self, comments: impl IntoIterator<Item = String>) {
-        let data = comments.into_iter().map(Data::Utf8);
+    pub fn set_comments(&mut self, comments: impl IntoIterator<Item = impl Into<String>>) {
+        let data = comments.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::COMMENT, data);
     }
 
@@ -774,8 +1283,8 @@ impl Userdata {
     }
 
     /// Adds all comments (`©cmt`).
-    pub fn add_comments(&mut self, comments: impl IntoIterator<Item = String>) {
-        let data = comments.into_iter().map(Data::Utf8);
+    pub fn add_comments(&mut self, comments: impl IntoIterator<Item = impl Into<String>>) {
+        let data = comments.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::COMMENT, data);
     }
 
@@ -791,16 +1300,8 @@ impl Userdata {
 
     /// Returns all comments formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_comments(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.comments().count() > 1 {
-            writeln!(f, "comments:")?;
-            for s in self.comments() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.comment() {
-            writeln!(f, "comment: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_comments(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("comment", "comments", Some("©cmt"), self.comments())
     }
 }
 
@@ -827,8 +1328,8 @@ impl Userdata {
     }
 
     /// Sets all composers (`©wrt`). This will remove all other composers.
-    pub fn set_composers(&mut self, composers: impl IntoIterator<Item = String>) {
-        let data = composers.into_iter().map(Data::Utf8);
+    pub fn set_composers(&mut self, composers: impl IntoIterator<Item = impl Into<String>>) {
+        let data = composers.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::COMPOSER, data);
     }
 
@@ -838,8 +1339,8 @@ impl Userdata {
     }
 
     /// Adds all composers (`©wrt`).
-    pub fn add_composers(&mut self, composers: impl IntoIterator<Item = String>) {
-        let data = composers.into_iter().map(Data::Utf8);
+    pub fn add_composers(&mut self, composers: impl IntoIterator<Item = impl Into<String>>) {
+        let data = composers.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::COMPOSER, data);
     }
 
@@ -855,16 +1356,8 @@ impl Userdata {
 
     /// Returns all composers formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_composers(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.composers().count() > 1 {
-            writeln!(f, "composers:")?;
-            for s in self.composers() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.composer() {
-            writeln!(f, "composer: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_composers(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("composer", "composers", Some("©wrt"), self.composers())
     }
 }
 
@@ -891,8 +1384,8 @@ impl Userdata {
     }
 
     /// Sets all custom genres (`©gen`). This will remove all other custom genres.
-    pub fn set_custom_genres(&mut self, custom_genres: impl IntoIterator<Item = String>) {
-        let data = custom_genres.into_iter().map(Data::Utf8);
+    pub fn set_custom_genres(&mut self, custom_genres: impl IntoIterator<Item = impl Into<String>>) {
+        let data = custom_genres.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::CUSTOM_GENRE, data);
     }
 
@@ -902,8 +1395,8 @@ impl Userdata {
     }
 
     /// Adds all custom genres (`©gen`).
-    pub fn add_custom_genres(&mut self, custom_genres: impl IntoIterator<Item = String>) {
-        let data = custom_genres.into_iter().map(Data::Utf8);
+    pub fn add_custom_genres(&mut self, custom_genres: impl IntoIterator<Item = impl Into<String>>) {
+        let data = custom_genres.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::CUSTOM_GENRE, data);
     }
 
@@ -919,16 +1412,8 @@ impl Userdata {
 
     /// Returns all custom genres formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_custom_genres(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.custom_genres().count() > 1 {
-            writeln!(f, "custom genres:")?;
-            for s in self.custom_genres() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.custom_genre() {
-            writeln!(f, "custom genre: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_custom_genres(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("custom genre", "custom genres", Some("©gen"), self.custom_genres())
     }
 }
 
@@ -955,8 +1440,8 @@ impl Userdata {
     }
 
     /// Sets all descriptions (`desc`). This will remove all other descriptions.
-    pub fn set_descriptions(&mut self, descriptions: impl IntoIterator<Item = String>) {
-        let data = descriptions.into_iter().map(Data::Utf8);
+    pub fn set_descriptions(&mut self, descriptions: impl IntoIterator<Item = impl Into<String>>) {
+        let data = descriptions.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::DESCRIPTION, data);
     }
 
@@ -966,8 +1451,8 @@ impl Userdata {
     }
 
     /// Adds all descriptions (`desc`).
-    pub fn add_descriptions(&mut self, descriptions: impl IntoIterator<Item = String>) {
-        let data = descriptions.into_iter().map(Data::Utf8);
+    pub fn add_descriptions(&mut self, descriptions: impl IntoIterator<Item = impl Into<String>>) {
+        let data = descriptions.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::DESCRIPTION, data);
     }
 
@@ -983,16 +1468,64 @@ impl Userdata {
 
     /// Returns all descriptions formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_descriptions(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.descriptions().count() > 1 {
-            writeln!(f, "descriptions:")?;
-            for s in self.descriptions() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.description() {
-            writeln!(f, "description: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_descriptions(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("description", "descriptions", Some("desc"), self.descriptions())
+    }
+}
+
+/// ### Director
+impl Userdata {
+    /// Returns all directors (`©dir`).
+    pub fn directors(&self) -> impl Iterator<Item=&str> {
+        self.strings_of(&ident::DIRECTOR)
+    }
+
+    /// Returns the first director (`©dir`).
+    pub fn director(&self) -> Option<&str> {
+        self.strings_of(&ident::DIRECTOR).next()
+    }
+
+    /// Removes and returns all directors (`©dir`).
+    pub fn take_directors(&mut self) -> impl Iterator<Item=String> + '_ {
+        self.take_strings_of(&ident::DIRECTOR)
+    }
+
+    /// Removes all and returns the first director (`©dir`).
+    pub fn take_director(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::DIRECTOR).next()
+    }
+
+    /// Sets all directors (`©dir`). This will remove all other directors.
+    pub fn set_directors(&mut self, directors: impl IntoIterator<Item = impl Into<String>>) {
+        let data = directors.into_iter().map(|v| Data::Utf8(v.into()));
+        self.set_all_data(ident::DIRECTOR, data);
+    }
+
+    /// Sets the director (`©dir`). This will remove all other directors.
+    pub fn set_director(&mut self, director: impl Into<String>) {
+        self.set_data(ident::DIRECTOR, Data::Utf8(director.into()));
+    }
+
+    /// Adds all directors (`©dir`).
+    pub fn add_directors(&mut self, directors: impl IntoIterator<Item = impl Into<String>>) {
+        let data = directors.into_iter().map(|v| Data::Utf8(v.into()));
+        self.add_all_data(ident::DIRECTOR, data);
+    }
+
+    /// Adds an director (`©dir`).
+    pub fn add_director(&mut self, director: impl Into<String>) {
+        self.add_data(ident::DIRECTOR, Data::Utf8(director.into()));
+    }
+
+    /// Removes all directors (`©dir`).
+    pub fn remove_directors(&mut self) {
+        self.remove_data_of(&ident::DIRECTOR);
+    }
+
+    /// Returns all directors formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_directors(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("director", "directors", Some("©dir"), self.directors())
     }
 }
 
@@ -1019,8 +1552,8 @@ impl Userdata {
     }
 
     /// Sets all groupings (`©grp`). This will remove all other groupings.
-    pub fn set_groupings(&mut self, groupings: impl IntoIterator<Item = String>) {
-        let data = groupings.into_iter().map(Data::Utf8);
+    pub fn set_groupings(&mut self, groupings: impl IntoIterator<Item = impl Into<String>>) {
+        let data = groupings.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::GROUPING, data);
     }
 
@@ -1030,8 +1563,8 @@ impl Userdata {
     }
 
     /// Adds all groupings (`©grp`).
-    pub fn add_groupings(&mut self, groupings: impl IntoIterator<Item = String>) {
-        let data = groupings.into_iter().map(Data::Utf8);
+    pub fn add_groupings(&mut self, groupings: impl IntoIterator<Item = impl Into<String>>) {
+        let data = groupings.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::GROUPING, data);
     }
 
@@ -1047,16 +1580,8 @@ impl Userdata {
 
     /// Returns all groupings formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_groupings(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.groupings().count() > 1 {
-            writeln!(f, "groupings:")?;
-            for s in self.groupings() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.grouping() {
-            writeln!(f, "grouping: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_groupings(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("grouping", "groupings", Some("©grp"), self.groupings())
     }
 }
 
@@ -1083,8 +1608,8 @@ impl Userdata {
     }
 
     /// Sets all keywords (`keyw`). This will remove all other keywords.
-    pub fn set_keywords(&mut self, keywords: impl IntoIterator<Item = String>) {
-        let data = keywords.into_iter().map(Data::Utf8);
+    pub fn set_keywords(&mut self, keywords: impl IntoIterator<Item = impl Into<String>>) {
+        let data = keywords.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::KEYWORD, data);
     }
 
@@ -1094,8 +1619,8 @@ impl Userdata {
     }
 
     /// Adds all keywords (`keyw`).
-    pub fn add_keywords(&mut self, keywords: impl IntoIterator<Item = String>) {
-        let data = keywords.into_iter().map(Data::Utf8);
+    pub fn add_keywords(&mut self, keywords: impl IntoIterator<Item = impl Into<String>>) {
+        let data = keywords.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::KEYWORD, data);
     }
 
@@ -1111,16 +1636,8 @@ impl Userdata {
 
     /// Returns all keywords formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_keywords(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.keywords().count() > 1 {
-            writeln!(f, "keywords:")?;
-            for s in self.keywords() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.keyword() {
-            writeln!(f, "keyword: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_keywords(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("keyword", "keywords", Some("keyw"), self.keywords())
     }
 }
 
@@ -1147,8 +1664,8 @@ impl Userdata {
     }
 
     /// Sets all lyricists (`----:com.apple.iTunes:LYRICIST`). This will remove all other lyricists.
-    pub fn set_lyricists(&mut self, lyricists: impl IntoIterator<Item = String>) {
-        let data = lyricists.into_iter().map(Data::Utf8);
+    pub fn set_lyricists(&mut self, lyricists: impl IntoIterator<Item = impl Into<String>>) {
+        let data = lyricists.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::LYRICIST, data);
     }
 
@@ -1158,8 +1675,8 @@ impl Userdata {
     }
 
     /// Adds all lyricists (`----:com.apple.iTunes:LYRICIST`).
-    pub fn add_lyricists(&mut self, lyricists: impl IntoIterator<Item = String>) {
-        let data = lyricists.into_iter().map(Data::Utf8);
+    pub fn add_lyricists(&mut self, lyricists: impl IntoIterator<Item = impl Into<String>>) {
+        let data = lyricists.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::LYRICIST, data);
     }
 
@@ -1175,16 +1692,64 @@ impl Userdata {
 
     /// Returns all lyricists formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_lyricists(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.lyricists().count() > 1 {
-            writeln!(f, "lyricists:")?;
-            for s in self.lyricists() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.lyricist() {
-            writeln!(f, "lyricist: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_lyricists(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("lyricist", "lyricists", Some("----:com.apple.iTunes:LYRICIST"), self.lyricists())
+    }
+}
+
+/// ### Producer
+impl Userdata {
+    /// Returns all producers (`©prd`).
+    pub fn producers(&self) -> impl Iterator<Item=&str> {
+        self.strings_of(&ident::PRODUCER)
+    }
+
+    /// Returns the first producer (`©prd`).
+    pub fn producer(&self) -> Option<&str> {
+        self.strings_of(&ident::PRODUCER).next()
+    }
+
+    /// Removes and returns all producers (`©prd`).
+    pub fn take_producers(&mut self) -> impl Iterator<Item=String> + '_ {
+        self.take_strings_of(&ident::PRODUCER)
+    }
+
+    /// Removes all and returns the first producer (`©prd`).
+    pub fn take_producer(&mut self) -> Option<String> {
+        self.take_strings_of(&ident::PRODUCER).next()
+    }
+
+    /// Sets all producers (`©prd`). This will remove all other producers.
+    pub fn set_producers(&mut self, producers: impl IntoIterator<Item = impl Into<String>>) {
+        let data = producers.into_iter().map(|v| Data::Utf8(v.into()));
+        self.set_all_data(ident::PRODUCER, data);
+    }
+
+    /// Sets the producer (`©prd`). This will remove all other producers.
+    pub fn set_producer(&mut self, producer: impl Into<String>) {
+        self.set_data(ident::PRODUCER, Data::Utf8(producer.into()));
+    }
+
+    /// Adds all producers (`©prd`).
+    pub fn add_producers(&mut self, producers: impl IntoIterator<Item = impl Into<String>>) {
+        let data = producers.into_iter().map(|v| Data::Utf8(v.into()));
+        self.add_all_data(ident::PRODUCER, data);
+    }
+
+    /// Adds an producer (`©prd`).
+    pub fn add_producer(&mut self, producer: impl Into<String>) {
+        self.add_data(ident::PRODUCER, Data::Utf8(producer.into()));
+    }
+
+    /// Removes all producers (`©prd`).
+    pub fn remove_producers(&mut self) {
+        self.remove_data_of(&ident::PRODUCER);
+    }
+
+    /// Returns all producers formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_producers(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("producer", "producers", Some("©prd"), self.producers())
     }
 }
 
@@ -1211,8 +1776,8 @@ impl Userdata {
     }
 
     /// Sets all album artist sort orders (`soaa`). This will remove all other album artist sort orders.
-    pub fn set_album_artist_sort_orders(&mut self, album_artist_sort_orders: impl IntoIterator<Item = String>) {
-        let data = album_artist_sort_orders.into_iter().map(Data::Utf8);
+    pub fn set_album_artist_sort_orders(&mut self, album_artist_sort_orders: impl IntoIterator<Item = impl Into<String>>) {
+        let data = album_artist_sort_orders.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::ALBUM_ARTIST_SORT_ORDER, data);
     }
 
@@ -1222,8 +1787,8 @@ impl Userdata {
     }
 
     /// Adds all album artist sort orders (`soaa`).
-    pub fn add_album_artist_sort_orders(&mut self, album_artist_sort_orders: impl IntoIterator<Item = String>) {
-        let data = album_artist_sort_orders.into_iter().map(Data::Utf8);
+    pub fn add_album_artist_sort_orders(&mut self, album_artist_sort_orders: impl IntoIterator<Item = impl Into<String>>) {
+        let data = album_artist_sort_orders.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::ALBUM_ARTIST_SORT_ORDER, data);
     }
 
@@ -1239,16 +1804,8 @@ impl Userdata {
 
     /// Returns all album artist sort orders formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_album_artist_sort_orders(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.album_artist_sort_orders().count() > 1 {
-            writeln!(f, "album artist sort orders:")?;
-            for s in self.album_artist_sort_orders() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.album_artist_sort_order() {
-            writeln!(f, "album artist sort order: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_album_artist_sort_orders(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("album artist sort order", "album artist sort orders", Some("soaa"), self.album_artist_sort_orders())
     }
 }
 
@@ -1275,8 +1832,8 @@ impl Userdata {
     }
 
     /// Sets all artist sort orders (`soar`). This will remove all other artist sort orders.
-    pub fn set_artist_sort_orders(&mut self, artist_sort_orders: impl IntoIterator<Item = String>) {
-        let data = artist_sort_orders.into_iter().map(Data::Utf8);
+    pub fn set_artist_sort_orders(&mut self, artist_sort_orders: impl IntoIterator<Item = impl Into<String>>) {
+        let data = artist_sort_orders.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::ARTIST_SORT_ORDER, data);
     }
 
@@ -1286,8 +1843,8 @@ impl Userdata {
     }
 
     /// Adds all artist sort orders (`soar`).
-    pub fn add_artist_sort_orders(&mut self, artist_sort_orders: impl IntoIterator<Item = String>) {
-        let data = artist_sort_orders.into_iter().map(Data::Utf8);
+    pub fn add_artist_sort_orders(&mut self, artist_sort_orders: impl IntoIterator<Item = impl Into<String>>) {
+        let data = artist_sort_orders.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::ARTIST_SORT_ORDER, data);
     }
 
@@ -1303,16 +1860,8 @@ impl Userdata {
 
     /// Returns all artist sort orders formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_artist_sort_orders(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.artist_sort_orders().count() > 1 {
-            writeln!(f, "artist sort orders:")?;
-            for s in self.artist_sort_orders() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.artist_sort_order() {
-            writeln!(f, "artist sort order: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_artist_sort_orders(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("artist sort order", "artist sort orders", Some("soar"), self.artist_sort_orders())
     }
 }
 
@@ -1339,8 +1888,8 @@ impl Userdata {
     }
 
     /// Sets all composer sort orders (`soco`). This will remove all other composer sort orders.
-    pub fn set_composer_sort_orders(&mut self, composer_sort_orders: impl IntoIterator<Item = String>) {
-        let data = composer_sort_orders.into_iter().map(Data::Utf8);
+    pub fn set_composer_sort_orders(&mut self, composer_sort_orders: impl IntoIterator<Item = impl Into<String>>) {
+        let data = composer_sort_orders.into_iter().map(|v| Data::Utf8(v.into()));
         self.set_all_data(ident::COMPOSER_SORT_ORDER, data);
     }
 
@@ -1350,8 +1899,8 @@ impl Userdata {
     }
 
     /// Adds all composer sort orders (`soco`).
-    pub fn add_composer_sort_orders(&mut self, composer_sort_orders: impl IntoIterator<Item = String>) {
-        let data = composer_sort_orders.into_iter().map(Data::Utf8);
+    pub fn add_composer_sort_orders(&mut self, composer_sort_orders: impl IntoIterator<Item = impl Into<String>>) {
+        let data = composer_sort_orders.into_iter().map(|v| Data::Utf8(v.into()));
         self.add_all_data(ident::COMPOSER_SORT_ORDER, data);
     }
 
@@ -1367,16 +1916,8 @@ impl Userdata {
 
     /// Returns all composer sort orders formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_composer_sort_orders(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.composer_sort_orders().count() > 1 {
-            writeln!(f, "composer sort orders:")?;
-            for s in self.composer_sort_orders() {
-                writeln!(f, "    {}", s)?;
-            }
-        } else if let Some(s) = self.composer_sort_order() {
-            writeln!(f, "composer sort order: {}", s)?;
-        }
-        Ok(())
+    pub(crate) fn format_composer_sort_orders(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.values("composer sort order", "composer sort orders", Some("soco"), self.composer_sort_orders())
     }
 }
 
@@ -1384,11 +1925,7 @@ impl Userdata {
 impl Userdata {
     /// Returns the compilation flag (`cpil`).
     pub fn compilation(&self) -> bool {
-        let vec = match self.bytes_of(&ident::COMPILATION).next() {
-            Some(v) => v,
-            None => return false,
-        };
-        vec.first().map(|&v| v == 1).unwrap_or(false)
+        self.data_of(&ident::COMPILATION).next().and_then(Data::flag).unwrap_or(false)
     }
 
     /// Sets the compilation flag to true (`cpil`).
@@ -1403,11 +1940,8 @@ impl Userdata {
 
     /// Returns the compilation formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_compilation(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.compilation() {
-            true => writeln!(f, "compilation"),
-            false => Ok(()),
-        }
+    pub(crate) fn format_compilation(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.flag("compilation", Some("cpil"), self.compilation())
     }
 }
 
@@ -1415,11 +1949,7 @@ impl Userdata {
 impl Userdata {
     /// Returns the gapless playback flag (`pgap`).
     pub fn gapless_playback(&self) -> bool {
-        let vec = match self.bytes_of(&ident::GAPLESS_PLAYBACK).next() {
-            Some(v) => v,
-            None => return false,
-        };
-        vec.first().map(|&v| v == 1).unwrap_or(false)
+        self.data_of(&ident::GAPLESS_PLAYBACK).next().and_then(Data::flag).unwrap_or(false)
     }
 
     /// Sets the gapless playback flag to true (`pgap`).
@@ -1434,11 +1964,32 @@ impl Userdata {
 
     /// Returns the gapless playback formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_gapless_playback(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.gapless_playback() {
-            true => writeln!(f, "gapless playback"),
-            false => Ok(()),
-        }
+    pub(crate) fn format_gapless_playback(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.flag("gapless playback", Some("pgap"), self.gapless_playback())
+    }
+}
+
+/// ### Podcast
+impl Userdata {
+    /// Returns the podcast flag (`pcst`).
+    pub fn podcast(&self) -> bool {
+        self.data_of(&ident::PODCAST).next().and_then(Data::flag).unwrap_or(false)
+    }
+
+    /// Sets the podcast flag to true (`pcst`).
+    pub fn set_podcast(&mut self) {
+        self.set_data(ident::PODCAST, Data::BeSigned(vec![1]));
+    }
+
+    /// Removes the podcast flag (`pcst`).
+    pub fn remove_podcast(&mut self) {
+        self.remove_data_of(&ident::PODCAST)
+    }
+
+    /// Returns the podcast formatted in an easily readable way.
+    #[allow(unused)]
+    pub(crate) fn format_podcast(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.flag("podcast", Some("pcst"), self.podcast())
     }
 }
 
@@ -1446,11 +1997,7 @@ impl Userdata {
 impl Userdata {
     /// Returns the show movement flag (`shwm`).
     pub fn show_movement(&self) -> bool {
-        let vec = match self.bytes_of(&ident::SHOW_MOVEMENT).next() {
-            Some(v) => v,
-            None => return false,
-        };
-        vec.first().map(|&v| v == 1).unwrap_or(false)
+        self.data_of(&ident::SHOW_MOVEMENT).next().and_then(Data::flag).unwrap_or(false)
     }
 
     /// Sets the show movement flag to true (`shwm`).
@@ -1465,11 +2012,8 @@ impl Userdata {
 
     /// Returns the show movement formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_show_movement(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.show_movement() {
-            true => writeln!(f, "show movement"),
-            false => Ok(()),
-        }
+    pub(crate) fn format_show_movement(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.flag("show movement", Some("shwm"), self.show_movement())
     }
 }
 
@@ -1494,11 +2038,8 @@ impl Userdata {
 
     /// Returns the bpm formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_bpm(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.bpm() {
-            Some(s) => writeln!(f, "bpm: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_bpm(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("bpm", Some("tmpo"), self.bpm())
     }
 }
 
@@ -1523,11 +2064,8 @@ impl Userdata {
 
     /// Returns the movement count formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_movement_count(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.movement_count() {
-            Some(s) => writeln!(f, "movement count: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_movement_count(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("movement count", Some("©mvc"), self.movement_count())
     }
 }
 
@@ -1552,11 +2090,8 @@ impl Userdata {
 
     /// Returns the movement index formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_movement_index(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.movement_index() {
-            Some(s) => writeln!(f, "movement index: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_movement_index(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("movement index", Some("©mvi"), self.movement_index())
     }
 }
 
@@ -1581,11 +2116,8 @@ impl Userdata {
 
     /// Returns the tv episode formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_tv_episode(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.tv_episode() {
-            Some(s) => writeln!(f, "tv episode: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_tv_episode(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("tv episode", Some("tves"), self.tv_episode())
     }
 }
 
@@ -1610,10 +2142,165 @@ impl Userdata {
 
     /// Returns the tv season formatted in an easily readable way.
     #[allow(unused)]
-    pub(crate) fn format_tv_season(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.tv_season() {
-            Some(s) => writeln!(f, "tv season: {}", s),
-            None => Ok(()),
-        }
+    pub(crate) fn format_tv_season(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        f.value("tv season", Some("tvsn"), self.tv_season())
     }
 }
+
+impl Tag {
+    /// Formats all values in the display order of `generate.toml`.
+    pub(crate) fn format_all(&self, f: &mut TagFormatter<'_, '_>) -> std::fmt::Result {
+        self.format_album_artists(f)?;
+        self.format_album_artist_sort_orders(f)?;
+        self.format_artists(f)?;
+        self.format_artist_sort_orders(f)?;
+        self.format_composers(f)?;
+        self.format_composer_sort_orders(f)?;
+        self.format_lyricists(f)?;
+        self.format_narrator(f)?;
+        self.format_directors(f)?;
+        self.format_producers(f)?;
+        self.format_album(f)?;
+        self.format_album_sort_order(f)?;
+        self.format_title(f)?;
+        self.format_title_sort_order(f)?;
+        self.format_genres(f)?;
+        self.format_year(f)?;
+        self.format_track(f)?;
+        self.format_disc(f)?;
+        self.format_artworks(f)?;
+        self.format_advisory_rating(f)?;
+        self.format_media_type(f)?;
+        self.format_location(f)?;
+        self.format_language(f)?;
+        self.format_groupings(f)?;
+        self.format_descriptions(f)?;
+        self.format_long_description(f)?;
+        self.format_store_description(f)?;
+        self.format_comments(f)?;
+        self.format_categories(f)?;
+        self.format_keywords(f)?;
+        self.format_copyright(f)?;
+        self.format_owner(f)?;
+        self.format_purchase_date(f)?;
+        self.format_encoder(f)?;
+        self.format_publisher(f)?;
+        self.format_tv_show_name(f)?;
+        self.format_tv_show_name_sort_order(f)?;
+        self.format_tv_network_name(f)?;
+        self.format_tv_episode_name(f)?;
+        self.format_tv_episode(f)?;
+        self.format_tv_season(f)?;
+        self.format_podcast(f)?;
+        self.format_podcast_url(f)?;
+        self.format_podcast_episode_global_unique_id(f)?;
+        self.format_bpm(f)?;
+        self.format_movement(f)?;
+        self.format_work(f)?;
+        self.format_movement_count(f)?;
+        self.format_movement_index(f)?;
+        self.format_series(f)?;
+        self.format_series_part(f)?;
+        self.format_duration(f)?;
+        self.format_channel_config(f)?;
+        self.format_sample_rate(f)?;
+        self.format_aac_profile(f)?;
+        self.format_bit_depth(f)?;
+        self.format_avg_bitrate(f)?;
+        self.format_max_bitrate(f)?;
+        self.format_show_movement(f)?;
+        self.format_gapless_playback(f)?;
+        self.format_compilation(f)?;
+        self.format_isrc(f)?;
+        self.format_label(f)?;
+        self.format_asin(f)?;
+        self.format_cddb_1(f)?;
+        self.format_cddb_ids(f)?;
+        self.format_cddb_track_number(f)?;
+        self.format_itunes_normalization(f)?;
+        self.format_replay_gain_track_gain(f)?;
+        self.format_replay_gain_track_peak(f)?;
+        self.format_replay_gain_album_gain(f)?;
+        self.format_replay_gain_album_peak(f)?;
+        self.format_r128_track_gain(f)?;
+        self.format_r128_album_gain(f)?;
+        self.format_lyrics(f)?;
+        self.format_chapter_list(f)?;
+        self.format_chapter_track(f)?;
+        Ok(())
+    }
+}
+
+/// All identifiers with dedicated accessors sorted by their name, for example to build field
+/// pickers and validators.
+pub const KNOWN_IDENTS: &[IdentInfo] = &[
+    IdentInfo { ident: DataIdent::Fourcc(ident::ADVISORY_RATING), name: "advisory_rating", kind: ValueKind::U8, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ALBUM), name: "album", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ALBUM_ARTIST), name: "album_artist", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ALBUM_ARTIST_SORT_ORDER), name: "album_artist_sort_order", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ALBUM_SORT_ORDER), name: "album_sort_order", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ARTIST), name: "artist", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ARTIST_SORT_ORDER), name: "artist_sort_order", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ARTWORK), name: "artwork", kind: ValueKind::Image, multiple: true },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::ASIN.mean), name: Cow::Borrowed(ident::ASIN.name) }, name: "asin", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::BPM), name: "bpm", kind: ValueKind::U16, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::CATEGORY), name: "category", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::CDDB_1.mean), name: Cow::Borrowed(ident::CDDB_1.name) }, name: "cddb_1", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::CDDB_IDS.mean), name: Cow::Borrowed(ident::CDDB_IDS.name) }, name: "cddb_ids", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::CDDB_TRACK_NUMBER.mean), name: Cow::Borrowed(ident::CDDB_TRACK_NUMBER.name) }, name: "cddb_track_number", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::COMMENT), name: "comment", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::COMPILATION), name: "compilation", kind: ValueKind::Flag, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::COMPOSER), name: "composer", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::COMPOSER_SORT_ORDER), name: "composer_sort_order", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::COPYRIGHT), name: "copyright", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::CUSTOM_GENRE), name: "custom_genre", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::DESCRIPTION), name: "description", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::DIRECTOR), name: "director", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::DISC_NUMBER), name: "disc_number", kind: ValueKind::NumberPair, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::ENCODER), name: "encoder", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::GAPLESS_PLAYBACK), name: "gapless_playback", kind: ValueKind::Flag, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::GROUPING), name: "grouping", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::ISRC.mean), name: Cow::Borrowed(ident::ISRC.name) }, name: "isrc", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::ITUNES_NORMALIZATION.mean), name: Cow::Borrowed(ident::ITUNES_NORMALIZATION.name) }, name: "itunes_normalization", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::KEYWORD), name: "keyword", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::LABEL.mean), name: Cow::Borrowed(ident::LABEL.name) }, name: "label", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::LANGUAGE.mean), name: Cow::Borrowed(ident::LANGUAGE.name) }, name: "language", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::LOCATION), name: "location", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::LONG_DESCRIPTION), name: "long_description", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::LYRICIST.mean), name: Cow::Borrowed(ident::LYRICIST.name) }, name: "lyricist", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::LYRICS), name: "lyrics", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::MEDIA_TYPE), name: "media_type", kind: ValueKind::U8, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::MOVEMENT), name: "movement", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::MOVEMENT_COUNT), name: "movement_count", kind: ValueKind::U16, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::MOVEMENT_INDEX), name: "movement_index", kind: ValueKind::U16, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::NARRATOR), name: "narrator", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::OWNER), name: "owner", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::PODCAST), name: "podcast", kind: ValueKind::Flag, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::PODCAST_EPISODE_GLOBAL_UNIQUE_ID), name: "podcast_episode_global_unique_id", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::PODCAST_URL), name: "podcast_url", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::PRODUCER), name: "producer", kind: ValueKind::String, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::PUBLISHER), name: "publisher", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::PURCHASE_DATE), name: "purchase_date", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::R128_ALBUM_GAIN.mean), name: Cow::Borrowed(ident::R128_ALBUM_GAIN.name) }, name: "r128_album_gain", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::R128_TRACK_GAIN.mean), name: Cow::Borrowed(ident::R128_TRACK_GAIN.name) }, name: "r128_track_gain", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::REPLAY_GAIN_ALBUM_GAIN.mean), name: Cow::Borrowed(ident::REPLAY_GAIN_ALBUM_GAIN.name) }, name: "replay_gain_album_gain", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::REPLAY_GAIN_ALBUM_PEAK.mean), name: Cow::Borrowed(ident::REPLAY_GAIN_ALBUM_PEAK.name) }, name: "replay_gain_album_peak", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::REPLAY_GAIN_TRACK_GAIN.mean), name: Cow::Borrowed(ident::REPLAY_GAIN_TRACK_GAIN.name) }, name: "replay_gain_track_gain", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::REPLAY_GAIN_TRACK_PEAK.mean), name: Cow::Borrowed(ident::REPLAY_GAIN_TRACK_PEAK.name) }, name: "replay_gain_track_peak", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::SERIES.mean), name: Cow::Borrowed(ident::SERIES.name) }, name: "series", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Freeform { mean: Cow::Borrowed(ident::SERIES_PART.mean), name: Cow::Borrowed(ident::SERIES_PART.name) }, name: "series_part", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::SHOW_MOVEMENT), name: "show_movement", kind: ValueKind::Flag, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::STANDARD_GENRE), name: "standard_genre", kind: ValueKind::U16, multiple: true },
+    IdentInfo { ident: DataIdent::Fourcc(ident::STORE_DESCRIPTION), name: "store_description", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TITLE), name: "title", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TITLE_SORT_ORDER), name: "title_sort_order", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TRACK_NUMBER), name: "track_number", kind: ValueKind::NumberPair, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TV_EPISODE), name: "tv_episode", kind: ValueKind::U32, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TV_EPISODE_NAME), name: "tv_episode_name", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TV_NETWORK_NAME), name: "tv_network_name", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TV_SEASON), name: "tv_season", kind: ValueKind::U32, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TV_SHOW_NAME), name: "tv_show_name", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::TV_SHOW_NAME_SORT_ORDER), name: "tv_show_name_sort_order", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::WORK), name: "work", kind: ValueKind::String, multiple: false },
+    IdentInfo { ident: DataIdent::Fourcc(ident::YEAR), name: "year", kind: ValueKind::String, multiple: false },
+];
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use crate::tag::TagFormatter;
 use crate::{Data, Userdata, ident};
 
 /// A list of standard genre codes and values found in the `gnre` atom. The codes are equivalent to
@@ -179,7 +180,7 @@ impl Userdata {
     }
 
     /// Sets the custom genre (`©gen`). This will remove all other standard or custom genres.
-    pub fn set_genres(&mut self, genres: impl IntoIterator<Item = String>) {
+    pub fn set_genres(&mut self, genres: impl IntoIterator<Item = impl Into<String>>) {
         self.set_custom_genres(genres);
         self.remove_standard_genres();
     }
@@ -196,16 +197,8 @@ impl Userdata {
     }
 
     /// Returns all genres formatted in an easily readable way.
-    pub(crate) fn format_genres(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.genres().count() > 1 {
-            writeln!(f, "genres:")?;
-            for v in self.genres() {
-                writeln!(f, "    {v}")?;
-            }
-        } else if let Some(s) = self.genre() {
-            writeln!(f, "genre: {s}")?;
-        }
-        Ok(())
+    pub(crate) fn format_genres(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.values("genre", "genres", Some("©gen"), self.genres())
     }
 }
 
@@ -0,0 +1,566 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{Chapter, Data, DataIdent, ErrorKind, Fourcc, ImgFmt, ItemData, MetaItem, Userdata};
+
+/// The version of the JSON schema written by [`Userdata::to_json_string`].
+const VERSION: u64 = 1;
+
+/// ### JSON
+///
+/// The userdata can be exported to, and imported from a JSON document, so it can be edited by
+/// other tools. The schema is stable and versioned:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "items": [
+///     {"ident": "©nam", "data": [{"type": "utf8", "value": "title"}]},
+///     {"mean": "com.apple.iTunes", "name": "ISRC", "data": [{"type": "utf8", "value": "isrc"}]},
+///     {"mdta": "com.apple.quicktime.make", "data": [{"type": "utf8", "value": "make"}]},
+///     {"ident": "covr", "data": [{"type": "jpeg", "value": "/9j/4AAQ..."}]}
+///   ],
+///   "chapter_list": [
+///     {"start_ns": 0, "title": "first chapter"}
+///   ],
+///   "chapter_track": []
+/// }
+/// ```
+///
+/// Each item has either an `"ident"` containing a 4 byte atom identifier, in which every
+/// character represents one byte (`U+0000` to `U+00FF`), a freeform `"mean"` and `"name"`, or a
+/// QuickTime metadata key `"mdta"`. The data `"type"` is one of:
+///
+/// - `"utf8"`, `"utf16"`: the `"value"` is a string.
+/// - `"reserved"`, `"be_signed"`, `"jpeg"`, `"png"`, `"bmp"`, `"gif"`: the `"value"` is the
+///   base64 encoded data.
+/// - `"image"`, `"unknown"`: the `"value"` is the base64 encoded data, and the `"code"` is the
+///   data type code.
+///
+/// Chapter start times are stored as integer nanoseconds. Unknown fields are ignored.
+impl Userdata {
+    /// Exports the metadata items and chapters as a JSON document.
+    ///
+    /// Items containing [`Data::Shallow`] can't be exported, they have to be read first using
+    /// [`Userdata::read_shallow_data`].
+    pub fn to_json_string(&self) -> crate::Result<String> {
+        let mut json = format!("{{\n  \"version\": {VERSION},\n  \"items\": [");
+        for (i, item) in self.meta_items.iter().enumerate() {
+            json.push_str(if i == 0 { "\n    {" } else { ",\n    {" });
+            match &item.ident {
+                DataIdent::Fourcc(ident) => {
+                    json.push_str("\"ident\": ");
+                    write_str(&mut json, &ident.to_string());
+                }
+                DataIdent::Freeform { mean, name } => {
+                    json.push_str("\"mean\": ");
+                    write_str(&mut json, mean);
+                    json.push_str(", \"name\": ");
+                    write_str(&mut json, name);
+                }
+                DataIdent::Mdta(key) => {
+                    json.push_str("\"mdta\": ");
+                    write_str(&mut json, key);
+                }
+            }
+            json.push_str(", \"data\": [");
+            for (j, data) in item.data.iter().enumerate() {
+                if j > 0 {
+                    json.push_str(", ");
+                }
+                write_data(&mut json, &item.ident, data)?;
+            }
+            json.push_str("]}");
+        }
+        json.push_str(if self.meta_items.is_empty() { "],\n" } else { "\n  ],\n" });
+
+        json.push_str("  \"chapter_list\": ");
+        write_chapters(&mut json, &self.chapter_list);
+        json.push_str(",\n  \"chapter_track\": ");
+        write_chapters(&mut json, &self.chapter_track);
+        json.push_str("\n}\n");
+
+        Ok(json)
+    }
+
+    /// Imports metadata items and chapters from a JSON document, previously exported using
+    /// [`Userdata::to_json_string`] or created by another tool.
+    pub fn from_json_str(json: &str) -> crate::Result<Userdata> {
+        let mut parser = Parser { input: json.as_bytes(), pos: 0 };
+        let value = parser.parse_document()?;
+
+        let mut userdata = Userdata::default();
+        let mut version = None;
+        for (key, value) in value.into_object("document")? {
+            match key.as_str() {
+                "version" => version = Some(value.into_u64("version")?),
+                "items" => {
+                    for item in value.into_array("items")? {
+                        userdata.meta_items.push(Arc::new(read_item(item)?));
+                    }
+                }
+                "chapter_list" => userdata.chapter_list = read_chapters(value)?,
+                "chapter_track" => userdata.chapter_track = read_chapters(value)?,
+                _ => (),
+            }
+        }
+
+        match version {
+            Some(VERSION) => Ok(userdata),
+            Some(v) => Err(json_error(format!("Unsupported JSON schema version {v}"))),
+            None => Err(json_error("Missing JSON schema version")),
+        }
+    }
+}
+
+fn write_data(json: &mut String, ident: &DataIdent, data: &Data) -> crate::Result<()> {
+    let (ty, code, bytes) = match data {
+        Data::Utf8(s) => ("utf8", None, s.as_bytes()),
+        Data::Utf16(s) => ("utf16", None, s.as_bytes()),
+        Data::Reserved(d) => ("reserved", None, d.as_slice()),
+        Data::BeSigned(d) => ("be_signed", None, d.as_slice()),
+        Data::Jpeg(d) => ("jpeg", None, d.as_slice()),
+        Data::Png(d) => ("png", None, d.as_slice()),
+        Data::Bmp(d) => ("bmp", None, d.as_slice()),
+        Data::Gif(d) => ("gif", None, d.as_slice()),
+        Data::Image { code, data, .. } => ("image", Some(*code), data.as_slice()),
+        Data::Unknown { code, data } => ("unknown", Some(*code), data.as_slice()),
+        Data::Shallow { .. } => {
+            return Err(crate::Error::new(
+                ErrorKind::InvalidShallowData,
+                format!("Shallow data of {ident} can't be exported without reading it first"),
+            ));
+        }
+    };
+
+    write!(json, "{{\"type\": \"{ty}\"").ok();
+    if let Some(code) = code {
+        write!(json, ", \"code\": {code}").ok();
+    }
+    json.push_str(", \"value\": ");
+    match data {
+        Data::Utf8(s) | Data::Utf16(s) => write_str(json, s),
+        _ => {
+            json.push('"');
+            base64_encode(json, bytes);
+            json.push('"');
+        }
+    }
+    json.push('}');
+    Ok(())
+}
+
+fn write_chapters(json: &mut String, chapters: &[Chapter]) {
+    json.push('[');
+    for (i, c) in chapters.iter().enumerate() {
+        json.push_str(if i == 0 { "\n    " } else { ",\n    " });
+        write!(json, "{{\"start_ns\": {}, \"title\": ", c.start.as_nanos()).ok();
+        write_str(json, &c.title);
+        json.push('}');
+    }
+    json.push_str(if chapters.is_empty() { "]" } else { "\n  ]" });
+}
+
+fn write_str(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => {
+                write!(json, "\\u{:04x}", c as u32).ok();
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+fn read_item(value: Value) -> crate::Result<MetaItem> {
+    let mut ident = None;
+    let mut mean = None;
+    let mut name = None;
+    let mut mdta = None;
+    let mut data = ItemData::default();
+    for (key, value) in value.into_object("item")? {
+        match key.as_str() {
+            "ident" => ident = Some(value.into_string("ident")?),
+            "mean" => mean = Some(value.into_string("mean")?),
+            "name" => name = Some(value.into_string("name")?),
+            "mdta" => mdta = Some(value.into_string("mdta")?),
+            "data" => {
+                for d in value.into_array("data")? {
+                    data.push(read_data(d)?);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let ident = match (ident, mean, name, mdta) {
+        (Some(ident), None, None, None) => {
+            let mut bytes = [0; 4];
+            let mut chars = ident.chars();
+            for b in bytes.iter_mut() {
+                let c = chars.next().and_then(|c| u8::try_from(c).ok());
+                *b = c.ok_or_else(|| json_error(format!("Invalid item ident {ident:?}")))?;
+            }
+            if chars.next().is_some() {
+                return Err(json_error(format!("Invalid item ident {ident:?}")));
+            }
+            DataIdent::Fourcc(Fourcc(bytes))
+        }
+        (None, Some(mean), Some(name), None) => DataIdent::freeform(mean, name),
+        (None, None, None, Some(key)) => DataIdent::mdta(key),
+        _ => return Err(json_error("Item requires either an ident, mean and name, or mdta key")),
+    };
+
+    Ok(MetaItem::new(ident, data))
+}
+
+fn read_data(value: Value) -> crate::Result<Data> {
+    let mut ty = None;
+    let mut code = None;
+    let mut val = None;
+    for (key, value) in value.into_object("data")? {
+        match key.as_str() {
+            "type" => ty = Some(value.into_string("type")?),
+            "code" => {
+                let c = value.into_u64("code")?;
+                code = Some(u32::try_from(c).map_err(|_| json_error("Data type code too large"))?);
+            }
+            "value" => val = Some(value.into_string("value")?),
+            _ => (),
+        }
+    }
+
+    let ty = ty.ok_or_else(|| json_error("Missing data type"))?;
+    let val = val.ok_or_else(|| json_error("Missing data value"))?;
+    let code = || code.ok_or_else(|| json_error(format!("Missing data type code of {ty} data")));
+    let data = match ty.as_str() {
+        "utf8" => Data::Utf8(val),
+        "utf16" => Data::Utf16(val),
+        "reserved" => Data::Reserved(base64_decode(&val)?),
+        "be_signed" => Data::BeSigned(base64_decode(&val)?),
+        "jpeg" => Data::Jpeg(base64_decode(&val)?),
+        "png" => Data::Png(base64_decode(&val)?),
+        "bmp" => Data::Bmp(base64_decode(&val)?),
+        "gif" => Data::Gif(base64_decode(&val)?),
+        "image" => {
+            let code = code()?;
+            let data = base64_decode(&val)?;
+            match ImgFmt::detect(&data) {
+                Some(fmt) => Data::Image { code, fmt, data },
+                None => Data::Unknown { code, data },
+            }
+        }
+        "unknown" => Data::Unknown { code: code()?, data: base64_decode(&val)? },
+        _ => return Err(json_error(format!("Unknown data type {ty:?}"))),
+    };
+    Ok(data)
+}
+
+fn read_chapters(value: Value) -> crate::Result<Vec<Chapter>> {
+    let mut chapters = Vec::new();
+    for c in value.into_array("chapters")? {
+        let mut start = None;
+        let mut title = None;
+        for (key, value) in c.into_object("chapter")? {
+            match key.as_str() {
+                "start_ns" => start = Some(value.into_u64("start_ns")?),
+                "title" => title = Some(value.into_string("title")?),
+                _ => (),
+            }
+        }
+        let start = start.ok_or_else(|| json_error("Missing chapter start"))?;
+        let title = title.ok_or_else(|| json_error("Missing chapter title"))?;
+        chapters.push(Chapter::new(Duration::from_nanos(start), title));
+    }
+    Ok(chapters)
+}
+
+fn json_error(description: impl Into<Cow<'static, str>>) -> crate::Error {
+    crate::Error::new(ErrorKind::InvalidJson, description)
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes the bytes using the standard base64 alphabet with padding.
+fn base64_encode(out: &mut String, bytes: &[u8]) {
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let idx = (n >> (18 - 6 * i)) & 0x3f;
+                out.push(BASE64_ALPHABET[idx as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+}
+
+/// Decodes base64 using the standard alphabet, padding is optional.
+fn base64_decode(s: &str) -> crate::Result<Vec<u8>> {
+    let s = s.trim_end_matches('=');
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    let mut n = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(json_error("Invalid base64 data")),
+        };
+        n = n << 6 | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((n >> bits) as u8);
+        }
+    }
+    if bits >= 6 {
+        return Err(json_error("Invalid base64 data length"));
+    }
+    Ok(bytes)
+}
+
+/// A parsed JSON value. Numbers are limited to non-negative integers, which is all the schema
+/// requires.
+enum Value {
+    Null,
+    Bool,
+    Number(u64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn into_object(self, what: &str) -> crate::Result<Vec<(String, Value)>> {
+        match self {
+            Self::Object(o) => Ok(o),
+            _ => Err(json_error(format!("Expected {what} to be an object"))),
+        }
+    }
+
+    fn into_array(self, what: &str) -> crate::Result<Vec<Value>> {
+        match self {
+            Self::Array(a) => Ok(a),
+            _ => Err(json_error(format!("Expected {what} to be an array"))),
+        }
+    }
+
+    fn into_string(self, what: &str) -> crate::Result<String> {
+        match self {
+            Self::String(s) => Ok(s),
+            _ => Err(json_error(format!("Expected {what} to be a string"))),
+        }
+    }
+
+    fn into_u64(self, what: &str) -> crate::Result<u64> {
+        match self {
+            Self::Number(n) => Ok(n),
+            _ => Err(json_error(format!("Expected {what} to be a number"))),
+        }
+    }
+}
+
+/// The maximum nesting depth of arrays and objects.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn parse_document(&mut self) -> crate::Result<Value> {
+        let value = self.parse_value(0)?;
+        self.skip_whitespace();
+        if self.pos < self.input.len() {
+            return Err(self.error("Trailing characters after the JSON document"));
+        }
+        Ok(value)
+    }
+
+    fn error(&self, msg: &str) -> crate::Error {
+        json_error(format!("{msg} at byte {}", self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.input.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> crate::Result<()> {
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("Expected '{}'", c as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn keyword(&mut self, word: &str, value: Value) -> crate::Result<Value> {
+        if !self.input[self.pos..].starts_with(word.as_bytes()) {
+            return Err(self.error("Invalid literal"));
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    fn parse_value(&mut self, depth: usize) -> crate::Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("JSON nested too deeply"));
+        }
+
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut members = Vec::new();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(members));
+                }
+                loop {
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error("Expected an object key"));
+                    }
+                    let key = self.parse_string()?;
+                    self.expect(b':')?;
+                    members.push((key, self.parse_value(depth + 1)?));
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => break,
+                        _ => return Err(self.error("Expected ',' or '}'")),
+                    }
+                }
+                self.pos += 1;
+                Ok(Value::Object(members))
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut elements = Vec::new();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(elements));
+                }
+                loop {
+                    elements.push(self.parse_value(depth + 1)?);
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => break,
+                        _ => return Err(self.error("Expected ',' or ']'")),
+                    }
+                }
+                self.pos += 1;
+                Ok(Value::Array(elements))
+            }
+            Some(b'"') => Ok(Value::String(self.parse_string()?)),
+            Some(b'0'..=b'9') => {
+                let start = self.pos;
+                while let Some(b'0'..=b'9') = self.input.get(self.pos) {
+                    self.pos += 1;
+                }
+                if let Some(b'.' | b'e' | b'E') = self.input.get(self.pos) {
+                    return Err(self.error("Only integer numbers are supported"));
+                }
+                let digits = std::str::from_utf8(&self.input[start..self.pos]).unwrap_or("");
+                let n = digits.parse().map_err(|_| self.error("Number out of range"))?;
+                Ok(Value::Number(n))
+            }
+            Some(b'-') => Err(self.error("Negative numbers are not supported")),
+            Some(b't') => self.keyword("true", Value::Bool),
+            Some(b'f') => self.keyword("false", Value::Bool),
+            Some(b'n') => self.keyword("null", Value::Null),
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end of JSON document")),
+        }
+    }
+
+    fn parse_string(&mut self) -> crate::Result<String> {
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(&c) = self.input.get(self.pos) {
+                if c == b'"' || c == b'\\' || c < b' ' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            // The input is a `&str` and the loop only stops at ascii characters.
+            s.push_str(std::str::from_utf8(&self.input[start..self.pos]).unwrap_or_default());
+
+            match self.input.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let c = match self.input.get(self.pos) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            let hi = self.parse_hex4()?;
+                            let code = if (0xd800..0xdc00).contains(&hi) {
+                                if !self.input[self.pos + 1..].starts_with(b"\\u") {
+                                    return Err(self.error("Unpaired surrogate"));
+                                }
+                                self.pos += 2;
+                                let lo = self.parse_hex4()?;
+                                if !(0xdc00..0xe000).contains(&lo) {
+                                    return Err(self.error("Unpaired surrogate"));
+                                }
+                                0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+                            } else {
+                                hi
+                            };
+                            char::from_u32(code).ok_or_else(|| self.error("Unpaired surrogate"))?
+                        }
+                        _ => return Err(self.error("Invalid escape sequence")),
+                    };
+                    s.push(c);
+                    self.pos += 1;
+                }
+                Some(_) => return Err(self.error("Control character in string")),
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    /// Parses the 4 hex digits following the `u` at the current position, and leaves the
+    /// position at the last digit.
+    fn parse_hex4(&mut self) -> crate::Result<u32> {
+        let digits = self.input.get(self.pos + 1..self.pos + 5);
+        let digits = digits.filter(|d| d.iter().all(u8::is_ascii_hexdigit));
+        let digits = digits.and_then(|d| std::str::from_utf8(d).ok());
+        let code = digits.and_then(|d| u32::from_str_radix(d, 16).ok());
+        let code = code.ok_or_else(|| self.error("Invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{DataIdent, Ident, Userdata, ident};
+
+/// Maximum lengths of string items in characters, see [`Userdata::validate_lengths`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LengthLimits {
+    limits: Vec<(DataIdent, usize)>,
+}
+
+impl LengthLimits {
+    /// The identifiers of items, which iTunes truncates to 255 characters.
+    const ITUNES_255: [DataIdent; 21] = [
+        DataIdent::Fourcc(ident::TITLE),
+        DataIdent::Fourcc(ident::ARTIST),
+        DataIdent::Fourcc(ident::ALBUM),
+        DataIdent::Fourcc(ident::ALBUM_ARTIST),
+        DataIdent::Fourcc(ident::COMPOSER),
+        DataIdent::Fourcc(ident::GROUPING),
+        DataIdent::Fourcc(ident::COMMENT),
+        DataIdent::Fourcc(ident::CUSTOM_GENRE),
+        DataIdent::Fourcc(ident::COPYRIGHT),
+        DataIdent::Fourcc(ident::DESCRIPTION),
+        DataIdent::Fourcc(ident::WORK),
+        DataIdent::Fourcc(ident::MOVEMENT),
+        DataIdent::Fourcc(ident::TV_SHOW_NAME),
+        DataIdent::Fourcc(ident::TV_EPISODE_NAME),
+        DataIdent::Fourcc(ident::TV_NETWORK_NAME),
+        DataIdent::Fourcc(ident::TITLE_SORT_ORDER),
+        DataIdent::Fourcc(ident::ARTIST_SORT_ORDER),
+        DataIdent::Fourcc(ident::ALBUM_SORT_ORDER),
+        DataIdent::Fourcc(ident::ALBUM_ARTIST_SORT_ORDER),
+        DataIdent::Fourcc(ident::COMPOSER_SORT_ORDER),
+        DataIdent::Fourcc(ident::TV_SHOW_NAME_SORT_ORDER),
+    ];
+
+    /// Creates limits without any entries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The limits of iTunes, which truncates the title, artist, album, comment, description,
+    /// sort orders and many other string items to 255 characters when syncing to a device.
+    pub fn itunes() -> Self {
+        let limits = Self::ITUNES_255.into_iter().map(|i| (i, 255)).collect();
+        Self { limits }
+    }
+
+    /// Sets the maximum length of the item with the identifier in characters, replacing a
+    /// previous limit.
+    pub fn set(mut self, ident: impl Into<DataIdent>, max_len: usize) -> Self {
+        let ident = ident.into();
+        match self.limits.iter_mut().find(|(i, _)| *i == ident) {
+            Some((_, l)) => *l = max_len,
+            None => self.limits.push((ident, max_len)),
+        }
+        self
+    }
+
+    /// Removes the limit of the item with the identifier.
+    pub fn remove(mut self, ident: &impl Ident) -> Self {
+        self.limits.retain(|(i, _)| *ident != *i);
+        self
+    }
+
+    /// Returns the maximum length of the item with the identifier in characters.
+    pub fn get(&self, ident: &impl Ident) -> Option<usize> {
+        self.limits.iter().find(|(i, _)| *ident == *i).map(|(_, l)| *l)
+    }
+}
+
+/// A string that exceeds its maximum length, see [`Userdata::validate_lengths`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LengthViolation {
+    /// The identifier of the item.
+    pub ident: DataIdent,
+    /// The index of the string among the data of the item.
+    pub index: usize,
+    /// The length of the string in characters.
+    pub len: usize,
+    /// The maximum length in characters.
+    pub max_len: usize,
+}
+
+/// ### Length limits
+impl Userdata {
+    /// Returns all strings that exceed their maximum length, so over-long values can be fixed
+    /// before a player or device silently truncates them. See [`LengthLimits::itunes`].
+    pub fn validate_lengths(&self, limits: &LengthLimits) -> Vec<LengthViolation> {
+        let mut violations = Vec::new();
+        for item in self.meta_items.iter() {
+            let Some(max_len) = limits.get(&item.ident) else {
+                continue;
+            };
+            for (index, data) in item.data.iter().enumerate() {
+                let Some(s) = data.string() else {
+                    continue;
+                };
+                let len = s.chars().count();
+                if len > max_len {
+                    let ident = item.ident.clone();
+                    violations.push(LengthViolation { ident, index, len, max_len });
+                }
+            }
+        }
+        violations
+    }
+}
@@ -0,0 +1,151 @@
+use crate::Userdata;
+
+/// The difference between the reference loudness of ReplayGain (-18 LUFS) and EBU R128
+/// (-23 LUFS) in dB.
+const R128_OFFSET: f32 = 5.0;
+
+/// The ReplayGain of a track or album.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReplayGain {
+    /// The gain in dB.
+    pub gain: f32,
+    /// The peak amplitude, where `1.0` is full scale.
+    pub peak: Option<f32>,
+}
+
+/// The loudness normalization values of a track, which are stored in several freeform items used
+/// by different players, see [`Userdata::loudness`].
+///
+/// | Value   | Items                                                                           |
+/// |---------|---------------------------------------------------------------------------------|
+/// | `track` | `replaygain_track_gain`, `replaygain_track_peak`, `R128_TRACK_GAIN`, `iTunNORM` |
+/// | `album` | `replaygain_album_gain`, `replaygain_album_peak`, `R128_ALBUM_GAIN`             |
+///
+/// All items use the `com.apple.iTunes` mean.
+///
+/// ```
+/// use mp4ameta::{Loudness, ReplayGain, Tag};
+///
+/// let mut tag = Tag::default();
+/// let track = ReplayGain { gain: -6.5, peak: Some(0.98) };
+/// tag.set_loudness(Loudness { track: Some(track), album: None });
+///
+/// assert_eq!(tag.replay_gain_track_gain(), Some("-6.50 dB"));
+/// assert_eq!(tag.r128_track_gain(), Some("-2944"));
+/// assert_eq!(
+///     tag.itunes_normalization(),
+///     Some(" 00001173 00001173 00002B9F 00002B9F 00000000 00000000 00007D71 00007D71 00000000 00000000"),
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Loudness {
+    /// The track gain and peak.
+    pub track: Option<ReplayGain>,
+    /// The album gain and peak.
+    pub album: Option<ReplayGain>,
+}
+
+/// ### Loudness
+impl Userdata {
+    /// Returns the track and album gain, or `None` if neither is present. The ReplayGain items
+    /// are preferred, if they are missing the gain is read from the EBU R128 items, which don't
+    /// contain a peak.
+    pub fn loudness(&self) -> Option<Loudness> {
+        let replay_gain = |gain: Option<&str>, peak: Option<&str>, r128: Option<&str>| {
+            let peak = peak.and_then(|p| p.trim().parse().ok());
+            match gain.and_then(parse_gain) {
+                Some(gain) => Some(ReplayGain { gain, peak }),
+                None => {
+                    let r128 = r128?.trim().parse::<i16>().ok()?;
+                    Some(ReplayGain { gain: r128 as f32 / 256.0 + R128_OFFSET, peak })
+                }
+            }
+        };
+        let loudness = Loudness {
+            track: replay_gain(
+                self.replay_gain_track_gain(),
+                self.replay_gain_track_peak(),
+                self.r128_track_gain(),
+            ),
+            album: replay_gain(
+                self.replay_gain_album_gain(),
+                self.replay_gain_album_peak(),
+                self.r128_album_gain(),
+            ),
+        };
+        (loudness != Loudness::default()).then_some(loudness)
+    }
+
+    /// Sets the ReplayGain, EBU R128 and iTunes normalization (`iTunNORM`) items consistently,
+    /// removing the items of values that are `None`.
+    pub fn set_loudness(&mut self, loudness: Loudness) {
+        match loudness.track {
+            Some(rg) => {
+                self.set_replay_gain_track_gain(format_gain(rg.gain));
+                match rg.peak {
+                    Some(p) => self.set_replay_gain_track_peak(format!("{p:.6}")),
+                    None => self.remove_replay_gain_track_peak(),
+                }
+                self.set_r128_track_gain(format_r128(rg.gain));
+                self.set_itunes_normalization(format_itunes_normalization(rg));
+            }
+            None => {
+                self.remove_replay_gain_track_gain();
+                self.remove_replay_gain_track_peak();
+                self.remove_r128_track_gain();
+                self.remove_itunes_normalization();
+            }
+        }
+        match loudness.album {
+            Some(rg) => {
+                self.set_replay_gain_album_gain(format_gain(rg.gain));
+                match rg.peak {
+                    Some(p) => self.set_replay_gain_album_peak(format!("{p:.6}")),
+                    None => self.remove_replay_gain_album_peak(),
+                }
+                self.set_r128_album_gain(format_r128(rg.gain));
+            }
+            None => {
+                self.remove_replay_gain_album_gain();
+                self.remove_replay_gain_album_peak();
+                self.remove_r128_album_gain();
+            }
+        }
+    }
+
+    /// Removes all ReplayGain, EBU R128 and iTunes normalization (`iTunNORM`) items.
+    pub fn remove_loudness(&mut self) {
+        self.set_loudness(Loudness::default());
+    }
+}
+
+/// Parses a gain like `"-6.50 dB"`.
+fn parse_gain(s: &str) -> Option<f32> {
+    let s = s.trim();
+    let s = s.strip_suffix("dB").or_else(|| s.strip_suffix("db")).unwrap_or(s);
+    s.trim().parse().ok()
+}
+
+fn format_gain(gain: f32) -> String {
+    format!("{gain:.2} dB")
+}
+
+/// Formats the gain as a Q7.8 fixed point number relative to -23 LUFS.
+fn format_r128(gain: f32) -> String {
+    let q = ((gain - R128_OFFSET) * 256.0).round();
+    (q.clamp(i16::MIN as f32, i16::MAX as f32) as i16).to_string()
+}
+
+/// Formats the gain and peak as ten hexadecimal values. The first two pairs contain the gain of
+/// the left and right channel relative to 1/1000 and 1/2500 of full scale, and the fourth pair
+/// contains the peak relative to 32768. The remaining values aren't used for playback and are
+/// written as zero.
+fn format_itunes_normalization(rg: ReplayGain) -> String {
+    let scaled = |base: f32| {
+        let v = (base * 10f32.powf(-rg.gain / 10.0)).round();
+        v.clamp(0.0, u32::MAX as f32) as u32
+    };
+    let peak = (rg.peak.unwrap_or(0.0) * 32768.0).round().clamp(0.0, u32::MAX as f32) as u32;
+    let values = [scaled(1000.0), scaled(1000.0), scaled(2500.0), scaled(2500.0), 0, 0, peak, peak];
+    values.into_iter().chain([0, 0]).map(|v| format!(" {v:08X}")).collect()
+}
@@ -1,32 +1,81 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
 use std::fs::OpenOptions;
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
+use std::sync::Arc;
 
+use crate::tag::TagFormatter;
 use crate::{
-    AdvisoryRating, Chapter, Data, DataIdent, Ident, Img, ImgBuf, ImgFmt, ImgMut, ImgRef,
-    MediaType, MetaItem, StorageFile, WriteConfig, atom, ident,
+    AdvisoryRating, Chapter, ChapterSync, Data, DataIdent, Fourcc, Ident, Img, ImgBuf, ImgFmt,
+    ImgMut, ImgRef, ItemData, Language, Location, MediaType, MetaItem, ParseWarning, SharedImg,
+    StorageFile, WriteConfig, atom, ident,
 };
 
+pub use audiobook::AudiobookInfo;
+pub use compat::CompatIssue;
+pub use generated::KNOWN_IDENTS;
 pub use genre::*;
-
+pub use limits::{LengthLimits, LengthViolation};
+pub use loudness::{Loudness, ReplayGain};
+pub use order::ItemOrder;
+pub use tv::TvEpisodeInfo;
+pub use work::ClassicalWork;
+
+mod audiobook;
+mod compat;
 #[rustfmt::skip]
 mod generated;
 mod genre;
+mod json;
+mod limits;
+mod loudness;
+#[cfg(feature = "normalization")]
+mod normalize;
+mod order;
+#[cfg(feature = "image")]
+mod thumbnail;
 mod tuple;
+mod tv;
+mod work;
 
 /// User defined MPEG-4 audio metadata that can be modified.
+///
+/// Metadata items are kept in the order they were read from the file, new items are appended, and
+/// items are written in this order. Replacing the data of an item keeps its position. See
+/// [`Userdata::sort_items`] to change the order.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Userdata {
-    pub(crate) meta_items: Vec<MetaItem>,
+    pub(crate) meta_items: Vec<Arc<MetaItem>>,
     pub(crate) chapter_list: Vec<Chapter>,
     pub(crate) chapter_track: Vec<Chapter>,
+    #[cfg(feature = "image")]
+    pub(crate) thumbnail_cache: thumbnail::ThumbnailCache,
 }
 
 impl Userdata {
     /// Attempts to write the MPEG-4 audio tag to the writer.
+    ///
+    /// The chapter list and chapter track are written independently, see [`WriteChapters`].
+    /// Files without a media data (`mdat`) atom, such as templates that don't contain any audio
+    /// yet, are supported. If a chapter track is written one is created at the end of the file.
+    ///
+    /// [`WriteChapters`]: crate::WriteChapters
     pub fn write_with(&self, file: &mut impl StorageFile, cfg: &WriteConfig) -> crate::Result<()> {
-        atom::write_tag(file, cfg, self)
+        atom::write_tag(file, cfg, self, None)
+    }
+
+    /// Attempts to write the MPEG-4 audio tag to the writer. Additionally returns non-fatal issues
+    /// that were encountered, such as atoms that were removed because they became inconsistent.
+    pub fn write_with_warnings(
+        &self,
+        file: &mut impl StorageFile,
+        cfg: &WriteConfig,
+    ) -> crate::Result<Vec<ParseWarning>> {
+        let mut warnings = Vec::new();
+        atom::write_tag(file, cfg, self, Some(&mut warnings))?;
+        Ok(warnings)
     }
 
     /// Attempts to write the MPEG-4 audio tag to the writer. This will overwrite any metadata
@@ -46,6 +95,34 @@ impl Userdata {
     pub fn write_to_path(&self, path: impl AsRef<Path>) -> crate::Result<()> {
         self.write_with_path(path, &WriteConfig::DEFAULT)
     }
+
+    /// Attempts to write the MPEG-4 audio tag to a copy of the input file, and returns the
+    /// resulting file. The whole file is kept in memory, no filesystem access is necessary.
+    pub fn write_to_vec(&self, input: &[u8], cfg: &WriteConfig) -> crate::Result<Vec<u8>> {
+        let mut file = Cursor::new(input.to_vec());
+        self.write_with(&mut file, cfg)?;
+        Ok(file.into_inner())
+    }
+
+    /// Attempts to read the content of all [`Data::Shallow`] items from the reader, which has to
+    /// be the file the tag was read from. See
+    /// [`ReadConfig::shallow_data_size`](crate::ReadConfig::shallow_data_size).
+    pub fn read_shallow_data(&mut self, reader: &mut (impl Read + Seek)) -> crate::Result<()> {
+        for item in self.meta_items.iter_mut() {
+            if !item.data.iter().any(Data::is_shallow) {
+                continue;
+            }
+            for data in Arc::make_mut(item).data.iter_mut().filter(|d| d.is_shallow()) {
+                *data = data.get_or_read(reader)?.into_owned();
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if any item contains [`Data::Shallow`].
+    pub(crate) fn has_shallow_data(&self) -> bool {
+        self.meta_items.iter().any(|i| i.data.iter().any(Data::is_shallow))
+    }
 }
 
 // ## Custom values
@@ -76,6 +153,27 @@ impl Userdata {
         self.set_data(ident::ARTWORK, image.into());
     }
 
+    /// Sets the artwork image (`covr`) to a shared image without copying its data. This will
+    /// remove all other artworks.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::{Img, Userdata};
+    ///
+    /// let cover = Img::png(vec![0; 1024]).shared();
+    /// let mut tracks = vec![Userdata::default(); 100];
+    /// for t in tracks.iter_mut() {
+    ///     t.set_artwork_shared(&cover);
+    /// }
+    /// assert_eq!(tracks[99].artwork(), Some(cover.img()));
+    /// ```
+    pub fn set_artwork_shared(&mut self, image: &SharedImg) {
+        match self.meta_items.iter_mut().find(|a| ident::ARTWORK == a.ident) {
+            Some(a) => *a = Arc::clone(&image.0),
+            None => self.meta_items.push(Arc::clone(&image.0)),
+        }
+    }
+
     /// Sets all artwork image data (`covr`). This will remove all other artworks.
     pub fn set_artworks(&mut self, images: impl IntoIterator<Item = ImgBuf>) {
         self.set_all_data(ident::ARTWORK, images.into_iter().map(Img::into));
@@ -97,39 +195,35 @@ impl Userdata {
     }
 
     /// Returns information about all artworks formatted in an easily readable way.
-    pub(crate) fn format_artworks(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fn format_artwork(f: &mut fmt::Formatter<'_>, i: ImgRef<'_>) -> fmt::Result {
-            match i.fmt {
-                ImgFmt::Png => write!(f, "png")?,
-                ImgFmt::Jpeg => write!(f, "jpeg")?,
-                ImgFmt::Bmp => write!(f, "bmp")?,
-            };
-
-            let len = i.data.len();
-
-            if len < 1024 {
-                writeln!(f, " {len}")?;
-            } else if len < 1024 * 1024 {
-                let size = len / 1024;
-                writeln!(f, " {size}k")?;
-            } else {
-                let size = len / (1024 * 1024);
-                writeln!(f, " {size}M")?;
+    pub(crate) fn format_artworks(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        struct Artwork<'a>(ImgRef<'a>);
+
+        impl fmt::Display for Artwork<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0.fmt {
+                    ImgFmt::Png => write!(f, "png")?,
+                    ImgFmt::Jpeg => write!(f, "jpeg")?,
+                    ImgFmt::Bmp => write!(f, "bmp")?,
+                    ImgFmt::Gif => write!(f, "gif")?,
+                    ImgFmt::Heic => write!(f, "heic")?,
+                    ImgFmt::WebP => write!(f, "webp")?,
+                };
+
+                let len = self.0.data.len();
+
+                if len < 1024 {
+                    write!(f, " {len}")
+                } else if len < 1024 * 1024 {
+                    let size = len / 1024;
+                    write!(f, " {size}k")
+                } else {
+                    let size = len / (1024 * 1024);
+                    write!(f, " {size}M")
+                }
             }
-            Ok(())
         }
 
-        if self.artworks().count() > 1 {
-            writeln!(f, "artworks:")?;
-            for a in self.artworks() {
-                write!(f, "    ")?;
-                format_artwork(f, a)?;
-            }
-        } else if let Some(a) = self.artwork() {
-            write!(f, "artwork: ")?;
-            format_artwork(f, a)?;
-        }
-        Ok(())
+        f.values("artwork", "artworks", Some("covr"), self.artworks().map(Artwork))
     }
 }
 
@@ -156,11 +250,8 @@ impl Userdata {
         self.remove_data_of(&ident::MEDIA_TYPE);
     }
 
-    pub(crate) fn format_media_type(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.media_type() {
-            Some(m) => writeln!(f, "media type: {m}"),
-            None => Ok(()),
-        }
+    pub(crate) fn format_media_type(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("media type", Some("stik"), self.media_type())
     }
 }
 
@@ -187,11 +278,66 @@ impl Userdata {
         self.remove_data_of(&ident::ADVISORY_RATING);
     }
 
-    pub(crate) fn format_advisory_rating(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.advisory_rating() {
-            Some(r) => writeln!(f, "advisory rating: {r}"),
-            None => Ok(()),
+    pub(crate) fn format_advisory_rating(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("advisory rating", Some("rtng"), self.advisory_rating())
+    }
+}
+
+/// ### Location
+impl Userdata {
+    /// Returns the location (`©xyz`), or if it's not present, the QuickTime location
+    /// (`com.apple.quicktime.location.ISO6709`). Values that aren't valid ISO 6709 strings are
+    /// ignored.
+    pub fn location(&self) -> Option<Location> {
+        self.strings_of(&ident::LOCATION)
+            .chain(self.strings_of(&ident::QUICKTIME_LOCATION))
+            .find_map(|s| s.parse().ok())
+    }
+
+    /// Sets the location (`©xyz`). If the QuickTime location
+    /// (`com.apple.quicktime.location.ISO6709`) is present, it's updated as well.
+    pub fn set_location(&mut self, location: Location) {
+        let string = location.to_string();
+        if self.strings_of(&ident::QUICKTIME_LOCATION).next().is_some() {
+            self.set_data(ident::QUICKTIME_LOCATION, Data::Utf8(string.clone()));
         }
+        self.set_data(ident::LOCATION, Data::Utf8(string));
+    }
+
+    /// Removes the location (`©xyz`) and the QuickTime location
+    /// (`com.apple.quicktime.location.ISO6709`).
+    pub fn remove_location(&mut self) {
+        self.remove_data_of(&ident::LOCATION);
+        self.remove_data_of(&ident::QUICKTIME_LOCATION);
+    }
+
+    pub(crate) fn format_location(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("location", Some("©xyz"), self.location())
+    }
+}
+
+/// ### Language
+impl Userdata {
+    /// Returns the language of the content (`----:com.apple.iTunes:LANGUAGE`), as written by
+    /// MusicBrainz Picard. Values that aren't valid ISO 639 codes are ignored. The languages of
+    /// the tracks are stored separately, see [`track_languages`](crate::track_languages).
+    pub fn language(&self) -> Option<Language> {
+        self.strings_of(&ident::LANGUAGE).find_map(|s| s.parse().ok())
+    }
+
+    /// Sets the language of the content (`----:com.apple.iTunes:LANGUAGE`), which is stored as
+    /// an ISO 639-2/T code.
+    pub fn set_language(&mut self, language: Language) {
+        self.set_data(ident::LANGUAGE, Data::Utf8(language.to_string()));
+    }
+
+    /// Removes the language of the content (`----:com.apple.iTunes:LANGUAGE`).
+    pub fn remove_language(&mut self) {
+        self.remove_data_of(&ident::LANGUAGE);
+    }
+
+    pub(crate) fn format_language(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("language", Some("----:com.apple.iTunes:LANGUAGE"), self.language())
     }
 }
 
@@ -225,6 +371,90 @@ impl Userdata {
             self.take_chapter_track()
         }
     }
+
+    /// Synchronizes the chapter list and chapter track, so that both contain the same chapters.
+    /// Some players only read one of them.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::{Chapter, ChapterSync, Userdata};
+    /// use std::time::Duration;
+    ///
+    /// let mut tag = Userdata::default();
+    /// tag.chapter_track_mut().push(Chapter::new(Duration::ZERO, "first chapter"));
+    ///
+    /// tag.sync_chapters(ChapterSync::Populated);
+    /// assert_eq!(tag.chapter_list(), tag.chapter_track());
+    /// ```
+    pub fn sync_chapters(&mut self, direction: ChapterSync) {
+        match direction {
+            ChapterSync::ListToTrack => self.chapter_track.clone_from(&self.chapter_list),
+            ChapterSync::TrackToList => self.chapter_list.clone_from(&self.chapter_track),
+            ChapterSync::Populated => {
+                if self.chapter_list.is_empty() {
+                    self.chapter_list.clone_from(&self.chapter_track);
+                } else if self.chapter_track.is_empty() {
+                    self.chapter_track.clone_from(&self.chapter_list);
+                }
+            }
+        }
+    }
+
+    /// Replaces the titles of all chapters of both, the chapter list and track, using the
+    /// template. The placeholder `{n}` is replaced by the chapter number starting at 1, `{n:03}`
+    /// additionally pads the number with zeros to a width of 3 digits.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::{Chapter, Userdata};
+    /// use std::time::Duration;
+    ///
+    /// let mut tag = Userdata::default();
+    /// tag.chapter_track_mut().extend([
+    ///     Chapter::new(Duration::ZERO, "tts_0001"),
+    ///     Chapter::new(Duration::from_secs(60), "tts_0002"),
+    /// ]);
+    ///
+    /// tag.retitle_chapters("Chapter {n:02}");
+    /// assert_eq!(tag.chapter_track()[0].title, "Chapter 01");
+    /// assert_eq!(tag.chapter_track()[1].title, "Chapter 02");
+    /// ```
+    pub fn retitle_chapters(&mut self, template: &str) {
+        self.retitle_chapters_with(|i| format_chapter_title(template, i + 1));
+    }
+
+    /// Replaces the titles of all chapters of both, the chapter list and track, with the titles
+    /// returned by the closure, which is called with the zero based index of each chapter.
+    pub fn retitle_chapters_with(&mut self, mut titles: impl FnMut(usize) -> String) {
+        for chapters in [&mut self.chapter_list, &mut self.chapter_track] {
+            for (i, c) in chapters.iter_mut().enumerate() {
+                c.title = titles(i);
+            }
+        }
+    }
+}
+
+/// Replaces `{n}` and `{n:0<width>}` placeholders inside the template with the number.
+fn format_chapter_title(template: &str, n: usize) -> String {
+    let mut title = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{n") {
+        title.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else { break };
+        let width = match &rest[2..end] {
+            "" => Some(0),
+            spec => spec.strip_prefix(":0").and_then(|w| w.parse::<usize>().ok()),
+        };
+        match width {
+            Some(width) => title.push_str(&format!("{n:0width$}")),
+            None => title.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    title.push_str(rest);
+    title
 }
 
 /// ### Chapter list
@@ -263,6 +493,165 @@ impl Userdata {
     }
 }
 
+/// A snapshot of [`Userdata`], created by [`Userdata::snapshot`].
+///
+/// Metadata items are shared with the userdata the snapshot was taken from, and only copied once
+/// either of them is modified. Taking a snapshot doesn't copy data like artwork images.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserdataSnapshot(Userdata);
+
+/// ### Snapshots
+impl Userdata {
+    /// Takes a snapshot of the userdata, which can later be restored using
+    /// [`Userdata::restore`]. This is cheap, since metadata items are shared instead of being
+    /// copied, which allows implementing undo and redo.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::Userdata;
+    ///
+    /// let mut tag = Userdata::default();
+    /// tag.set_title("title");
+    ///
+    /// let snapshot = tag.snapshot();
+    /// tag.set_title("new title");
+    /// tag.set_artist("artist");
+    ///
+    /// tag.restore(snapshot);
+    /// assert_eq!(tag.title(), Some("title"));
+    /// assert_eq!(tag.artist(), None);
+    /// ```
+    pub fn snapshot(&self) -> UserdataSnapshot {
+        UserdataSnapshot(self.clone())
+    }
+
+    /// Restores the state of a snapshot taken using [`Userdata::snapshot`].
+    pub fn restore(&mut self, snapshot: UserdataSnapshot) {
+        *self = snapshot.0;
+    }
+}
+
+/// ### Canonicalization
+impl Userdata {
+    /// Converts values that other taggers store in a non-standard way to the representation used
+    /// by iTunes. Flags (`cpil`, `pgap` and `shwm`) that are stored as strings like `"1"` or as
+    /// integers of another width are converted to a single byte signed integer.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::{Data, Userdata, ident};
+    ///
+    /// let mut tag = Userdata::default();
+    /// tag.set_data(ident::COMPILATION, Data::Utf8("1".into()));
+    /// assert!(tag.compilation());
+    ///
+    /// tag.canonicalize();
+    /// assert_eq!(tag.data_of(&ident::COMPILATION).next(), Some(&Data::BeSigned(vec![1])));
+    /// ```
+    pub fn canonicalize(&mut self) {
+        let is_flag = [ident::COMPILATION, ident::GAPLESS_PLAYBACK, ident::SHOW_MOVEMENT];
+        let non_canonical = |d: &Data| match d.flag() {
+            Some(flag) => *d != Data::BeSigned(vec![flag as u8]),
+            None => false,
+        };
+        for item in self.meta_items.iter_mut() {
+            if !is_flag.iter().any(|i| i == &item.ident) || !item.data.iter().any(non_canonical) {
+                continue;
+            }
+            for d in Arc::make_mut(item).data.iter_mut().filter(|d| non_canonical(d)) {
+                if let Some(flag) = d.flag() {
+                    *d = Data::BeSigned(vec![flag as u8]);
+                }
+            }
+        }
+    }
+}
+
+/// ### Comparison
+impl Userdata {
+    /// Returns true if both userdata contain the same metadata, irrespective of the order of the
+    /// metadata items. Different writers order items differently, and may split the values of an
+    /// identifier across multiple items, which doesn't change their meaning.
+    ///
+    /// The order of the values of an identifier is also ignored, except for the artists, album
+    /// artists, composers, their sort orders and the artwork, where the first value is the primary
+    /// one. Chapters are compared in order.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::Userdata;
+    ///
+    /// let mut a = Userdata::default();
+    /// a.set_title("title");
+    /// a.add_genre("rock");
+    /// a.add_genre("pop");
+    ///
+    /// let mut b = Userdata::default();
+    /// b.add_genre("pop");
+    /// b.add_genre("rock");
+    /// b.set_title("title");
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.semantic_eq(&b));
+    ///
+    /// b.set_title("other title");
+    /// assert!(!a.semantic_eq(&b));
+    /// ```
+    pub fn semantic_eq(&self, other: &Userdata) -> bool {
+        if self.chapter_list != other.chapter_list || self.chapter_track != other.chapter_track {
+            return false;
+        }
+
+        let items = self.grouped_data();
+        let other_items = other.grouped_data();
+        if items.len() != other_items.len() {
+            return false;
+        }
+        items.iter().all(|(ident, data)| {
+            let Some(other_data) = other_items.get(ident) else {
+                return false;
+            };
+            match ORDERED_IDENTS.iter().any(|i| i == *ident) {
+                true => data == other_data,
+                false => unordered_eq(data, other_data),
+            }
+        })
+    }
+
+    /// Returns the data of all items grouped by their identifier, omitting items without data.
+    fn grouped_data(&self) -> HashMap<&DataIdent, Vec<&Data>> {
+        let mut map: HashMap<&DataIdent, Vec<&Data>> = HashMap::new();
+        for item in self.meta_items.iter().filter(|i| !i.data.is_empty()) {
+            map.entry(&item.ident).or_default().extend(item.data.iter());
+        }
+        map
+    }
+}
+
+/// Identifiers whose first value is the primary one, so the order of their values matters.
+const ORDERED_IDENTS: [Fourcc; 7] = [
+    ident::ARTIST,
+    ident::ALBUM_ARTIST,
+    ident::COMPOSER,
+    ident::ARTIST_SORT_ORDER,
+    ident::ALBUM_ARTIST_SORT_ORDER,
+    ident::COMPOSER_SORT_ORDER,
+    ident::ARTWORK,
+];
+
+/// Compares the values as multisets. Items contain only a few values, so the quadratic matching
+/// is cheaper than sorting.
+fn unordered_eq(a: &[&Data], b: &[&Data]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut matched = vec![false; b.len()];
+    a.iter().all(|d| {
+        let pos = b.iter().zip(matched.iter()).position(|(o, &m)| !m && d == o);
+        pos.map(|p| matched[p] = true).is_some()
+    })
+}
+
 /// ## Data accessors
 impl Userdata {
     /// Returns references to all byte data corresponding to the identifier.\
@@ -463,7 +852,7 @@ impl Userdata {
     /// ```
     pub fn data_mut_of(&mut self, ident: &impl Ident) -> impl Iterator<Item = &mut Data> {
         match self.meta_items.iter_mut().find(|a| ident == &a.ident) {
-            Some(a) => a.data.iter_mut(),
+            Some(a) => Arc::make_mut(a).data.iter_mut(),
             None => [].iter_mut(),
         }
     }
@@ -486,13 +875,13 @@ impl Userdata {
         while i < self.meta_items.len() {
             if ident == &self.meta_items[i].ident {
                 let removed = self.meta_items.remove(i);
-                return removed.data.into_iter();
+                return Arc::unwrap_or_clone(removed).data.into_iter();
             }
 
             i += 1;
         }
 
-        Vec::new().into_iter()
+        ItemData::default().into_iter()
     }
 
     /// Returns an iterator over references to all byte data.
@@ -539,14 +928,13 @@ impl Userdata {
     /// assert_eq!(bytes.next(), None);
     /// ```
     pub fn bytes_mut(&mut self) -> impl Iterator<Item = (&DataIdent, &mut Vec<u8>)> {
-        self.data_mut().filter_map(|(i, d)| Some((i, d.bytes_mut()?)))
+        self.data_mut_where(Data::is_bytes).filter_map(|(i, d)| Some((i, d.bytes_mut()?)))
     }
 
     /// Consumes `self` and returns an iterator over all byte data.
     ///
     /// # Example
     /// ```
-    /// use std::rc::Rc;
     /// use mp4ameta::{Userdata, Data, DataIdent, Fourcc};
     ///
     /// let mut tag = Userdata::default();
@@ -608,14 +996,13 @@ impl Userdata {
     /// assert_eq!(strings.next(), None);
     /// ```
     pub fn strings_mut(&mut self) -> impl Iterator<Item = (&DataIdent, &mut String)> {
-        self.data_mut().filter_map(|(i, d)| Some((i, d.string_mut()?)))
+        self.data_mut_where(Data::is_string).filter_map(|(i, d)| Some((i, d.string_mut()?)))
     }
 
     /// Consumes `self` and returns an iterator over all strings.
     ///
     /// # Example
     /// ```
-    /// use std::rc::Rc;
     /// use mp4ameta::{Userdata, Data, DataIdent, Fourcc};
     ///
     /// let mut tag = Userdata::default();
@@ -677,14 +1064,13 @@ impl Userdata {
     /// assert_eq!(images.next(), None);
     /// ```
     pub fn images_mut(&mut self) -> impl Iterator<Item = (&DataIdent, ImgMut<'_>)> {
-        self.data_mut().filter_map(|(i, d)| Some((i, d.image_mut()?)))
+        self.data_mut_where(Data::is_image).filter_map(|(i, d)| Some((i, d.image_mut()?)))
     }
 
     /// Consumes `self` and returns an iterator over all images.
     ///
     /// # Example
     /// ```
-    /// use std::rc::Rc;
     /// use mp4ameta::{Userdata, Data, DataIdent, Fourcc, Img};
     ///
     /// let mut tag = Userdata::default();
@@ -746,18 +1132,29 @@ impl Userdata {
     /// assert_eq!(strings.next(), None);
     /// ```
     pub fn data_mut(&mut self) -> impl Iterator<Item = (&DataIdent, &mut Data)> {
-        self.meta_items.iter_mut().flat_map(|a| {
-            let ident = &a.ident;
-            let data = &mut a.data;
-            data.iter_mut().map(move |d| (ident, d))
-        })
+        self.data_mut_where(|_| true)
+    }
+
+    /// Returns an iterator over mutable references to all data matching the predicate. Items
+    /// without matching data aren't copied if they're shared with a snapshot.
+    fn data_mut_where(
+        &mut self,
+        predicate: impl Fn(&Data) -> bool + Copy,
+    ) -> impl Iterator<Item = (&DataIdent, &mut Data)> {
+        self.meta_items.iter_mut().filter(move |a| a.data.iter().any(predicate)).flat_map(
+            move |a| {
+                let a = Arc::make_mut(a);
+                let ident = &a.ident;
+                let data = &mut a.data;
+                data.iter_mut().filter(move |d| predicate(d)).map(move |d| (ident, d))
+            },
+        )
     }
 
     /// Consumes `self` and returns an iterator over all data.
     ///
     /// # Example
     /// ```
-    /// use std::rc::Rc;
     /// use mp4ameta::{Userdata, Data, DataIdent, Fourcc, Img};
     ///
     /// let mut tag = Userdata::default();
@@ -772,6 +1169,7 @@ impl Userdata {
     /// ```
     pub fn into_data(self) -> impl Iterator<Item = (DataIdent, Data)> {
         self.meta_items.into_iter().flat_map(move |a| {
+            let a = Arc::unwrap_or_clone(a);
             let ident = a.ident;
             let data = a.data;
             data.into_iter().map(move |d| (ident.clone(), d))
@@ -1003,7 +1401,7 @@ impl Userdata {
         let pos = self.meta_items.iter().position(|a| ident == &a.ident);
 
         if let Some(i) = pos {
-            self.meta_items[i].data.retain(predicate);
+            Arc::make_mut(&mut self.meta_items[i]).data.retain(predicate);
             if self.meta_items[i].data.is_empty() {
                 self.meta_items.remove(i);
             }
@@ -1134,17 +1532,16 @@ impl Userdata {
     pub fn retain_data(&mut self, predicate: impl Fn(&DataIdent, &Data) -> bool) {
         let mut i = 0;
         while i < self.meta_items.len() {
-            let a = &mut self.meta_items[i];
-            let mut j = 0;
-            while j < a.data.len() {
-                if predicate(&a.ident, &a.data[j]) {
-                    j += 1;
-                } else {
-                    a.data.remove(j);
-                }
+            let a = &self.meta_items[i];
+            if a.data.iter().all(|d| predicate(&a.ident, d)) {
+                i += 1;
+                continue;
             }
 
-            if a.data.is_empty() {
+            let MetaItem { ident, data } = Arc::make_mut(&mut self.meta_items[i]);
+            data.retain(|d| predicate(ident, d));
+
+            if data.is_empty() {
                 self.meta_items.remove(i);
             } else {
                 i += 1;
@@ -1213,10 +1610,13 @@ impl Userdata {
     pub fn set_data(&mut self, ident: impl Ident + Into<DataIdent>, data: Data) {
         match self.meta_items.iter_mut().find(|a| ident == a.ident) {
             Some(a) => {
+                let a = Arc::make_mut(a);
                 a.data.clear();
                 a.data.push(data);
             }
-            None => self.meta_items.push(MetaItem::new(ident.into(), vec![data])),
+            None => {
+                self.meta_items.push(Arc::new(MetaItem::new(ident.into(), ItemData::One(data))))
+            }
         }
     }
 
@@ -1248,11 +1648,13 @@ impl Userdata {
     ) {
         match self.meta_items.iter_mut().find(|a| ident == a.ident) {
             Some(a) => {
+                let a = Arc::make_mut(a);
                 a.data.clear();
                 a.data.extend(data);
             }
             None => {
-                self.meta_items.push(MetaItem::new(ident.into(), data.into_iter().collect()));
+                let item = MetaItem::new(ident.into(), data.into_iter().collect());
+                self.meta_items.push(Arc::new(item));
             }
         }
     }
@@ -1277,8 +1679,10 @@ impl Userdata {
     /// ```
     pub fn add_data(&mut self, ident: impl Ident + Into<DataIdent>, data: Data) {
         match self.meta_items.iter_mut().find(|a| ident == a.ident) {
-            Some(a) => a.data.push(data),
-            None => self.meta_items.push(MetaItem::new(ident.into(), vec![data])),
+            Some(a) => Arc::make_mut(a).data.push(data),
+            None => {
+                self.meta_items.push(Arc::new(MetaItem::new(ident.into(), ItemData::One(data))))
+            }
         }
     }
 
@@ -1309,8 +1713,11 @@ impl Userdata {
         data: impl IntoIterator<Item = Data>,
     ) {
         match self.meta_items.iter_mut().find(|a| ident == a.ident) {
-            Some(a) => a.data.extend(data),
-            None => self.meta_items.push(MetaItem::new(ident.into(), data.into_iter().collect())),
+            Some(a) => Arc::make_mut(a).data.extend(data),
+            None => {
+                let item = MetaItem::new(ident.into(), data.into_iter().collect());
+                self.meta_items.push(Arc::new(item));
+            }
         }
     }
 
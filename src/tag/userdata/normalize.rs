@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use crate::{UnicodeForm, Userdata};
+
+/// ### Unicode normalization
+impl Userdata {
+    /// Normalizes all strings of [`Utf8`] and [`Utf16`] data, and all chapter titles to the
+    /// Unicode form. Apple tools usually write decomposed strings (NFD), while most other tools
+    /// write composed strings (NFC), so strings that look identical may not compare equal.
+    ///
+    /// Freeform and QuickTime metadata identifiers aren't changed. See also
+    /// [`WriteConfig::normalize_strings`](crate::WriteConfig::normalize_strings).
+    ///
+    /// [`Utf8`]: crate::Data::Utf8
+    /// [`Utf16`]: crate::Data::Utf16
+    pub fn normalize_strings(&mut self, form: UnicodeForm) {
+        let is_normalized = |s: Option<&str>| s.is_none_or(|s| form.is_normalized(s));
+
+        for item in self.meta_items.iter_mut() {
+            if item.data.iter().all(|d| is_normalized(d.string())) {
+                continue;
+            }
+            for s in Arc::make_mut(item).data.iter_mut().filter_map(|d| d.string_mut()) {
+                if !form.is_normalized(s) {
+                    *s = form.normalize(s);
+                }
+            }
+        }
+
+        for c in self.chapter_list.iter_mut().chain(self.chapter_track.iter_mut()) {
+            if !form.is_normalized(&c.title) {
+                c.title = form.normalize(&c.title);
+            }
+        }
+    }
+}
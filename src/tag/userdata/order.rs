@@ -0,0 +1,105 @@
+use crate::{DataIdent, Fourcc, Userdata, ident};
+
+/// The order of metadata items, see [`Userdata::sort_items`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemOrder {
+    /// Sorted alphabetically by the [`Display`](std::fmt::Display) representation of the
+    /// identifier, for example `"----:com.apple.iTunes:ISRC"`, `"aART"`, `"cpil"`, `"©ART"`.
+    Alphabetical,
+    /// The order written by iTunes and the Apple Music app, starting with the title, artist and
+    /// album. Other items with a fourcc follow in alphabetical order, then freeform (`----`)
+    /// items and QuickTime metadata keys (`mdta`) in their previous order.
+    Apple,
+}
+
+/// The order of fourcc items written by iTunes.
+const APPLE_ORDER: &[Fourcc] = &[
+    ident::TITLE,
+    ident::ARTIST,
+    ident::ALBUM_ARTIST,
+    ident::ALBUM,
+    ident::GROUPING,
+    ident::COMPOSER,
+    ident::COMMENT,
+    ident::CUSTOM_GENRE,
+    ident::STANDARD_GENRE,
+    ident::YEAR,
+    ident::TRACK_NUMBER,
+    ident::DISC_NUMBER,
+    ident::BPM,
+    ident::COMPILATION,
+    ident::WORK,
+    ident::MOVEMENT,
+    ident::MOVEMENT_INDEX,
+    ident::MOVEMENT_COUNT,
+    ident::SHOW_MOVEMENT,
+    ident::TV_SHOW_NAME,
+    ident::TV_EPISODE_NAME,
+    ident::TV_SEASON,
+    ident::TV_EPISODE,
+    ident::TV_NETWORK_NAME,
+    ident::DESCRIPTION,
+    ident::LONG_DESCRIPTION,
+    ident::STORE_DESCRIPTION,
+    ident::LYRICS,
+    ident::TITLE_SORT_ORDER,
+    ident::ARTIST_SORT_ORDER,
+    ident::ALBUM_ARTIST_SORT_ORDER,
+    ident::ALBUM_SORT_ORDER,
+    ident::COMPOSER_SORT_ORDER,
+    ident::TV_SHOW_NAME_SORT_ORDER,
+    ident::MEDIA_TYPE,
+    ident::ADVISORY_RATING,
+    ident::GAPLESS_PLAYBACK,
+    ident::PODCAST,
+    ident::CATEGORY,
+    ident::KEYWORD,
+    ident::PODCAST_URL,
+    ident::PODCAST_EPISODE_GLOBAL_UNIQUE_ID,
+    ident::PURCHASE_DATE,
+    ident::OWNER,
+    ident::COPYRIGHT,
+    ident::ENCODER,
+    ident::ARTWORK,
+];
+
+/// ### Item order
+impl Userdata {
+    /// Sorts the metadata items, which are written in this order. Items with equal keys keep
+    /// their relative order.
+    ///
+    /// Items are otherwise kept in the order they were read from the file, and new items are
+    /// appended, so writing a tag without changes doesn't reorder the items. Sorting them
+    /// explicitly produces deterministic output regardless of the input file, which some players
+    /// rely on to display fields in the expected order.
+    ///
+    /// ```
+    /// use mp4ameta::{ItemOrder, Tag, ident};
+    ///
+    /// let mut tag = Tag::default();
+    /// tag.set_album("album");
+    /// tag.set_title("title");
+    /// tag.set_isrc("isrc");
+    ///
+    /// tag.sort_items(ItemOrder::Apple);
+    /// let idents = tag.data().map(|(i, _)| i.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(idents, ["©nam", "©alb", "----:com.apple.iTunes:ISRC"]);
+    ///
+    /// tag.sort_items(ItemOrder::Alphabetical);
+    /// let idents = tag.data().map(|(i, _)| i.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(idents, ["----:com.apple.iTunes:ISRC", "©alb", "©nam"]);
+    /// ```
+    pub fn sort_items(&mut self, order: ItemOrder) {
+        match order {
+            ItemOrder::Alphabetical => self.meta_items.sort_by_cached_key(|i| i.ident.to_string()),
+            ItemOrder::Apple => self.meta_items.sort_by_cached_key(|i| match &i.ident {
+                DataIdent::Fourcc(f) => match APPLE_ORDER.iter().position(|o| o == f) {
+                    Some(pos) => (0, pos, String::new()),
+                    None => (1, 0, f.to_string()),
+                },
+                DataIdent::Freeform { .. } => (2, 0, String::new()),
+                DataIdent::Mdta(_) => (3, 0, String::new()),
+            }),
+        }
+    }
+}
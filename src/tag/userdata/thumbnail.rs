@@ -0,0 +1,72 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use image::DynamicImage;
+
+use crate::{ErrorKind, Userdata};
+
+/// ### Artwork thumbnails
+impl Userdata {
+    /// Decodes the first artwork image (`covr`) and scales it down to fit within `max_dim` by
+    /// `max_dim` pixels, preserving the aspect ratio. Images that already fit aren't scaled.
+    ///
+    /// The decoded image is cached, so thumbnails of different sizes can be created without
+    /// decoding the artwork again, until it's modified.
+    pub fn artwork_thumbnail(&self, max_dim: u32) -> crate::Result<Option<DynamicImage>> {
+        let Some(artwork) = self.artwork() else {
+            return Ok(None);
+        };
+
+        let mut hasher = DefaultHasher::new();
+        artwork.data.hash(&mut hasher);
+        let key = hasher.finish();
+
+        let mut cache = self.thumbnail_cache.0.lock().unwrap_or_else(|e| e.into_inner());
+        let decoded = match &*cache {
+            Some((k, img)) if *k == key => Arc::clone(img),
+            _ => {
+                let img = image::load_from_memory(artwork.data).map_err(|e| {
+                    let description = format!("Error decoding artwork: {e}");
+                    crate::Error::new(ErrorKind::ImgDecoding(Box::new(e)), description)
+                })?;
+                let img = Arc::new(img);
+                *cache = Some((key, Arc::clone(&img)));
+                img
+            }
+        };
+        drop(cache);
+
+        if decoded.width() <= max_dim && decoded.height() <= max_dim {
+            return Ok(Some(DynamicImage::clone(&decoded)));
+        }
+        Ok(Some(decoded.thumbnail(max_dim, max_dim)))
+    }
+}
+
+/// The decoded artwork used to create thumbnails, along with a hash of the encoded image data.
+#[derive(Default)]
+pub(crate) struct ThumbnailCache(Mutex<Option<(u64, Arc<DynamicImage>)>>);
+
+impl Clone for ThumbnailCache {
+    fn clone(&self) -> Self {
+        let cache = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        Self(Mutex::new(cache.clone()))
+    }
+}
+
+impl fmt::Debug for ThumbnailCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ThumbnailCache")
+    }
+}
+
+/// The cache doesn't affect the equality of userdata.
+impl PartialEq for ThumbnailCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ThumbnailCache {}
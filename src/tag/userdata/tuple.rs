@@ -1,5 +1,6 @@
 use std::fmt;
 
+use crate::tag::TagFormatter;
 use crate::{Data, Userdata, ident};
 
 /// ### Track
@@ -64,6 +65,28 @@ impl Userdata {
         }
     }
 
+    /// Sets the track number, preserving the total number of tracks (`trkn`). Unlike
+    /// [`Userdata::set_track_number`] the width of the existing data is preserved, and it is only
+    /// widened if the value doesn't fit.
+    pub fn set_track_number_only(&mut self, track_number: u16) {
+        let vec = self.bytes_mut_of(&ident::TRACK_NUMBER).next();
+        match vec {
+            Some(v) => set_number_only(v, track_number),
+            None => self.set_new_track(track_number, 0),
+        }
+    }
+
+    /// Sets the total number of tracks, preserving the track number (`trkn`). Unlike
+    /// [`Userdata::set_total_tracks`] the width of the existing data is preserved, and it is only
+    /// widened if the value doesn't fit.
+    pub fn set_total_tracks_only(&mut self, total_tracks: u16) {
+        let vec = self.bytes_mut_of(&ident::TRACK_NUMBER).next();
+        match vec {
+            Some(v) => set_total_only(v, total_tracks),
+            None => self.set_new_track(0, total_tracks),
+        }
+    }
+
     /// Removes the track number and the total number of tracks (`trkn`).
     pub fn remove_track(&mut self) {
         self.remove_data_of(&ident::TRACK_NUMBER);
@@ -88,13 +111,8 @@ impl Userdata {
     }
 
     /// Returns the track numer and total number of tracks formatted in an easily readable way.
-    pub(crate) fn format_track(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.track() {
-            (Some(n), Some(t)) => writeln!(f, "track: {n} of {t}"),
-            (Some(n), None) => writeln!(f, "track: {n}"),
-            (None, Some(t)) => writeln!(f, "track: ? of {t}"),
-            (None, None) => Ok(()),
-        }
+    pub(crate) fn format_track(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("track", Some("trkn"), format_tuple(self.track()))
     }
 }
 
@@ -160,6 +178,28 @@ impl Userdata {
         }
     }
 
+    /// Sets the disc number, preserving the total number of discs (`disk`). Unlike
+    /// [`Userdata::set_disc_number`] the width of the existing data is preserved, and it is only
+    /// widened if the value doesn't fit.
+    pub fn set_disc_number_only(&mut self, disc_number: u16) {
+        let vec = self.bytes_mut_of(&ident::DISC_NUMBER).next();
+        match vec {
+            Some(v) => set_number_only(v, disc_number),
+            None => self.set_new_disc(disc_number, 0),
+        }
+    }
+
+    /// Sets the total number of discs, preserving the disc number (`disk`). Unlike
+    /// [`Userdata::set_total_discs`] the width of the existing data is preserved, and it is only
+    /// widened if the value doesn't fit.
+    pub fn set_total_discs_only(&mut self, total_discs: u16) {
+        let vec = self.bytes_mut_of(&ident::DISC_NUMBER).next();
+        match vec {
+            Some(v) => set_total_only(v, total_discs),
+            None => self.set_new_disc(0, total_discs),
+        }
+    }
+
     /// Removes the disc number and the total number of discs (`disk`).
     pub fn remove_disc(&mut self) {
         self.remove_data_of(&ident::DISC_NUMBER);
@@ -184,18 +224,30 @@ impl Userdata {
     }
 
     /// Returns the disc numer and total number of discs formatted in an easily readable way.
-    pub(crate) fn format_disc(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.disc() {
-            (Some(d), Some(t)) => writeln!(f, "disc: {d} of {t}"),
-            (Some(d), None) => writeln!(f, "disc: {d}"),
-            (None, Some(t)) => writeln!(f, "disc: ? of {t}"),
-            (None, None) => Ok(()),
-        }
+    pub(crate) fn format_disc(&self, f: &mut TagFormatter<'_, '_>) -> fmt::Result {
+        f.value("disc", Some("disk"), format_tuple(self.disc()))
     }
 }
 
+fn format_tuple(tuple: (Option<u16>, Option<u16>)) -> Option<String> {
+    match tuple {
+        (Some(n), Some(t)) => Some(format!("{n} of {t}")),
+        (Some(n), None) => Some(n.to_string()),
+        (None, Some(t)) => Some(format!("? of {t}")),
+        (None, None) => None,
+    }
+}
+
+// The tuple is usually stored as 8 bytes `[0, 0, n0, n1, t0, t1, 0, 0]`, but some taggers omit
+// the trailing padding or the total (`[0, 0, n0, n1]`), while others store only the number as a
+// plain integer of 1 to 3 bytes.
 fn number(vec: &[u8]) -> Option<u16> {
-    be_int!(vec, 2, u16).and_then(|n| if n == 0 { None } else { Some(n) })
+    let n = match vec.len() {
+        0 => return None,
+        1..4 => u16::try_from(vec.iter().fold(0u32, |n, &b| (n << 8) | b as u32)).ok()?,
+        _ => be_int!(vec, 2, u16)?,
+    };
+    if n == 0 { None } else { Some(n) }
 }
 
 fn total(vec: &[u8]) -> Option<u16> {
@@ -203,15 +255,42 @@ fn total(vec: &[u8]) -> Option<u16> {
 }
 
 fn set_number(vec: &mut Vec<u8>, number: u16) {
+    normalize(vec);
     set_be_int!(vec, 2, number, u16);
     check_correct_size(vec);
 }
 
 fn set_total(vec: &mut Vec<u8>, total: u16) {
+    normalize(vec);
     set_be_int!(vec, 4, total, u16);
     check_correct_size(vec);
 }
 
+fn set_number_only(vec: &mut Vec<u8>, number: u16) {
+    match vec.len() {
+        1..4 if vec.len() > 1 || number <= u8::MAX as u16 => {
+            let bytes = (number as u32).to_be_bytes();
+            let len = vec.len();
+            vec.copy_from_slice(&bytes[4 - len..]);
+        }
+        1 => *vec = number.to_be_bytes().to_vec(),
+        _ => set_be_int!(vec, 2, number, u16),
+    }
+}
+
+fn set_total_only(vec: &mut Vec<u8>, total: u16) {
+    normalize(vec);
+    set_be_int!(vec, 4, total, u16);
+}
+
+/// Converts a plain integer number to the tuple layout.
+fn normalize(vec: &mut Vec<u8>) {
+    if (1..4).contains(&vec.len()) {
+        *vec = new(number(vec).unwrap_or(0), 0);
+        vec.truncate(4);
+    }
+}
+
 // NOTE: iTunes/Apple Music requires the atom size to be 8 bytes for correct parsing.
 //       Smaller sizes 6 bytes for example will cause parsing issues.
 fn check_correct_size(vec: &mut Vec<u8>) {
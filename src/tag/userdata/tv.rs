@@ -0,0 +1,97 @@
+use crate::{Userdata, ident};
+
+/// The metadata of a TV episode, which is stored in six separate items, see
+/// [`Userdata::tv_episode_info`].
+///
+/// ```
+/// use mp4ameta::{Tag, TvEpisodeInfo};
+///
+/// let mut tag = Tag::default();
+/// tag.set_tv_episode_info(TvEpisodeInfo {
+///     show: Some("show".into()),
+///     season: Some(1),
+///     episode: Some(2),
+///     episode_id: Some("S01E02".into()),
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(tag.tv_show_name(), Some("show"));
+/// assert_eq!(tag.tv_episode(), Some(2));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TvEpisodeInfo {
+    /// The name of the show (`tvsh`).
+    pub show: Option<String>,
+    /// The name of the network (`tvnn`).
+    pub network: Option<String>,
+    /// The name of the episode, which is stored as the title (`©nam`). Since the title isn't
+    /// specific to TV episodes, it's only set if this is `Some`, and never removed.
+    pub episode_name: Option<String>,
+    /// The season number (`tvsn`).
+    pub season: Option<u32>,
+    /// The episode number (`tves`).
+    pub episode: Option<u32>,
+    /// The episode id, for example `"S01E02"` (`tven`). This is the same item as
+    /// [`Userdata::tv_episode_name`].
+    pub episode_id: Option<String>,
+}
+
+/// ### TV episode
+impl Userdata {
+    /// Returns the show, network, episode name, season, episode and episode id, or `None` if none
+    /// of the TV specific items are present. A title alone isn't considered TV metadata.
+    ///
+    /// Unlike [`Userdata::tv_season`] and [`Userdata::tv_episode`], the season and episode
+    /// numbers are also read if they are stored as integers of another width than 4 bytes.
+    pub fn tv_episode_info(&self) -> Option<TvEpisodeInfo> {
+        let info = TvEpisodeInfo {
+            show: self.tv_show_name().map(str::to_owned),
+            network: self.tv_network_name().map(str::to_owned),
+            episode_name: None,
+            season: self.data_of(&ident::TV_SEASON).next().and_then(|d| d.try_into().ok()),
+            episode: self.data_of(&ident::TV_EPISODE).next().and_then(|d| d.try_into().ok()),
+            episode_id: self.tv_episode_name().map(str::to_owned),
+        };
+        if info == TvEpisodeInfo::default() {
+            return None;
+        }
+        Some(TvEpisodeInfo {
+            episode_name: self.title().map(str::to_owned),
+            ..info
+        })
+    }
+
+    /// Sets the show, network, episode name, season, episode and episode id, removing TV specific
+    /// items that are `None`. The title is left untouched if the episode name is `None`. The
+    /// season and episode numbers are stored as 4 byte integers.
+    pub fn set_tv_episode_info(&mut self, info: TvEpisodeInfo) {
+        match info.show {
+            Some(s) => self.set_tv_show_name(s),
+            None => self.remove_tv_show_name(),
+        }
+        match info.network {
+            Some(n) => self.set_tv_network_name(n),
+            None => self.remove_tv_network_name(),
+        }
+        if let Some(n) = info.episode_name {
+            self.set_title(n);
+        }
+        match info.season {
+            Some(s) => self.set_tv_season(s),
+            None => self.remove_tv_season(),
+        }
+        match info.episode {
+            Some(e) => self.set_tv_episode(e),
+            None => self.remove_tv_episode(),
+        }
+        match info.episode_id {
+            Some(i) => self.set_tv_episode_name(i),
+            None => self.remove_tv_episode_name(),
+        }
+    }
+
+    /// Removes the show, network, season, episode and episode id. The title is left untouched.
+    pub fn remove_tv_episode_info(&mut self) {
+        self.set_tv_episode_info(TvEpisodeInfo::default());
+    }
+}
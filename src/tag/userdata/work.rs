@@ -0,0 +1,99 @@
+use crate::Userdata;
+
+/// The work and movement of a classical piece, which are stored in five separate items, see
+/// [`Userdata::classical_work`].
+///
+/// ```
+/// use mp4ameta::{ClassicalWork, Tag};
+///
+/// let mut tag = Tag::default();
+/// tag.set_classical_work(ClassicalWork::new("Symphony No. 9").movement("Allegro", 1, 4));
+///
+/// assert_eq!(tag.work(), Some("Symphony No. 9"));
+/// assert_eq!(tag.movement_index(), Some(1));
+/// assert!(tag.show_movement());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClassicalWork {
+    /// The work (`©wrk`).
+    pub work: Option<String>,
+    /// The movement (`©mvn`).
+    pub movement: Option<String>,
+    /// The movement index (`©mvi`).
+    pub movement_index: Option<u16>,
+    /// The movement count (`©mvc`).
+    pub movement_count: Option<u16>,
+    /// Whether the work and movement are shown instead of the title (`shwm`).
+    pub show_movement: bool,
+}
+
+impl ClassicalWork {
+    /// Creates a work without a movement.
+    pub fn new(work: impl Into<String>) -> Self {
+        Self { work: Some(work.into()), ..Default::default() }
+    }
+
+    /// Sets the movement, its index and the total number of movements, and enables
+    /// [`ClassicalWork::show_movement`].
+    pub fn movement(mut self, movement: impl Into<String>, index: u16, count: u16) -> Self {
+        self.movement = Some(movement.into());
+        self.movement_index = Some(index);
+        self.movement_count = Some(count);
+        self.show_movement = true;
+        self
+    }
+}
+
+/// ### Classical work
+impl Userdata {
+    /// Returns the work, movement, movement index, movement count and show movement flag, or
+    /// `None` if none of them are present.
+    pub fn classical_work(&self) -> Option<ClassicalWork> {
+        let work = ClassicalWork {
+            work: self.work().map(str::to_owned),
+            movement: self.movement().map(str::to_owned),
+            movement_index: self.movement_index(),
+            movement_count: self.movement_count(),
+            show_movement: self.show_movement(),
+        };
+        (work != ClassicalWork::default()).then_some(work)
+    }
+
+    /// Sets the work, movement, movement index, movement count and show movement flag, removing
+    /// items that are `None`.
+    ///
+    /// Combinations that confuse players are avoided: without a movement, the movement index,
+    /// movement count and show movement flag aren't written.
+    pub fn set_classical_work(&mut self, work: ClassicalWork) {
+        match work.work {
+            Some(w) => self.set_work(w),
+            None => self.remove_work(),
+        }
+
+        let Some(movement) = work.movement else {
+            self.remove_movement();
+            self.remove_movement_index();
+            self.remove_movement_count();
+            self.remove_show_movement();
+            return;
+        };
+        self.set_movement(movement);
+        match work.movement_index {
+            Some(i) => self.set_movement_index(i),
+            None => self.remove_movement_index(),
+        }
+        match work.movement_count {
+            Some(c) => self.set_movement_count(c),
+            None => self.remove_movement_count(),
+        }
+        match work.show_movement {
+            true => self.set_show_movement(),
+            false => self.remove_show_movement(),
+        }
+    }
+
+    /// Removes the work, movement, movement index, movement count and show movement flag.
+    pub fn remove_classical_work(&mut self) {
+        self.set_classical_work(ClassicalWork::default());
+    }
+}
@@ -0,0 +1,272 @@
+//! Setting items from templates containing placeholders.
+//!
+//! A [`TagTemplate`] maps item identifiers to template strings such as `"{track:02} {title}"`.
+//! Placeholders are resolved against a [`Context`] provided by the caller, which can be
+//! initialized from the existing userdata and the path of the file.
+//!
+//! ```
+//! use mp4ameta::template::{Context, TagTemplate};
+//! use mp4ameta::{Tag, ident};
+//!
+//! let mut tag = Tag::default();
+//! tag.set_album("album");
+//! tag.set_track_number(3);
+//!
+//! let template = TagTemplate::new()
+//!     .set(ident::TITLE, "{track:02} - {filename}")
+//!     .set(ident::COMMENT, "from {album}");
+//! let ctx = Context::from_userdata(&tag).with_path("music/intro.m4a");
+//! tag.apply_template(&template, &ctx).unwrap();
+//!
+//! assert_eq!(tag.title(), Some("03 - intro"));
+//! assert_eq!(tag.comment(), Some("from album"));
+//! assert_eq!(ctx.render("{filename}.{extension}").unwrap(), "intro.m4a");
+//! ```
+//!
+//! The same syntax is used to render relative paths, see [`Userdata::render_path`].
+//!
+//! # Syntax
+//! - `{name}` is replaced by the value of `name`.
+//! - `{name:0N}` is replaced by the value of `name`, padded with zeros to a width of `N`.
+//! - `{{` and `}}` are replaced by literal braces.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::{Data, DataIdent, ErrorKind, Userdata};
+
+/// A set of item identifiers and template strings, which can be applied to userdata using
+/// [`Userdata::apply_template`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TagTemplate {
+    fields: Vec<(DataIdent, String)>,
+}
+
+impl TagTemplate {
+    /// Creates an empty template.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the template used for the item with the identifier. The rendered value is stored as
+    /// [`Data::Utf8`], so this should only be used with items containing strings.
+    pub fn set(mut self, ident: impl Into<DataIdent>, template: impl Into<String>) -> Self {
+        let ident = ident.into();
+        let template = template.into();
+        match self.fields.iter_mut().find(|(i, _)| *i == ident) {
+            Some((_, t)) => *t = template,
+            None => self.fields.push((ident, template)),
+        }
+        self
+    }
+
+    /// Returns the identifiers and template strings.
+    pub fn fields(&self) -> impl Iterator<Item = (&DataIdent, &str)> {
+        self.fields.iter().map(|(i, t)| (i, t.as_str()))
+    }
+}
+
+/// The values that placeholders of a template are resolved against.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Context {
+    values: HashMap<String, String>,
+}
+
+impl Context {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a context containing the values of the userdata. The following placeholders are
+    /// available if the corresponding item is present:
+    ///
+    /// `title`, `artist`, `album`, `album_artist`, `composer`, `genre`, `year`, `comment`,
+    /// `track`, `total_tracks`, `disc`, `total_discs`
+    pub fn from_userdata(userdata: &Userdata) -> Self {
+        let mut ctx = Self::new();
+        let strings = [
+            ("title", userdata.title()),
+            ("artist", userdata.artist()),
+            ("album", userdata.album()),
+            ("album_artist", userdata.album_artist()),
+            ("composer", userdata.composer()),
+            ("genre", userdata.genre()),
+            ("year", userdata.year()),
+            ("comment", userdata.comment()),
+        ];
+        for (key, value) in strings {
+            if let Some(v) = value {
+                ctx.insert(key, v);
+            }
+        }
+        let numbers = [
+            ("track", userdata.track_number()),
+            ("total_tracks", userdata.total_tracks()),
+            ("disc", userdata.disc_number()),
+            ("total_discs", userdata.total_discs()),
+        ];
+        for (key, value) in numbers {
+            if let Some(v) = value {
+                ctx.insert(key, v.to_string());
+            }
+        }
+        ctx
+    }
+
+    /// Adds the `filename` (without extension), `extension` and `dirname` (the name of the
+    /// parent directory) of the path to the context, if present.
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let parts = [
+            ("filename", path.file_stem()),
+            ("extension", path.extension()),
+            ("dirname", path.parent().and_then(Path::file_name)),
+        ];
+        for (key, value) in parts {
+            if let Some(v) = value {
+                self.insert(key, v.to_string_lossy());
+            }
+        }
+        self
+    }
+
+    /// Adds the value, replacing the previous value of the placeholder.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Inserts the value, replacing the previous value of the placeholder.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    /// Returns the value of the placeholder.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Renders the template string, for example to create a file name. Returns an error of kind
+    /// [`ErrorKind::InvalidTemplate`] if the template is malformed or contains a placeholder that
+    /// isn't present in the context.
+    pub fn render(&self, template: &str) -> crate::Result<String> {
+        self.render_with(template, |v| Cow::Borrowed(v))
+    }
+
+    /// Renders the pattern as a relative path, for example `"{artist}/{track:02} {title}.m4a"`.
+    /// Characters that aren't allowed in file names on common filesystems, including path
+    /// separators, are replaced by `_` in the values of placeholders, so only the pattern itself
+    /// can create directories. Trailing dots and spaces of path components are removed, and
+    /// components that are empty afterwards, such as `..`, are replaced by `_`.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidTemplate`] if the pattern can't be rendered,
+    /// see [`Context::render`], or if it doesn't result in a relative path.
+    pub fn render_path(&self, pattern: &str) -> crate::Result<PathBuf> {
+        let rendered = self.render_with(pattern, sanitize)?;
+
+        let mut path = PathBuf::new();
+        for component in rendered.split(['/', '\\']) {
+            match component.trim_end_matches(['.', ' ']) {
+                "" => path.push("_"),
+                c => path.push(c),
+            }
+        }
+        if !path.is_relative() {
+            return Err(template_error(format!("Pattern {pattern:?} results in an absolute path")));
+        }
+        Ok(path)
+    }
+
+    fn render_with(
+        &self,
+        template: &str,
+        map_value: impl Fn(&str) -> Cow<'_, str>,
+    ) -> crate::Result<String> {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(i) = rest.find(['{', '}']) {
+            out.push_str(&rest[..i]);
+            let brace = rest.as_bytes()[i];
+            rest = &rest[i + 1..];
+
+            if let Some(r) = rest.strip_prefix(brace as char) {
+                out.push(brace as char);
+                rest = r;
+                continue;
+            }
+            if brace == b'}' {
+                return Err(template_error(format!("Unmatched '}}' in template {template:?}")));
+            }
+
+            let end = rest.find('}').ok_or_else(|| {
+                template_error(format!("Unterminated placeholder in template {template:?}"))
+            })?;
+            let placeholder = &rest[..end];
+            rest = &rest[end + 1..];
+
+            let (key, width) = match placeholder.split_once(':') {
+                Some((key, spec)) => {
+                    let width = spec.strip_prefix('0').and_then(|w| w.parse::<usize>().ok());
+                    let width = width.ok_or_else(|| {
+                        template_error(format!("Invalid format spec {spec:?} of {{{key}}}"))
+                    })?;
+                    (key, width)
+                }
+                None => (placeholder, 0),
+            };
+            let value = self.get(key).map(&map_value).ok_or_else(|| {
+                template_error(format!("Unresolved placeholder {{{key}}} in template {template:?}"))
+            })?;
+
+            let len = value.chars().count();
+            out.extend(std::iter::repeat_n('0', width.saturating_sub(len)));
+            out.push_str(&value);
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+/// ### Templates
+impl Userdata {
+    /// Renders the pattern as a relative path using the values of the userdata, see
+    /// [`Context::from_userdata`] and [`Context::render_path`].
+    pub fn render_path(&self, pattern: &str) -> crate::Result<PathBuf> {
+        Context::from_userdata(self).render_path(pattern)
+    }
+
+    /// Renders all template strings of the template using the context, and sets the items to the
+    /// resulting values. Items for which the rendered value is empty are removed. If any template
+    /// can't be rendered an error is returned and the userdata is left untouched, see
+    /// [`Context::render`].
+    pub fn apply_template(&mut self, template: &TagTemplate, ctx: &Context) -> crate::Result<()> {
+        let rendered = (template.fields.iter())
+            .map(|(ident, t)| Ok((ident, ctx.render(t)?)))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        for (ident, value) in rendered {
+            if value.is_empty() {
+                self.remove_data_of(ident);
+            } else {
+                self.set_data(ident.clone(), Data::Utf8(value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces characters that aren't allowed in file names on common filesystems.
+fn sanitize(value: &str) -> Cow<'_, str> {
+    let illegal =
+        |c: char| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c < ' ';
+    if !value.contains(illegal) {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(value.chars().map(|c| if illegal(c) { '_' } else { c }).collect())
+}
+
+fn template_error(description: String) -> crate::Error {
+    crate::Error::new(ErrorKind::InvalidTemplate, description)
+}
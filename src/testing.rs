@@ -0,0 +1,89 @@
+//! Helpers for verifying that writing tags doesn't corrupt the audio data, only available with
+//! the `testing` feature.
+//!
+//! The audio data is compared using [`audio_checksum`], so the checks are independent of the
+//! metadata and of the position of the media data (`mdat`).
+//!
+//! ```
+//! use mp4ameta::testing::AudioGuard;
+//! use mp4ameta::Tag;
+//!
+//! let path = std::env::temp_dir().join("mp4ameta-testing-doc.m4a");
+//! std::fs::copy("files/sample.m4a", &path).unwrap();
+//!
+//! let guard = AudioGuard::from_path(&path).unwrap();
+//! let mut tag = Tag::read_from_path(&path).unwrap();
+//! tag.set_title("a much longer title that grows the movie atom".repeat(100));
+//! tag.write_to_path(&path).unwrap();
+//! guard.verify_path(&path).unwrap();
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use crate::{ChecksumAlgorithm, ErrorKind, Userdata, WriteConfig, audio_checksum};
+
+/// The checksum of the audio data of a file, taken before it's written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioGuard {
+    checksum: u64,
+}
+
+impl AudioGuard {
+    /// Computes the checksum of the audio data of the reader.
+    pub fn new(reader: &mut (impl Read + Seek)) -> crate::Result<Self> {
+        let checksum = audio_checksum(reader, ChecksumAlgorithm::Fnv1a64)?;
+        Ok(Self { checksum })
+    }
+
+    /// Computes the checksum of the audio data of the file at the path.
+    pub fn from_path(path: impl AsRef<Path>) -> crate::Result<Self> {
+        Self::new(&mut File::open(path)?)
+    }
+
+    /// Returns an error of the kind [`ErrorKind::VerificationFailed`] if the audio data of the
+    /// reader differs from the one the guard was created from.
+    pub fn verify(&self, reader: &mut (impl Read + Seek)) -> crate::Result<()> {
+        if Self::new(reader)? != *self {
+            return Err(crate::Error::new(
+                ErrorKind::VerificationFailed,
+                "The audio data changed while writing",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns an error of the kind [`ErrorKind::VerificationFailed`] if the audio data of the
+    /// file at the path differs from the one the guard was created from.
+    pub fn verify_path(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        self.verify(&mut File::open(path)?)
+    }
+}
+
+/// Writes the userdata to a copy of the input like [`Userdata::write_to_vec`], and checks that the
+/// written file contains the same audio data, see [`AudioGuard::verify`].
+pub fn write_to_vec_checked(
+    userdata: &Userdata,
+    input: &[u8],
+    cfg: &WriteConfig,
+) -> crate::Result<Vec<u8>> {
+    let guard = AudioGuard::new(&mut Cursor::new(input))?;
+    let output = userdata.write_to_vec(input, cfg)?;
+    guard.verify(&mut Cursor::new(&output))?;
+    Ok(output)
+}
+
+/// Asserts that both files contain the same audio data.
+///
+/// # Panics
+/// If the audio data differs, or either file can't be read.
+#[track_caller]
+pub fn assert_audio_eq(before: &[u8], after: &[u8]) {
+    let checksum = |data: &[u8]| match AudioGuard::new(&mut Cursor::new(data)) {
+        Ok(guard) => guard.checksum,
+        Err(e) => panic!("failed to read audio data: {e}"),
+    };
+    assert!(checksum(before) == checksum(after), "the audio data differs");
+}
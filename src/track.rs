@@ -0,0 +1,60 @@
+//! Reading and writing of the languages of tracks.
+//!
+//! The language of a track is stored in its media header (`mdhd`) as a packed ISO 639-2/T code.
+//! Since the size of the media header doesn't change, the language is overwritten in place
+//! without rewriting the file.
+
+use std::fs::OpenOptions;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use crate::{Fourcc, Language, StorageFile, atom};
+
+/// The language of a track, see [`track_languages`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackLanguage {
+    /// The id of the track.
+    pub track_id: u32,
+    /// The component subtype of the track's handler (`hdlr`), for example `soun` or `text`.
+    pub handler: Option<Fourcc>,
+    /// The language of the track's media. Tracks with an unspecified or invalid language are
+    /// [`Language::UNDETERMINED`].
+    pub language: Language,
+}
+
+/// Reads the languages of all tracks of the reader, in the order of the tracks.
+pub fn track_languages(reader: &mut (impl Read + Seek)) -> crate::Result<Vec<TrackLanguage>> {
+    atom::read_track_languages(reader)
+}
+
+/// Sets the language of the track of the file at the indicated path, see
+/// [`set_track_language_in`].
+pub fn set_track_language(
+    path: impl AsRef<Path>,
+    track_id: u32,
+    language: Language,
+) -> crate::Result<bool> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    set_track_language_in(&mut file, track_id, language)
+}
+
+/// Sets the language of the media header (`mdhd`) of the track with the id in place. Returns
+/// whether the track exists, the file is left untouched otherwise.
+///
+/// ```
+/// use mp4ameta::{Language, set_track_language_in, track_languages};
+///
+/// let mut file = std::io::Cursor::new(std::fs::read("files/sample.m4a").unwrap());
+/// let track_id = track_languages(&mut file).unwrap()[0].track_id;
+///
+/// let language = "de".parse::<Language>().unwrap();
+/// assert!(set_track_language_in(&mut file, track_id, language).unwrap());
+/// assert_eq!(track_languages(&mut file).unwrap()[0].language, language);
+/// ```
+pub fn set_track_language_in(
+    file: &mut impl StorageFile,
+    track_id: u32,
+    language: Language,
+) -> crate::Result<bool> {
+    atom::set_track_language(file, track_id, language)
+}
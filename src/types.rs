@@ -1,8 +1,11 @@
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
-use crate::ErrorKind;
+use crate::{Data, ErrorKind, Fourcc, ItemData, MetaItem, ident};
 
 /// The iTunes media type of a file. This is stored in the `stik` atom.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -116,6 +119,9 @@ impl fmt::Display for AdvisoryRating {
 /// The channel configuration of an MPEG-4 audio track.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ChannelConfig {
+    /// The channels aren't described by the configuration, but by other parts of the audio
+    /// specific config, for example a program config element or an object based audio scene.
+    Custom = 0,
     /// 1.0, channel: front-center.
     Mono = 1,
     /// 2.0, channels: front-left, front-right.
@@ -130,9 +136,18 @@ pub enum ChannelConfig {
     FiveOne = 6,
     /// 7.1, channels: front-center, front-left, front-right, side-left, side-right, back-left, back-right, LFE-channel.
     SevenOne = 7,
+    /// 6.1, channels: front-center, front-left, front-right, side-left, side-right, back-center, LFE-channel.
+    SixOne = 11,
+    /// 7.1 rear surround, channels: front-center, front-left, front-right, side-left, side-right, back-left, back-right, LFE-channel.
+    SevenOneRear = 12,
+    /// 22.2, 24 channels arranged in three layers.
+    TwentyTwoTwo = 13,
+    /// 7.1 top front, channels: front-center, front-left, front-right, side-left, side-right, LFE-channel, top-front-left, top-front-right.
+    SevenOneTop = 14,
 }
 
 impl ChannelConfig {
+    const CUSTOM: u8 = Self::Custom as u8;
     const MONO: u8 = Self::Mono as u8;
     const STEREO: u8 = Self::Stereo as u8;
     const THREE: u8 = Self::Three as u8;
@@ -140,10 +155,15 @@ impl ChannelConfig {
     const FIVE: u8 = Self::Five as u8;
     const FIVE_ONE: u8 = Self::FiveOne as u8;
     const SEVEN_ONE: u8 = Self::SevenOne as u8;
+    const SIX_ONE: u8 = Self::SixOne as u8;
+    const SEVEN_ONE_REAR: u8 = Self::SevenOneRear as u8;
+    const TWENTY_TWO_TWO: u8 = Self::TwentyTwoTwo as u8;
+    const SEVEN_ONE_TOP: u8 = Self::SevenOneTop as u8;
 
-    /// Returns the number of channels.
+    /// Returns the number of channels, or 0 for [`ChannelConfig::Custom`].
     pub const fn channel_count(&self) -> u8 {
         match self {
+            Self::Custom => 0,
             Self::Mono => 1,
             Self::Stereo => 2,
             Self::Three => 3,
@@ -151,6 +171,26 @@ impl ChannelConfig {
             Self::Five => 5,
             Self::FiveOne => 6,
             Self::SevenOne => 8,
+            Self::SixOne => 7,
+            Self::SevenOneRear => 8,
+            Self::TwentyTwoTwo => 24,
+            Self::SevenOneTop => 8,
+        }
+    }
+
+    /// Returns the usual configuration for the number of channels.
+    #[cfg(feature = "audio-info")]
+    pub(crate) const fn from_channel_count(count: u8) -> Option<Self> {
+        match count {
+            1 => Some(Self::Mono),
+            2 => Some(Self::Stereo),
+            3 => Some(Self::Three),
+            4 => Some(Self::Four),
+            5 => Some(Self::Five),
+            6 => Some(Self::FiveOne),
+            7 => Some(Self::SixOne),
+            8 => Some(Self::SevenOne),
+            _ => None,
         }
     }
 }
@@ -160,6 +200,7 @@ impl TryFrom<u8> for ChannelConfig {
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
+            Self::CUSTOM => Ok(Self::Custom),
             Self::MONO => Ok(Self::Mono),
             Self::STEREO => Ok(Self::Stereo),
             Self::THREE => Ok(Self::Three),
@@ -167,6 +208,10 @@ impl TryFrom<u8> for ChannelConfig {
             Self::FIVE => Ok(Self::Five),
             Self::FIVE_ONE => Ok(Self::FiveOne),
             Self::SEVEN_ONE => Ok(Self::SevenOne),
+            Self::SIX_ONE => Ok(Self::SixOne),
+            Self::SEVEN_ONE_REAR => Ok(Self::SevenOneRear),
+            Self::TWENTY_TWO_TWO => Ok(Self::TwentyTwoTwo),
+            Self::SEVEN_ONE_TOP => Ok(Self::SevenOneTop),
             _ => Err(Self::Error::new(
                 crate::ErrorKind::UnknownChannelConfig(value),
                 "Unknown channel config index",
@@ -178,6 +223,7 @@ impl TryFrom<u8> for ChannelConfig {
 impl fmt::Display for ChannelConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::Custom => write!(f, "Custom"),
             Self::Mono => write!(f, "Mono"),
             Self::Stereo => write!(f, "Stereo"),
             Self::Three => write!(f, "3.0"),
@@ -185,6 +231,10 @@ impl fmt::Display for ChannelConfig {
             Self::Five => write!(f, "5.0"),
             Self::FiveOne => write!(f, "5.1"),
             Self::SevenOne => write!(f, "7.1"),
+            Self::SixOne => write!(f, "6.1"),
+            Self::SevenOneRear => write!(f, "7.1 (rear surround)"),
+            Self::TwentyTwoTwo => write!(f, "22.2"),
+            Self::SevenOneTop => write!(f, "7.1 (top front)"),
         }
     }
 }
@@ -253,6 +303,27 @@ impl SampleRate {
             Self::Hz7350 => 7350,
         }
     }
+
+    /// Returns the sample rate corresponding to the rate in Hz, if any.
+    #[cfg(feature = "audio-info")]
+    pub(crate) const fn from_hz(hz: u32) -> Option<Self> {
+        match hz {
+            96000 => Some(Self::Hz96000),
+            88200 => Some(Self::Hz88200),
+            64000 => Some(Self::Hz64000),
+            48000 => Some(Self::Hz48000),
+            44100 => Some(Self::Hz44100),
+            32000 => Some(Self::Hz32000),
+            24000 => Some(Self::Hz24000),
+            22050 => Some(Self::Hz22050),
+            16000 => Some(Self::Hz16000),
+            12000 => Some(Self::Hz12000),
+            11025 => Some(Self::Hz11025),
+            8000 => Some(Self::Hz8000),
+            7350 => Some(Self::Hz7350),
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<u8> for SampleRate {
@@ -287,6 +358,37 @@ impl fmt::Display for SampleRate {
     }
 }
 
+/// The profile of an AAC audio track.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AacProfile {
+    /// AAC Main.
+    Main,
+    /// AAC Low Complexity.
+    Lc,
+    /// AAC Scalable Sample Rate.
+    Ssr,
+    /// AAC Long Term Prediction.
+    Ltp,
+    /// High Efficiency AAC, AAC LC with spectral band replication (SBR).
+    He,
+    /// High Efficiency AAC v2, AAC LC with spectral band replication (SBR) and parametric stereo
+    /// (PS).
+    HeV2,
+}
+
+impl fmt::Display for AacProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Main => write!(f, "AAC Main"),
+            Self::Lc => write!(f, "AAC LC"),
+            Self::Ssr => write!(f, "AAC SSR"),
+            Self::Ltp => write!(f, "AAC LTP"),
+            Self::He => write!(f, "HE-AAC"),
+            Self::HeV2 => write!(f, "HE-AACv2"),
+        }
+    }
+}
+
 /// Audio information of an mp4 track.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct AudioInfo {
@@ -296,10 +398,46 @@ pub struct AudioInfo {
     pub channel_config: Option<ChannelConfig>,
     /// The sample rate of the track.
     pub sample_rate: Option<SampleRate>,
+    /// The sample rate of the track in Hz. Unlike [`sample_rate`](Self::sample_rate) this is
+    /// also present for rates that don't correspond to a [`SampleRate`].
+    pub sample_rate_hz: Option<u32>,
+    /// The AAC profile of the track.
+    pub aac_profile: Option<AacProfile>,
+    /// Whether spectral band replication (SBR) is signaled.
+    pub sbr: bool,
+    /// Whether parametric stereo (PS) is signaled.
+    pub ps: bool,
+    /// The bit depth of the track, only present for lossless codecs.
+    pub bit_depth: Option<u8>,
     /// The maximum bitrate of the track.
     pub max_bitrate: Option<u32>,
     /// The average bitrate of the track.
     pub avg_bitrate: Option<u32>,
+    /// The timescale of the movie: the number of units that pass per second.
+    pub timescale: u32,
+    /// The duration of the movie in units of the [`timescale`](Self::timescale).
+    pub raw_duration: u64,
+    /// The timescale of the audio track's media.
+    pub media_timescale: Option<u32>,
+    /// The duration of the audio track's media in units of the
+    /// [`media_timescale`](Self::media_timescale).
+    pub media_raw_duration: Option<u64>,
+}
+
+/// Video information of an mp4 track.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VideoInfo {
+    /// The presentation width of the track in pixels. If the track header (`tkhd`) doesn't
+    /// specify one, the coded width of the sample description (`stsd`) is used.
+    pub width: u32,
+    /// The presentation height of the track in pixels. If the track header (`tkhd`) doesn't
+    /// specify one, the coded height of the sample description (`stsd`) is used.
+    pub height: u32,
+    /// The codec of the track, the identifier of the sample description (`stsd`) entry, for
+    /// example `avc1` or `hvc1`.
+    pub codec: Fourcc,
+    /// The number of frames of the track.
+    pub frame_count: u32,
 }
 
 /// Type alias for an image reference.
@@ -334,6 +472,68 @@ impl<T> Img<T> {
     pub const fn png(data: T) -> Self {
         Self::new(ImgFmt::Png, data)
     }
+
+    pub const fn gif(data: T) -> Self {
+        Self::new(ImgFmt::Gif, data)
+    }
+}
+
+impl ImgBuf {
+    /// Creates an image, detecting the format from the magic number at the start of the data.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::{Img, ImgFmt};
+    ///
+    /// let img = Img::from_bytes(b"\x89PNG\r\n\x1a\n...".to_vec()).unwrap();
+    /// assert_eq!(img.fmt, ImgFmt::Png);
+    /// assert!(Img::from_bytes(b"text".to_vec()).is_err());
+    /// ```
+    pub fn from_bytes(data: Vec<u8>) -> crate::Result<ImgBuf> {
+        match ImgFmt::detect(&data) {
+            Some(fmt) => Ok(Img::new(fmt, data)),
+            None => Err(crate::Error::new(
+                ErrorKind::UnsupportedImgFmt,
+                "Unknown image format, expected a bmp, gif, jpeg, png, heic or webp image",
+            )),
+        }
+    }
+
+    /// Converts the image into a [`SharedImg`], which can be set as the artwork of many tags
+    /// without copying the image data.
+    pub fn shared(self) -> SharedImg {
+        let item = MetaItem::new(ident::ARTWORK.into(), ItemData::One(self.into()));
+        SharedImg(Arc::new(item))
+    }
+}
+
+/// An artwork image that is shared between tags, see [`Userdata::set_artwork_shared`].
+///
+/// Cloning is cheap and doesn't copy the image data. The data is only copied once the artwork of
+/// a tag is modified.
+///
+/// [`Userdata::set_artwork_shared`]: crate::Userdata::set_artwork_shared
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedImg(pub(crate) Arc<MetaItem>);
+
+impl SharedImg {
+    /// Returns a reference to the image.
+    pub fn img(&self) -> ImgRef<'_> {
+        match self.0.data.first() {
+            Some(Data::Jpeg(d)) => Img::jpeg(d),
+            Some(Data::Png(d)) => Img::png(d),
+            Some(Data::Bmp(d)) => Img::bmp(d),
+            Some(Data::Gif(d)) => Img::gif(d),
+            Some(Data::Image { fmt, data, .. }) => Img::new(*fmt, data),
+            _ => unreachable!("shared images are only created from images"),
+        }
+    }
+}
+
+impl From<ImgBuf> for SharedImg {
+    fn from(img: ImgBuf) -> Self {
+        img.shared()
+    }
 }
 
 /// The image format used to store images inside the userdata of an MPEG-4 file.
@@ -342,9 +542,29 @@ pub enum ImgFmt {
     Bmp,
     Jpeg,
     Png,
+    Gif,
+    /// A HEIC image. There is no well-known data type code for HEIC images, they are stored as
+    /// [`Data::Image`] instead.
+    Heic,
+    /// A WebP image. There is no well-known data type code for WebP images, they are stored as
+    /// [`Data::Image`] instead.
+    WebP,
 }
 
 impl ImgFmt {
+    /// Detects the image format from the magic number at the start of the data.
+    pub fn detect(data: &[u8]) -> Option<ImgFmt> {
+        match data {
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => Some(Self::Png),
+            [0xff, 0xd8, 0xff, ..] => Some(Self::Jpeg),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(Self::Gif),
+            [b'B', b'M', ..] => Some(Self::Bmp),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(Self::WebP),
+            _ if is_heic(data) => Some(Self::Heic),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if the img fmt is [`Bmp`].
     ///
     /// [`Bmp`]: ImgFmt::Bmp
@@ -368,6 +588,43 @@ impl ImgFmt {
     pub fn is_png(&self) -> bool {
         matches!(self, Self::Png)
     }
+
+    /// Returns `true` if the img fmt is [`Gif`].
+    ///
+    /// [`Gif`]: ImgFmt::Gif
+    #[must_use]
+    pub fn is_gif(&self) -> bool {
+        matches!(self, Self::Gif)
+    }
+
+    /// Returns `true` if the img fmt is [`Heic`].
+    ///
+    /// [`Heic`]: ImgFmt::Heic
+    #[must_use]
+    pub fn is_heic(&self) -> bool {
+        matches!(self, Self::Heic)
+    }
+
+    /// Returns `true` if the img fmt is [`WebP`].
+    ///
+    /// [`WebP`]: ImgFmt::WebP
+    #[must_use]
+    pub fn is_webp(&self) -> bool {
+        matches!(self, Self::WebP)
+    }
+}
+
+/// Whether the data starts with a file type box (`ftyp`) of a HEIF image brand.
+fn is_heic(data: &[u8]) -> bool {
+    const BRANDS: [&[u8; 4]; 8] =
+        [b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1"];
+
+    match data {
+        [_, _, _, _, b'f', b't', b'y', b'p', b0, b1, b2, b3, ..] => {
+            BRANDS.iter().any(|b| **b == [*b0, *b1, *b2, *b3])
+        }
+        _ => false,
+    }
 }
 
 /// A chapter.
@@ -375,7 +632,9 @@ impl ImgFmt {
 /// Note that chapter titles have a relatively small maximum size.
 /// For chapter lists this limit is 255 ([`u8::MAX`]);
 /// For chapter tracks this limit is 65535 ([`u16::MAX`]);
-/// If this limit is exceeded the title is truncated.
+/// If this limit is exceeded the title is truncated, unless
+/// [`WriteConfig::truncate_chapter_titles`](crate::WriteConfig::truncate_chapter_titles) is
+/// disabled.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Chapter {
     /// The start of the chapter.
@@ -385,7 +644,633 @@ pub struct Chapter {
 }
 
 impl Chapter {
+    /// The maximum length of a chapter title in bytes, that can be stored in a chapter track.
+    pub const MAX_TITLE_LEN: usize = u16::MAX as usize;
+
     pub fn new(start: Duration, title: impl Into<String>) -> Self {
         Self { start, title: title.into() }
     }
+
+    /// Creates a new chapter starting at the number of units of the timescale, for example video
+    /// frames. Converting the start back using [`Chapter::ticks`] yields exactly the same number of
+    /// units, for all timescales below 500,000,000.
+    ///
+    /// The chapter list is written in [`WriteConfig::chpl_timescale`], and a new chapter track in
+    /// [`WriteConfig::chapter_track_timescale`], so when using the same timescales the chapters
+    /// are written without rounding errors.
+    ///
+    /// # Panics
+    /// If the timescale is zero.
+    ///
+    /// # Example
+    /// ```
+    /// use mp4ameta::Chapter;
+    ///
+    /// // the 1001st frame of a 29.97 fps video
+    /// let chapter = Chapter::from_ticks(1001 * 1001, 30000, "title");
+    /// assert_eq!(chapter.start.as_nanos(), 33_400_033_333);
+    /// assert_eq!(chapter.ticks(30000), 1001 * 1001);
+    /// ```
+    ///
+    /// [`WriteConfig::chpl_timescale`]: crate::WriteConfig::chpl_timescale
+    /// [`WriteConfig::chapter_track_timescale`]: crate::WriteConfig::chapter_track_timescale
+    pub fn from_ticks(ticks: u64, timescale: u32, title: impl Into<String>) -> Self {
+        Self::new(crate::atom::scale_duration(timescale, ticks), title)
+    }
+
+    /// Returns the start of the chapter in units of the timescale, rounded to the nearest unit.
+    ///
+    /// The timescale chapters were read with is reported by [`Tag::chpl_timescale`] for the
+    /// chapter list and [`ChapterTrack::timescale`] for chapter tracks.
+    ///
+    /// # Panics
+    /// If the timescale is zero.
+    ///
+    /// [`Tag::chpl_timescale`]: crate::Tag::chpl_timescale
+    pub fn ticks(&self, timescale: u32) -> u64 {
+        assert!(timescale != 0, "the timescale must not be zero");
+        crate::atom::unscale_duration(timescale, self.start)
+    }
+
+    /// Creates a new chapter, or returns an error of the kind
+    /// [`ErrorKind::ChapterTitleTooLong`](crate::ErrorKind::ChapterTitleTooLong) if the title
+    /// exceeds [`Chapter::MAX_TITLE_LEN`] bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use mp4ameta::Chapter;
+    ///
+    /// assert!(Chapter::try_new(Duration::ZERO, "title").is_ok());
+    /// assert!(Chapter::try_new(Duration::ZERO, "a".repeat(70000)).is_err());
+    /// ```
+    pub fn try_new(start: Duration, title: impl Into<String>) -> crate::Result<Self> {
+        let chapter = Self::new(start, title);
+        chapter.validate()?;
+        Ok(chapter)
+    }
+
+    /// Returns an error of the kind
+    /// [`ErrorKind::ChapterTitleTooLong`](crate::ErrorKind::ChapterTitleTooLong) if the title
+    /// exceeds [`Chapter::MAX_TITLE_LEN`] bytes.
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.title.len() > Self::MAX_TITLE_LEN {
+            return Err(crate::Error::new(
+                crate::ErrorKind::ChapterTitleTooLong,
+                format!(
+                    "Chapter title is {} bytes long, which exceeds the maximum of {} bytes",
+                    self.title.len(),
+                    Self::MAX_TITLE_LEN,
+                ),
+            ));
+        }
+        Ok(())
+    }
 }
+
+/// A chapter track of a file. See [`Tag::chapter_tracks`](crate::Tag::chapter_tracks).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChapterTrack {
+    /// The track id.
+    pub track_id: u32,
+    /// The language of the track.
+    pub language: Language,
+    /// The timescale of the track, which the chapter starts are stored in, see
+    /// [`Chapter::ticks`].
+    pub timescale: u32,
+    /// The chapters of the track.
+    pub chapters: Vec<Chapter>,
+}
+
+/// The chapter representations a file contains.
+/// See [`Tag::chapter_sources`](crate::Tag::chapter_sources).
+///
+/// Only representations that are enabled in the [`ReadConfig`](crate::ReadConfig) are detected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChapterSources {
+    /// Whether the file contains a chapter list (`chpl`).
+    pub has_chpl: bool,
+    /// Whether the file contains a chapter track.
+    pub has_track: bool,
+    /// The track id of the chapter track.
+    pub track_id: Option<u32>,
+}
+
+/// The direction in which chapters are synchronized between the chapter list and chapter track.
+/// See [`Userdata::sync_chapters`](crate::Userdata::sync_chapters).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChapterSync {
+    /// Overwrite the chapter track with the chapter list.
+    ListToTrack,
+    /// Overwrite the chapter list with the chapter track.
+    TrackToList,
+    /// If only one of the chapter list and chapter track is populated, copy it to the other one.
+    /// Otherwise nothing is changed.
+    Populated,
+}
+
+/// The text encoding used for chapter titles of a chapter track and for written metadata strings.
+/// See [`WriteConfig::chapter_text_encoding`](crate::WriteConfig::chapter_text_encoding) and
+/// [`WriteConfig::string_encoding`](crate::WriteConfig::string_encoding).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// UTF-8 without a byte order mark.
+    #[default]
+    Utf8,
+    /// Big endian UTF-16 with a byte order mark.
+    Utf16Be,
+}
+
+/// A Unicode normalization form.
+/// See [`Userdata::normalize_strings`](crate::Userdata::normalize_strings).
+#[cfg(feature = "normalization")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnicodeForm {
+    /// Canonical decomposition followed by canonical composition. Commonly used on Linux and
+    /// Windows.
+    Nfc,
+    /// Canonical decomposition. Commonly written by Apple tools.
+    Nfd,
+    /// Compatibility decomposition followed by canonical composition.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+#[cfg(feature = "normalization")]
+impl UnicodeForm {
+    /// Returns the string in this normalization form.
+    pub fn normalize(self, s: &str) -> String {
+        use unicode_normalization::UnicodeNormalization;
+
+        match self {
+            Self::Nfc => s.nfc().collect(),
+            Self::Nfd => s.nfd().collect(),
+            Self::Nfkc => s.nfkc().collect(),
+            Self::Nfkd => s.nfkd().collect(),
+        }
+    }
+
+    /// Returns whether the string is already in this normalization form.
+    pub fn is_normalized(self, s: &str) -> bool {
+        use unicode_normalization::{is_nfc, is_nfd, is_nfkc, is_nfkd};
+
+        match self {
+            Self::Nfc => is_nfc(s),
+            Self::Nfd => is_nfd(s),
+            Self::Nfkc => is_nfkc(s),
+            Self::Nfkd => is_nfkd(s),
+        }
+    }
+}
+
+/// The flags of a track header (`tkhd`) atom.
+/// See [`WriteConfig::chapter_track_flags`](crate::WriteConfig::chapter_track_flags).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrackFlags {
+    /// Whether the track is enabled.
+    pub enabled: bool,
+    /// Whether the track is used in the presentation.
+    pub in_movie: bool,
+    /// Whether the track is used when previewing the presentation.
+    pub in_preview: bool,
+}
+
+impl TrackFlags {
+    /// The flags of a chapter track, which is part of the presentation but disabled, since it's
+    /// only referenced by other tracks. This matches what Apple tools and ffmpeg write.
+    pub const CHAPTER: Self = Self { enabled: false, in_movie: true, in_preview: false };
+
+    #[cfg(feature = "chapters")]
+    const ENABLED: u8 = 0x01;
+    #[cfg(feature = "chapters")]
+    const IN_MOVIE: u8 = 0x02;
+    #[cfg(feature = "chapters")]
+    const IN_PREVIEW: u8 = 0x04;
+
+    /// Returns the flags as they are stored in the track header.
+    #[cfg(feature = "chapters")]
+    pub(crate) fn to_bytes(self) -> [u8; 3] {
+        let mut flags = 0;
+        if self.enabled {
+            flags |= Self::ENABLED;
+        }
+        if self.in_movie {
+            flags |= Self::IN_MOVIE;
+        }
+        if self.in_preview {
+            flags |= Self::IN_PREVIEW;
+        }
+        [0, 0, flags]
+    }
+}
+
+/// The sample description of the text samples of a chapter track (`stsd/text`), which specifies
+/// how chapter titles are displayed.
+/// See [`WriteConfig::chapter_text_description`](crate::WriteConfig::chapter_text_description).
+///
+/// # Example
+/// ```
+/// use mp4ameta::TextSampleDescription;
+///
+/// let description = TextSampleDescription::DEFAULT
+///     .with_justification(1, -1)
+///     .with_background_color([0, 0, 0, 0xff])
+///     .with_font(1, 18, "Helvetica");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextSampleDescription {
+    /// The display flags, for example `0x20` to scroll text in.
+    pub display_flags: u32,
+    /// The horizontal justification: `0` left, `1` centered and `-1` right.
+    pub horizontal_justification: i8,
+    /// The vertical justification: `0` top, `1` centered and `-1` bottom.
+    pub vertical_justification: i8,
+    /// The background color as RGBA.
+    pub background_color: [u8; 4],
+    /// The default text color as RGBA.
+    pub text_color: [u8; 4],
+    /// The id of the default font.
+    pub font_id: u16,
+    /// The style flags of the default font: `0x01` bold, `0x02` italic and `0x04` underline.
+    pub font_style: u8,
+    /// The size of the default font, `0` lets the player decide.
+    pub font_size: u8,
+    /// The name of the default font. Names longer than 255 bytes are truncated.
+    pub font_name: Cow<'static, str>,
+}
+
+impl Default for TextSampleDescription {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TextSampleDescription {
+    /// The description written by default, with an unnamed font and transparent colors.
+    pub const DEFAULT: Self = Self {
+        display_flags: 1,
+        horizontal_justification: 0,
+        vertical_justification: 0,
+        background_color: [0; 4],
+        text_color: [0; 4],
+        font_id: 1,
+        font_style: 0,
+        font_size: 0,
+        font_name: Cow::Borrowed(""),
+    };
+
+    pub const fn with_display_flags(mut self, display_flags: u32) -> Self {
+        self.display_flags = display_flags;
+        self
+    }
+
+    pub const fn with_justification(mut self, horizontal: i8, vertical: i8) -> Self {
+        self.horizontal_justification = horizontal;
+        self.vertical_justification = vertical;
+        self
+    }
+
+    pub const fn with_background_color(mut self, rgba: [u8; 4]) -> Self {
+        self.background_color = rgba;
+        self
+    }
+
+    pub const fn with_text_color(mut self, rgba: [u8; 4]) -> Self {
+        self.text_color = rgba;
+        self
+    }
+
+    pub fn with_font(mut self, id: u16, size: u8, name: impl Into<Cow<'static, str>>) -> Self {
+        self.font_id = id;
+        self.font_size = size;
+        self.font_name = name.into();
+        self
+    }
+
+    /// Returns the content of the text sample entry.
+    #[cfg(feature = "chapters")]
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut name = &self.font_name.as_bytes()[..self.font_name.len().min(u8::MAX as usize)];
+        while std::str::from_utf8(name).is_err() {
+            name = &name[..name.len() - 1];
+        }
+
+        let mut data = Vec::with_capacity(43 + name.len());
+        data.extend(self.display_flags.to_be_bytes());
+        data.push(self.horizontal_justification as u8);
+        data.push(self.vertical_justification as u8);
+        data.extend(self.background_color);
+        // box record: top, left, bottom, right
+        data.extend([0; 8]);
+        // style record: start char, end char
+        data.extend([0; 4]);
+        data.extend(self.font_id.to_be_bytes());
+        data.push(self.font_style);
+        data.push(self.font_size);
+        data.extend(self.text_color);
+        // font table box
+        data.extend((13 + name.len() as u32).to_be_bytes());
+        data.extend(b"ftab");
+        data.extend(1u16.to_be_bytes()); // entry count
+        data.extend(self.font_id.to_be_bytes());
+        data.push(name.len() as u8);
+        data.extend(name);
+        data
+    }
+}
+
+/// A geographic location, stored as an ISO 6709 string inside the `©xyz` atom, or the QuickTime
+/// metadata key `com.apple.quicktime.location.ISO6709`.
+///
+/// When parsing, coordinates in decimal degrees (`±DD.DD±DDD.DD`), degrees and minutes
+/// (`±DDMM.MM±DDDMM.MM`) and degrees, minutes and seconds (`±DDMMSS.SS±DDDMMSS.SS`) are
+/// supported. An optional coordinate reference system (`CRS...`) is ignored. When formatting, the
+/// coordinates are written in decimal degrees with 4 fractional digits, like Apple devices do.
+///
+/// # Example
+/// ```
+/// use mp4ameta::Location;
+///
+/// let location: Location = "+48.8577+002.2950+035.000/".parse().unwrap();
+/// assert_eq!(location, Location::new(48.8577, 2.295).with_altitude(35.0));
+/// assert_eq!(location.to_string(), "+48.8577+002.2950+35.000/");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Location {
+    /// The latitude in degrees, positive values are north of the equator.
+    pub latitude: f64,
+    /// The longitude in degrees, positive values are east of the prime meridian.
+    pub longitude: f64,
+    /// The altitude in meters.
+    pub altitude: Option<f64>,
+}
+
+impl Location {
+    pub const fn new(latitude: f64, longitude: f64) -> Self {
+        Self { latitude, longitude, altitude: None }
+    }
+
+    pub const fn with_altitude(mut self, altitude: f64) -> Self {
+        self.altitude = Some(altitude);
+        self
+    }
+}
+
+impl FromStr for Location {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_iso6709(s).ok_or_else(|| {
+            crate::Error::new(
+                ErrorKind::InvalidLocation,
+                format!("Invalid ISO 6709 location {s:?}"),
+            )
+        })
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+08.4}{:+09.4}", self.latitude, self.longitude)?;
+        if let Some(altitude) = self.altitude {
+            write!(f, "{altitude:+.3}")?;
+        }
+        f.write_str("/")
+    }
+}
+
+fn parse_iso6709(s: &str) -> Option<Location> {
+    let s = s.trim();
+    let s = s.strip_suffix('/').unwrap_or(s);
+
+    let (latitude, rest) = split_iso6709_component(s)?;
+    let (longitude, rest) = split_iso6709_component(rest)?;
+    let latitude = parse_iso6709_angle(latitude, 2).filter(|a| a.abs() <= 90.0)?;
+    let longitude = parse_iso6709_angle(longitude, 3).filter(|a| a.abs() <= 180.0)?;
+
+    let (altitude, rest) = match rest.starts_with(['+', '-']) {
+        true => {
+            let (altitude, rest) = split_iso6709_component(rest)?;
+            (Some(altitude.parse().ok()?), rest)
+        }
+        false => (None, rest),
+    };
+    if !rest.is_empty() && !rest.starts_with("CRS") {
+        return None;
+    }
+
+    Some(Location { latitude, longitude, altitude })
+}
+
+/// Splits off a signed component, which is terminated by the next sign, a coordinate reference
+/// system or the end of the string.
+fn split_iso6709_component(s: &str) -> Option<(&str, &str)> {
+    if !s.starts_with(['+', '-']) {
+        return None;
+    }
+    let end = s[1..].find(|c: char| !c.is_ascii_digit() && c != '.').map_or(s.len(), |i| i + 1);
+    let component = &s[..end];
+    if component.len() < 2 || component.matches('.').count() > 1 {
+        return None;
+    }
+    Some((component, &s[end..]))
+}
+
+/// Parses an angle of the form `±D.D`, `±DMM.M` or `±DMMSS.S` where the degrees have
+/// `degree_digits` digits.
+fn parse_iso6709_angle(s: &str, degree_digits: usize) -> Option<f64> {
+    let (sign, digits) = s.split_at(1);
+    let int_len = digits.find('.').unwrap_or(digits.len());
+
+    let angle = match int_len.checked_sub(degree_digits)? {
+        0 => digits.parse().ok()?,
+        2 => {
+            let degrees: f64 = digits[..degree_digits].parse().ok()?;
+            let minutes: f64 = digits[degree_digits..].parse().ok()?;
+            if minutes >= 60.0 {
+                return None;
+            }
+            degrees + minutes / 60.0
+        }
+        4 => {
+            let degrees: f64 = digits[..degree_digits].parse().ok()?;
+            let minutes: f64 = digits[degree_digits..degree_digits + 2].parse().ok()?;
+            let seconds: f64 = digits[degree_digits + 2..].parse().ok()?;
+            if minutes >= 60.0 || seconds >= 60.0 {
+                return None;
+            }
+            degrees + minutes / 60.0 + seconds / 3600.0
+        }
+        _ => return None,
+    };
+
+    match sign {
+        "-" => Some(-angle),
+        _ => Some(angle),
+    }
+}
+
+/// An ISO 639-2/T language code, for example `eng` or `deu`. This is how languages of tracks are
+/// stored in the media header (`mdhd`), see [`track_languages`](crate::track_languages), and how
+/// the language of the metadata is stored, see
+/// [`Userdata::language`](crate::Userdata::language).
+///
+/// When parsing, ISO 639-1 codes (`de`) and ISO 639-2/B codes (`ger`) are converted to their
+/// ISO 639-2/T equivalent, case is ignored. Other three letter codes, such as ISO 639-3 codes,
+/// are kept as they are.
+///
+/// # Example
+/// ```
+/// use mp4ameta::Language;
+///
+/// let language: Language = "de".parse().unwrap();
+/// assert_eq!(language, Language::new(*b"deu").unwrap());
+/// assert_eq!(language, "ger".parse().unwrap());
+/// assert_eq!(language.iso639_1(), Some("de"));
+/// assert_eq!(language.to_string(), "deu");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Language([u8; 3]);
+
+impl Default for Language {
+    fn default() -> Self {
+        Self::UNDETERMINED
+    }
+}
+
+impl Language {
+    /// The undetermined language (`und`).
+    pub const UNDETERMINED: Self = Self(*b"und");
+
+    /// Returns the language of the ISO 639-2/T code, or `None` if the code isn't made up of
+    /// lowercase ascii letters.
+    pub const fn new(code: [u8; 3]) -> Option<Self> {
+        match code {
+            [a, b, c]
+                if a.is_ascii_lowercase() && b.is_ascii_lowercase() && c.is_ascii_lowercase() =>
+            {
+                Some(Self(code))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the language of the ISO 639-1 code, for example `"en"`, ignoring case.
+    pub fn from_iso639_1(code: &str) -> Option<Self> {
+        let code = code.to_ascii_lowercase();
+        let (_, t) = ISO_639_1.iter().find(|(c, _)| *c == code)?;
+        Some(Self(*t))
+    }
+
+    /// Returns the ISO 639-2/T code.
+    pub const fn code(&self) -> [u8; 3] {
+        self.0
+    }
+
+    /// Returns the ISO 639-2/T code as a string.
+    pub fn as_str(&self) -> &str {
+        // the code is always made up of ascii letters
+        std::str::from_utf8(&self.0).unwrap_or("und")
+    }
+
+    /// Returns the ISO 639-1 code, or `None` if the language has none.
+    pub fn iso639_1(&self) -> Option<&'static str> {
+        ISO_639_1.iter().find(|(_, t)| *t == self.0).map(|(c, _)| *c)
+    }
+
+    /// Returns the ISO 639-2/B code, which differs from the ISO 639-2/T code for some languages,
+    /// for example `ger` instead of `deu`.
+    pub fn iso639_2b(&self) -> &str {
+        match ISO_639_2B.iter().find(|(_, t)| *t == self.0) {
+            Some((b, _)) => std::str::from_utf8(b).unwrap_or("und"),
+            None => self.as_str(),
+        }
+    }
+
+    /// Returns true if the language is undetermined (`und`).
+    pub fn is_undetermined(&self) -> bool {
+        *self == Self::UNDETERMINED
+    }
+}
+
+impl FromStr for Language {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_ascii_lowercase();
+        let language = match *code.as_bytes() {
+            [_, _] => Self::from_iso639_1(&code),
+            [a, b, c] => {
+                let code = [a, b, c];
+                match ISO_639_2B.iter().find(|(b, _)| *b == code) {
+                    Some((_, t)) => Some(Self(*t)),
+                    None => Self::new(code),
+                }
+            }
+            _ => None,
+        };
+        language.ok_or_else(|| {
+            crate::Error::new(ErrorKind::InvalidLanguage, format!("Invalid ISO 639 language {s:?}"))
+        })
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// ISO 639-1 codes and their ISO 639-2/T equivalent.
+#[rustfmt::skip]
+const ISO_639_1: [(&str, [u8; 3]); 183] = [
+    ("aa", *b"aar"), ("ab", *b"abk"), ("ae", *b"ave"), ("af", *b"afr"), ("ak", *b"aka"),
+    ("am", *b"amh"), ("an", *b"arg"), ("ar", *b"ara"), ("as", *b"asm"), ("av", *b"ava"),
+    ("ay", *b"aym"), ("az", *b"aze"), ("ba", *b"bak"), ("be", *b"bel"), ("bg", *b"bul"),
+    ("bi", *b"bis"), ("bm", *b"bam"), ("bn", *b"ben"), ("bo", *b"bod"), ("br", *b"bre"),
+    ("bs", *b"bos"), ("ca", *b"cat"), ("ce", *b"che"), ("ch", *b"cha"), ("co", *b"cos"),
+    ("cr", *b"cre"), ("cs", *b"ces"), ("cu", *b"chu"), ("cv", *b"chv"), ("cy", *b"cym"),
+    ("da", *b"dan"), ("de", *b"deu"), ("dv", *b"div"), ("dz", *b"dzo"), ("ee", *b"ewe"),
+    ("el", *b"ell"), ("en", *b"eng"), ("eo", *b"epo"), ("es", *b"spa"), ("et", *b"est"),
+    ("eu", *b"eus"), ("fa", *b"fas"), ("ff", *b"ful"), ("fi", *b"fin"), ("fj", *b"fij"),
+    ("fo", *b"fao"), ("fr", *b"fra"), ("fy", *b"fry"), ("ga", *b"gle"), ("gd", *b"gla"),
+    ("gl", *b"glg"), ("gn", *b"grn"), ("gu", *b"guj"), ("gv", *b"glv"), ("ha", *b"hau"),
+    ("he", *b"heb"), ("hi", *b"hin"), ("ho", *b"hmo"), ("hr", *b"hrv"), ("ht", *b"hat"),
+    ("hu", *b"hun"), ("hy", *b"hye"), ("hz", *b"her"), ("ia", *b"ina"), ("id", *b"ind"),
+    ("ie", *b"ile"), ("ig", *b"ibo"), ("ii", *b"iii"), ("ik", *b"ipk"), ("io", *b"ido"),
+    ("is", *b"isl"), ("it", *b"ita"), ("iu", *b"iku"), ("ja", *b"jpn"), ("jv", *b"jav"),
+    ("ka", *b"kat"), ("kg", *b"kon"), ("ki", *b"kik"), ("kj", *b"kua"), ("kk", *b"kaz"),
+    ("kl", *b"kal"), ("km", *b"khm"), ("kn", *b"kan"), ("ko", *b"kor"), ("kr", *b"kau"),
+    ("ks", *b"kas"), ("ku", *b"kur"), ("kv", *b"kom"), ("kw", *b"cor"), ("ky", *b"kir"),
+    ("la", *b"lat"), ("lb", *b"ltz"), ("lg", *b"lug"), ("li", *b"lim"), ("ln", *b"lin"),
+    ("lo", *b"lao"), ("lt", *b"lit"), ("lu", *b"lub"), ("lv", *b"lav"), ("mg", *b"mlg"),
+    ("mh", *b"mah"), ("mi", *b"mri"), ("mk", *b"mkd"), ("ml", *b"mal"), ("mn", *b"mon"),
+    ("mr", *b"mar"), ("ms", *b"msa"), ("mt", *b"mlt"), ("my", *b"mya"), ("na", *b"nau"),
+    ("nb", *b"nob"), ("nd", *b"nde"), ("ne", *b"nep"), ("ng", *b"ndo"), ("nl", *b"nld"),
+    ("nn", *b"nno"), ("no", *b"nor"), ("nr", *b"nbl"), ("nv", *b"nav"), ("ny", *b"nya"),
+    ("oc", *b"oci"), ("oj", *b"oji"), ("om", *b"orm"), ("or", *b"ori"), ("os", *b"oss"),
+    ("pa", *b"pan"), ("pi", *b"pli"), ("pl", *b"pol"), ("ps", *b"pus"), ("pt", *b"por"),
+    ("qu", *b"que"), ("rm", *b"roh"), ("rn", *b"run"), ("ro", *b"ron"), ("ru", *b"rus"),
+    ("rw", *b"kin"), ("sa", *b"san"), ("sc", *b"srd"), ("sd", *b"snd"), ("se", *b"sme"),
+    ("sg", *b"sag"), ("si", *b"sin"), ("sk", *b"slk"), ("sl", *b"slv"), ("sm", *b"smo"),
+    ("sn", *b"sna"), ("so", *b"som"), ("sq", *b"sqi"), ("sr", *b"srp"), ("ss", *b"ssw"),
+    ("st", *b"sot"), ("su", *b"sun"), ("sv", *b"swe"), ("sw", *b"swa"), ("ta", *b"tam"),
+    ("te", *b"tel"), ("tg", *b"tgk"), ("th", *b"tha"), ("ti", *b"tir"), ("tk", *b"tuk"),
+    ("tl", *b"tgl"), ("tn", *b"tsn"), ("to", *b"ton"), ("tr", *b"tur"), ("ts", *b"tso"),
+    ("tt", *b"tat"), ("tw", *b"twi"), ("ty", *b"tah"), ("ug", *b"uig"), ("uk", *b"ukr"),
+    ("ur", *b"urd"), ("uz", *b"uzb"), ("ve", *b"ven"), ("vi", *b"vie"), ("vo", *b"vol"),
+    ("wa", *b"wln"), ("wo", *b"wol"), ("xh", *b"xho"), ("yi", *b"yid"), ("yo", *b"yor"),
+    ("za", *b"zha"), ("zh", *b"zho"), ("zu", *b"zul"),
+];
+
+/// ISO 639-2/B codes that differ from their ISO 639-2/T equivalent.
+#[rustfmt::skip]
+const ISO_639_2B: [([u8; 3], [u8; 3]); 20] = [
+    (*b"alb", *b"sqi"), (*b"arm", *b"hye"), (*b"baq", *b"eus"), (*b"bur", *b"mya"),
+    (*b"chi", *b"zho"), (*b"cze", *b"ces"), (*b"dut", *b"nld"), (*b"fre", *b"fra"),
+    (*b"geo", *b"kat"), (*b"ger", *b"deu"), (*b"gre", *b"ell"), (*b"ice", *b"isl"),
+    (*b"mac", *b"mkd"), (*b"mao", *b"mri"), (*b"may", *b"msa"), (*b"per", *b"fas"),
+    (*b"rum", *b"ron"), (*b"slo", *b"slk"), (*b"tib", *b"bod"), (*b"wel", *b"cym"),
+];
@@ -0,0 +1,64 @@
+use std::ffi::{CStr, CString};
+
+use mp4ameta::capi::*;
+
+#[test]
+fn capi() {
+    let target_file = "target/capi.m4a";
+    std::fs::copy("files/sample.m4a", target_file).unwrap();
+    let path = CString::new(target_file).unwrap();
+    let title = b"\xa9nam";
+    let artwork = [0x89, b'P', b'N', b'G'];
+
+    unsafe {
+        let tag = mp4ameta_tag_read(path.as_ptr());
+        assert!(!tag.is_null());
+
+        let value = CString::new("title").unwrap();
+        assert_eq!(mp4ameta_tag_set_string(tag, title.as_ptr(), value.as_ptr()), 0);
+        assert_eq!(
+            mp4ameta_tag_set_artwork(tag, artwork.as_ptr(), artwork.len(), MP4AMETA_IMG_PNG),
+            0
+        );
+        assert_eq!(mp4ameta_tag_chapter_clear(tag, MP4AMETA_CHAPTER_TRACK), 0);
+        let chapter = CString::new("chapter").unwrap();
+        assert_eq!(mp4ameta_tag_chapter_add(tag, MP4AMETA_CHAPTER_TRACK, 0, chapter.as_ptr()), 0);
+        assert_eq!(mp4ameta_tag_chapter_add(tag, 7, 0, chapter.as_ptr()), -1);
+        assert!(!mp4ameta_last_error().is_null());
+        // the error is cleared by the next successful call
+        assert_eq!(mp4ameta_tag_chapter_count(tag, MP4AMETA_CHAPTER_TRACK), 1);
+        assert!(mp4ameta_last_error().is_null());
+
+        assert_eq!(mp4ameta_tag_write(tag, path.as_ptr()), 0);
+        mp4ameta_tag_free(tag);
+
+        let tag = mp4ameta_tag_read(path.as_ptr());
+        assert!(!tag.is_null());
+
+        let s = mp4ameta_tag_get_string(tag, title.as_ptr());
+        assert_eq!(CStr::from_ptr(s).to_str(), Ok("title"));
+        mp4ameta_string_free(s);
+
+        let (mut len, mut fmt) = (0, -1);
+        let data = mp4ameta_tag_get_artwork(tag, &mut len, &mut fmt);
+        assert_eq!(std::slice::from_raw_parts(data, len), artwork);
+        assert_eq!(fmt, MP4AMETA_IMG_PNG);
+
+        assert_eq!(mp4ameta_tag_chapter_count(tag, MP4AMETA_CHAPTER_TRACK), 1);
+        let mut start = u64::MAX;
+        let s = mp4ameta_tag_chapter_get(tag, MP4AMETA_CHAPTER_TRACK, 0, &mut start);
+        assert_eq!(CStr::from_ptr(s).to_str(), Ok("chapter"));
+        assert_eq!(start, 0);
+        mp4ameta_string_free(s);
+
+        assert_eq!(mp4ameta_tag_remove(tag, title.as_ptr()), 0);
+        assert!(mp4ameta_tag_get_string(tag, title.as_ptr()).is_null());
+        mp4ameta_tag_free(tag);
+    }
+
+    let missing = CString::new("target/does-not-exist.m4a").unwrap();
+    unsafe {
+        assert!(mp4ameta_tag_read(missing.as_ptr()).is_null());
+        assert!(!mp4ameta_last_error().is_null());
+    }
+}
@@ -1,4 +1,8 @@
-use mp4ameta::{Data, Img, STANDARD_GENRES, Tag, ident};
+use std::time::Duration;
+
+use mp4ameta::{
+    Chapter, Data, ErrorKind, Fourcc, Img, ImgFmt, Location, STANDARD_GENRES, Tag, ident,
+};
 
 #[test]
 fn multiple_value_handling() {
@@ -46,6 +50,116 @@ fn multiple_value_handling() {
     assert_eq!(tag.artist(), None);
 }
 
+#[test]
+fn plural_setter_handling() {
+    let mut tag = Tag::default();
+
+    tag.set_artists(["1", "2"]);
+    assert_eq!(tag.artists().collect::<Vec<_>>(), ["1", "2"]);
+
+    tag.add_artists(vec![String::from("3")]);
+    assert_eq!(tag.artists().collect::<Vec<_>>(), ["1", "2", "3"]);
+
+    tag.set_composers(["composer"]);
+    tag.set_genres(["genre 1", "genre 2"]);
+    assert_eq!(tag.genres().collect::<Vec<_>>(), ["genre 1", "genre 2"]);
+
+    assert_eq!(tag.take_artists().collect::<Vec<_>>(), ["1", "2", "3"]);
+    assert_eq!(tag.artist(), None);
+    assert_eq!(tag.take_composers().collect::<Vec<_>>(), ["composer"]);
+    assert_eq!(tag.composers().next(), None);
+}
+
+#[test]
+fn known_idents() {
+    use mp4ameta::{DataIdent, ValueKind, ident::KNOWN_IDENTS};
+
+    let find = |name| KNOWN_IDENTS.iter().find(|i| i.name == name).unwrap();
+
+    let artist = find("artist");
+    assert_eq!(artist.ident, DataIdent::Fourcc(ident::ARTIST));
+    assert_eq!(artist.kind, ValueKind::String);
+    assert!(artist.multiple);
+
+    let isrc = find("isrc");
+    assert_eq!(isrc.ident, DataIdent::from(ident::ISRC));
+    assert!(!isrc.multiple);
+
+    assert_eq!(find("compilation").kind, ValueKind::Flag);
+    assert_eq!(find("bpm").kind, ValueKind::U16);
+    assert_eq!(find("track_number").kind, ValueKind::NumberPair);
+    assert_eq!(find("artwork").kind, ValueKind::Image);
+
+    // sorted and unique
+    assert!(KNOWN_IDENTS.windows(2).all(|w| w[0].name < w[1].name));
+}
+
+#[test]
+fn fourcc_macro() {
+    use mp4ameta::fourcc;
+
+    assert_eq!(fourcc!("©nam"), ident::TITLE);
+    assert_eq!(fourcc!("----"), ident::FREEFORM);
+    assert_eq!(fourcc!("\u{ff}abc"), Fourcc(*b"\xffabc"));
+    assert_eq!(fourcc!("©ART").to_string(), "©ART");
+}
+
+#[test]
+fn ident_parsing() {
+    use mp4ameta::DataIdent;
+
+    let parse = |s: &str| s.parse::<DataIdent>();
+
+    assert_eq!(parse("aART").unwrap(), DataIdent::Fourcc(ident::ALBUM_ARTIST));
+    assert_eq!(parse("©nam").unwrap(), DataIdent::Fourcc(ident::TITLE));
+    assert_eq!(parse("(c)nam").unwrap(), DataIdent::Fourcc(ident::TITLE));
+    assert_eq!(parse("----:com.apple.iTunes:ISRC").unwrap(), DataIdent::from(ident::ISRC));
+    assert_eq!(
+        parse("----:mean:name:with:colons").unwrap(),
+        DataIdent::freeform("mean", "name:with:colons")
+    );
+    assert_eq!(
+        parse("mdta:com.apple.quicktime.make").unwrap(),
+        DataIdent::mdta("com.apple.quicktime.make")
+    );
+
+    for s in ["", "abc", "abcde", "(c)na", "€abc", "----:mean", "----::name", "mdta:"] {
+        assert!(matches!(parse(s).unwrap_err().kind, ErrorKind::InvalidIdent), "{s:?}");
+    }
+
+    // round trip through display
+    for info in mp4ameta::ident::KNOWN_IDENTS {
+        assert_eq!(parse(&info.ident.to_string()).unwrap(), info.ident);
+    }
+    assert_eq!(parse(&ident::QUICKTIME_LOCATION.to_string()).unwrap(), ident::QUICKTIME_LOCATION);
+}
+
+#[test]
+fn data_conversion() {
+    assert_eq!(Data::from("string"), Data::Utf8("string".into()));
+    assert_eq!(Data::from(String::from("string")), Data::Utf8("string".into()));
+    assert_eq!(Data::be_u8(1), Data::BeSigned(vec![1]));
+    assert_eq!(Data::be_u16(0x0102), Data::BeSigned(vec![1, 2]));
+    assert_eq!(Data::be_u32(0x0102), Data::BeSigned(vec![0, 0, 1, 2]));
+    assert_eq!(Data::be_u64(1), Data::BeSigned(vec![0, 0, 0, 0, 0, 0, 0, 1]));
+
+    assert_eq!(u32::try_from(&Data::be_u16(300)).unwrap(), 300);
+    assert_eq!(u16::try_from(&Data::Reserved(vec![0, 0, 1, 2])).unwrap(), 0x0102);
+    assert_eq!(u64::try_from(&Data::be_u64(u64::MAX)).unwrap(), u64::MAX);
+    let err = u8::try_from(&Data::be_u16(300)).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::DataTypeMismatch));
+    let err = u32::try_from(&Data::from("1")).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::DataTypeMismatch));
+    assert!(u32::try_from(&Data::BeSigned(Vec::new())).is_err());
+
+    let utf16 = Data::Utf16("utf16".into());
+    assert_eq!(<&str>::try_from(&utf16).unwrap(), "utf16");
+    assert_eq!(String::try_from(&utf16).unwrap(), "utf16");
+    assert_eq!(String::try_from(utf16).unwrap(), "utf16");
+    let err = String::try_from(Data::be_u8(1)).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::DataTypeMismatch));
+}
+
 #[test]
 fn genre_handling() {
     let mut tag = Tag::default();
@@ -171,7 +285,7 @@ fn work_movement_handling() {
     assert_eq!(tag.movement(), None);
     assert_eq!(tag.movement_count(), None);
     assert_eq!(tag.movement_index(), None);
-    assert_eq!(tag.show_movement(), false);
+    assert!(!tag.show_movement());
     assert_eq!(tag.work(), None);
 
     tag.set_movement(movement);
@@ -183,10 +297,305 @@ fn work_movement_handling() {
     assert_eq!(tag.movement(), Some(movement));
     assert_eq!(tag.movement_count(), Some(count));
     assert_eq!(tag.movement_index(), Some(index));
-    assert_eq!(tag.show_movement(), true);
+    assert!(tag.show_movement());
     assert_eq!(tag.work(), Some(work));
 }
 
+#[test]
+fn classical_work_handling() {
+    use mp4ameta::ClassicalWork;
+
+    let mut tag = Tag::default();
+    assert_eq!(tag.classical_work(), None);
+
+    let work = ClassicalWork::new("work").movement("movement", 2, 4);
+    tag.set_classical_work(work.clone());
+    assert_eq!(tag.classical_work(), Some(work));
+    assert!(tag.show_movement());
+
+    // the movement index, count and flag aren't written without a movement
+    let work = ClassicalWork {
+        work: Some("work".into()),
+        movement: None,
+        movement_index: Some(1),
+        movement_count: Some(3),
+        show_movement: true,
+    };
+    tag.set_classical_work(work);
+    assert_eq!(tag.classical_work(), Some(ClassicalWork::new("work")));
+    assert_eq!(tag.movement_index(), None);
+    assert!(!tag.show_movement());
+
+    tag.set_movement_index(1);
+    tag.remove_classical_work();
+    assert_eq!(tag.classical_work(), None);
+    assert!(tag.is_empty());
+}
+
+#[test]
+fn tv_episode_info_handling() {
+    use mp4ameta::TvEpisodeInfo;
+
+    let mut tag = Tag::default();
+    assert_eq!(tag.tv_episode_info(), None);
+
+    let info = TvEpisodeInfo {
+        show: Some("show".into()),
+        network: Some("network".into()),
+        episode_name: Some("episode".into()),
+        season: Some(3),
+        episode: Some(70000),
+        episode_id: Some("S03E70000".into()),
+    };
+    tag.set_tv_episode_info(info.clone());
+    assert_eq!(tag.tv_episode_info(), Some(info));
+    assert_eq!(tag.title(), Some("episode"));
+    assert_eq!(tag.tv_season(), Some(3));
+
+    // integers of other widths
+    tag.set_data(ident::TV_SEASON, Data::BeSigned(vec![4]));
+    tag.set_data(ident::TV_EPISODE, Data::BeSigned(vec![0, 0, 0, 0, 0, 0, 1, 2]));
+    let info = tag.tv_episode_info().unwrap();
+    assert_eq!((info.season, info.episode), (Some(4), Some(258)));
+    assert_eq!(tag.tv_season(), None);
+
+    // the title isn't specific to TV episodes, so it's never removed
+    tag.set_tv_episode_info(TvEpisodeInfo { season: Some(1), ..Default::default() });
+    assert_eq!(tag.title(), Some("episode"));
+    assert_eq!(tag.tv_show_name(), None);
+    assert_eq!(tag.data_of(&ident::TV_SEASON).next(), Some(&Data::BeSigned(vec![0, 0, 0, 1])));
+
+    tag.remove_tv_episode_info();
+    assert_eq!(tag.title(), Some("episode"));
+    assert_eq!(tag.tv_episode_info(), None);
+    tag.remove_title();
+    assert!(tag.is_empty());
+}
+
+#[test]
+fn audiobook_info_handling() {
+    use mp4ameta::AudiobookInfo;
+
+    let mut tag = Tag::default();
+    assert_eq!(tag.audiobook_info(), None);
+
+    let info = AudiobookInfo {
+        author: Some("author".into()),
+        narrator: Some("narrator".into()),
+        series: Some("series".into()),
+        series_part: Some("2.5".into()),
+        description: Some("description".into()),
+        asin: Some("B000000000".into()),
+    };
+    tag.set_audiobook_info(info.clone());
+    assert_eq!(tag.audiobook_info(), Some(info));
+    assert_eq!(tag.artist(), Some("author"));
+    assert_eq!(tag.strings_of(&ident::NARRATOR).next(), Some("narrator"));
+    assert_eq!(tag.strings_of(&ident::SERIES_PART).next(), Some("2.5"));
+    assert_eq!(tag.strings_of(&ident::ASIN).next(), Some("B000000000"));
+
+    // the author and description are general items, which aren't removed
+    tag.remove_audiobook_info();
+    assert_eq!(tag.audiobook_info(), None);
+    assert_eq!(tag.artist(), Some("author"));
+    assert_eq!(tag.description(), Some("description"));
+    tag.remove_artists();
+    tag.remove_descriptions();
+    assert!(tag.is_empty());
+
+    // the freeform narrator is read if there's no narrator, and replaced when written
+    tag.set_data(ident::FREEFORM_NARRATOR, Data::Utf8("freeform narrator".into()));
+    let info = tag.audiobook_info().unwrap();
+    assert_eq!(info.narrator.as_deref(), Some("freeform narrator"));
+    tag.set_audiobook_info(info);
+    assert_eq!(tag.narrator(), Some("freeform narrator"));
+    assert_eq!(tag.strings_of(&ident::FREEFORM_NARRATOR).next(), None);
+}
+
+#[test]
+fn credits_handling() {
+    let mut tag = Tag::default();
+    tag.set_narrator("narrator");
+    tag.add_director("director 1");
+    tag.add_director("director 2");
+    tag.set_producer("producer");
+    tag.set_long_description("long description");
+
+    assert_eq!(tag.narrator(), Some("narrator"));
+    assert_eq!(tag.directors().collect::<Vec<_>>(), ["director 1", "director 2"]);
+    assert_eq!(tag.producer(), Some("producer"));
+    assert_eq!(tag.long_description(), Some("long description"));
+    assert_eq!(tag.strings_of(&ident::DIRECTOR).count(), 2);
+    assert_eq!(tag.strings_of(&ident::LONG_DESCRIPTION).next(), Some("long description"));
+
+    let display = tag.format(mp4ameta::FormatStyle::KEY_VALUE).to_string();
+    assert!(display.starts_with("narrator=narrator\ndirector=director 1\ndirector=director 2\n"));
+
+    tag.remove_directors();
+    tag.remove_long_description();
+    assert_eq!(tag.director(), None);
+    assert_eq!(tag.long_description(), None);
+}
+
+#[test]
+fn description_handling() {
+    let mut tag = Tag::default();
+    tag.set_description("description");
+    tag.set_long_description("long description");
+    tag.set_store_description("store description");
+
+    assert_eq!(tag.description(), Some("description"));
+    assert_eq!(tag.long_description(), Some("long description"));
+    assert_eq!(tag.store_description(), Some("store description"));
+    assert_eq!(
+        tag.data_of(&ident::STORE_DESCRIPTION).next(),
+        Some(&Data::Utf8("store description".into()))
+    );
+
+    let display = tag.to_string();
+    assert!(display.contains(
+        "description: description\nlong description: long description\n\
+         store description: store description\n"
+    ));
+
+    tag.remove_store_description();
+    assert_eq!(tag.store_description(), None);
+    assert_eq!(tag.long_description(), Some("long description"));
+}
+
+#[test]
+fn language_handling() {
+    use mp4ameta::Language;
+
+    let german = Language::new(*b"deu").unwrap();
+    for s in ["deu", "ger", "de", "DE", " Deu "] {
+        assert_eq!(s.parse::<Language>().unwrap(), german);
+    }
+    assert_eq!(german.iso639_1(), Some("de"));
+    assert_eq!(german.iso639_2b(), "ger");
+    assert_eq!("en".parse::<Language>().unwrap().iso639_2b(), "eng");
+    assert_eq!(Language::from_iso639_1("zh"), Language::new(*b"zho"));
+    assert_eq!(Language::from_iso639_1("xx"), None);
+    // ISO 639-3 codes without an ISO 639-1 equivalent are kept
+    let yue = "yue".parse::<Language>().unwrap();
+    assert_eq!((yue.as_str(), yue.iso639_1()), ("yue", None));
+    assert_eq!(Language::new(*b"DEU"), None);
+    assert_eq!(Language::default(), Language::UNDETERMINED);
+    for s in ["", "d", "deut", "d3u", "xx"] {
+        let err = s.parse::<Language>().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidLanguage), "{s:?}");
+    }
+
+    let mut tag = Tag::default();
+    assert_eq!(tag.language(), None);
+    tag.set_language(german);
+    assert_eq!(tag.strings_of(&ident::LANGUAGE).next(), Some("deu"));
+    assert_eq!(tag.language(), Some(german));
+    assert!(tag.to_string().contains("language: deu"));
+
+    // codes written by other taggers are converted, invalid ones are ignored
+    tag.set_data(ident::LANGUAGE, Data::Utf8("ger".into()));
+    assert_eq!(tag.language(), Some(german));
+    tag.set_data(ident::LANGUAGE, Data::Utf8("German".into()));
+    assert_eq!(tag.language(), None);
+    tag.remove_language();
+    assert!(tag.is_empty());
+}
+
+#[test]
+fn loudness_handling() {
+    use mp4ameta::{Loudness, ReplayGain};
+
+    let mut tag = Tag::default();
+    assert_eq!(tag.loudness(), None);
+
+    let track = ReplayGain { gain: -6.5, peak: Some(0.98) };
+    let album = ReplayGain { gain: -7.25, peak: None };
+    tag.set_loudness(Loudness { track: Some(track), album: Some(album) });
+    assert_eq!(tag.replay_gain_track_gain(), Some("-6.50 dB"));
+    assert_eq!(tag.replay_gain_track_peak(), Some("0.980000"));
+    assert_eq!(tag.replay_gain_album_gain(), Some("-7.25 dB"));
+    assert_eq!(tag.replay_gain_album_peak(), None);
+    assert_eq!(tag.r128_album_gain(), Some("-3136"));
+    assert!(tag.itunes_normalization().is_some());
+    assert_eq!(tag.loudness(), Some(Loudness { track: Some(track), album: Some(album) }));
+
+    // only R128 items present
+    tag.remove_replay_gain_track_gain();
+    tag.remove_replay_gain_track_peak();
+    let loudness = tag.loudness().unwrap();
+    assert_eq!(loudness.track, Some(ReplayGain { gain: -6.5, peak: None }));
+
+    tag.set_loudness(Loudness { track: Some(track), album: None });
+    assert_eq!(tag.replay_gain_album_gain(), None);
+    assert_eq!(tag.r128_album_gain(), None);
+
+    tag.remove_loudness();
+    assert_eq!(tag.loudness(), None);
+    assert_eq!(tag.itunes_normalization(), None);
+    assert_eq!(tag.r128_track_gain(), None);
+}
+
+#[test]
+fn compat_handling() {
+    use mp4ameta::{CompatIssue, TargetPlayer};
+
+    let png = |width: u32, height: u32| {
+        let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        data.extend(width.to_be_bytes());
+        data.extend(height.to_be_bytes());
+        Img::png(data)
+    };
+    // start of image, app0 segment, baseline start of frame
+    let jpeg = |width: u16, height: u16| {
+        let mut data = b"\xff\xd8\xff\xe0\0\x04\0\0\xff\xc0\0\x11\x08".to_vec();
+        data.extend(height.to_be_bytes());
+        data.extend(width.to_be_bytes());
+        Img::jpeg(data)
+    };
+
+    let mut tag = Tag::default();
+    tag.set_artwork(png(3000, 3000));
+    assert_eq!(tag.check_compat(TargetPlayer::AppleBooks), []);
+
+    tag.set_artwork(jpeg(4000, 2000));
+    tag.add_artwork(png(3000, 3001));
+    tag.add_artwork(Img::bmp(b"BM".to_vec()));
+    assert_eq!(
+        tag.check_compat(TargetPlayer::AppleBooks),
+        [
+            CompatIssue::ArtworkTooLarge {
+                index: 0,
+                width: 4000,
+                height: 2000,
+                max_size: 3000
+            },
+            CompatIssue::ArtworkTooLarge {
+                index: 1,
+                width: 3000,
+                height: 3001,
+                max_size: 3000
+            },
+            CompatIssue::UnsupportedArtworkFmt { index: 2, fmt: ImgFmt::Bmp },
+            CompatIssue::MultipleArtworks,
+        ]
+    );
+    assert_eq!(tag.check_compat(TargetPlayer::Vlc), []);
+    tag.remove_artworks();
+
+    tag.chapter_list_mut().push(Chapter::new(Duration::ZERO, "a".repeat(256)));
+    assert_eq!(tag.check_compat(TargetPlayer::AppleBooks), [CompatIssue::ChapterListOnly]);
+    assert_eq!(
+        tag.check_compat(TargetPlayer::Audiobookshelf),
+        [CompatIssue::ChapterTitleTooLong { index: 0, len: 256 }]
+    );
+
+    *tag.chapter_list_mut() = vec![Chapter::new(Duration::ZERO, "first")];
+    *tag.chapter_track_mut() = vec![Chapter::new(Duration::ZERO, "second")];
+    assert_eq!(tag.check_compat(TargetPlayer::AppleBooks), []);
+    assert_eq!(tag.check_compat(TargetPlayer::Vlc), [CompatIssue::ChaptersDiffer]);
+}
+
 #[test]
 fn tag_destructuring() {
     let mut tag = Tag::default();
@@ -242,3 +651,534 @@ fn tag_destructuring() {
     assert_eq!(tag.year(), None);
     assert_eq!(tag.artwork(), None);
 }
+
+#[test]
+fn retitle_chapters_handling() {
+    let mut tag = Tag::default();
+    let chapters = (0..12).map(|i| Chapter::new(Duration::from_secs(i * 60), format!("tts {i}")));
+    tag.chapter_list_mut().extend(chapters.clone().take(3));
+    tag.chapter_track_mut().extend(chapters);
+
+    tag.retitle_chapters("Chapter {n:03} of {n}");
+    assert_eq!(tag.chapter_list()[0].title, "Chapter 001 of 1");
+    assert_eq!(tag.chapter_list()[2].title, "Chapter 003 of 3");
+    assert_eq!(tag.chapter_track()[11].title, "Chapter 012 of 12");
+    assert_eq!(tag.chapter_track()[11].start, Duration::from_secs(11 * 60));
+
+    tag.retitle_chapters("{n:x} {m} {n");
+    assert_eq!(tag.chapter_list()[0].title, "{n:x} {m} {n");
+
+    tag.retitle_chapters_with(|i| format!("Part {}", (b'A' + i as u8) as char));
+    assert_eq!(tag.chapter_list()[1].title, "Part B");
+    assert_eq!(tag.chapter_track()[3].title, "Part D");
+}
+
+#[test]
+fn snapshot_handling() {
+    let mut tag = Tag::default();
+    tag.set_title("title");
+    tag.set_artwork(Img::png(vec![0; 1024]));
+    let artwork_ptr = tag.artwork().unwrap().data.as_ptr();
+
+    let snapshot = tag.snapshot();
+    tag.set_title("new title");
+    tag.images_mut_of(&ident::ARTWORK).next().unwrap().data[0] = 1;
+    assert_eq!(tag.artwork().unwrap().data[0], 1);
+    assert_ne!(tag.artwork().unwrap().data.as_ptr(), artwork_ptr);
+
+    let redo = tag.snapshot();
+    tag.restore(snapshot.clone());
+    assert_eq!(tag.title(), Some("title"));
+    assert_eq!(tag.artwork().unwrap().data[0], 0);
+    assert_eq!(tag.artwork().unwrap().data.as_ptr(), artwork_ptr);
+
+    tag.restore(redo);
+    assert_eq!(tag.title(), Some("new title"));
+    assert_eq!(tag.artwork().unwrap().data[0], 1);
+}
+
+#[test]
+fn snapshot_mut_iterators_share_unmodified_items() {
+    let mut tag = Tag::default();
+    tag.set_title("title");
+    tag.set_compilation();
+    tag.set_artwork(Img::png(vec![0; 1024]));
+    let artwork_ptr = tag.artwork().unwrap().data.as_ptr();
+
+    let snapshot = tag.snapshot();
+    for (_, s) in tag.strings_mut() {
+        s.push('1');
+    }
+    tag.bytes_mut().for_each(drop);
+    tag.canonicalize();
+    assert_eq!(tag.title(), Some("title1"));
+    assert_eq!(tag.artwork().unwrap().data.as_ptr(), artwork_ptr);
+
+    tag.restore(snapshot);
+    assert_eq!(tag.title(), Some("title"));
+    assert_eq!(tag.artwork().unwrap().data.as_ptr(), artwork_ptr);
+}
+
+#[test]
+fn shared_artwork_handling() {
+    let cover = Img::jpeg(vec![0; 1024]).shared();
+    let mut tag1 = Tag::default();
+    let mut tag2 = Tag::default();
+    tag1.set_title("title");
+    tag1.add_artwork(Img::png(vec![1]));
+    tag1.add_artwork(Img::png(vec![2]));
+    tag1.set_artist("artist");
+
+    tag1.set_artwork_shared(&cover);
+    tag2.set_artwork_shared(&cover);
+    assert_eq!(tag1.artworks().collect::<Vec<_>>(), [cover.img()]);
+    assert_eq!(tag1.data().nth(1).unwrap().0, &ident::ARTWORK.into());
+    assert_eq!(tag1.artwork().unwrap().data.as_ptr(), cover.img().data.as_ptr());
+    assert_eq!(tag2.artwork().unwrap().data.as_ptr(), cover.img().data.as_ptr());
+
+    tag1.images_mut_of(&ident::ARTWORK).next().unwrap().data[0] = 1;
+    assert_eq!(tag1.artwork().unwrap().data[0], 1);
+    assert_eq!(tag2.artwork().unwrap().data[0], 0);
+    assert_eq!(cover.img().data[0], 0);
+}
+
+#[test]
+fn artwork_format_detection() {
+    let detect = |data: &[u8]| Img::from_bytes(data.to_vec()).map(|i| i.fmt);
+    assert_eq!(detect(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR").unwrap(), ImgFmt::Png);
+    assert_eq!(detect(b"\xff\xd8\xff\xe0\0\x10JFIF").unwrap(), ImgFmt::Jpeg);
+    assert_eq!(detect(b"GIF89a\x01\0\x01\0").unwrap(), ImgFmt::Gif);
+    assert_eq!(detect(b"GIF87a\x01\0\x01\0").unwrap(), ImgFmt::Gif);
+    assert_eq!(detect(b"BM\x3a\0\0\0").unwrap(), ImgFmt::Bmp);
+
+    assert_eq!(detect(b"\0\0\0\x18ftypheic\0\0\0\0mif1heic").unwrap(), ImgFmt::Heic);
+    assert_eq!(detect(b"RIFF\x24\0\0\0WEBPVP8 ").unwrap(), ImgFmt::WebP);
+    for data in [&b""[..], b"\x89PN", b"GIF90a", b"\0\0\0\x18ftypM4A "] {
+        let err = detect(data).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::UnsupportedImgFmt));
+    }
+
+    let mut tag = Tag::default();
+    tag.set_artwork(Img::from_bytes(b"GIF89a".to_vec()).unwrap());
+    assert_eq!(tag.data_of(&ident::ARTWORK).next(), Some(&Data::Gif(b"GIF89a".to_vec())));
+    assert_eq!(tag.artwork(), Some(Img::gif(&b"GIF89a"[..])));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn artwork_thumbnail() {
+    use mp4ameta::image::{GenericImageView, ImageFormat, RgbImage};
+
+    let encode = |width, height| {
+        let mut data = std::io::Cursor::new(Vec::new());
+        RgbImage::new(width, height).write_to(&mut data, ImageFormat::Png).unwrap();
+        Img::png(data.into_inner())
+    };
+
+    let mut tag = Tag::default();
+    assert!(tag.artwork_thumbnail(64).unwrap().is_none());
+
+    tag.set_artwork(encode(400, 200));
+    assert_eq!(tag.artwork_thumbnail(100).unwrap().unwrap().dimensions(), (100, 50));
+    assert_eq!(tag.artwork_thumbnail(800).unwrap().unwrap().dimensions(), (400, 200));
+
+    // the cached decode is invalidated once the artwork changes
+    tag.set_artwork(encode(30, 60));
+    assert_eq!(tag.artwork_thumbnail(20).unwrap().unwrap().dimensions(), (10, 20));
+    assert_eq!(tag.clone(), tag);
+
+    tag.set_artwork(Img::png(vec![0; 16]));
+    let err = tag.artwork_thumbnail(20).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::ImgDecoding(_)));
+}
+
+#[test]
+fn semantic_eq_handling() {
+    let mut a = Tag::default();
+    a.set_title("title");
+    a.add_artist("1");
+    a.add_artist("2");
+    a.add_genre("rock");
+    a.add_genre("pop");
+    a.set_artwork(Img::png(vec![1]));
+    a.add_artwork(Img::jpeg(vec![2]));
+
+    let mut b = Tag::default();
+    b.add_artwork(Img::png(vec![1]));
+    b.add_artwork(Img::jpeg(vec![2]));
+    b.add_genre("pop");
+    b.add_genre("rock");
+    b.add_artist("1");
+    b.add_artist("2");
+    b.set_title("title");
+    assert_ne!(a.userdata, b.userdata);
+    assert!(a.semantic_eq(&b));
+    assert!(b.semantic_eq(&a));
+
+    // the order of artists and artworks matters
+    let mut d = b.clone();
+    d.set_artists(["2".to_owned(), "1".to_owned()]);
+    assert!(!a.semantic_eq(&d));
+    let mut d = b.clone();
+    d.set_artwork(Img::jpeg(vec![2]));
+    d.add_artwork(Img::png(vec![1]));
+    assert!(!a.semantic_eq(&d));
+
+    // duplicate values are counted
+    let mut d = b.clone();
+    d.add_genre("pop");
+    assert!(!a.semantic_eq(&d));
+
+    let mut d = b.clone();
+    d.chapter_list_mut().push(Chapter::new(Duration::ZERO, "chapter"));
+    assert!(!a.semantic_eq(&d));
+}
+
+#[test]
+fn display_handling() {
+    let mut tag = Tag::default();
+    tag.set_title("title");
+    tag.set_owner("owner");
+    tag.set_purchase_date("2020-01-01 00:00:00");
+    tag.set_podcast();
+    tag.set_podcast_url("https://example.com/feed");
+    tag.set_podcast_episode_global_unique_id("episode");
+    tag.set_cddb_track_number("3");
+
+    let string = tag.to_string();
+    let lines = string.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "title: title",
+            "owner: owner",
+            "purchase date: 2020-01-01 00:00:00",
+            "podcast",
+            "podcast url: https://example.com/feed",
+            "podcast episode global unique id: episode",
+            "duration: 0ns",
+            "cddb track number: 3",
+            "filetype: ",
+        ]
+    );
+}
+
+#[test]
+fn format_style_handling() {
+    use mp4ameta::FormatStyle;
+
+    let mut tag = Tag::default();
+    tag.set_title("title");
+    tag.set_lyrics("line 1\nline 2\\");
+    tag.add_genre("rock");
+    tag.add_genre("pop");
+    tag.set_compilation();
+
+    let verbose = tag.format(FormatStyle::VERBOSE.with_raw_idents()).to_string();
+    assert!(verbose.starts_with("title (©nam): title\ngenres (©gen):\n    rock\n    pop\n"));
+
+    assert_eq!(
+        tag.format(FormatStyle::COMPACT).to_string(),
+        "title: title | genres: rock, pop | duration: 0ns | compilation | lyrics: line 1\nline 2\\ \
+         | filetype: \n",
+    );
+    assert_eq!(
+        tag.format(FormatStyle::KEY_VALUE).to_string(),
+        "title=title\ngenre=rock\ngenre=pop\nduration=0ns\ncompilation=true\n\
+         lyrics=line 1\\nline 2\\\\\nfiletype=\n",
+    );
+}
+
+#[test]
+fn json_handling() {
+    use mp4ameta::{DataIdent, FreeformIdent, Userdata};
+
+    let mut tag = Tag::default();
+    tag.set_title("title \"quoted\"\n\u{1}");
+    tag.add_artist("artist");
+    tag.add_artist("🎵");
+    tag.set_track(3, 12);
+    tag.set_bpm(120);
+    tag.set_data(FreeformIdent::new_static("com.apple.iTunes", "ISRC"), Data::Utf16("isrc".into()));
+    tag.set_data(DataIdent::mdta("com.apple.quicktime.make"), Data::Utf8("make".into()));
+    tag.set_data(Fourcc(*b"test"), Data::Unknown { code: 99, data: vec![0, 1, 2, 3, 255] });
+    for len in 0..5 {
+        tag.add_artwork(Img::png((0..len).collect::<Vec<u8>>()));
+    }
+    tag.chapter_list_mut().push(Chapter::new(Duration::from_nanos(1_500_000_123), "first"));
+    tag.chapter_track_mut().push(Chapter::new(Duration::ZERO, "track chapter"));
+
+    let json = tag.userdata.to_json_string().unwrap();
+    assert!(json.starts_with("{\n  \"version\": 1,\n  \"items\": [\n    {\"ident\": \"©nam\", "));
+    assert!(json.contains("\"value\": \"AAECAw==\"}"));
+    assert!(json.contains("{\"start_ns\": 1500000123, \"title\": \"first\"}"));
+    assert_eq!(Userdata::from_json_str(&json).unwrap(), tag.userdata);
+
+    let json = r#"{
+        "items": [
+            {"ident": "\u00a9ART", "unknown_field": null, "data": [
+                {"type": "utf8", "value": "\ud83c\udfb5 \/"},
+                {"type": "reserved", "value": "AAE"}
+            ]}
+        ],
+        "version": 1
+    }"#;
+    let userdata = Userdata::from_json_str(json).unwrap();
+    assert_eq!(userdata.artist(), Some("🎵 /"));
+    assert_eq!(userdata.bytes_of(&ident::ARTIST).next(), Some(&[0, 1][..]));
+    assert!(userdata.chapter_list().is_empty());
+
+    for json in [
+        "",
+        "{}",
+        r#"{"version": 2}"#,
+        r#"{"version": 1} x"#,
+        r#"{"version": 1, "items": [{"ident": "toolong", "data": []}]}"#,
+        r#"{"version": 1, "items": [{"ident": "ab€c", "data": []}]}"#,
+        r#"{"version": 1, "items": [{"data": []}]}"#,
+        r#"{"version": 1, "items": [{"ident": "test", "data": [{"type": "png", "value": "*"}]}]}"#,
+        r#"{"version": 1, "items": [{"ident": "test", "data": [{"type": "unknown", "value": ""}]}]}"#,
+        r#"{"version": 1, "chapter_list": [{"start_ns": 1.5, "title": ""}]}"#,
+        r#"{"version": 1, "chapter_list": [{"title": "\ud800"}]}"#,
+    ] {
+        let err = Userdata::from_json_str(json).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidJson), "{json}: {err:?}");
+    }
+}
+
+#[test]
+fn template_handling() {
+    use mp4ameta::FreeformIdent;
+    use mp4ameta::template::{Context, TagTemplate};
+
+    let mut tag = Tag::default();
+    tag.set_artist("artist");
+    tag.set_comment("comment");
+    tag.set_track(7, 12);
+
+    let ctx = Context::from_userdata(&tag).with_path("/music/album/07 song.m4a").with("n", "1");
+    assert_eq!(ctx.get("track"), Some("7"));
+    assert_eq!(ctx.get("total_tracks"), Some("12"));
+    assert_eq!(ctx.get("disc"), None);
+    assert_eq!(ctx.get("dirname"), Some("album"));
+    assert_eq!(ctx.render("{{{track:03}/{total_tracks:01}}} {n:04}").unwrap(), "{007/12} 0001");
+
+    let template = TagTemplate::new()
+        .set(ident::TITLE, "{filename}")
+        .set(ident::ALBUM, "{dirname} ({extension})")
+        .set(FreeformIdent::new_static("com.apple.iTunes", "SOURCE"), "{artist} - {n}")
+        .set(ident::COMMENT, "");
+    tag.apply_template(&template, &ctx).unwrap();
+    assert_eq!(tag.title(), Some("07 song"));
+    assert_eq!(tag.album(), Some("album (m4a)"));
+    assert_eq!(
+        tag.strings_of(&FreeformIdent::new_static("com.apple.iTunes", "SOURCE")).next(),
+        Some("artist - 1"),
+    );
+    assert_eq!(tag.comment(), None);
+
+    let before = tag.clone();
+    for t in ["{missing}", "{track", "track}", "{track:2}", "{track:0x}"] {
+        let template = TagTemplate::new().set(ident::TITLE, "changed").set(ident::ALBUM, t);
+        let err = tag.apply_template(&template, &ctx).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidTemplate), "{t}: {err:?}");
+    }
+    assert_eq!(tag, before);
+}
+
+#[test]
+fn render_path_handling() {
+    use std::path::PathBuf;
+
+    let mut tag = Tag::default();
+    tag.set_artist("AC/DC");
+    tag.set_album("Who? What: <Live>...");
+    tag.set_title("..");
+    tag.set_track_number(4);
+
+    let path = tag.render_path("{artist} - {album}/{track:02} {title}.m4a").unwrap();
+    let expected: PathBuf = ["AC_DC - Who_ What_ _Live_", "04 ...m4a"].iter().collect();
+    assert_eq!(path, expected);
+
+    let path = tag.render_path("/{title}/../{title}").unwrap();
+    let expected: PathBuf = ["_", "_", "_", "_"].iter().collect();
+    assert_eq!(path, expected);
+
+    let err = tag.render_path("{composer}/{title}").unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::InvalidTemplate));
+}
+
+#[cfg(feature = "normalization")]
+#[test]
+fn normalize_strings_handling() {
+    use mp4ameta::UnicodeForm;
+
+    const NFC: &str = "Beyonc\u{e9}";
+    const NFD: &str = "Beyonce\u{301}";
+
+    let mut tag = Tag::default();
+    tag.set_artist(NFD);
+    tag.set_title("plain");
+    tag.set_data(ident::ALBUM, Data::Utf16(NFD.to_owned()));
+    tag.set_bpm(120);
+    tag.chapter_list_mut().push(Chapter::new(Duration::ZERO, NFD));
+
+    let mut nfc = tag.clone();
+    nfc.normalize_strings(UnicodeForm::Nfc);
+    assert_eq!(nfc.artist(), Some(NFC));
+    assert_eq!(nfc.data_of(&ident::ALBUM).next(), Some(&Data::Utf16(NFC.to_owned())));
+    assert_eq!(nfc.title(), Some("plain"));
+    assert_eq!(nfc.bpm(), Some(120));
+    assert_eq!(nfc.chapter_list()[0].title, NFC);
+
+    let mut nfd = nfc.clone();
+    nfd.normalize_strings(UnicodeForm::Nfd);
+    assert_eq!(nfd, tag);
+}
+
+#[test]
+fn length_limits_handling() {
+    use mp4ameta::{FreeformIdent, LengthLimits, LengthViolation};
+
+    let isrc = FreeformIdent::new_static("com.apple.iTunes", "ISRC");
+    let mut tag = Tag::default();
+    tag.set_title("t".repeat(255));
+    tag.add_artist("a");
+    tag.add_artist("ä".repeat(256));
+    tag.set_description("d".repeat(300));
+    tag.set_lyrics("l".repeat(300));
+    tag.set_data(isrc, Data::Utf8("isrc".into()));
+
+    let violations = tag.validate_lengths(&LengthLimits::itunes());
+    assert_eq!(
+        violations,
+        [
+            LengthViolation {
+                ident: ident::ARTIST.into(),
+                index: 1,
+                len: 256,
+                max_len: 255
+            },
+            LengthViolation {
+                ident: ident::DESCRIPTION.into(),
+                index: 0,
+                len: 300,
+                max_len: 255
+            },
+        ]
+    );
+
+    let limits =
+        LengthLimits::itunes().remove(&ident::DESCRIPTION).set(ident::TITLE, 10).set(isrc, 3);
+    assert_eq!(limits.get(&ident::DESCRIPTION), None);
+    assert_eq!(limits.get(&ident::TITLE), Some(10));
+    let idents: Vec<_> = tag.validate_lengths(&limits).into_iter().map(|v| v.ident).collect();
+    assert_eq!(idents, [ident::TITLE.into(), ident::ARTIST.into(), isrc.into()]);
+
+    assert!(tag.validate_lengths(&LengthLimits::new()).is_empty());
+}
+
+#[test]
+fn location_handling() {
+    let mut tag = Tag::default();
+    assert_eq!(tag.location(), None);
+
+    tag.set_data(ident::LOCATION, Data::Utf8("+37.3318-122.0312/".into()));
+    assert_eq!(tag.location(), Some(Location::new(37.3318, -122.0312)));
+
+    let parse = |s: &str| s.parse::<Location>().ok();
+    let approx_eq = |a: Location, b: Location| {
+        (a.latitude - b.latitude).abs() < 1e-6
+            && (a.longitude - b.longitude).abs() < 1e-6
+            && a.altitude == b.altitude
+    };
+    let dm = parse("+4851.462+00217.700CRSWGS_84/").unwrap();
+    assert!(approx_eq(dm, Location::new(48.8577, 2.295)));
+    let dms = parse("-335130.0+1511245.0-005.5/").unwrap();
+    assert!(approx_eq(dms, Location::new(-33.858333, 151.2125).with_altitude(-5.5)));
+    assert_eq!(parse("+91.0000+000.0000/"), None);
+    assert_eq!(parse("+37.3318/"), None);
+    assert_eq!(parse("37.3318-122.0312/"), None);
+    assert_eq!(parse("+37.3318-122.0312 /"), None);
+
+    // fall back to the quicktime key
+    tag.set_data(ident::LOCATION, Data::Utf8("invalid".into()));
+    tag.set_data(ident::QUICKTIME_LOCATION, Data::Utf8("+51.5007-000.1246+012.000/".into()));
+    let location = Location::new(51.5007, -0.1246).with_altitude(12.0);
+    assert_eq!(tag.location(), Some(location));
+
+    tag.set_location(Location::new(-12.5, 130.75));
+    assert_eq!(tag.strings_of(&ident::LOCATION).next(), Some("-12.5000+130.7500/"));
+    assert_eq!(tag.strings_of(&ident::QUICKTIME_LOCATION).next(), Some("-12.5000+130.7500/"));
+
+    tag.remove_location();
+    assert_eq!(tag.location(), None);
+    assert!(tag.meta_items_is_empty());
+}
+
+#[test]
+fn flag_handling() {
+    let mut tag = Tag::default();
+    assert!(!tag.compilation());
+
+    for (data, flag) in [
+        (Data::BeSigned(vec![1]), true),
+        (Data::BeSigned(vec![0]), false),
+        (Data::BeSigned(vec![0, 0, 0, 1]), true),
+        (Data::Reserved(vec![0, 1]), true),
+        (Data::Unknown { code: 22, data: vec![0, 1] }, true),
+        (Data::Utf8("1".into()), true),
+        (Data::Utf8("0".into()), false),
+        (Data::Utf8("True".into()), true),
+        (Data::Utf16("no".into()), false),
+        (Data::Utf8("maybe".into()), false),
+    ] {
+        tag.set_data(ident::GAPLESS_PLAYBACK, data);
+        assert_eq!(tag.gapless_playback(), flag);
+    }
+
+    tag.set_data(ident::COMPILATION, Data::Utf8("1".into()));
+    tag.set_data(ident::GAPLESS_PLAYBACK, Data::Reserved(vec![0, 0]));
+    tag.set_data(ident::SHOW_MOVEMENT, Data::Utf8("invalid".into()));
+    tag.canonicalize();
+    assert_eq!(tag.data_of(&ident::COMPILATION).next(), Some(&Data::BeSigned(vec![1])));
+    assert_eq!(tag.data_of(&ident::GAPLESS_PLAYBACK).next(), Some(&Data::BeSigned(vec![0])));
+    assert_eq!(tag.data_of(&ident::SHOW_MOVEMENT).next(), Some(&Data::Utf8("invalid".into())));
+}
+
+#[test]
+fn track_disc_layout_handling() {
+    let mut tag = Tag::default();
+    let trkn = |tag: &Tag| tag.bytes_of(&ident::TRACK_NUMBER).next().unwrap().to_vec();
+
+    // total omitted
+    tag.set_data(ident::TRACK_NUMBER, Data::Reserved(vec![0, 0, 1, 44]));
+    assert_eq!(tag.track(), (Some(300), None));
+    tag.set_track_number_only(7);
+    assert_eq!(trkn(&tag), [0, 0, 0, 7]);
+    tag.set_total_tracks_only(12);
+    assert_eq!(trkn(&tag), [0, 0, 0, 7, 0, 12]);
+
+    // the width is preserved
+    tag.set_data(ident::TRACK_NUMBER, Data::Reserved(vec![0, 0, 0, 3, 0, 9, 0, 0]));
+    tag.set_total_tracks_only(10);
+    assert_eq!(trkn(&tag), [0, 0, 0, 3, 0, 10, 0, 0]);
+
+    // plain integers
+    tag.set_data(ident::TRACK_NUMBER, Data::BeSigned(vec![5]));
+    assert_eq!(tag.track(), (Some(5), None));
+    tag.set_track_number_only(6);
+    assert_eq!(trkn(&tag), [6]);
+    tag.set_track_number_only(256);
+    assert_eq!(trkn(&tag), [1, 0]);
+    assert_eq!(tag.track_number(), Some(256));
+    tag.set_total_tracks_only(300);
+    assert_eq!(trkn(&tag), [0, 0, 1, 0, 1, 44]);
+    assert_eq!(tag.track(), (Some(256), Some(300)));
+
+    tag.set_data(ident::DISC_NUMBER, Data::Reserved(vec![0, 2]));
+    assert_eq!(tag.disc(), (Some(2), None));
+    tag.set_total_discs(3);
+    assert_eq!(tag.disc(), (Some(2), Some(3)));
+    assert_eq!(tag.bytes_of(&ident::DISC_NUMBER).next().unwrap(), [0, 0, 0, 2, 0, 3, 0, 0]);
+}
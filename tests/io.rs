@@ -1,9 +1,18 @@
+use std::borrow::Cow;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+#[cfg(feature = "audio-info")]
+use mp4ameta::{AacProfile, ChannelConfig, SampleRate};
 use mp4ameta::{
-    AdvisoryRating, ChannelConfig, Chapter, Data, Fourcc, Img, MediaType, SampleRate, Tag, Userdata,
+    AdvisoryRating, BrandPolicy, Chapter, ChapterSync, Data, DataIdent, ErrorKind, Fourcc,
+    FreeformIdent, Img, ImgFmt, ItemListLocation, MediaType, ParseWarning, ReadConfig, Tag,
+    TextEncoding, Userdata, WriteChapters, WriteConfig, ident,
+};
+#[cfg(feature = "chapters")]
+use mp4ameta::{
+    ChapterSources, ChplTimescale, Language, TargetPlayer, TextSampleDescription, TrackFlags,
 };
 use walkdir::WalkDir;
 
@@ -11,6 +20,7 @@ const EXTENSIONS: [&str; 6] = [".m4a", ".m4b", ".m4p", ".m4v", ".mp4", ".3gp"];
 
 /// Allows for some rounding errors of the start duration. These issues appear when chapters are
 /// written because the timescale of the file is used.
+#[cfg(feature = "chapters")]
 #[derive(Debug)]
 struct CmpChapter {
     /// The start of the chapter.
@@ -19,12 +29,14 @@ struct CmpChapter {
     pub title: String,
 }
 
+#[cfg(feature = "chapters")]
 impl CmpChapter {
     fn new(start: Duration, title: impl Into<String>) -> Self {
         Self { start, title: title.into() }
     }
 }
 
+#[cfg(feature = "chapters")]
 impl PartialEq<Chapter> for CmpChapter {
     fn eq(&self, other: &Chapter) -> bool {
         const EPSILON: f32 = 0.01;
@@ -161,7 +173,7 @@ fn assert_tag_1(tag: &Tag) {
     assert_eq!(tag.bpm(), Some(132));
     assert_eq!(tag.category(), Some("TEST CATEGORY"));
     assert_eq!(tag.comment(), Some("TEST COMMENT"));
-    assert_eq!(tag.compilation(), true);
+    assert!(tag.compilation());
     assert_eq!(tag.composer(), Some("TEST COMPOSER"));
     assert_eq!(tag.copyright(), Some("TEST COPYRIGHT"));
     assert_eq!(tag.description(), Some("TEST DESCRIPTION"));
@@ -169,7 +181,7 @@ fn assert_tag_1(tag: &Tag) {
     assert_eq!(tag.disc_number(), Some(1));
     assert_eq!(tag.total_discs(), Some(2));
     assert_eq!(tag.encoder(), Some("Lavf58.29.100"));
-    assert_eq!(tag.gapless_playback(), true);
+    assert!(tag.gapless_playback());
     assert_eq!(tag.genre(), Some("Hard Rock"));
     assert_eq!(tag.grouping(), Some("TEST GROUPING"));
     assert_eq!(tag.keyword(), Some("TEST KEYWORD"));
@@ -194,7 +206,7 @@ fn assert_tag_2(tag: &Tag) {
     assert_eq!(tag.bpm(), Some(98));
     assert_eq!(tag.category(), Some("NEW CATEGORY"));
     assert_eq!(tag.comment(), Some("NEW COMMENT"));
-    assert_eq!(tag.compilation(), true);
+    assert!(tag.compilation());
     assert_eq!(tag.composer(), Some("NEW COMPOSER"));
     assert_eq!(tag.copyright(), Some("NEW COPYRIGHT"));
     assert_eq!(tag.description(), Some("NEW DESCRIPTION"));
@@ -202,7 +214,7 @@ fn assert_tag_2(tag: &Tag) {
     assert_eq!(tag.disc_number(), Some(2));
     assert_eq!(tag.total_discs(), None);
     assert_eq!(tag.encoder(), Some("Lavf58.12.100"));
-    assert_eq!(tag.gapless_playback(), true);
+    assert!(tag.gapless_playback());
     assert_eq!(tag.genre(), Some("Hard Rock"));
     assert_eq!(tag.grouping(), Some("NEW GROUPING"));
     assert_eq!(tag.keyword(), Some("NEW KEYWORD"));
@@ -217,6 +229,7 @@ fn assert_tag_2(tag: &Tag) {
     assert_eq!(tag.isrc(), Some("NEW ISRC"));
     assert_eq!(tag.lyricist(), Some("NEW LYRICIST"));
 
+    #[cfg(feature = "chapters")]
     assert_eq!(
         [
             CmpChapter::new(Duration::ZERO, "CHAPTER 1"),
@@ -225,6 +238,7 @@ fn assert_tag_2(tag: &Tag) {
         ],
         tag.chapter_list(),
     );
+    #[cfg(feature = "chapters")]
     assert_eq!(
         [
             CmpChapter::new(Duration::ZERO, "CHAPTER 1"),
@@ -250,7 +264,7 @@ fn assert_tag_3(tag: &Tag) {
     assert_eq!(tag.bpm(), Some(132));
     assert_eq!(tag.category(), Some("TEST CATEGORY"));
     assert_eq!(tag.comment(), Some("TEST COMMENT"));
-    assert_eq!(tag.compilation(), true);
+    assert!(tag.compilation());
     assert_eq!(tag.composer(), Some("TEST COMPOSER"));
     assert_eq!(tag.copyright(), Some("TEST COPYRIGHT"));
     assert_eq!(tag.description(), Some("TEST DESCRIPTION"));
@@ -258,7 +272,7 @@ fn assert_tag_3(tag: &Tag) {
     assert_eq!(tag.disc_number(), Some(1));
     assert_eq!(tag.total_discs(), Some(2));
     assert_eq!(tag.encoder(), Some("Lavf58.29.100"));
-    assert_eq!(tag.gapless_playback(), true);
+    assert!(tag.gapless_playback());
 
     let mut genres = tag.genres();
     assert_eq!(genres.next(), Some("GENRE 1"));
@@ -291,14 +305,117 @@ fn assert_tag_3(tag: &Tag) {
 #[track_caller]
 fn assert_readonly(tag: &Tag) {
     assert_eq!(tag.duration(), Duration::from_millis(486));
+    assert_eq!(tag.timescale(), 1000);
+    assert_eq!(tag.raw_duration(), 486);
     assert_eq!(tag.filetype(), "M4A \u{0}\u{0}\u{2}\u{0}isomiso2");
+    #[cfg(feature = "audio-info")]
+    assert_audio_info(tag);
+}
+
+#[cfg(feature = "audio-info")]
+#[track_caller]
+fn assert_audio_info(tag: &Tag) {
+    assert_eq!(tag.media_timescale(), Some(44100));
+    assert_eq!(tag.media_raw_duration(), Some(21399));
     assert_eq!(tag.channel_config(), Some(ChannelConfig::Mono));
     assert_eq!(tag.sample_rate(), Some(SampleRate::Hz44100));
+    assert_eq!(tag.aac_profile(), Some(AacProfile::Lc));
+    assert!(!tag.sbr());
+    assert!(!tag.ps());
+    assert_eq!(tag.bit_depth(), None);
     assert_eq!(tag.avg_bitrate(), Some(64776));
     assert_eq!(tag.max_bitrate(), Some(69000));
 }
 
 #[test]
+#[cfg(feature = "audio-info")]
+fn non_standard_audio_info() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let tag = Tag::read_from_slice(&input).unwrap();
+    assert_eq!(tag.sample_rate_hz(), Some(44100));
+
+    // an explicit frequency of 176400Hz and a 6.1 channel config in the decoder specific descriptor
+    let mut data = input.clone();
+    let desc = data.windows(5).position(|w| w == [0x05, 0x80, 0x80, 0x80, 0x05]).unwrap() + 5;
+    let bits = (2u64 << 35) | (15 << 31) | (176400 << 7) | (11 << 3);
+    data[desc..desc + 5].copy_from_slice(&bits.to_be_bytes()[3..]);
+    let tag = Tag::read_from_slice(&data).unwrap();
+    assert_eq!(tag.sample_rate(), None);
+    assert_eq!(tag.sample_rate_hz(), Some(176400));
+    assert_eq!(tag.channel_config(), Some(ChannelConfig::SixOne));
+    assert_eq!(tag.channel_config().unwrap().channel_count(), 7);
+
+    // backwards compatible signaling of SBR after the general audio specific config
+    let mut data = input.clone();
+    assert_eq!(data[desc..desc + 5], [0x12, 0x08, 0x56, 0xe5, 0x00]);
+    data[desc + 4] = 0b1001_1000;
+    let tag = Tag::read_from_slice(&data).unwrap();
+    assert_eq!(tag.aac_profile(), Some(AacProfile::He));
+    assert_eq!((tag.sbr(), tag.ps()), (true, false));
+    assert_eq!(tag.sample_rate(), Some(SampleRate::Hz44100));
+
+    // explicit signaling of PS with the object type
+    let mut data = input.clone();
+    let bits = (29u64 << 35) | (4 << 31) | (1 << 27) | (3 << 23) | (2 << 18);
+    data[desc..desc + 5].copy_from_slice(&bits.to_be_bytes()[3..]);
+    let tag = Tag::read_from_slice(&data).unwrap();
+    assert_eq!(tag.aac_profile(), Some(AacProfile::HeV2));
+    assert_eq!((tag.sbr(), tag.ps()), (true, true));
+    assert_eq!(tag.channel_config(), Some(ChannelConfig::Mono));
+
+    // replace the mp4a sample entry with an alac one of the same size
+    let mut data = input;
+    let entry = data.windows(4).position(|w| w == b"mp4a").unwrap() - 4;
+    let entry_len = u32::from_be_bytes(data[entry..entry + 4].try_into().unwrap()) as usize;
+    let mut alac = data[entry..entry + 36].to_vec();
+    alac[4..8].copy_from_slice(b"alac");
+    alac.extend([0, 0, 0, 36]);
+    alac.extend(b"alac");
+    alac.extend([0; 4]); // version and flags
+    alac.extend(4096u32.to_be_bytes()); // frame length
+    alac.extend([0, 24, 40, 10, 14]); // compatible version, bit depth, rice parameters
+    alac.push(8); // channel count
+    alac.extend([0, 255]); // maximum run
+    alac.extend(0u32.to_be_bytes()); // maximum frame size
+    alac.extend(2000000u32.to_be_bytes()); // average bitrate
+    alac.extend(88200u32.to_be_bytes()); // sample rate
+    let free_len = entry_len - alac.len();
+    alac.extend((free_len as u32).to_be_bytes());
+    alac.extend(b"free");
+    alac.resize(entry_len, 0);
+    data[entry..entry + entry_len].copy_from_slice(&alac);
+    let tag = Tag::read_from_slice(&data).unwrap();
+    assert_eq!(tag.sample_rate(), Some(SampleRate::Hz88200));
+    assert_eq!(tag.sample_rate_hz(), Some(88200));
+    assert_eq!(tag.channel_config(), Some(ChannelConfig::SevenOne));
+    assert_eq!(tag.avg_bitrate(), Some(2000000));
+    assert_eq!(tag.max_bitrate(), None);
+    assert_eq!(tag.bit_depth(), Some(24));
+    assert_eq!(tag.aac_profile(), None);
+}
+
+#[test]
+fn video_info() {
+    let tag = read_tag("files/sample-64.mp4");
+    let info = tag.video_info().unwrap();
+    assert_eq!((info.width, info.height), (128, 72));
+    assert_eq!(info.codec, Fourcc(*b"avc1"));
+    assert_eq!(info.frame_count, 333);
+
+    let tag = read_tag("files/sample-multi-track.3gp");
+    let info = tag.video_info().unwrap();
+    assert_eq!((info.width, info.height), (176, 144));
+    assert_eq!(info.codec, Fourcc(*b"mp4v"));
+    assert_eq!(info.frame_count, 1050);
+
+    assert_eq!(read_tag("files/sample.m4a").video_info(), None);
+    let cfg = ReadConfig { read_video_info: false, ..ReadConfig::DEFAULT };
+    let tag = Tag::read_with_path("files/sample-64.mp4", &cfg).unwrap();
+    assert_eq!(tag.video_info(), None);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
 fn chaptered() {
     let tag = read_tag("files/sample-chaptered.m4a");
     let chapters = [
@@ -320,6 +437,15 @@ fn collection() {
     }
 }
 
+#[test]
+fn json_round_trip() {
+    read_dir("files", |_, t| {
+        let json = t.userdata.to_json_string().unwrap();
+        let userdata = Userdata::from_json_str(&json).unwrap();
+        assert_eq!(userdata, t.userdata);
+    });
+}
+
 #[test]
 fn sample_files() {
     let _ = fs::remove_dir_all("target/files");
@@ -358,6 +484,7 @@ fn sample_files() {
 }
 
 #[test]
+#[cfg(feature = "chapters")]
 fn chapter_list_title_truncation() {
     let target_file = use_sample_file("files/sample.m4a", "target/chapter_list_title.m4a");
 
@@ -379,6 +506,7 @@ fn chapter_list_title_truncation() {
 }
 
 #[test]
+#[cfg(feature = "chapters")]
 fn chapter_track_title_truncation() {
     let target_file = use_sample_file("files/sample.m4a", "target/chapter_track_title.m4a");
 
@@ -400,6 +528,27 @@ fn chapter_track_title_truncation() {
 }
 
 #[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_title_too_long() {
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    let mut tag = Userdata::default();
+    tag.chapter_track_mut().push(Chapter::new(Duration::ZERO, "ä".repeat(40000)));
+    let cfg = WriteConfig {
+        truncate_chapter_titles: false,
+        ..WriteConfig::DEFAULT
+    };
+    let err = tag.write_to_vec(&input, &cfg).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::ChapterTitleTooLong));
+
+    // truncated at a char boundary
+    let output = tag.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_track()[0].title, "ä".repeat(32767));
+}
+
+#[test]
+#[cfg(feature = "chapters")]
 fn previous_chapter_track_media_data_is_removed() {
     let target_file = use_sample_file("files/sample.m4a", "target/chapter_track_doesnt_grow.m4a");
 
@@ -525,3 +674,2134 @@ fn write_empty() {
     assert!(tag.is_empty());
     assert_readonly(&tag);
 }
+
+#[test]
+fn write_major_brand() {
+    let target_file = use_sample_file("files/sample.m4a", "target/write_major_brand.m4a");
+
+    let tag = get_tag_2();
+    let cfg = WriteConfig {
+        set_major_brand: Some(Fourcc(*b"M4B ")),
+        ..Default::default()
+    };
+    tag.write_with_path(target_file, &cfg).unwrap();
+
+    let tag = read_tag(target_file);
+    assert_tag_2(&tag);
+    assert_eq!(tag.filetype(), "M4B \u{0}\u{0}\u{2}\u{0}isomiso2M4B ");
+    assert_eq!(tag.duration(), Duration::from_millis(486));
+    #[cfg(feature = "audio-info")]
+    assert_eq!(tag.avg_bitrate(), Some(64776));
+
+    // writing the same brand again doesn't change anything
+    tag.write_with_path(target_file, &cfg).unwrap();
+    let tag = read_tag(target_file);
+    assert_eq!(tag.filetype(), "M4B \u{0}\u{0}\u{2}\u{0}isomiso2M4B ");
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn write_chapters_prefer_existing() {
+    let target_file = use_sample_file("files/sample.m4a", "target/write_chapters_prefer.m4a");
+
+    let chapters = [
+        Chapter::new(Duration::ZERO, "The Pledge"),
+        Chapter::new(Duration::from_millis(135), "The Turn"),
+        Chapter::new(Duration::from_millis(324), "The Prestige"),
+    ];
+
+    let mut tag = Userdata::default();
+    tag.chapter_list_mut().extend(chapters.clone());
+    tag.chapter_track_mut().extend(chapters.clone());
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::NONE
+    };
+    tag.write_with_path(target_file, &cfg).unwrap();
+
+    let tag = read_tag(target_file);
+    assert_eq!(tag.chapter_list(), []);
+    assert_eq!(tag.chapter_track(), chapters);
+
+    let mut tag = tag.userdata;
+    tag.chapter_list_mut().extend(chapters[..2].iter().cloned());
+    tag.chapter_track_mut().truncate(2);
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::PreferExisting,
+        ..WriteConfig::NONE
+    };
+    tag.write_with_path(target_file, &cfg).unwrap();
+
+    let tag = read_tag(target_file);
+    assert_eq!(tag.chapter_list(), []);
+    assert_eq!(tag.chapter_track(), &chapters[..2]);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn repair_chapters() {
+    let target_file = use_sample_file("files/sample.m4a", "target/repair_chapters.m4a");
+
+    let chapters = [
+        Chapter::new(Duration::ZERO, "The Pledge"),
+        Chapter::new(Duration::from_millis(135), "The Turn"),
+        Chapter::new(Duration::from_millis(324), "The Prestige"),
+    ];
+
+    assert!(mp4ameta::repair::rebuild_chapter_track(target_file).is_err());
+
+    let mut tag = Userdata::default();
+    tag.chapter_list_mut().extend(chapters.clone());
+    write_tag(&tag, target_file);
+
+    mp4ameta::repair::rebuild_chapter_track(target_file).unwrap();
+    let tag = read_tag(target_file);
+    assert_eq!(tag.chapter_list(), chapters);
+    assert_eq!(tag.chapter_track(), chapters);
+
+    let mut tag = Userdata::default();
+    tag.chapter_track_mut().extend(chapters.clone());
+    write_tag(&tag, target_file);
+
+    mp4ameta::repair::rebuild_chapter_list(target_file).unwrap();
+    let tag = read_tag(target_file);
+    assert_eq!(tag.chapter_list(), chapters);
+    assert_eq!(tag.chapter_track(), chapters);
+}
+
+#[test]
+fn write_verify() {
+    let cfg = WriteConfig { verify: true, ..Default::default() };
+    for (in_file, target_file) in [
+        ("files/sample.m4a", "target/write_verify.m4a"),
+        ("files/sample-chaptered.m4a", "target/write_verify_chaptered.m4a"),
+        ("files/sample-64.mp4", "target/write_verify_64.mp4"),
+    ] {
+        let target_file = use_sample_file(in_file, target_file);
+
+        get_tag_2().write_with_path(target_file, &cfg).unwrap();
+        assert_tag_2(&read_tag(target_file));
+
+        get_tag_1().write_with_path(target_file, &cfg).unwrap();
+        assert_tag_1(&read_tag(target_file));
+    }
+}
+
+#[test]
+fn error_context() {
+    let mut data = fs::read("files/sample.m4a").unwrap();
+    let pos = data.windows(4).position(|w| w == b"stco").unwrap();
+    let len = u32::from_be_bytes(data[pos - 4..pos].try_into().unwrap()) as u64;
+    // corrupt the number of entries
+    data[pos + 8..pos + 12].copy_from_slice(&1000u32.to_be_bytes());
+
+    let err = Tag::read_from(&mut std::io::Cursor::new(&data)).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::SizeMismatch));
+    assert_eq!(err.path_string(), "moov/trak[0]/mdia/minf/stbl/stco");
+    assert_eq!(err.path()[1].index, Some(0));
+    assert_eq!(err.path().last().map(|s| s.fourcc), Some(Fourcc(*b"stco")));
+    assert_eq!(err.expected_size(), Some(8 + 4 * 1000));
+    assert_eq!(err.found_size(), Some(len - 8));
+    assert_eq!(err.offset(), Some(pos as u64 + 12));
+    let msg = err.to_string();
+    assert!(msg.contains(&format!("at moov/trak[0]/mdia/minf/stbl/stco, offset {:#x}", pos + 12)));
+    assert!(msg.contains(&format!("expected size {}, found {}", 8 + 4 * 1000, len - 8)));
+}
+
+#[test]
+fn read_warnings() {
+    let mut file = std::io::Cursor::new(fs::read("files/sample.m4a").unwrap());
+    let mut userdata = Userdata::default();
+    userdata.set_data(Fourcc(*b"test"), Data::Reserved(Vec::new()));
+    userdata.set_data(Fourcc(*b"unkn"), Data::Unknown { code: 99, data: vec![1, 2, 3] });
+    userdata.write_to(&mut file).unwrap();
+
+    file.set_position(0);
+    let (tag, warnings) = Tag::read_with_warnings(&mut file, &ReadConfig::DEFAULT).unwrap();
+    assert_eq!(tag.data_of(&Fourcc(*b"test")).count(), 1);
+    assert!(warnings.iter().any(|w| matches!(w, ParseWarning::EmptyData { .. })));
+    let unknown = warnings.iter().find(|w| matches!(w, ParseWarning::UnknownDataType { .. }));
+    assert!(matches!(unknown, Some(ParseWarning::UnknownDataType { code: 99, .. })));
+    let msg = unknown.unwrap().to_string();
+    assert!(msg.ends_with("known codes are 0, 1, 2, 12, 13, 14, 21, 27"), "{msg}");
+    assert_eq!(warnings.len(), 2);
+}
+
+#[test]
+fn inspect_layout() {
+    let mut data = fs::read("files/sample.m4a").unwrap();
+    let layout = mp4ameta::inspect::layout_from(&mut std::io::Cursor::new(&data)).unwrap();
+    assert_eq!(layout.file_len, data.len() as u64);
+    assert!(layout.moov_len > 0);
+    assert!(layout.mdat_len > 0);
+    assert!(layout.ilst_len > 0);
+    assert!(layout.artwork_len > 0);
+    assert!(layout.ilst_len < layout.moov_len);
+    assert_eq!(layout.unparsed_len, 0);
+
+    // trailing junk
+    data.extend_from_slice(&[0; 10]);
+    let junk = mp4ameta::inspect::layout_from(&mut std::io::Cursor::new(&data)).unwrap();
+    assert_eq!(junk.unparsed_len, 10);
+    assert_eq!(junk.file_len, layout.file_len + 10);
+
+    let chaptered = mp4ameta::inspect::layout("files/sample-chaptered.m4a").unwrap();
+    assert!(chaptered.chpl_len > 0);
+}
+
+#[test]
+fn probe() {
+    for path in ["files/sample.m4a", "files/sample-chaptered.m4a"] {
+        let probe = mp4ameta::probe(path).unwrap();
+        let tag = Tag::read_from_path(path).unwrap();
+        assert_eq!(probe.has_ilst, !tag.meta_items_is_empty());
+        #[cfg(feature = "chapters")]
+        assert_eq!(probe.has_chpl, tag.chapter_sources().has_chpl);
+        #[cfg(feature = "chapters")]
+        assert_eq!(probe.has_chapter_track, tag.chapter_sources().has_track);
+        assert_eq!(probe.brand.to_string(), tag.filetype()[..4]);
+        assert_eq!(probe.duration, tag.duration());
+    }
+
+    // untagged
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let cfg = WriteConfig {
+        write_meta_items: true,
+        write_chapters: WriteChapters::Both,
+        ..WriteConfig::NONE
+    };
+    let output = Userdata::default().write_to_vec(&input, &cfg).unwrap();
+    let probe = mp4ameta::probe_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert!(!probe.has_ilst);
+    #[cfg(feature = "chapters")]
+    assert!(!probe.has_chpl);
+    #[cfg(feature = "chapters")]
+    assert!(!probe.has_chapter_track);
+
+    let err = mp4ameta::probe_from(&mut std::io::Cursor::new(b"\0\0\0\x08abcd")).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::NoFtyp));
+}
+
+#[test]
+fn read_audio_info() {
+    for path in ["files/sample.m4a", "files/sample-chaptered.m4a", "files/sample-64.mp4"] {
+        let mut file = fs::File::open(path).unwrap();
+        let info = mp4ameta::read_audio_info(&mut file).unwrap();
+        assert_eq!(info, Tag::read_from_path(path).unwrap().info);
+    }
+
+    // classic QuickTime files without a filetype atom are accepted
+    let mut input = fs::read("files/sample.m4a").unwrap();
+    let expected = Tag::read_from_slice(&input).unwrap().info;
+    input[4..8].copy_from_slice(b"free");
+    let info = mp4ameta::read_audio_info(&mut std::io::Cursor::new(&input)).unwrap();
+    assert_eq!(info, expected);
+
+    let err = mp4ameta::read_audio_info(&mut std::io::Cursor::new(b"\0\0\0\x08abcd")).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::NoFtyp));
+}
+
+#[test]
+fn read_duration() {
+    for path in ["files/sample.m4a", "files/sample-chaptered.m4a", "files/sample-64.mp4"] {
+        let mut file = fs::File::open(path).unwrap();
+        let duration = mp4ameta::read_duration(&mut file).unwrap();
+        assert_eq!(duration, Tag::read_from_path(path).unwrap().duration());
+    }
+
+    let mut input = fs::read("files/sample.m4a").unwrap();
+    let expected = Tag::read_from_slice(&input).unwrap().duration();
+    input[4..8].copy_from_slice(b"free");
+    let duration = mp4ameta::read_duration(&mut std::io::Cursor::new(&input)).unwrap();
+    assert_eq!(duration, expected);
+
+    let err = mp4ameta::read_duration(&mut std::io::Cursor::new(b"\0\0\0\x08abcd")).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::NoFtyp));
+    let err = mp4ameta::read_duration(&mut std::io::Cursor::new(b"\0\0\0\x08free")).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::AtomNotFound(Fourcc(f)) if f == *b"moov"));
+}
+
+#[test]
+fn track_languages() {
+    use mp4ameta::{Language, set_track_language_in};
+
+    for path in ["files/sample.m4a", "files/sample-chaptered.m4a", "files/sample-64.mp4"] {
+        let input = fs::read(path).unwrap();
+        let mut file = std::io::Cursor::new(input.clone());
+        let languages = mp4ameta::track_languages(&mut file).unwrap();
+        let tables = mp4ameta::sample_tables(&mut file).unwrap();
+        let ids = languages.iter().map(|l| (l.track_id, l.handler)).collect::<Vec<_>>();
+        assert_eq!(ids, tables.iter().map(|t| (t.track_id, t.handler)).collect::<Vec<_>>());
+
+        // only the two bytes of the language change
+        let language = Language::new(*b"deu").unwrap();
+        let track_id = languages[0].track_id;
+        assert!(set_track_language_in(&mut file, track_id, language).unwrap());
+        let output = file.into_inner();
+        assert_eq!(output.len(), input.len());
+        assert_eq!(input.iter().zip(output.iter()).filter(|(a, b)| a != b).count(), 2);
+
+        let mut file = std::io::Cursor::new(output);
+        let changed = mp4ameta::track_languages(&mut file).unwrap();
+        assert_eq!(changed[0].language, language);
+        assert_eq!(changed[1..], languages[1..]);
+        let output = file.into_inner();
+        assert_eq!(Tag::read_from_slice(&output).unwrap(), Tag::read_from_slice(&input).unwrap());
+
+        // unknown tracks leave the file untouched
+        let mut file = std::io::Cursor::new(input.clone());
+        assert!(!set_track_language_in(&mut file, u32::MAX, language).unwrap());
+        assert_eq!(file.into_inner(), input);
+    }
+}
+
+#[test]
+fn sample_tables() {
+    let mut file = fs::File::open("files/sample.m4a").unwrap();
+    let tables = mp4ameta::sample_tables(&mut file).unwrap();
+    let audio = tables.iter().find(|t| t.handler == Some(Fourcc(*b"soun"))).unwrap();
+    assert_eq!(audio.timescale, 44100);
+
+    let sizes = audio.sample_sizes(&mut file).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(sizes.len(), audio.sample_count() as usize);
+    let durations = audio.sample_durations(&mut file).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(durations.len(), audio.sample_count() as usize);
+    assert_eq!(durations.iter().map(|&d| d as u64).sum::<u64>(), 21399);
+
+    let offsets = audio.chunk_offsets(&mut file).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(offsets.len(), audio.chunk_count() as usize);
+    let per_chunk = audio.samples_per_chunk(&mut file).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(per_chunk.len(), audio.chunk_count() as usize);
+    assert_eq!(per_chunk.iter().sum::<u32>(), audio.sample_count());
+
+    let file_len = file.metadata().unwrap().len();
+    assert!(offsets.iter().all(|&o| o < file_len));
+
+    let mut file = fs::File::open("files/sample-64.mp4").unwrap();
+    for table in mp4ameta::sample_tables(&mut file).unwrap() {
+        let sizes = table.sample_sizes(&mut file).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(sizes.len(), table.sample_count() as usize);
+        let offsets = table.chunk_offsets(&mut file).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(offsets.len(), table.chunk_count() as usize);
+    }
+
+    // tables are read in chunks, instead of entry by entry
+    struct CountReads<R>(R, usize);
+    impl<R: std::io::Read> std::io::Read for CountReads<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.1 += 1;
+            self.0.read(buf)
+        }
+    }
+    impl<R: std::io::Seek> std::io::Seek for CountReads<R> {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+    let data = fs::read("files/sample-64.mp4").unwrap();
+    let tables = mp4ameta::sample_tables(&mut std::io::Cursor::new(&data)).unwrap();
+    let video = tables.iter().find(|t| t.handler == Some(Fourcc(*b"vide"))).unwrap();
+    let mut reader = CountReads(std::io::Cursor::new(&data), 0);
+    assert_eq!(video.sample_sizes(&mut reader).count(), 333);
+    assert_eq!(reader.1, 1);
+
+    // the entries before the end of a truncated table are returned
+    let data = fs::read("files/sample.m4a").unwrap();
+    let tables = mp4ameta::sample_tables(&mut std::io::Cursor::new(&data)).unwrap();
+    let stsz = data.windows(4).position(|w| w == b"stsz").unwrap() + 16;
+    let truncated = &data[..stsz + 4 * 5 + 2];
+    let sizes = tables[0].sample_sizes(&mut std::io::Cursor::new(truncated)).collect::<Vec<_>>();
+    assert_eq!(sizes.len(), 6);
+    assert!(sizes[..5].iter().all(Result::is_ok));
+    assert!(sizes[5].is_err());
+}
+
+#[test]
+fn audio_checksum() {
+    use mp4ameta::ChecksumAlgorithm;
+
+    let checksum = |data: &[u8], algo| {
+        mp4ameta::audio_checksum(&mut std::io::Cursor::new(data), algo).unwrap()
+    };
+
+    let input = fs::read("files/sample.m4a").unwrap();
+    let crc32 = checksum(&input, ChecksumAlgorithm::Crc32);
+    let fnv = checksum(&input, ChecksumAlgorithm::Fnv1a64);
+    // the audio data spans the whole media data atom
+    assert_eq!(crc32, 0x82fd4a8a);
+    assert_ne!(crc32, fnv);
+
+    // rewriting metadata and chapters moves the media data, but doesn't change the audio
+    let mut userdata = get_tag_1().userdata;
+    userdata.set_title("a much longer title that grows the movie atom".repeat(100));
+    *userdata.chapter_track_mut() = vec![
+        Chapter::new(Duration::ZERO, "first"),
+        Chapter::new(Duration::from_millis(500), "second"),
+    ];
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+    assert_ne!(output.len(), input.len());
+    assert_eq!(checksum(&output, ChecksumAlgorithm::Crc32), crc32);
+    assert_eq!(checksum(&output, ChecksumAlgorithm::Fnv1a64), fnv);
+
+    let mut corrupted = input.clone();
+    *corrupted.last_mut().unwrap() ^= 0xff;
+    assert_ne!(checksum(&corrupted, ChecksumAlgorithm::Crc32), crc32);
+}
+
+#[test]
+fn write_to_vec() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let mut userdata = get_tag_1().userdata;
+    *userdata.chapter_track_mut() = vec![
+        Chapter::new(Duration::ZERO, "first"),
+        Chapter::new(Duration::from_millis(500), "second"),
+    ];
+    userdata.sync_chapters(ChapterSync::TrackToList);
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+    assert_ne!(input, output);
+
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_tag_1(&tag);
+    #[cfg(feature = "chapters")]
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+    #[cfg(feature = "chapters")]
+    assert_eq!(tag.chapter_list(), userdata.chapter_list());
+}
+
+#[test]
+fn read_from_slice() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let tag = Tag::read_from_slice(&input).unwrap();
+    assert_eq!(tag, Tag::read_from_path("files/sample-chaptered.m4a").unwrap());
+
+    let cfg = ReadConfig { read_image_data: false, ..ReadConfig::DEFAULT };
+    let tag = Tag::read_with_slice(&input, &cfg).unwrap();
+    assert_eq!(tag, Tag::read_with_path("files/sample-chaptered.m4a", &cfg).unwrap());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn read_many_track_chapters() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = Userdata::default();
+    *userdata.chapter_track_mut() =
+        (0..1000).map(|i| Chapter::new(Duration::from_millis(i), format!("chapter {i}"))).collect();
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_track().len(), 1000);
+    for (i, c) in tag.chapter_track().iter().enumerate() {
+        assert_eq!(c.title, format!("chapter {i}"));
+    }
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_lazy() {
+    let mut file = fs::File::open("files/sample-chaptered.m4a").unwrap();
+    let tag = Tag::read_from(&mut file).unwrap();
+
+    let chapters = Tag::chapter_track_lazy(&mut file).unwrap();
+    assert_eq!(chapters.len(), tag.chapter_track().len());
+    let chapters = chapters.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(chapters, tag.chapter_track());
+
+    let first = Tag::chapter_track_lazy(&mut file).unwrap().next().unwrap().unwrap();
+    assert_eq!(first, tag.chapter_track()[0]);
+
+    let mut file = fs::File::open("files/sample.m4a").unwrap();
+    assert_eq!(Tag::chapter_track_lazy(&mut file).unwrap().count(), 0);
+
+    // classic QuickTime files without a filetype atom are accepted
+    let mut input = fs::read("files/sample-chaptered.m4a").unwrap();
+    input[4..8].copy_from_slice(b"free");
+    let mut file = std::io::Cursor::new(input);
+    let chapters = Tag::chapter_track_lazy(&mut file).unwrap();
+    assert_eq!(chapters.collect::<Result<Vec<_>, _>>().unwrap(), tag.chapter_track());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn write_utf16_chapter_titles() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = Userdata::default();
+    *userdata.chapter_track_mut() = vec![
+        Chapter::new(Duration::ZERO, "first 🎵"),
+        Chapter::new(Duration::from_millis(500), "😀".repeat(20000)),
+    ];
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        chapter_text_encoding: TextEncoding::Utf16Be,
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+
+    let title: Vec<u8> = "first 🎵".encode_utf16().flat_map(u16::to_be_bytes).collect();
+    assert!(output.windows(title.len() + 2).any(|w| w[..2] == [0xfe, 0xff] && w[2..] == title));
+
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_track()[0].title, "first 🎵");
+    // 2 bytes byte order mark + 4 bytes per char
+    assert_eq!(tag.chapter_track()[1].title, "😀".repeat(16383));
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_edit_list() {
+    let count_elst = |data: &[u8]| data.windows(4).filter(|w| w == b"elst").count();
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    let mut userdata = Userdata::default();
+    userdata.chapter_track_mut().push(Chapter::new(Duration::ZERO, "chapter"));
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(count_elst(&output), count_elst(&input) + 1);
+
+    // existing edit lists are preserved when rewriting the chapter track
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+    assert_eq!(count_elst(&output), count_elst(&input) + 1);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_flags() {
+    let tkhd_flags = |data: &[u8]| -> Vec<[u8; 4]> {
+        let positions = data.windows(4).enumerate().filter(|(_, w)| w == b"tkhd");
+        positions.map(|(i, _)| data[i + 4..i + 8].try_into().unwrap()).collect()
+    };
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    let mut userdata = Userdata::default();
+    userdata.chapter_track_mut().push(Chapter::new(Duration::ZERO, "chapter"));
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    let flags = tkhd_flags(&output);
+    assert_eq!(flags[..flags.len() - 1], tkhd_flags(&input));
+    assert_eq!(flags.last(), Some(&[0, 0, 0, 2]));
+
+    // the flags of an existing chapter track are updated
+    let cfg = WriteConfig {
+        chapter_track_flags: TrackFlags { enabled: true, in_movie: true, in_preview: true },
+        ..cfg
+    };
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+    assert_eq!(tkhd_flags(&output).last(), Some(&[0, 0, 0, 7]));
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_text_description() {
+    let text_description = |data: &[u8]| -> Vec<u8> {
+        let pos = data.windows(4).rposition(|w| w == b"stsd").unwrap();
+        let len = u32::from_be_bytes(data[pos + 12..pos + 16].try_into().unwrap()) as usize;
+        assert_eq!(&data[pos + 16..pos + 20], b"text");
+        data[pos + 20..pos + 12 + len].to_vec()
+    };
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    let mut userdata = Userdata::default();
+    userdata.chapter_track_mut().push(Chapter::new(Duration::ZERO, "chapter"));
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    #[rustfmt::skip]
+    let expected = [
+        0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 13, b'f', b't', b'a', b'b', 0, 1, 0, 1, 0,
+    ];
+    assert_eq!(text_description(&output), expected);
+
+    let description = TextSampleDescription::DEFAULT
+        .with_justification(1, -1)
+        .with_background_color([1, 2, 3, 4])
+        .with_font(2, 18, "Arial");
+    let custom_cfg = WriteConfig {
+        chapter_text_description: Some(description),
+        ..cfg.clone()
+    };
+    let output = userdata.write_to_vec(&output, &custom_cfg).unwrap();
+    let custom = text_description(&output);
+    assert_eq!(custom[4..10], [1, 0xff, 1, 2, 3, 4]);
+    assert_eq!(custom[22..26], [0, 2, 0, 18]);
+    assert_eq!(custom[custom.len() - 6..], *b"\x05Arial");
+
+    // an existing description is preserved when only the titles change
+    userdata.chapter_track_mut()[0].title = "new title".into();
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+    assert_eq!(text_description(&output), custom);
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_in_place_edit() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let mut tag = Tag::read_from_slice(&input).unwrap();
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+
+    // a title with the same length is overwritten in place
+    let old_title = tag.chapter_track()[1].title.clone();
+    let new_title = "X".repeat(old_title.len());
+    tag.chapter_track_mut()[1].title = new_title.clone();
+    let output = tag.write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(output.len(), input.len());
+    let title_pos =
+        |data: &[u8], title: &str| data.windows(title.len()).position(|w| w == title.as_bytes());
+    assert_eq!(title_pos(&output, &new_title), title_pos(&input, &old_title));
+    let written = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(written.chapter_track(), tag.chapter_track());
+
+    // a longer title moves the samples, the previous ones are removed
+    tag.chapter_track_mut()[1].title = format!("{new_title} and more");
+    let output = tag.write_to_vec(&output, &cfg).unwrap();
+    assert_eq!(title_pos(&output, &format!("\x08{new_title}")), None);
+    let written = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(written.chapter_track(), tag.chapter_track());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_sample_table_atoms() {
+    const STSS: [u8; 20] = [
+        0, 0, 0, 20, // size
+        b's', b't', b's', b's', // fourcc
+        0, 0, 0, 0, // version and flags
+        0, 0, 0, 1, // entry count
+        0, 0, 0, 1, // sample number
+    ];
+    const STBL: &[&[u8; 4]] = &[b"trak", b"mdia", b"minf", b"stbl"];
+    let mut input = fs::read("files/sample-chaptered.m4a").unwrap();
+
+    // the chapter track is the last track and the media data precedes the movie atom, so no
+    // chunk offsets have to be updated
+    let moov = input.windows(4).position(|w| w == b"moov").unwrap() - 4;
+    let moov_len = u32::from_be_bytes(input[moov..moov + 4].try_into().unwrap());
+    let trak = input.windows(4).rposition(|w| w == b"trak").unwrap() - 4;
+    let moov_end = moov + moov_len as usize;
+    assert!(insert_in_atom(&mut input, trak, moov_end, STBL, 0, &STSS));
+    input[moov..moov + 4].copy_from_slice(&(moov_len + STSS.len() as u32).to_be_bytes());
+
+    let mut tag = Tag::read_from_slice(&input).unwrap();
+    let chapter_track = tag.chapter_track().to_vec();
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    let contains_stss = |data: &[u8]| data.windows(STSS.len()).any(|w| w == STSS);
+
+    // overwriting the samples in place keeps the sample table consistent
+    let title = "X".repeat(chapter_track[1].title.len());
+    tag.chapter_track_mut()[1].title = title.clone();
+    let mut file = std::io::Cursor::new(input.clone());
+    let warnings = tag.write_with_warnings(&mut file, &cfg).unwrap();
+    assert_eq!(warnings, [ParseWarning::UnchangedChapterList]);
+    assert!(contains_stss(file.get_ref()));
+
+    // rewriting the sample tables removes atoms that describe the previous samples
+    tag.chapter_track_mut()[1].title = format!("{title} and more");
+    let mut file = std::io::Cursor::new(input);
+    let warnings = tag.write_with_warnings(&mut file, &cfg).unwrap();
+    let [ParseWarning::UnchangedChapterList, ParseWarning::RemovedSampleTableAtom { fourcc, .. }] =
+        warnings[..]
+    else {
+        panic!("unexpected warnings {warnings:?}");
+    };
+    assert_eq!(fourcc, Fourcc(*b"stss"));
+    let output = file.into_inner();
+    assert!(!contains_stss(&output));
+    let written = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(written.chapter_track(), tag.chapter_track());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_language_and_timescale() {
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    let mut userdata = Userdata::default();
+    *userdata.chapter_track_mut() = vec![
+        Chapter::new(Duration::ZERO, "first"),
+        Chapter::new(Duration::from_millis(250), "second"),
+    ];
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        chapter_track_language: Language::new(*b"eng").unwrap(),
+        chapter_track_timescale: Some(1000),
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+
+    // the last media header belongs to the new chapter track
+    let pos = output.windows(4).rposition(|w| w == b"mdhd").unwrap();
+    let mdhd = &output[pos + 8..pos + 28];
+    assert_eq!(mdhd[8..12], 1000u32.to_be_bytes());
+    // ('e' - 0x60) << 10 | ('n' - 0x60) << 5 | ('g' - 0x60)
+    assert_eq!(mdhd[16..18], 0x15c7u16.to_be_bytes());
+
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+    assert_eq!(tag.chapter_tracks[0].timescale, 1000);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_ticks() {
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    // frames of a 29.97 fps video, which can't be represented exactly in nanoseconds
+    let timescale = 30000;
+    let frames = [0, 7, 13];
+    let chapters: Vec<Chapter> = (frames.iter())
+        .map(|f| Chapter::from_ticks(f * 1001, timescale, format!("frame {f}")))
+        .collect();
+    let mut userdata = Userdata::default();
+    *userdata.chapter_list_mut() = chapters.clone();
+    *userdata.chapter_track_mut() = chapters;
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::Both,
+        chpl_timescale: ChplTimescale::Fixed(std::num::NonZeroU32::new(timescale).unwrap()),
+        chapter_track_timescale: Some(timescale),
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+
+    let read_cfg = ReadConfig {
+        chpl_timescale: cfg.chpl_timescale,
+        ..ReadConfig::DEFAULT
+    };
+    let tag = Tag::read_with_slice(&output, &read_cfg).unwrap();
+    assert_eq!(tag.chpl_timescale, Some(timescale));
+    assert_eq!(tag.chapter_tracks[0].timescale, timescale);
+    for chapters in [tag.chapter_list(), tag.chapter_track()] {
+        let ticks: Vec<u64> = chapters.iter().map(|c| c.ticks(timescale)).collect();
+        assert_eq!(ticks, [0, 7 * 1001, 13 * 1001]);
+    }
+
+    // rewriting doesn't shift the boundaries
+    let output = tag.write_to_vec(&output, &cfg).unwrap();
+    let rewritten = Tag::read_with_slice(&output, &read_cfg).unwrap();
+    assert_eq!(rewritten.chapter_list(), tag.chapter_list());
+    assert_eq!(rewritten.chapter_track(), tag.chapter_track());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn write_for_player() {
+    // contains a chapter list, which is removed for apple books
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let mut userdata = Userdata::default();
+    *userdata.chapter_list_mut() = vec![Chapter::new(Duration::ZERO, "first")];
+    *userdata.chapter_track_mut() = vec![Chapter::new(Duration::ZERO, "first")];
+
+    for (player, has_chpl, brand) in [
+        (TargetPlayer::AppleBooks, false, *b"M4B "),
+        (TargetPlayer::Vlc, true, *b"M4A "),
+        (TargetPlayer::Audiobookshelf, true, *b"M4A "),
+    ] {
+        let cfg = WriteConfig { verify: true, ..WriteConfig::for_player(player) };
+        let output = userdata.write_to_vec(&input, &cfg).unwrap();
+        let tag = Tag::read_from_slice(&output).unwrap();
+        assert_eq!(tag.chapter_sources().has_chpl, has_chpl, "{player:?}");
+        assert!(tag.chapter_sources().has_track, "{player:?}");
+        assert_eq!(output[8..12], brand, "{player:?}");
+    }
+    assert_eq!(ChplTimescale::FFMPEG, ChplTimescale::DEFAULT);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn unchanged_chapter_warnings() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let mut tag = Tag::read_from_slice(&input).unwrap();
+    assert_eq!(tag.chapter_list(), tag.chapter_track());
+    let write = |tag: &Tag, write_chapters| {
+        let cfg = WriteConfig { write_chapters, ..WriteConfig::DEFAULT };
+        let mut file = std::io::Cursor::new(input.clone());
+        tag.write_with_warnings(&mut file, &cfg).unwrap()
+    };
+
+    // the chapter list still matches the chapter track
+    assert_eq!(write(&tag, WriteChapters::ChapterTrack), []);
+    assert_eq!(write(&tag, WriteChapters::Both), []);
+
+    tag.chapter_track_mut()[0].title = "changed".to_owned();
+    assert_eq!(write(&tag, WriteChapters::ChapterTrack), [ParseWarning::UnchangedChapterList]);
+    let track_id = tag.chapter_tracks[0].track_id;
+    assert_eq!(
+        write(&tag, WriteChapters::ChapterList),
+        [ParseWarning::UnchangedChapterTrack { track_id }]
+    );
+    assert_eq!(write(&tag, WriteChapters::Both), []);
+    assert_eq!(write(&tag, WriteChapters::None), []);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn remove_other_chapter_format() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let mut tag = Tag::read_from_slice(&input).unwrap();
+    tag.chapter_track_mut()[0].title = "changed".to_owned();
+    tag.chapter_list_mut()[0].title = "changed".to_owned();
+
+    for (write_chapters, has_chpl, has_track) in [
+        (WriteChapters::ChapterList, true, false),
+        (WriteChapters::ChapterTrack, false, true),
+        (WriteChapters::Both, true, true),
+    ] {
+        let cfg = WriteConfig {
+            write_chapters,
+            remove_other_chapter_format: true,
+            verify: true,
+            ..WriteConfig::DEFAULT
+        };
+        let mut file = std::io::Cursor::new(input.clone());
+        let warnings = tag.write_with_warnings(&mut file, &cfg).unwrap();
+        assert_eq!(warnings, [], "{write_chapters:?}");
+
+        let written = Tag::read_from_slice(file.get_ref()).unwrap();
+        let sources = written.chapter_sources();
+        assert_eq!((sources.has_chpl, sources.has_track), (has_chpl, has_track));
+        if has_chpl {
+            assert_eq!(written.chapter_list(), tag.chapter_list());
+        }
+        if has_track {
+            assert_eq!(written.chapter_track(), tag.chapter_track());
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_edit_keeps_track() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let mut tag = Tag::read_from_slice(&input).unwrap();
+    let track = tag.chapter_tracks[0].clone();
+
+    // the configured language and timescale only apply to new chapter tracks
+    let language = if track.language.as_str() == "eng" { "deu" } else { "eng" };
+    let timescale = track.timescale + 1;
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        chapter_track_language: language.parse().unwrap(),
+        chapter_track_timescale: Some(timescale),
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    tag.chapter_track_mut()[0].title = "a longer first title".to_owned();
+    tag.chapter_track_mut().insert(1, Chapter::new(Duration::from_secs(60), "added"));
+    let output = tag.write_to_vec(&input, &cfg).unwrap();
+
+    let written = Tag::read_from_slice(&output).unwrap();
+    let [written_track] = &written.chapter_tracks[..] else {
+        panic!("unexpected chapter tracks {:?}", written.chapter_tracks);
+    };
+    assert_eq!(written_track.track_id, track.track_id);
+    assert_eq!(written_track.language, track.language);
+    assert_eq!(written_track.timescale, track.timescale);
+    assert_eq!(written_track.chapters, tag.chapter_track());
+    let track_ids = |data: &[u8]| {
+        let tables = mp4ameta::sample_tables(&mut std::io::Cursor::new(data)).unwrap();
+        tables.iter().map(|t| t.track_id).collect::<Vec<_>>()
+    };
+    assert_eq!(track_ids(&output), track_ids(&input));
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_sources() {
+    let tag = read_tag("files/sample.m4a");
+    assert_eq!(tag.chapter_sources(), ChapterSources::default());
+
+    let tag = read_tag("files/sample-chaptered.m4a");
+    let sources = tag.chapter_sources();
+    assert_eq!(sources.has_chpl, !tag.chapter_list().is_empty());
+    assert!(sources.has_track);
+    assert!(sources.track_id.is_some());
+
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = Userdata::default();
+    userdata.chapter_list_mut().push(Chapter::new(Duration::ZERO, "chapter"));
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterList,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    let expected = ChapterSources { has_chpl: true, has_track: false, track_id: None };
+    assert_eq!(tag.chapter_sources(), expected);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn multiple_chapter_tracks() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let english = vec![
+        Chapter::new(Duration::ZERO, "Intro"),
+        Chapter::new(Duration::from_millis(400), "Outro"),
+    ];
+    let german = vec![
+        Chapter::new(Duration::ZERO, "Anfang"),
+        Chapter::new(Duration::from_millis(400), "Ende"),
+    ];
+
+    let mut userdata = Userdata::default();
+    userdata.chapter_track_mut().clone_from(&english);
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        chapter_track_language: Language::new(*b"eng").unwrap(),
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    let english_id = tag.chapter_tracks()[0].track_id;
+
+    let mut userdata = Userdata::default();
+    userdata.chapter_track_mut().clone_from(&german);
+    let german_id = english_id + 1;
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        chapter_track_language: Language::new(*b"deu").unwrap(),
+        chapter_track_id: Some(german_id),
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+
+    let tracks = tag.chapter_tracks();
+    assert_eq!(tracks.len(), 2);
+    assert_eq!(tracks[0].track_id, english_id);
+    assert_eq!(tracks[0].language.as_str(), "eng");
+    assert_eq!(tracks[0].chapters, english);
+    assert_eq!(tracks[1].track_id, german_id);
+    assert_eq!(tracks[1].language.as_str(), "deu");
+    assert_eq!(tracks[1].chapters, german);
+    assert_eq!(tag.chapter_track(), english);
+
+    // removing the first chapter track keeps the second one
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        chapter_track_id: Some(english_id),
+        ..WriteConfig::DEFAULT
+    };
+    let output = Userdata::default().write_to_vec(&output, &cfg).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    let tracks = tag.chapter_tracks();
+    assert_eq!(tracks.len(), 1);
+    assert_eq!(tracks[0].track_id, german_id);
+    assert_eq!(tracks[0].chapters, german);
+
+    // the id of a track that isn't a chapter track is rejected
+    let cfg = WriteConfig { chapter_track_id: Some(1), ..cfg };
+    let err = Userdata::default().write_to_vec(&output, &cfg).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::InvalidChapterTrack));
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn write_distinct_chapter_list_and_track() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let list = vec![
+        Chapter::new(Duration::ZERO, "Part 1"),
+        Chapter::new(Duration::from_millis(500), "Part 2"),
+    ];
+    let track = vec![
+        Chapter::new(Duration::ZERO, "Intro"),
+        Chapter::new(Duration::from_millis(250), "Verse"),
+        Chapter::new(Duration::from_millis(500), "Chorus"),
+        Chapter::new(Duration::from_millis(750), "Outro"),
+    ];
+
+    let mut userdata = Userdata::default();
+    userdata.chapter_list_mut().clone_from(&list);
+    userdata.chapter_track_mut().clone_from(&track);
+    let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_list(), list);
+    assert_eq!(tag.chapter_track(), track);
+
+    // change both again, in a single pass
+    userdata.chapter_list_mut().truncate(1);
+    userdata.chapter_track_mut()[1].title = "Bridge".to_owned();
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.chapter_list(), userdata.chapter_list());
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+    assert_eq!(tag.audio_info(), read_tag("files/sample-chaptered.m4a").audio_info());
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chpl_timescale_auto() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let chapters = vec![
+        Chapter::new(Duration::ZERO, "first"),
+        Chapter::new(Duration::from_millis(300), "second"),
+    ];
+    let mut userdata = Userdata::default();
+    userdata.chapter_list_mut().clone_from(&chapters);
+
+    let read_cfg = ReadConfig {
+        chpl_timescale: ChplTimescale::Auto,
+        ..ReadConfig::DEFAULT
+    };
+    for (timescale, expected) in [
+        (ChplTimescale::DEFAULT, 10_000_000),
+        (ChplTimescale::Fixed(1_000.try_into().unwrap()), 1_000),
+    ] {
+        let write_cfg = WriteConfig {
+            write_chapters: WriteChapters::ChapterList,
+            chpl_timescale: timescale,
+            ..WriteConfig::DEFAULT
+        };
+        let output = userdata.write_to_vec(&input, &write_cfg).unwrap();
+        let tag = Tag::read_with(&mut std::io::Cursor::new(&output), &read_cfg).unwrap();
+        assert_eq!(tag.chpl_timescale(), Some(expected));
+        assert_eq!(tag.chapter_list(), chapters);
+    }
+
+    let tag = Tag::read_with_path("files/sample.m4a", &read_cfg).unwrap();
+    assert_eq!(tag.chpl_timescale(), None);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_duration_mismatch() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = Userdata::default();
+    userdata.chapter_track_mut().push(Chapter::new(Duration::ZERO, "chapter"));
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    let mut output = userdata.write_to_vec(&input, &cfg).unwrap();
+
+    let (_, warnings) =
+        Tag::read_with_warnings(&mut std::io::Cursor::new(&output), &ReadConfig::DEFAULT).unwrap();
+    assert!(warnings.is_empty());
+
+    // corrupt the duration of the chapter track header, which is the last version 0 track header
+    let tkhd = output.windows(4).rposition(|w| w == b"tkhd").unwrap();
+    assert_eq!(output[tkhd + 4], 0);
+    output[tkhd + 24..tkhd + 28].copy_from_slice(&1u32.to_be_bytes());
+    // corrupt the duration of the chapter media header
+    let mdhd = output.windows(4).rposition(|w| w == b"mdhd").unwrap();
+    assert_eq!(output[mdhd + 4], 0);
+    output[mdhd + 20..mdhd + 24].copy_from_slice(&1u32.to_be_bytes());
+
+    let (tag, warnings) =
+        Tag::read_with_warnings(&mut std::io::Cursor::new(&output), &ReadConfig::DEFAULT).unwrap();
+    let track_id = tag.chapter_sources().track_id.unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0],
+        ParseWarning::TrackDurationMismatch {
+            track_id,
+            duration: Duration::from_millis(1),
+            movie_duration: tag.duration(),
+        }
+    );
+
+    // rewriting the chapter track corrects both durations
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+    let (tag, warnings) =
+        Tag::read_with_warnings(&mut std::io::Cursor::new(&output), &ReadConfig::DEFAULT).unwrap();
+    assert!(warnings.is_empty());
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+}
+
+/// Removes all top level atoms with the identifier from the file.
+fn strip_atoms(data: &[u8], fourcc: &[u8; 4]) -> Vec<u8> {
+    let mut stripped = Vec::with_capacity(data.len());
+    let mut pos = 0;
+    while pos < data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        if &data[pos + 4..pos + 8] != fourcc {
+            stripped.extend_from_slice(&data[pos..pos + len]);
+        }
+        pos += len;
+    }
+    stripped
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn write_without_mdat() {
+    let input = strip_atoms(&fs::read("files/sample.m4a").unwrap(), b"mdat");
+    let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+
+    // metadata only
+    let mut userdata = Userdata::default();
+    userdata.set_title("template");
+    let cfg_meta = WriteConfig { write_chapters: WriteChapters::None, ..cfg.clone() };
+    let output = userdata.write_to_vec(&input, &cfg_meta).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.title(), Some("template"));
+    assert_eq!(strip_atoms(&output, b"mdat").len(), output.len());
+
+    // a new media data atom is created for the chapter track
+    userdata.chapter_track_mut().push(Chapter::new(Duration::ZERO, "first"));
+    userdata.chapter_track_mut().push(Chapter::new(Duration::from_millis(200), "second"));
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.title(), Some("template"));
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+
+    // the created media data atom is reused
+    userdata.chapter_track_mut()[1].title = "changed".to_owned();
+    let rewritten = userdata.write_to_vec(&output, &cfg).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&rewritten)).unwrap();
+    assert_eq!(tag.chapter_track(), userdata.chapter_track());
+    assert_eq!(rewritten.len(), output.len() + 1);
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_id() {
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::DEFAULT
+    };
+    let mut userdata = Userdata::default();
+    userdata.chapter_track_mut().push(Chapter::new(Duration::ZERO, "first"));
+    let write = |input: &[u8]| {
+        let output = userdata.write_to_vec(input, &cfg).unwrap();
+        let tables = mp4ameta::sample_tables(&mut std::io::Cursor::new(output)).unwrap();
+        let chapter_table = tables.iter().find(|t| t.handler == Some(Fourcc(*b"text")));
+        chapter_table.unwrap().track_id
+    };
+    let position = |data: &[u8], fourcc: &[u8]| data.windows(4).position(|w| w == fourcc).unwrap();
+    let next_track_id = |data: &[u8]| position(data, b"mvhd") + 100..position(data, b"mvhd") + 104;
+    let track_id = |data: &[u8]| position(data, b"tkhd") + 16..position(data, b"tkhd") + 20;
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    // a movie without tracks uses the next track id of the movie header, the chapter track isn't
+    // referenced by any other track though
+    let mut no_tracks = strip_atoms(&input, b"mdat");
+    let trak = position(&no_tracks, b"trak");
+    no_tracks[trak..trak + 4].copy_from_slice(b"free");
+    let expected = u32::from_be_bytes(no_tracks[next_track_id(&no_tracks)].try_into().unwrap());
+    assert_eq!(write(&no_tracks), expected);
+
+    // the largest track id doesn't overflow
+    let mut max_id = input.clone();
+    let (next_track_id, track_id) = (next_track_id(&input), track_id(&input));
+    max_id[next_track_id].copy_from_slice(&u32::MAX.to_be_bytes());
+    max_id[track_id].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert_eq!(write(&max_id), 1);
+}
+
+#[test]
+fn dash_init_segment() {
+    // turn the sample into an initialization segment: no media data, a zero movie duration and a
+    // movie extends header (mehd) containing the duration of all fragments
+    let mut data = strip_atoms(&fs::read("files/sample.m4a").unwrap(), b"mdat");
+    let mvhd = data.windows(4).position(|w| w == b"mvhd").unwrap();
+    assert_eq!(data[mvhd + 4], 0);
+    let timescale = u32::from_be_bytes(data[mvhd + 16..mvhd + 20].try_into().unwrap());
+    let duration = u32::from_be_bytes(data[mvhd + 20..mvhd + 24].try_into().unwrap());
+    data[mvhd + 20..mvhd + 24].copy_from_slice(&0u32.to_be_bytes());
+
+    let mut mvex = Vec::new();
+    mvex.extend(24u32.to_be_bytes());
+    mvex.extend(b"mvex");
+    mvex.extend(16u32.to_be_bytes());
+    mvex.extend(b"mehd");
+    mvex.extend([0; 4]);
+    mvex.extend(duration.to_be_bytes());
+
+    let moov = data.windows(4).position(|w| w == b"moov").unwrap() - 4;
+    let moov_len = u32::from_be_bytes(data[moov..moov + 4].try_into().unwrap());
+    data[moov..moov + 4].copy_from_slice(&(moov_len + mvex.len() as u32).to_be_bytes());
+    let moov_end = moov + moov_len as usize;
+    data.splice(moov_end..moov_end, mvex);
+
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&data)).unwrap();
+    assert_eq!(tag.timescale(), timescale);
+    assert_eq!(tag.raw_duration(), duration as u64);
+    assert_eq!(tag.duration(), Duration::from_millis(486));
+    let duration = mp4ameta::read_duration(&mut std::io::Cursor::new(&data)).unwrap();
+    assert_eq!(duration, tag.duration());
+    #[cfg(feature = "audio-info")]
+    assert_eq!(tag.sample_rate(), Some(SampleRate::Hz44100));
+    #[cfg(feature = "audio-info")]
+    assert_eq!(tag.channel_config(), Some(ChannelConfig::Mono));
+
+    // metadata only writes
+    let mut userdata = tag.userdata.clone();
+    userdata.set_title("init segment");
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::None,
+        verify: true,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&data, &cfg).unwrap();
+    let tag = Tag::read_from(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.title(), Some("init segment"));
+    assert_eq!(tag.duration(), Duration::from_millis(486));
+}
+
+#[test]
+fn read_interned_freeform_idents() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = get_tag_1().userdata;
+    userdata.set_data(DataIdent::freeform("com.example", "custom"), Data::Utf8("data".into()));
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+
+    let tag = Tag::read_from_slice(&output).unwrap();
+    let freeform: Vec<_> = tag
+        .data()
+        .filter_map(|(ident, _)| match ident {
+            DataIdent::Freeform { mean, name } => Some((mean, name)),
+            DataIdent::Fourcc(_) | DataIdent::Mdta(_) => None,
+        })
+        .collect();
+    assert_eq!(freeform.len(), 3);
+    for (mean, name) in freeform {
+        let known = mean == "com.apple.iTunes";
+        assert_eq!(matches!(mean, Cow::Borrowed(_)), known, "{mean}");
+        assert_eq!(matches!(name, Cow::Borrowed(_)), known, "{name}");
+    }
+}
+
+#[test]
+fn itunes_cddb_ids() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = get_tag_1().userdata;
+    userdata.set_cddb_1("A50CB70C+229295+12+150+13512+33855+56190+75615+96857+118390+136737+157692+181407+197895+214470");
+    userdata.set_cddb_ids("12+AD1D0E2BF1FA9D60BF15F30C07A4F7BE+6305463");
+    userdata.set_cddb_track_number("3");
+    // blobs with other type codes are preserved verbatim
+    let blob = Data::Unknown { code: 99, data: b"\x00\x01\x02\xff".to_vec() };
+    userdata.set_data(DataIdent::freeform(ident::APPLE_ITUNES_MEAN, "iTunNORM"), blob.clone());
+    userdata.set_data(
+        DataIdent::freeform(ident::APPLE_ITUNES_MEAN, "iTunes_CDDB_2"),
+        Data::Reserved(vec![0, 7]),
+    );
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.userdata, userdata);
+    assert_eq!(tag.cddb_ids(), Some("12+AD1D0E2BF1FA9D60BF15F30C07A4F7BE+6305463"));
+    assert_eq!(tag.cddb_track_number(), Some("3"));
+    assert!(tag.cddb_1().unwrap().starts_with("A50CB70C+"));
+    assert_eq!(
+        tag.data_of(&ident::CDDB_IDS).next(),
+        Some(&Data::Utf8(tag.cddb_ids().unwrap().into()))
+    );
+
+    // rewriting the tag doesn't change the items
+    let rewritten = tag.userdata.write_to_vec(&output, &WriteConfig::DEFAULT).unwrap();
+    assert_eq!(rewritten, output);
+}
+
+#[test]
+fn read_limits() {
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let tag = Tag::read_with_slice(&input, &ReadConfig::UNTRUSTED).unwrap();
+    assert_eq!(tag, Tag::read_from_slice(&input).unwrap());
+
+    let cfg = ReadConfig { max_depth: 3, ..ReadConfig::DEFAULT };
+    let err = Tag::read_with_slice(&input, &cfg).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::NestingTooDeep));
+
+    #[cfg(feature = "chapters")]
+    for cfg in [
+        ReadConfig { read_chapter_list: false, ..ReadConfig::DEFAULT },
+        ReadConfig { read_chapter_track: false, ..ReadConfig::DEFAULT },
+    ] {
+        let count = tag.chapters().len();
+        let tag =
+            Tag::read_with_slice(&input, &ReadConfig { max_chapter_count: count, ..cfg.clone() })
+                .unwrap();
+        assert_eq!(tag.chapters().len(), count);
+
+        let cfg = ReadConfig { max_chapter_count: count - 1, ..cfg };
+        let err = Tag::read_with_slice(&input, &cfg).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::TooManyChapters));
+    }
+
+    let mut userdata = Userdata::default();
+    userdata.set_data(Fourcc(*b"test"), Data::Reserved(vec![0; 100]));
+    userdata.set_artwork(Img::png(vec![0; 200]));
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+
+    let cfg = ReadConfig {
+        max_data_size: 100,
+        read_image_data: false,
+        ..ReadConfig::DEFAULT
+    };
+    let tag = Tag::read_with_slice(&output, &cfg).unwrap();
+    assert_eq!(tag.bytes_of(&Fourcc(*b"test")).next(), Some(&[0; 100][..]));
+
+    let cfg = ReadConfig { max_data_size: 100, ..ReadConfig::DEFAULT };
+    let err = Tag::read_with_slice(&output, &cfg).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::DataTooLarge));
+    assert_eq!(err.expected_size(), Some(100));
+    assert_eq!(err.found_size(), Some(200));
+}
+
+#[test]
+fn read_decreasing_stsc_first_chunk() {
+    let mut data = fs::read("files/sample-chaptered.m4a").unwrap();
+    // the sample to chunk table of the chapter track
+    let stsc = data.windows(4).rposition(|w| w == b"stsc").unwrap();
+    let entries = [[1u32, 1, 1], [1, 1, 1], [0, 1, 1]];
+    let bytes = entries.iter().flatten().flat_map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+    let old_len = u32::from_be_bytes(data[stsc - 4..stsc].try_into().unwrap()) as usize;
+    let grown = (12 + bytes.len() - (old_len - 4)) as u32;
+    data.splice(stsc + 8..stsc - 4 + old_len, 3u32.to_be_bytes().into_iter().chain(bytes));
+    for fourcc in [b"stsc", b"stbl", b"minf", b"mdia", b"trak", b"moov"] {
+        let pos = data[..stsc + 4].windows(4).rposition(|w| w == fourcc).unwrap() - 4;
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap());
+        data[pos..pos + 4].copy_from_slice(&(len + grown).to_be_bytes());
+    }
+
+    for cfg in [ReadConfig::UNTRUSTED, ReadConfig::DEFAULT] {
+        let res = Tag::read_with_slice(&data, &cfg);
+        #[cfg(feature = "chapters")]
+        assert!(matches!(res.unwrap_err().kind, ErrorKind::InvalidSampleTable));
+        #[cfg(not(feature = "chapters"))]
+        assert!(res.is_ok());
+    }
+    let err = mp4ameta::compact_in(&mut std::io::Cursor::new(data)).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::InvalidSampleTable));
+}
+
+#[test]
+fn shallow_data() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = Userdata::default();
+    userdata.set_title("title");
+    userdata.set_artwork(Img::png(vec![7; 200]));
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+
+    let cfg = ReadConfig { shallow_data_size: 100, ..ReadConfig::DEFAULT };
+    let mut tag = Tag::read_with_slice(&output, &cfg).unwrap();
+    assert_eq!(tag.title(), Some("title"));
+    assert_eq!(tag.artwork(), None);
+    let data = tag.data_of(&ident::ARTWORK).next().unwrap();
+    assert!(matches!(data, Data::Shallow { code: 14, len: 200, .. }));
+    let read = data.get_or_read(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(*read, Data::Png(vec![7; 200]));
+    let err = data.get_or_read(&mut std::io::Cursor::new(&input)).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::InvalidShallowData));
+
+    // shallow data is read from the file before it's written
+    tag.set_title("new title");
+    let written = tag.write_to_vec(&output, &WriteConfig::DEFAULT).unwrap();
+    let written = Tag::read_from_slice(&written).unwrap();
+    assert_eq!(written.title(), Some("new title"));
+    assert_eq!(written.artwork(), Some(Img::png(&[7; 200][..])));
+
+    tag.read_shallow_data(&mut std::io::Cursor::new(&output)).unwrap();
+    assert_eq!(tag.artwork(), Some(Img::png(&[7; 200][..])));
+
+    // a data atom with an extended 64 bit size
+    let mut extended = vec![0, 0, 0, 1];
+    extended.extend(b"data");
+    extended.extend(28u64.to_be_bytes());
+    extended.extend([0, 0, 0, 14, 0, 0, 0, 0, 1, 2, 3, 4]);
+    let data = Data::Shallow { code: 14, pos: 24, len: 4 };
+    let read = data.get_or_read(&mut std::io::Cursor::new(&extended)).unwrap();
+    assert_eq!(*read, Data::Png(vec![1, 2, 3, 4]));
+}
+
+#[test]
+#[cfg(feature = "chapters")]
+fn chapter_track_sample_count_bomb() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = Userdata::default();
+    *userdata.chapter_track_mut() =
+        vec![Chapter::new(Duration::ZERO, "first"), Chapter::new(Duration::from_secs(1), "second")];
+    let cfg = WriteConfig {
+        write_chapters: WriteChapters::ChapterTrack,
+        ..WriteConfig::DEFAULT
+    };
+    let mut data = userdata.write_to_vec(&input, &cfg).unwrap();
+
+    let find_last = |data: &[u8], fourcc: &[u8; 4]| data.windows(4).rposition(|w| w == fourcc);
+    // make the chapter track's sample sizes uniform, and turn the size table into a free atom
+    let pos = find_last(&data, b"stsz").unwrap() - 4;
+    data[pos..pos + 4].copy_from_slice(&20u32.to_be_bytes());
+    data[pos + 12..pos + 16].copy_from_slice(&4u32.to_be_bytes());
+    data[pos + 20..pos + 28].copy_from_slice(&[0, 0, 0, 8, b'f', b'r', b'e', b'e']);
+    // declare billions of samples in the other tables
+    let pos = find_last(&data, b"stsc").unwrap() - 4;
+    data[pos + 20..pos + 24].copy_from_slice(&u32::MAX.to_be_bytes());
+    let pos = find_last(&data, b"stts").unwrap() - 4;
+    data[pos + 16..pos + 20].copy_from_slice(&u32::MAX.to_be_bytes());
+
+    let err = Tag::read_from_slice(&data).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::InvalidSampleTable), "{err:?}");
+}
+
+/// Returns whether an atom exists at the path, for example `["moov", "udta", "meta", "ilst"]`.
+fn has_atom(data: &[u8], path: &[&[u8; 4]]) -> bool {
+    let Some((fourcc, rest)) = path.split_first() else {
+        return true;
+    };
+    let mut pos = 0;
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        if &data[pos + 4..pos + 8] == *fourcc {
+            // the metadata atom is a full atom, except for QuickTime files
+            let full = *fourcc == b"meta" && &data[pos + 12..pos + 16] != b"hdlr";
+            let content_pos = if full { pos + 12 } else { pos + 8 };
+            if has_atom(&data[content_pos..pos + len], rest) {
+                return true;
+            }
+        }
+        pos += len;
+    }
+    false
+}
+
+#[test]
+fn item_list_location() {
+    const UDTA_ILST: &[&[u8; 4]] = &[b"moov", b"udta", b"meta", b"ilst"];
+    const MOOV_ILST: &[&[u8; 4]] = &[b"moov", b"meta", b"ilst"];
+
+    let input = fs::read("files/sample.m4a").unwrap();
+    assert!(has_atom(&input, UDTA_ILST));
+    assert!(!has_atom(&input, MOOV_ILST));
+
+    let write = |input: &[u8], userdata: &Userdata, location| {
+        let cfg = WriteConfig {
+            item_list_location: location,
+            verify: true,
+            ..WriteConfig::DEFAULT
+        };
+        userdata.write_to_vec(input, &cfg).unwrap()
+    };
+
+    // move the item list into the movie atom
+    let userdata = get_tag_1().userdata;
+    let moved = write(&input, &userdata, ItemListLocation::Movie);
+    assert!(!has_atom(&moved, UDTA_ILST));
+    assert!(has_atom(&moved, MOOV_ILST));
+    assert_tag_1(&Tag::read_from_slice(&moved).unwrap());
+
+    // existing locations are preferred
+    let userdata = get_tag_2().userdata;
+    let output = write(&moved, &userdata, ItemListLocation::PreferExisting);
+    assert!(!has_atom(&output, UDTA_ILST));
+    assert!(has_atom(&output, MOOV_ILST));
+    assert_tag_2(&Tag::read_from_slice(&output).unwrap());
+
+    // if both exist, the one inside the user data atom takes precedence
+    let mut both = input.clone();
+    let moov_pos = both.windows(4).position(|w| w == b"moov").unwrap() - 4;
+    let moov_len = u32::from_be_bytes(both[moov_pos..moov_pos + 4].try_into().unwrap());
+    let meta_pos = moved.windows(4).rposition(|w| w == b"meta").unwrap() - 4;
+    let meta_len = u32::from_be_bytes(moved[meta_pos..meta_pos + 4].try_into().unwrap());
+    let meta = moved[meta_pos..meta_pos + meta_len as usize].to_vec();
+    let moov_end = moov_pos + moov_len as usize;
+    both.splice(moov_end..moov_end, meta);
+    both[moov_pos..moov_pos + 4].copy_from_slice(&(moov_len + meta_len).to_be_bytes());
+    // the media data is located after the movie atom, so the chunk offsets have to be shifted
+    let stco_pos = both.windows(4).position(|w| w == b"stco").unwrap() - 4;
+    let num_entries = u32::from_be_bytes(both[stco_pos + 12..stco_pos + 16].try_into().unwrap());
+    for i in 0..num_entries as usize {
+        let pos = stco_pos + 16 + 4 * i;
+        let offset = u32::from_be_bytes(both[pos..pos + 4].try_into().unwrap());
+        both[pos..pos + 4].copy_from_slice(&(offset + meta_len).to_be_bytes());
+    }
+    assert!(has_atom(&both, UDTA_ILST));
+    assert!(has_atom(&both, MOOV_ILST));
+    assert_eq!(Tag::read_from_slice(&both).unwrap(), Tag::read_from_slice(&input).unwrap());
+
+    // and both are written
+    let output = write(&both, &userdata, ItemListLocation::PreferExisting);
+    assert!(has_atom(&output, UDTA_ILST));
+    assert!(has_atom(&output, MOOV_ILST));
+    assert_tag_2(&Tag::read_from_slice(&output).unwrap());
+    let udta_stripped = write(&output, &userdata, ItemListLocation::Movie);
+    assert!(!has_atom(&udta_stripped, UDTA_ILST));
+    assert_tag_2(&Tag::read_from_slice(&udta_stripped).unwrap());
+}
+
+#[test]
+fn mdta_keys() {
+    const UDTA_KEYS: &[&[u8; 4]] = &[b"moov", b"udta", b"meta", b"keys"];
+    const MOOV_KEYS: &[&[u8; 4]] = &[b"moov", b"meta", b"keys"];
+    let make = DataIdent::mdta("com.apple.quicktime.make");
+    let model = DataIdent::mdta("com.apple.quicktime.model");
+
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut userdata = get_tag_1().userdata;
+    userdata.set_data(make.clone(), Data::Utf8("Apple".into()));
+    userdata.set_data(model.clone(), Data::Utf8("iPhone".into()));
+    let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    assert!(has_atom(&output, UDTA_KEYS));
+
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_tag_1(&tag);
+    assert_eq!(tag.strings_of(&make).next(), Some("Apple"));
+    assert_eq!(tag.strings_of(&model).next(), Some("iPhone"));
+    assert_eq!(tag.data().filter(|(i, _)| matches!(i, DataIdent::Mdta(_))).count(), 2);
+
+    // removing all keys removes the keys atom
+    let mut userdata = tag.userdata;
+    userdata.remove_data_of(&make);
+    userdata.remove_data_of(&model);
+    let output = userdata.write_to_vec(&output, &cfg).unwrap();
+    assert!(!has_atom(&output, UDTA_KEYS));
+    assert_tag_1(&Tag::read_from_slice(&output).unwrap());
+
+    // QuickTime stores the keys inside `moov/meta`, which lacks the version and flags
+    let mut userdata = Userdata::default();
+    userdata.set_data(make.clone(), Data::Utf8("Apple".into()));
+    let cfg = WriteConfig {
+        item_list_location: ItemListLocation::Movie,
+        ..WriteConfig::DEFAULT
+    };
+    let mut quicktime = userdata.write_to_vec(&input, &cfg).unwrap();
+    assert!(has_atom(&quicktime, MOOV_KEYS));
+    let moov_pos = quicktime.windows(4).position(|w| w == b"moov").unwrap() - 4;
+    let meta_pos = quicktime.windows(4).rposition(|w| w == b"meta").unwrap() - 4;
+    for pos in [moov_pos, meta_pos] {
+        let len = u32::from_be_bytes(quicktime[pos..pos + 4].try_into().unwrap());
+        quicktime[pos..pos + 4].copy_from_slice(&(len - 4).to_be_bytes());
+    }
+    quicktime.drain(meta_pos + 8..meta_pos + 12);
+    let stco_pos = quicktime.windows(4).position(|w| w == b"stco").unwrap() - 4;
+    let offset = u32::from_be_bytes(quicktime[stco_pos + 16..stco_pos + 20].try_into().unwrap());
+    quicktime[stco_pos + 16..stco_pos + 20].copy_from_slice(&(offset - 4).to_be_bytes());
+
+    let tag = Tag::read_from_slice(&quicktime).unwrap();
+    assert_eq!(tag.strings_of(&make).next(), Some("Apple"));
+
+    let mut userdata = tag.userdata;
+    userdata.set_data(model.clone(), Data::Utf8("iPhone".into()));
+    let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+    let output = userdata.write_to_vec(&quicktime, &cfg).unwrap();
+    assert!(has_atom(&output, MOOV_KEYS));
+    assert!(!has_atom(&output, UDTA_KEYS));
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.strings_of(&make).next(), Some("Apple"));
+    assert_eq!(tag.strings_of(&model).next(), Some("iPhone"));
+}
+
+#[test]
+fn update_encoder() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let read = |data: &[u8]| Tag::read_from(&mut std::io::Cursor::new(data)).unwrap();
+
+    let mut userdata = Userdata::default();
+    userdata.set_encoder("encoder 1");
+    let cfg = WriteConfig {
+        update_encoder: Some("encoder 2".into()),
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(userdata.encoder(), Some("encoder 1"));
+
+    let tag = read(&output);
+    assert_eq!(tag.encoder(), Some("encoder 2"));
+    assert_eq!(tag.strings_of(&ident::ORIGINAL_ENCODER).next(), Some("encoder 1"));
+
+    // the first encoder is kept
+    let cfg = WriteConfig {
+        update_encoder: Some("encoder 3".into()),
+        ..WriteConfig::DEFAULT
+    };
+    let tag = read(&tag.write_to_vec(&output, &cfg).unwrap());
+    assert_eq!(tag.encoder(), Some("encoder 3"));
+    assert_eq!(tag.strings_of(&ident::ORIGINAL_ENCODER).collect::<Vec<_>>(), ["encoder 1"]);
+
+    // the previous encoder is discarded
+    let cfg = WriteConfig {
+        update_encoder: Some("encoder 2".into()),
+        original_encoder_ident: None,
+        ..WriteConfig::DEFAULT
+    };
+    let tag = read(&userdata.write_to_vec(&input, &cfg).unwrap());
+    assert_eq!(tag.encoder(), Some("encoder 2"));
+    assert_eq!(tag.strings_of(&ident::ORIGINAL_ENCODER).next(), None);
+}
+
+#[test]
+fn padding() {
+    const META_FREE: &[&[u8; 4]] = &[b"moov", b"udta", b"meta", b"free"];
+    let mdat_pos = |data: &[u8]| {
+        let mut pos = 0;
+        loop {
+            let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+            if &data[pos + 4..pos + 8] == b"mdat" {
+                return pos;
+            }
+            pos += len;
+        }
+    };
+    let write = |input: &[u8], title: &str, padding| {
+        let mut userdata = Userdata::default();
+        userdata.set_title(title);
+        let cfg = WriteConfig { padding, verify: true, ..WriteConfig::DEFAULT };
+        let output = userdata.write_to_vec(input, &cfg).unwrap();
+        assert_eq!(Tag::read_from_slice(&output).unwrap().title(), Some(title));
+        output
+    };
+
+    // without configured padding, none is inserted
+    let input = fs::read("files/sample-multi-track.3gp").unwrap();
+    assert!(!has_atom(&write(&input, "title", 0), META_FREE));
+
+    // padding is inserted, since the media data has to be moved anyway
+    let padded = write(&input, "title", 1024);
+    assert!(has_atom(&padded, META_FREE));
+    assert!(mdat_pos(&padded) > mdat_pos(&input));
+
+    // growing and shrinking the movie atom is absorbed by the padding
+    for title in ["a longer title", "", &"a".repeat(1024)] {
+        let output = write(&padded, title, 0);
+        assert_eq!(mdat_pos(&output), mdat_pos(&padded));
+        assert_eq!(output.len(), padded.len());
+    }
+
+    // the padding is too small, so the media data is moved and new padding is inserted
+    let output = write(&padded, &"a".repeat(2048), 512);
+    assert!(mdat_pos(&output) > mdat_pos(&padded));
+    let grown = write(&output, &"a".repeat(2500), 0);
+    assert_eq!(mdat_pos(&grown), mdat_pos(&output));
+    assert!(has_atom(&grown, META_FREE));
+
+    // existing padding is left alone, if it's too small and no padding is configured
+    let output = write(&padded, &"a".repeat(2048), 0);
+    assert!(mdat_pos(&output) > mdat_pos(&padded));
+    assert!(has_atom(&output, META_FREE));
+}
+
+#[test]
+fn image_data_types() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let heic = b"\0\0\0\x18ftypheic\0\0\0\0mif1heic".to_vec();
+    let webp = b"RIFF\x24\0\0\0WEBPVP8 ".to_vec();
+
+    let mut userdata = Userdata::default();
+    userdata.set_artwork(Img::new(ImgFmt::Heic, heic.clone()));
+    userdata.add_data(ident::ARTWORK, Data::Image { code: 99, fmt: ImgFmt::WebP, data: webp });
+    userdata.add_artwork(Img::gif(b"GIF89a".to_vec()));
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+
+    // images with other type codes round trip unchanged
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(
+        tag.data_of(&ident::ARTWORK).collect::<Vec<_>>(),
+        userdata.data_of(&ident::ARTWORK).collect::<Vec<_>>()
+    );
+    let fmts = tag.artworks().map(|i| i.fmt).collect::<Vec<_>>();
+    assert_eq!(fmts, [ImgFmt::Heic, ImgFmt::WebP, ImgFmt::Gif]);
+    assert_eq!(tag.artwork().unwrap().data, heic);
+
+    let cfg = ReadConfig { read_image_data: false, ..ReadConfig::DEFAULT };
+    let tag = Tag::read_with_slice(&output, &cfg).unwrap();
+    assert_eq!(tag.artworks().map(|i| i.fmt).collect::<Vec<_>>(), fmts);
+}
+
+#[test]
+fn replace_artwork_in_place() {
+    use mp4ameta::artwork::replace_artwork_in_place_in;
+
+    let mut userdata = get_tag_1().userdata;
+    userdata.set_artwork(Img::png(vec![1; 1000]));
+    let input = fs::read("files/sample.m4a").unwrap();
+    let input = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+
+    let replace = |image| {
+        let mut file = std::io::Cursor::new(input.clone());
+        let replaced = replace_artwork_in_place_in(&mut file, image).unwrap();
+        (replaced, file.into_inner())
+    };
+
+    for image in [
+        Img::jpeg(vec![2; 1000]),
+        Img::png(vec![3; 992]),
+        Img::bmp(vec![4; 10]),
+        Img::gif(vec![7; 500]),
+    ] {
+        let (replaced, output) = replace(image.clone());
+        assert!(replaced);
+        assert_eq!(output.len(), input.len());
+        let tag = Tag::read_from_slice(&output).unwrap();
+        assert_eq!(tag.artworks().collect::<Vec<_>>(), [Img::new(image.fmt, &image.data[..])]);
+        assert_eq!(tag.title(), userdata.title());
+    }
+
+    // the remaining space is too small for a free atom, or the image is too large
+    for image in [Img::png(vec![5; 995]), Img::png(vec![6; 1001])] {
+        let (replaced, output) = replace(image);
+        assert!(!replaced);
+        assert_eq!(output, input);
+    }
+
+    // no artwork
+    let input = fs::read("files/sample-multi-track.3gp").unwrap();
+    let mut file = std::io::Cursor::new(input.clone());
+    assert!(!replace_artwork_in_place_in(&mut file, Img::png(vec![0; 10])).unwrap());
+    assert_eq!(file.into_inner(), input);
+}
+
+#[test]
+fn same_length_item_change() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let mut tag = Tag::read_from_slice(&input).unwrap();
+    let title = tag.title().unwrap().to_owned();
+    let new_title = "x".repeat(title.len());
+    tag.set_title(new_title.as_str());
+
+    let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+    let output = tag.write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(output.len(), input.len());
+
+    // only the bytes of the title are patched
+    let changed = input.iter().zip(output.iter()).filter(|(a, b)| a != b).count();
+    assert!(changed <= title.len());
+
+    let written = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(written.title(), Some(new_title.as_str()));
+    assert_eq!(written.artist(), tag.artist());
+    assert_eq!(written.album(), tag.album());
+}
+
+#[cfg(feature = "normalization")]
+#[test]
+fn normalize_strings() {
+    let input = fs::read("files/sample.m4a").unwrap();
+
+    let mut userdata = Userdata::default();
+    userdata.set_artist("Beyonce\u{301}");
+    userdata.chapter_list_mut().push(Chapter::new(Duration::ZERO, "Cafe\u{301}"));
+    let cfg = WriteConfig {
+        normalize_strings: Some(mp4ameta::UnicodeForm::Nfc),
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(userdata.artist(), Some("Beyonce\u{301}"));
+
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.artist(), Some("Beyonc\u{e9}"));
+    assert_eq!(tag.chapter_list()[0].title, "Caf\u{e9}");
+}
+
+#[test]
+fn utf16_strings() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let isrc = FreeformIdent::new_static("com.apple.iTunes", "ISRC");
+
+    let mut userdata = Userdata::default();
+    userdata.set_title("title ♫");
+    userdata.set_data(isrc, Data::Utf8("isrc".into()));
+    let cfg = WriteConfig {
+        string_encoding: TextEncoding::Utf16Be,
+        ..WriteConfig::DEFAULT
+    };
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(userdata.data_of(&ident::TITLE).next(), Some(&Data::Utf8("title ♫".into())));
+
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.data_of(&ident::TITLE).next(), Some(&Data::Utf16("title ♫".into())));
+    assert_eq!(tag.data_of(&isrc).next(), Some(&Data::Utf8("isrc".into())));
+    let mut encoded = vec![0, 0, 0, 2, 0, 0, 0, 0, 0xfe, 0xff];
+    encoded.extend("title ♫".encode_utf16().flat_map(u16::to_be_bytes));
+    assert!(output.windows(encoded.len()).any(|w| w == encoded));
+
+    // strings without a byte order mark are big endian
+    let mut userdata = Userdata::default();
+    let be = "be".encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let le = [0xff, 0xfe].into_iter().chain("le".encode_utf16().flat_map(u16::to_le_bytes));
+    userdata.add_data(ident::ARTIST, Data::Unknown { code: 2, data: be });
+    userdata.add_data(ident::ARTIST, Data::Unknown { code: 2, data: le.collect() });
+    let output = userdata.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.artists().collect::<Vec<_>>(), ["be", "le"]);
+}
+
+#[test]
+fn revision() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let read = |data: &[u8]| Tag::read_from_slice(data).unwrap();
+    let revision = |tag: &Tag| tag.strings_of(&ident::REVISION).next().map(str::to_owned);
+
+    // disabled by default
+    let tag = read(&input);
+    let output = tag.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+    assert_eq!(revision(&read(&output)), None);
+
+    let cfg = WriteConfig {
+        revision_ident: Some(ident::REVISION_IDENT),
+        ..WriteConfig::DEFAULT
+    };
+    let output = tag.write_to_vec(&input, &cfg).unwrap();
+    let tag = read(&output);
+    assert_eq!(revision(&tag).as_deref(), Some("1"));
+    let output = tag.write_to_vec(&output, &cfg).unwrap();
+    assert_eq!(revision(&read(&output)).as_deref(), Some("2"));
+
+    // a stale userdata doesn't decrease the revision of the file
+    let output = tag.write_to_vec(&output, &cfg).unwrap();
+    assert_eq!(revision(&read(&output)).as_deref(), Some("3"));
+    assert_eq!(revision(&tag).as_deref(), Some("1"));
+}
+
+/// Inserts bytes at the end of the atom at the path, before the last `back` bytes of its content,
+/// and updates the length of it and all its parents.
+fn insert_in_atom(
+    data: &mut Vec<u8>,
+    base: usize,
+    end: usize,
+    path: &[&[u8; 4]],
+    back: usize,
+    bytes: &[u8],
+) -> bool {
+    let Some((fourcc, rest)) = path.split_first() else {
+        data.splice(end - back..end - back, bytes.iter().copied());
+        return true;
+    };
+    let mut pos = base;
+    while pos + 8 <= end {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        if &data[pos + 4..pos + 8] == *fourcc {
+            let full = *fourcc == b"meta" && &data[pos + 12..pos + 16] != b"hdlr";
+            let content_pos = if full { pos + 12 } else { pos + 8 };
+            if insert_in_atom(data, content_pos, pos + len, rest, back, bytes) {
+                let len = (len + bytes.len()) as u32;
+                data[pos..pos + 4].copy_from_slice(&len.to_be_bytes());
+                return true;
+            }
+        }
+        pos += len;
+    }
+    false
+}
+
+#[test]
+fn handler_name() {
+    const META_HDLR: &[&[u8; 4]] = &[b"moov", b"udta", b"meta", b"hdlr"];
+    let mut input = fs::read("files/sample-chaptered.m4a").unwrap();
+    assert_eq!(Tag::read_from_slice(&input).unwrap().handler_name(), None);
+
+    // the media data precedes the movie atom, so no chunk offsets have to be updated
+    let len = input.len();
+    assert!(insert_in_atom(&mut input, 0, len, META_HDLR, 1, b"mp4v2"));
+    let tag = Tag::read_from_slice(&input).unwrap();
+    assert_eq!(tag.handler_name(), Some("mp4v2"));
+
+    let mut tag = tag;
+    tag.set_title("new title");
+    let output = tag.write_to_vec(&input, &WriteConfig::DEFAULT).unwrap();
+    assert_eq!(Tag::read_from_slice(&output).unwrap().handler_name(), Some("mp4v2"));
+
+    // moving the item list reuses the handler
+    let cfg = WriteConfig {
+        item_list_location: ItemListLocation::Movie,
+        ..WriteConfig::DEFAULT
+    };
+    let output = tag.write_to_vec(&input, &cfg).unwrap();
+    assert!(!has_atom(&output, &[b"moov", b"udta", b"meta", b"ilst"]));
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.handler_name(), Some("mp4v2"));
+    assert_eq!(tag.title(), Some("new title"));
+}
+
+#[test]
+fn compact() {
+    use mp4ameta::{ChecksumAlgorithm, audio_checksum, compact_in};
+
+    let checksum = |data: &[u8]| {
+        audio_checksum(&mut std::io::Cursor::new(data), ChecksumAlgorithm::Crc32).unwrap()
+    };
+    let compact = |data: &[u8]| {
+        let mut file = std::io::Cursor::new(data.to_vec());
+        let removed = compact_in(&mut file).unwrap();
+        (removed, file.into_inner())
+    };
+
+    // all media data is referenced
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    assert_eq!(compact(&input), (0, input.clone()));
+
+    // unreferenced data at the end of the media data
+    let mdat_pos = input.windows(4).position(|w| w == b"mdat").unwrap() - 4;
+    let mdat_len = u32::from_be_bytes(input[mdat_pos..mdat_pos + 4].try_into().unwrap());
+    let mut padded = input.clone();
+    padded[mdat_pos..mdat_pos + 4].copy_from_slice(&(mdat_len + 100).to_be_bytes());
+    let mdat_end = mdat_pos + mdat_len as usize;
+    padded.splice(mdat_end..mdat_end, [0xaa; 100]);
+    assert_eq!(compact(&padded), (100, input.clone()));
+
+    // the samples of the chapter track become unreferenced, when the track is hidden, the ones of
+    // the audio track are moved
+    let mut orphaned = input.clone();
+    let trak_pos = orphaned.windows(4).rposition(|w| w == b"trak").unwrap();
+    orphaned[trak_pos..trak_pos + 4].copy_from_slice(b"free");
+    let (removed, output) = compact(&orphaned);
+    assert_eq!(removed, 0x18 + 0x16 + 0x1a);
+    assert_eq!(output.len(), input.len() - removed as usize);
+    assert_eq!(checksum(&output), checksum(&input));
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.title(), Tag::read_from_slice(&input).unwrap().title());
+
+    // a track without a chunk offset table leaves the file untouched
+    let mut incomplete = padded.clone();
+    let stco_pos = incomplete.windows(4).rposition(|w| w == b"stco").unwrap();
+    incomplete[stco_pos..stco_pos + 4].copy_from_slice(b"free");
+    assert_eq!(compact(&incomplete), (0, incomplete.clone()));
+}
+
+#[test]
+fn item_order() {
+    use mp4ameta::ItemOrder;
+
+    let input = fs::read("files/sample.m4a").unwrap();
+    let idents = |u: &Userdata| u.data().map(|(i, _)| i.to_string()).collect::<Vec<_>>();
+    let cfg = WriteConfig { write_meta_items: true, ..WriteConfig::NONE };
+
+    let mut userdata = Userdata::default();
+    userdata.set_isrc("isrc");
+    userdata.set_year("2013");
+    userdata.set_artwork(Img::png(vec![0x89, b'P', b'N', b'G']));
+    userdata.set_artist("artist");
+    userdata.set_title("title");
+
+    // file order is preserved
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(idents(&tag), idents(&userdata));
+
+    userdata.sort_items(ItemOrder::Apple);
+    assert_eq!(idents(&userdata), ["©nam", "©ART", "©day", "covr", "----:com.apple.iTunes:ISRC"]);
+    let output = userdata.write_to_vec(&input, &cfg).unwrap();
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(idents(&tag), idents(&userdata));
+
+    userdata.sort_items(ItemOrder::Alphabetical);
+    assert_eq!(idents(&userdata), ["----:com.apple.iTunes:ISRC", "covr", "©ART", "©day", "©nam"]);
+}
+
+#[test]
+fn preserve_layout_round_trip() {
+    let cfg = WriteConfig { preserve_layout: true, ..WriteConfig::DEFAULT };
+    read_dir("files", |path, tag| {
+        let input = fs::read(path).unwrap();
+        let output = tag.write_to_vec(&input, &cfg).unwrap();
+        assert!(output == input, "{path:?} changed");
+    });
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn testing_audio_guard() {
+    use mp4ameta::testing::{AudioGuard, assert_audio_eq, write_to_vec_checked};
+
+    let input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let mut userdata = get_tag_1().userdata;
+    userdata.set_title("a much longer title that grows the movie atom".repeat(100));
+    *userdata.chapter_track_mut() = vec![Chapter::new(Duration::ZERO, "chapter")];
+    let output = write_to_vec_checked(&userdata, &input, &WriteConfig::DEFAULT).unwrap();
+    assert_audio_eq(&input, &output);
+
+    // corrupt a byte of the audio data
+    let guard = AudioGuard::new(&mut std::io::Cursor::new(&input)).unwrap();
+    let mut file = std::io::Cursor::new(&output);
+    let table = mp4ameta::sample_tables(&mut file).unwrap().remove(0);
+    let offset = table.chunk_offsets(&mut file).next().unwrap().unwrap();
+    let mut corrupted = output.clone();
+    corrupted[offset as usize] ^= 0xff;
+    let err = guard.verify(&mut std::io::Cursor::new(&corrupted)).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::VerificationFailed));
+}
+
+#[test]
+fn three_gpp_brands() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    for brand in [*b"3gp4", *b"3gp5", *b"3gp6", *b"3g2a"] {
+        let cfg = WriteConfig {
+            set_major_brand: Some(Fourcc(brand)),
+            verify: true,
+            ..WriteConfig::DEFAULT
+        };
+        let branded = get_tag_1().write_to_vec(&input, &cfg).unwrap();
+        assert_eq!(branded[8..12], brand);
+        let tag = Tag::read_from_slice(&branded).unwrap();
+        assert_tag_1(&tag);
+
+        let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+        let output = get_tag_2().write_to_vec(&branded, &cfg).unwrap();
+        assert_eq!(output[8..12], brand);
+        assert_tag_2(&Tag::read_from_slice(&output).unwrap());
+    }
+
+    // a recording with a 3gp5 major brand and 3gp4 compatible brand
+    let input = fs::read("files/sample-multi-track.3gp").unwrap();
+    assert_eq!(input[8..12], *b"3gp5");
+    let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+    let output = get_tag_1().write_to_vec(&input, &cfg).unwrap();
+    assert_tag_1(&Tag::read_from_slice(&output).unwrap());
+}
+
+#[test]
+fn brand_policy() {
+    let input = fs::read("files/sample.m4a").unwrap();
+    let tag = Tag::read_from_slice(&input).unwrap();
+
+    // unusual brands are accepted by default
+    for brand in [*b"dash", *b"M4VH", *b"qt  "] {
+        let cfg = WriteConfig {
+            set_major_brand: Some(Fourcc(brand)),
+            ..WriteConfig::DEFAULT
+        };
+        let branded = tag.write_to_vec(&input, &cfg).unwrap();
+        assert_eq!(Tag::read_from_slice(&branded).unwrap().userdata, tag.userdata);
+    }
+
+    let strict = |brands: &[Fourcc]| ReadConfig {
+        brand_policy: BrandPolicy::Strict(brands.to_vec().into()),
+        ..ReadConfig::DEFAULT
+    };
+    // the compatible brands are also checked
+    let cfg = strict(&[Fourcc(*b"isom")]);
+    assert_eq!(Tag::read_with_slice(&input, &cfg).unwrap().userdata, tag.userdata);
+    let cfg = strict(&[Fourcc(*b"dash"), Fourcc(*b"qt  ")]);
+    let err = Tag::read_with_slice(&input, &cfg).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::UnsupportedBrand(Fourcc(b)) if b == *b"M4A "));
+
+    // replace the filetype atom with a free atom
+    let mut stripped = input.clone();
+    stripped[4..8].copy_from_slice(b"free");
+    let err = Tag::read_from_slice(&stripped).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::NoFtyp));
+    let cfg = ReadConfig {
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::DEFAULT
+    };
+    let skipped = Tag::read_with_slice(&stripped, &cfg).unwrap();
+    assert_eq!(skipped.ftyp, "");
+    assert_eq!(skipped.userdata, tag.userdata);
+    assert_eq!(Tag::read_with_slice(&input, &cfg).unwrap(), tag);
+}
+
+#[test]
+fn quicktime_without_ftyp() {
+    let mut input = fs::read("files/sample.m4a").unwrap();
+    // replace the filetype atom with a free atom, like a classic QuickTime file
+    input[4..8].copy_from_slice(b"free");
+    let read_cfg = ReadConfig {
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::DEFAULT
+    };
+
+    let cfg = WriteConfig { verify: true, ..WriteConfig::DEFAULT };
+    let output = get_tag_1().write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(output[4..8], *b"free");
+    let tag = Tag::read_with_slice(&output, &read_cfg).unwrap();
+    assert_eq!(tag.ftyp, "");
+    assert_tag_1(&tag);
+
+    let output = get_tag_2().write_to_vec(&output, &cfg).unwrap();
+    assert_tag_2(&Tag::read_with_slice(&output, &read_cfg).unwrap());
+
+    // a filetype atom is only added if a major brand is set
+    let cfg = WriteConfig { set_major_brand: Some(Fourcc(*b"qt  ")), ..cfg };
+    let output = get_tag_1().write_to_vec(&input, &cfg).unwrap();
+    assert_eq!(output[4..12], *b"ftypqt  ");
+    let tag = Tag::read_from_slice(&output).unwrap();
+    assert_eq!(tag.ftyp, "qt  \0\0\0\0qt  ");
+    assert_tag_1(&tag);
+
+    // other files are still rejected
+    input[4..8].copy_from_slice(b"abcd");
+    let err = get_tag_1().write_to_vec(&input, &cfg).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::NoFtyp));
+}
+
+#[test]
+fn quicktime_without_ftyp_replace_artwork() {
+    use mp4ameta::artwork::replace_artwork_in_place_in;
+
+    let mut input = fs::read("files/sample.m4a").unwrap();
+    input[4..8].copy_from_slice(b"free");
+    let read_cfg = ReadConfig {
+        brand_policy: BrandPolicy::Skip,
+        ..ReadConfig::DEFAULT
+    };
+
+    let image = Img::png(vec![1; 100]);
+    let mut file = std::io::Cursor::new(input);
+    assert!(replace_artwork_in_place_in(&mut file, image.clone()).unwrap());
+    let tag = Tag::read_with_slice(file.get_ref(), &read_cfg).unwrap();
+    assert_eq!(tag.artwork(), Some(Img::new(image.fmt, &image.data[..])));
+}
+
+#[test]
+fn quicktime_without_ftyp_compact() {
+    let mut input = fs::read("files/sample-chaptered.m4a").unwrap();
+    input[4..8].copy_from_slice(b"free");
+
+    let mut file = std::io::Cursor::new(input.clone());
+    assert_eq!(mp4ameta::compact_in(&mut file).unwrap(), 0);
+    assert_eq!(file.into_inner(), input);
+}
+
+#[test]
+fn quicktime_without_ftyp_audio_checksum() {
+    use mp4ameta::ChecksumAlgorithm;
+
+    let mut input = fs::read("files/sample.m4a").unwrap();
+    let checksum =
+        mp4ameta::audio_checksum(&mut std::io::Cursor::new(&input), ChecksumAlgorithm::Crc32);
+    input[4..8].copy_from_slice(b"free");
+    let qt_checksum =
+        mp4ameta::audio_checksum(&mut std::io::Cursor::new(&input), ChecksumAlgorithm::Crc32);
+    assert_eq!(qt_checksum.unwrap(), checksum.unwrap());
+}
+
+#[test]
+fn quicktime_without_ftyp_sample_tables() {
+    let mut input = fs::read("files/sample-chaptered.m4a").unwrap();
+    let tables = mp4ameta::sample_tables(&mut std::io::Cursor::new(&input)).unwrap();
+    input[4..8].copy_from_slice(b"free");
+    let qt_tables = mp4ameta::sample_tables(&mut std::io::Cursor::new(&input)).unwrap();
+    assert_eq!(qt_tables, tables);
+}
+
+#[test]
+fn quicktime_without_ftyp_probe() {
+    let mut input = fs::read("files/sample.m4a").unwrap();
+    let probe = mp4ameta::probe_from(&mut std::io::Cursor::new(&input)).unwrap();
+    input[4..8].copy_from_slice(b"free");
+    let qt_probe = mp4ameta::probe_from(&mut std::io::Cursor::new(&input)).unwrap();
+    assert_eq!(qt_probe.brand, Fourcc::default());
+    assert_eq!(qt_probe.duration, probe.duration);
+    assert_eq!(qt_probe.has_ilst, probe.has_ilst);
+}